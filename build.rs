@@ -0,0 +1,16 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto");
+
+    #[cfg(feature = "grpc")]
+    {
+        let file_descriptors = protox::compile(["invmst.proto"], ["proto"])?;
+        tonic_build::configure()
+            .build_client(false)
+            // 分析结果远大于其他事件，装箱以免整个枚举随之变大
+            .boxed(".invmst.EvaluationEvent.event.master_finished")
+            .compile_fds(file_descriptors)?;
+    }
+
+    Ok(())
+}
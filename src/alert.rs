@@ -0,0 +1,182 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::LazyLock};
+
+use chrono::{DateTime, Local};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    APP_DATA_DIR,
+    alert::condition::Condition,
+    error::{InvmstError, InvmstResult},
+    metrics,
+    ticker::Ticker,
+};
+
+pub mod condition;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: u64,
+    pub ticker: String,
+    /// Expression over the metrics snapshot, e.g. "pe < 12 && roe > 0.15"
+    pub condition: String,
+    /// Whether the condition held at the last check, alerts are only triggered when the condition
+    /// becomes true rather than on every check while it holds
+    #[serde(default)]
+    pub active: bool,
+    pub triggered_at: Option<DateTime<Local>>,
+}
+
+/// Rule whose condition has become true, with the values of the metrics it references
+#[derive(Clone, Debug, Serialize)]
+pub struct AlertTrigger {
+    pub rule: AlertRule,
+    pub values: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AlertConfig {
+    rules: Vec<AlertRule>,
+}
+
+pub async fn add_rule(ticker: &str, condition: &str) -> InvmstResult<AlertRule> {
+    let ticker = Ticker::from_str(ticker)?;
+    parse_condition(condition)?;
+
+    let mut cfg: AlertConfig = confy::load_path(&*ALERT_CONFIG_PATH)?;
+
+    let rule = AlertRule {
+        id: cfg.rules.iter().map(|rule| rule.id).max().unwrap_or(0) + 1,
+        ticker: ticker.to_string(),
+        condition: condition.trim().to_string(),
+        active: false,
+        triggered_at: None,
+    };
+    cfg.rules.push(rule.clone());
+
+    confy::store_path(&*ALERT_CONFIG_PATH, &cfg)?;
+
+    Ok(rule)
+}
+
+pub async fn list_rules() -> InvmstResult<Vec<AlertRule>> {
+    let cfg: AlertConfig = confy::load_path(&*ALERT_CONFIG_PATH)?;
+
+    Ok(cfg.rules)
+}
+
+pub async fn remove_rule(id: u64) -> InvmstResult<()> {
+    let mut cfg: AlertConfig = confy::load_path(&*ALERT_CONFIG_PATH)?;
+
+    let count = cfg.rules.len();
+    cfg.rules.retain(|rule| rule.id != id);
+    if cfg.rules.len() == count {
+        return Err(InvmstError::NotExists(
+            "ALERT_NOT_EXISTS",
+            format!("Alert rule '{id}' not exists"),
+        ));
+    }
+
+    confy::store_path(&*ALERT_CONFIG_PATH, &cfg)?;
+
+    Ok(())
+}
+
+/// Evaluate all rules against the latest metrics and remember whether each holds. Rules of a
+/// ticker whose metrics are unavailable keep their state until the next check.
+pub async fn check_rules() -> InvmstResult<Vec<AlertTrigger>> {
+    let rules = list_rules().await?;
+
+    let mut snapshots: HashMap<String, Option<HashMap<String, f64>>> = HashMap::new();
+    let mut states: HashMap<u64, bool> = HashMap::new();
+    let mut triggers: Vec<AlertTrigger> = vec![];
+    let now = Local::now();
+    for rule in rules {
+        let condition = match Condition::from_str(&rule.condition) {
+            Ok(condition) => condition,
+            Err(err) => {
+                warn!("[Alerts] {err}");
+                continue;
+            }
+        };
+
+        if !snapshots.contains_key(&rule.ticker) {
+            let snapshot = match Ticker::from_str(&rule.ticker) {
+                Ok(ticker) => metrics::snapshot(&ticker).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = &snapshot {
+                warn!("[Alerts] Unable to fetch metrics of {}: {err}", rule.ticker);
+            }
+            snapshots.insert(rule.ticker.clone(), snapshot.ok());
+        }
+        let Some(Some(snapshot)) = snapshots.get(&rule.ticker) else {
+            continue;
+        };
+
+        let is_true = condition.evaluate(snapshot) == Some(true);
+        states.insert(rule.id, is_true);
+
+        if is_true && !rule.active {
+            let values = referenced_values(&condition, snapshot);
+            triggers.push(AlertTrigger {
+                rule: AlertRule {
+                    active: true,
+                    triggered_at: Some(now),
+                    ..rule
+                },
+                values,
+            });
+        }
+    }
+
+    // 重新加载后按 ID 更新，避免覆盖检查期间新增或删除的规则
+    let mut cfg: AlertConfig = confy::load_path(&*ALERT_CONFIG_PATH)?;
+    for rule in &mut cfg.rules {
+        if let Some(is_true) = states.get(&rule.id) {
+            rule.active = *is_true;
+        }
+        if triggers.iter().any(|trigger| trigger.rule.id == rule.id) {
+            rule.triggered_at = Some(now);
+        }
+    }
+    confy::store_path(&*ALERT_CONFIG_PATH, &cfg)?;
+
+    Ok(triggers)
+}
+
+/// Parse the condition and check that it only references the metrics of the snapshot
+pub fn parse_condition(condition: &str) -> InvmstResult<Condition> {
+    let condition = Condition::from_str(condition)?;
+
+    for variable in condition.variables() {
+        if !metrics::SNAPSHOT_VARIABLES.contains(&variable.as_str()) {
+            return Err(InvmstError::Invalid(
+                "UNKNOWN_METRIC",
+                format!(
+                    "Unknown metric '{variable}', available metrics: {}",
+                    metrics::SNAPSHOT_VARIABLES.join(", ")
+                ),
+            ));
+        }
+    }
+
+    Ok(condition)
+}
+
+/// Values of the metrics the condition references, skipping the unavailable ones
+pub fn referenced_values(
+    condition: &Condition,
+    snapshot: &HashMap<String, f64>,
+) -> Vec<(String, f64)> {
+    condition
+        .variables()
+        .into_iter()
+        .filter_map(|name| {
+            let value = *snapshot.get(&name)?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+static ALERT_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("alerts.toml"));
@@ -0,0 +1,324 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::error::{InvmstError, InvmstResult};
+
+/// Boolean expression over the metrics, e.g. "pe < 12 && roe > 15%"
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Compare(Operand, Comparator, Operand),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    Number(f64),
+    Variable(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparator {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Condition {
+    /// None if any variable the result depends on is unavailable, so that missing data never
+    /// triggers an alert
+    pub fn evaluate(&self, values: &HashMap<String, f64>) -> Option<bool> {
+        match self {
+            Condition::And(a, b) => match (a.evaluate(values), b.evaluate(values)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Condition::Or(a, b) => match (a.evaluate(values), b.evaluate(values)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            Condition::Not(a) => a.evaluate(values).map(|a| !a),
+            Condition::Compare(left, comparator, right) => {
+                let value = |operand: &Operand| match operand {
+                    Operand::Number(number) => Some(*number),
+                    Operand::Variable(name) => values.get(name).copied(),
+                };
+                let (left, right) = (value(left)?, value(right)?);
+
+                Some(match comparator {
+                    Comparator::Lt => left < right,
+                    Comparator::Le => left <= right,
+                    Comparator::Gt => left > right,
+                    Comparator::Ge => left >= right,
+                    Comparator::Eq => left == right,
+                    Comparator::Ne => left != right,
+                })
+            }
+        }
+    }
+
+    /// Names of the variables referenced, deduplicated in order of appearance
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = vec![];
+        self.collect_variables(&mut variables);
+        variables
+    }
+
+    fn collect_variables(&self, variables: &mut Vec<String>) {
+        match self {
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                a.collect_variables(variables);
+                b.collect_variables(variables);
+            }
+            Condition::Not(a) => a.collect_variables(variables),
+            Condition::Compare(left, _, right) => {
+                for operand in [left, right] {
+                    if let Operand::Variable(name) = operand {
+                        if !variables.contains(name) {
+                            variables.push(name.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = InvmstError;
+
+    fn from_str(s: &str) -> InvmstResult<Self> {
+        let invalid = |reason: String| {
+            InvmstError::Invalid(
+                "INVALID_CONDITION",
+                format!("Invalid condition '{s}': {reason}"),
+            )
+        };
+
+        let tokens = tokenize(s).map_err(invalid)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let condition = parser.parse_or().map_err(invalid)?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(invalid(format!("unexpected {token:?}")));
+        }
+
+        Ok(condition)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Variable(String),
+    Comparator(Comparator),
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens: Vec<Token> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || c == '.'
+            || (c == '-' && next.is_some_and(|n| n.is_ascii_digit() || n == '.'))
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let mut number: f64 = text
+                .parse()
+                .map_err(|_| format!("invalid number '{text}'"))?;
+            // 百分号表示比例，与指标的小数形式一致
+            if chars.get(i) == Some(&'%') {
+                number /= 100.0;
+                i += 1;
+            }
+            tokens.push(Token::Number(number));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            tokens.push(Token::Variable(name.to_lowercase()));
+        } else {
+            let (token, len) = match (c, next) {
+                ('<', Some('=')) => (Token::Comparator(Comparator::Le), 2),
+                ('>', Some('=')) => (Token::Comparator(Comparator::Ge), 2),
+                ('=', Some('=')) => (Token::Comparator(Comparator::Eq), 2),
+                ('!', Some('=')) => (Token::Comparator(Comparator::Ne), 2),
+                ('&', Some('&')) => (Token::And, 2),
+                ('|', Some('|')) => (Token::Or, 2),
+                ('<', _) => (Token::Comparator(Comparator::Lt), 1),
+                ('>', _) => (Token::Comparator(Comparator::Gt), 1),
+                ('!', _) => (Token::Not, 1),
+                ('(', _) => (Token::LeftParen, 1),
+                (')', _) => (Token::RightParen, 1),
+                _ => return Err(format!("unexpected '{c}'")),
+            };
+            tokens.push(token);
+            i += len;
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent by precedence, from low to high: ||, &&, !, comparison
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_not()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_not(&mut self) -> Result<Condition, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Condition::Not(Box::new(self.parse_not()?)))
+            }
+            Some(Token::LeftParen) => {
+                self.pos += 1;
+                let condition = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(condition),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }
+            _ => self.parse_compare(),
+        }
+    }
+
+    fn parse_compare(&mut self) -> Result<Condition, String> {
+        let left = self.parse_operand()?;
+        let comparator = match self.next() {
+            Some(Token::Comparator(comparator)) => comparator,
+            Some(token) => return Err(format!("expected a comparison but found {token:?}")),
+            None => return Err("expected a comparison".to_string()),
+        };
+        let right = self.parse_operand()?;
+
+        Ok(Condition::Compare(left, comparator, right))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Operand::Number(number)),
+            Some(Token::Variable(name)) => Ok(Operand::Variable(name)),
+            Some(token) => Err(format!("expected a metric or number but found {token:?}")),
+            None => Err("expected a metric or number".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let condition = Condition::from_str("pe < 12 && roe > 15%").unwrap();
+        assert_eq!(
+            condition,
+            Condition::And(
+                Box::new(Condition::Compare(
+                    Operand::Variable("pe".to_string()),
+                    Comparator::Lt,
+                    Operand::Number(12.0)
+                )),
+                Box::new(Condition::Compare(
+                    Operand::Variable("roe".to_string()),
+                    Comparator::Gt,
+                    Operand::Number(0.15)
+                )),
+            )
+        );
+        assert_eq!(condition.variables(), vec!["pe", "roe"]);
+
+        // && 优先于 ||
+        assert!(matches!(
+            Condition::from_str("pe < 10 || pb < 1 && roe >= 0.1").unwrap(),
+            Condition::Or(_, _)
+        ));
+        assert!(matches!(
+            Condition::from_str("!(pe < 10 || pb < 1) && return_1m > -0.1").unwrap(),
+            Condition::And(_, _)
+        ));
+
+        for s in [
+            "",
+            "pe",
+            "pe < ",
+            "pe < 12 &&",
+            "(pe < 12",
+            "pe < 12)",
+            "pe = 12",
+        ] {
+            assert!(Condition::from_str(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let values: HashMap<String, f64> = [("pe".to_string(), 11.0), ("roe".to_string(), 0.18)]
+            .into_iter()
+            .collect();
+        let evaluate = |s: &str| Condition::from_str(s).unwrap().evaluate(&values);
+
+        assert_eq!(evaluate("pe < 12 && roe > 0.15"), Some(true));
+        assert_eq!(evaluate("pe < 12 && roe > 20%"), Some(false));
+        assert_eq!(evaluate("!(pe >= 12)"), Some(true));
+        assert_eq!(evaluate("pb < 1"), None);
+        assert_eq!(evaluate("pb < 1 && pe > 12"), Some(false));
+        assert_eq!(evaluate("pb < 1 || pe < 12"), Some(true));
+        assert_eq!(evaluate("pb < 1 || pe > 12"), None);
+    }
+}
@@ -1,29 +1,236 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
+use chrono::{Local, NaiveDate};
+use log::warn;
 use strum::IntoEnumIterator;
 
 use crate::{
+    alert, config, data,
     error::{InvmstError, InvmstResult},
-    evaluate, financial, llm,
+    evaluate, export, financial, history, journal, llm,
     llm::Role,
+    master,
     master::Master,
+    metrics, migrate, paper, portfolio, preset, quote, schedule, screen, server,
+    ticker::Ticker,
+    watchlist,
 };
 
 pub static LLM_SUPPORTED_TYPES: &[&str] = &["chat"];
 pub static LLM_SUPPORTED_PROTOCOLS: &[&str] = &["openai"];
 
+pub type AlertRule = alert::AlertRule;
+pub type AlertTrigger = alert::AlertTrigger;
+pub type BearCase = master::bear_case::BearCase;
 pub type ChatCompletionEvent = llm::ChatCompletionEvent;
 pub type ChatCompletionOptions = llm::ChatCompletionOptions;
 pub type ChatCompletionStream = llm::ChatCompletionStream;
 pub type ChatMessage = llm::ChatMessage;
+pub type ComplianceRisk = financial::compliance::ComplianceRisk;
+pub type ConfigKey = config::ConfigKey;
+pub type DataAnomaly = financial::anomaly::DataAnomaly;
+pub type DataDiscrepancy = financial::reconcile::Discrepancy;
 pub type EvaluateOptions = evaluate::EvaluateOptions;
 pub type Evaluation = evaluate::Evaluation;
+pub type EvaluationDiff = history::diff::EvaluationDiff;
+pub type EvaluationEvent = evaluate::EvaluationEvent;
+pub type EvaluationStream = evaluate::EvaluationStream;
+pub type ExportDataset = export::ExportDataset;
+pub type ExportFormat = export::ExportFormat;
+pub type ExportOptions = export::ExportOptions;
+pub type FinancialsOptions = evaluate::FinancialsOptions;
+pub type FailureMode = master::pre_mortem::FailureMode;
+pub type Holding = master::Holding;
+pub type IndexEvaluation = evaluate::IndexEvaluation;
+pub type JournalEntry = journal::JournalEntry;
+pub type JournalReview = journal::JournalReview;
+pub type MasterDetail = master::MasterDetail;
+pub type MasterStyle = master::MasterStyle;
+pub type Metrics = metrics::Metrics;
+pub type MetricsOptions = metrics::MetricsOptions;
+pub type PaperAccount = paper::PaperAccount;
+pub type PaperReport = paper::PaperReport;
+pub type PaperRules = paper::PaperRules;
+pub type PaperStep = paper::PaperStep;
+pub type PaperTrade = paper::PaperTrade;
+pub type Portfolio = portfolio::Portfolio;
+pub type PortfolioHoldingIncome = portfolio::HoldingIncome;
+pub type PortfolioHoldingReport = portfolio::HoldingReport;
+pub type PortfolioPerformance = portfolio::PortfolioPerformance;
+pub type PortfolioRisk = portfolio::PortfolioRisk;
+pub type Preset = preset::Preset;
+pub type PriceSummary = evaluate::PriceSummary;
+pub type PromptStatus = master::prompt::PromptStatus;
 pub type Prospect = financial::Prospect;
+pub type Quote = quote::Quote;
+pub type RatingMatrix = history::matrix::RatingMatrix;
+pub type RecentDevelopments = master::digest::RecentDevelopments;
+pub type SectorOverview = financial::sector::SectorOverview;
+pub type ScheduleJob = schedule::ScheduleJob;
+pub type ScreenMatch = screen::ScreenMatch;
+pub type SizingMethod = portfolio::sizing::SizingMethod;
+pub type SizingOptions = portfolio::sizing::SizingOptions;
+pub type StockSnapshot = data::stock::StockSnapshot;
+pub type ToolCall = llm::ToolCall;
+pub type ToolDefinition = llm::ToolDefinition;
+pub type ValidationStatus = master::ValidationStatus;
+pub type Watchlist = watchlist::Watchlist;
+
+pub async fn alerts_add(ticker: &str, condition: &str) -> InvmstResult<AlertRule> {
+    alert::add_rule(ticker, condition).await
+}
+
+/// Alerts of the rules whose conditions have become true since the last check
+pub async fn alerts_check() -> InvmstResult<Vec<AlertTrigger>> {
+    alert::check_rules().await
+}
+
+pub async fn alerts_list() -> InvmstResult<Vec<AlertRule>> {
+    alert::list_rules().await
+}
+
+pub async fn alerts_remove(id: u64) -> InvmstResult<()> {
+    alert::remove_rule(id).await
+}
+
+pub async fn config_get(key: &str) -> InvmstResult<Option<String>> {
+    config::get(key).await
+}
+
+pub async fn config_list() -> InvmstResult<Vec<(ConfigKey, Option<String>)>> {
+    config::list().await
+}
+
+pub async fn config_set(key: &str, value: &str) -> InvmstResult<()> {
+    config::set(key, value).await
+}
+
+pub async fn data_export(
+    ticker: &str,
+    datasets: &[ExportDataset],
+    dir: &Path,
+    options: &ExportOptions,
+) -> InvmstResult<Vec<PathBuf>> {
+    export::export(ticker, datasets, dir, options).await
+}
+
+pub async fn data_migrate() -> InvmstResult<Vec<String>> {
+    migrate::run().await
+}
 
 pub async fn evaluate(ticker: &str, options: &EvaluateOptions) -> InvmstResult<Evaluation> {
     evaluate::run(ticker, options).await
 }
 
+/// Evaluate with pre-assembled data instead of fetching it, e.g. from your own data pipeline
+pub async fn evaluate_from_snapshot(
+    snapshot: StockSnapshot,
+    options: &EvaluateOptions,
+) -> InvmstResult<Evaluation> {
+    evaluate::run_snapshot(snapshot, options).await
+}
+
+pub async fn evaluate_stream(
+    ticker: &str,
+    options: &EvaluateOptions,
+) -> InvmstResult<EvaluationStream> {
+    evaluate::run_stream(ticker, options).await
+}
+
+pub async fn evaluate_stream_from_snapshot(
+    snapshot: StockSnapshot,
+    options: &EvaluateOptions,
+) -> InvmstResult<EvaluationStream> {
+    evaluate::run_snapshot_stream(snapshot, options).await
+}
+
+pub async fn evaluate_index(
+    symbol: &str,
+    options: &EvaluateOptions,
+) -> InvmstResult<IndexEvaluation> {
+    evaluate::run_index(symbol, options).await
+}
+
+pub async fn evaluations_csv(evaluations: &[Evaluation]) -> InvmstResult<String> {
+    export::evaluations_csv(evaluations)
+}
+
+/// Data of the stock the masters analyze, fetched without evaluating
+pub async fn financials(ticker: &str, options: &FinancialsOptions) -> InvmstResult<StockSnapshot> {
+    evaluate::run_financials(ticker, options).await
+}
+
+pub async fn history_ask(
+    id: &str,
+    question: &str,
+    options: &ChatCompletionOptions,
+) -> InvmstResult<ChatCompletionStream> {
+    history::ask::ask(&history::load(id).await?, question, options).await
+}
+
+/// Compare two evaluations of the same ticker, the narrative is skipped if the LLM fails
+pub async fn history_diff(id_a: &str, id_b: &str) -> InvmstResult<EvaluationDiff> {
+    let mut diff = history::diff::compare(history::load(id_a).await?, history::load(id_b).await?)?;
+
+    match history::diff::narrate(&diff).await {
+        Ok(narrative) => diff.narrative = Some(narrative),
+        Err(err) => warn!("[History Diff] {err}"),
+    }
+
+    Ok(diff)
+}
+
+pub async fn history_get(id: &str) -> InvmstResult<Evaluation> {
+    history::load(id).await
+}
+
+pub async fn history_list(ticker: Option<&str>) -> InvmstResult<Vec<Evaluation>> {
+    history::list(ticker).await
+}
+
+/// Ratings of the masters over the latest evaluation dates of the ticker, all dates if no limit
+pub async fn history_matrix(
+    ticker: &str,
+    dates_limit: Option<usize>,
+) -> InvmstResult<RatingMatrix> {
+    let ticker = Ticker::from_str(ticker)?.to_string();
+    let evaluations = history::list(Some(&ticker)).await?;
+
+    Ok(history::matrix::build(&ticker, &evaluations, dates_limit))
+}
+
+/// Data the masters analyzed in a saved evaluation
+pub async fn history_snapshot(id: &str) -> InvmstResult<StockSnapshot> {
+    history::load_snapshot(id).await
+}
+
+pub async fn journal_add(
+    ticker: &str,
+    action: &str,
+    shares: f64,
+    price: Option<f64>,
+    date: Option<NaiveDate>,
+    evaluation_id: Option<&str>,
+) -> InvmstResult<JournalEntry> {
+    journal::add_entry(ticker, action, shares, price, date, evaluation_id).await
+}
+
+pub async fn journal_list() -> InvmstResult<Vec<JournalEntry>> {
+    journal::list_entries().await
+}
+
+pub async fn journal_remove(id: u64) -> InvmstResult<()> {
+    journal::remove_entry(id).await
+}
+
+pub async fn journal_review() -> InvmstResult<Vec<JournalReview>> {
+    journal::review().await
+}
+
 pub async fn llm_chat_completion(
     prompt: &str,
     system: Option<&str>,
@@ -36,6 +243,8 @@ pub async fn llm_chat_completion(
             role: Role::System,
             content: system.to_string(),
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         });
     }
 
@@ -43,6 +252,8 @@ pub async fn llm_chat_completion(
         role: Role::User,
         content: prompt.to_string(),
         reasoning: None,
+        tool_calls: vec![],
+        tool_call_id: None,
     });
 
     llm::chat_completion(&messages, options).await
@@ -60,6 +271,8 @@ pub async fn llm_chat_completion_stream(
             role: Role::System,
             content: system.to_string(),
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         });
     }
 
@@ -67,18 +280,22 @@ pub async fn llm_chat_completion_stream(
         role: Role::User,
         content: prompt.to_string(),
         reasoning: None,
+        tool_calls: vec![],
+        tool_call_id: None,
     });
 
     llm::chat_completion_stream(&messages, options).await
 }
 
+/// Configure the provider of the LLM type, or append a fallback provider if fallback is set
 pub async fn llm_config(
     r#type: &str,
     protocol: &str,
     options: &HashMap<String, String>,
+    fallback: bool,
 ) -> InvmstResult<()> {
     match r#type {
-        "chat" => llm::config_chat(protocol, options).await,
+        "chat" => llm::config_chat(protocol, options, fallback).await,
         _ => Err(InvmstError::Invalid(
             "INVALID_LLM_TYPE",
             format!("Invalid LLM type '{type}'"),
@@ -86,6 +303,179 @@ pub async fn llm_config(
     }
 }
 
+/// Effective config of the LLM type, with secrets masked
+pub async fn llm_config_show(r#type: &str) -> InvmstResult<Vec<(String, String)>> {
+    match r#type {
+        "chat" => llm::config_chat_show().await,
+        _ => Err(InvmstError::Invalid(
+            "INVALID_LLM_TYPE",
+            format!("Invalid LLM type '{type}'"),
+        )),
+    }
+}
+
+pub async fn master_detail(name: &str) -> InvmstResult<MasterDetail> {
+    Master::from_str(name)
+        .map_err(|_| {
+            InvmstError::NotExists("MASTER_NOT_EXISTS", format!("Master '{name}' not exists"))
+        })?
+        .detail()
+}
+
 pub async fn masters() -> Vec<Master> {
     Master::iter().collect()
 }
+
+pub async fn metrics(ticker: &str, options: &MetricsOptions) -> InvmstResult<Metrics> {
+    metrics::compute(ticker, options).await
+}
+
+pub async fn paper_report() -> InvmstResult<PaperReport> {
+    paper::report().await
+}
+
+pub async fn paper_reset(cash: f64, rules: PaperRules) -> InvmstResult<PaperAccount> {
+    paper::reset(cash, rules).await
+}
+
+pub async fn paper_step(tickers: &[String], options: &EvaluateOptions) -> InvmstResult<PaperStep> {
+    paper::step(tickers, options).await
+}
+
+pub async fn portfolio_add(name: &str, ticker: &str, shares: f64) -> InvmstResult<Portfolio> {
+    portfolio::add_holding(name, ticker, shares).await
+}
+
+pub async fn portfolio_income(name: &str) -> InvmstResult<Vec<PortfolioHoldingIncome>> {
+    portfolio::project_income(name).await
+}
+
+pub async fn portfolio_list() -> InvmstResult<Vec<Portfolio>> {
+    portfolio::list().await
+}
+
+pub async fn portfolio_performance(
+    name: &str,
+    benchmark: &str,
+    backward_days: i64,
+) -> InvmstResult<PortfolioPerformance> {
+    portfolio::analyze_performance(name, benchmark, backward_days).await
+}
+
+pub async fn portfolio_remove(name: &str, ticker: Option<&str>) -> InvmstResult<()> {
+    portfolio::remove(name, ticker).await
+}
+
+pub async fn portfolio_risk(
+    name: &str,
+    benchmark: &str,
+    enable_commentary: bool,
+) -> InvmstResult<PortfolioRisk> {
+    portfolio::analyze_risk(name, benchmark, enable_commentary).await
+}
+
+pub async fn portfolio_show(
+    name: &str,
+    options: &SizingOptions,
+) -> InvmstResult<Vec<PortfolioHoldingReport>> {
+    portfolio::report(name, options).await
+}
+
+pub async fn position_size(
+    ticker: &str,
+    rating: u64,
+    options: &SizingOptions,
+) -> InvmstResult<Option<f64>> {
+    portfolio::suggest_position(ticker, rating, options).await
+}
+
+pub async fn preset(name: &str) -> InvmstResult<Preset> {
+    preset::get(name).await
+}
+
+pub async fn presets() -> InvmstResult<Vec<Preset>> {
+    preset::list().await
+}
+
+pub async fn prompt_edit_path(name: &str) -> InvmstResult<PathBuf> {
+    master::prompt::edit_path(name).await
+}
+
+pub async fn prompt_reset(name: Option<&str>) -> InvmstResult<Vec<String>> {
+    Ok(master::prompt::reset(name)
+        .await?
+        .iter()
+        .map(|prompt| prompt.to_string())
+        .collect())
+}
+
+pub async fn prompts() -> InvmstResult<Vec<PromptStatus>> {
+    master::prompt::list().await
+}
+
+pub async fn quote(ticker: &str) -> InvmstResult<Quote> {
+    quote::fetch(ticker).await
+}
+
+pub async fn schedule_add(
+    cron: &str,
+    tickers: &[String],
+    portfolio: Option<&str>,
+    watchlist: Option<&str>,
+    screen: Option<&str>,
+    masters: &[String],
+) -> InvmstResult<ScheduleJob> {
+    schedule::add_job(cron, tickers, portfolio, watchlist, screen, masters).await
+}
+
+pub async fn schedule_list() -> InvmstResult<Vec<ScheduleJob>> {
+    schedule::list_jobs().await
+}
+
+pub async fn schedule_remove(id: u64) -> InvmstResult<()> {
+    schedule::remove_job(id).await
+}
+
+/// Overviews of A-share sectors, with a macro comment from the LLM if requested
+/// Tickers meeting the condition over their latest metrics
+pub async fn screen(tickers: &[String], condition: &str) -> InvmstResult<Vec<ScreenMatch>> {
+    screen::run(tickers, condition).await
+}
+
+pub async fn sectors(
+    date: Option<NaiveDate>,
+    backward_days: i64,
+    comment: bool,
+) -> InvmstResult<(Vec<SectorOverview>, Option<String>)> {
+    let date = date.unwrap_or(Local::now().date_naive());
+    let sector_overviews = financial::get_sector_overviews(&date, backward_days).await?;
+
+    let comment = if comment && !sector_overviews.is_empty() {
+        Some(master::sectors::comment_sectors(&sector_overviews).await?)
+    } else {
+        None
+    };
+
+    Ok((sector_overviews, comment))
+}
+
+pub async fn serve(addr: &str) -> InvmstResult<()> {
+    server::serve(addr).await
+}
+
+#[cfg(feature = "grpc")]
+pub async fn serve_grpc(addr: &str) -> InvmstResult<()> {
+    crate::grpc::serve(addr).await
+}
+
+pub async fn watchlist_add(name: &str, tickers: &[String]) -> InvmstResult<Watchlist> {
+    watchlist::add_tickers(name, tickers).await
+}
+
+pub async fn watchlist_list() -> InvmstResult<Vec<Watchlist>> {
+    watchlist::list().await
+}
+
+pub async fn watchlist_remove(name: &str, ticker: Option<&str>) -> InvmstResult<()> {
+    watchlist::remove(name, ticker).await
+}
@@ -1,19 +1,195 @@
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
 use clap::Subcommand;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use invmst::error::InvmstError;
+use tabled::settings::Color;
+use tokio::time::Duration;
 
+mod alerts;
+mod ask;
+mod config;
+mod data;
 mod evaluate;
+mod history;
+mod journal;
 mod llm;
 mod masters;
+mod matrix;
+mod metrics;
+mod paper;
+mod portfolio;
+mod presets;
+mod prompts;
+mod quote;
+mod schedule;
+mod scheduler;
+mod sectors;
+mod serve;
+mod watchlist;
 
 #[derive(Subcommand)]
 pub enum Commands {
+    #[command(about = "Alert rules on metric thresholds, checked by the scheduler")]
+    #[clap(subcommand)]
+    Alerts(Box<alerts::AlertsCommand>),
+
+    #[command(about = "Ask a follow-up question about a saved evaluation")]
+    Ask(Box<ask::AskCommand>),
+
+    #[command(about = "Global settings used as defaults")]
+    #[clap(subcommand)]
+    Config(Box<config::ConfigCommand>),
+
+    #[command(about = "Fetched datasets")]
+    #[clap(subcommand)]
+    Data(Box<data::DataCommand>),
+
     #[command(about = "Evaluate investments")]
     #[clap(visible_aliases = &["eval"])]
     Evaluate(Box<evaluate::EvaluateCommand>),
 
+    #[command(about = "Saved evaluations")]
+    #[clap(subcommand)]
+    History(Box<history::HistoryCommand>),
+
+    #[command(about = "Trade journal linking decisions to evaluations")]
+    #[clap(subcommand)]
+    Journal(Box<journal::JournalCommand>),
+
     #[command(about = "LLM configuration and testing")]
     #[clap(subcommand)]
     Llm(Box<llm::LlmCommand>),
 
     #[command(about = "Display all investment masters")]
     Masters(Box<masters::MastersCommand>),
+
+    #[command(about = "Ratings of masters over the past evaluations of a stock")]
+    Matrix(Box<matrix::MatrixCommand>),
+
+    #[command(about = "Compute quantitative metrics of a stock")]
+    Metrics(Box<metrics::MetricsCommand>),
+
+    #[command(about = "Paper trading driven by master ratings")]
+    #[clap(subcommand)]
+    Paper(Box<paper::PaperCommand>),
+
+    #[command(about = "Manage portfolios and position sizing")]
+    #[clap(subcommand)]
+    Portfolio(Box<portfolio::PortfolioCommand>),
+
+    #[command(about = "Display all evaluation presets")]
+    Presets(Box<presets::PresetsCommand>),
+
+    #[command(about = "Customize the prompts of masters")]
+    #[clap(subcommand)]
+    Prompts(Box<prompts::PromptsCommand>),
+
+    #[command(about = "Quick snapshot of price and valuation without LLM")]
+    Quote(Box<quote::QuoteCommand>),
+
+    #[command(about = "Manage scheduled evaluation jobs")]
+    #[clap(subcommand)]
+    Schedule(Box<schedule::ScheduleCommand>),
+
+    #[command(about = "Run the scheduler of scheduled jobs and alert rules")]
+    #[clap(subcommand)]
+    Scheduler(Box<scheduler::SchedulerCommand>),
+
+    #[command(about = "Rank A-share sectors by performance and valuation")]
+    Sectors(Box<sectors::SectorsCommand>),
+
+    #[command(about = "Serve the HTTP API")]
+    Serve(Box<serve::ServeCommand>),
+
+    #[command(about = "Watchlists of tickers, e.g. evaluated by scheduled jobs")]
+    #[clap(subcommand)]
+    Watchlist(Box<watchlist::WatchlistCommand>),
+}
+
+/// Outcome classes of a failed or incomplete command, used as the exit code so scripts can tell
+/// them apart, the exit code is 0 on success
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Failure = 1,
+    DataError = 2,
+    LlmError = 3,
+    /// Finished but some requested parts are missing
+    Partial = 4,
+}
+
+impl From<&InvmstError> for Outcome {
+    fn from(err: &InvmstError) -> Self {
+        match err {
+            InvmstError::LlmError(_) => Outcome::LlmError,
+            InvmstError::ConfigError(_)
+            | InvmstError::Invalid(_, _)
+            | InvmstError::ParseEnumError(_)
+            | InvmstError::Required(_, _) => Outcome::Failure,
+            _ => Outcome::DataError,
+        }
+    }
+}
+
+pub fn exit_code() -> i32 {
+    EXIT_CODE.load(Ordering::Relaxed)
+}
+
+pub fn set_outcome(outcome: Outcome) {
+    EXIT_CODE.store(outcome as i32, Ordering::Relaxed);
+}
+
+pub fn is_color() -> bool {
+    COLOR.load(Ordering::Relaxed)
 }
+
+/// All colors of the CLI go through here, so that they can be turned off for logs and pipes
+pub fn set_color(enabled: bool) {
+    COLOR.store(enabled, Ordering::Relaxed);
+    if enabled {
+        colored::control::unset_override();
+    } else {
+        colored::control::set_override(false);
+    }
+}
+
+/// Color of the header column of tables
+pub fn highlight() -> Color {
+    if is_color() {
+        Color::FG_CYAN
+    } else {
+        Color::empty()
+    }
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Suppress spinners, so only the final result is printed
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Spinner on stderr showing the elapsed time, hidden in quiet mode
+pub fn new_spinner() -> ProgressBar {
+    let spinner = ProgressBar::new_spinner();
+    if is_quiet() {
+        spinner.set_draw_target(ProgressDrawTarget::hidden());
+        return spinner;
+    }
+
+    let template = if is_color() {
+        "{msg} {spinner:.cyan} [{elapsed}]"
+    } else {
+        "{msg} {spinner} [{elapsed}]"
+    };
+    spinner.set_style(ProgressStyle::with_template(template).unwrap());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+
+    spinner
+}
+
+static COLOR: AtomicBool = AtomicBool::new(true);
+static EXIT_CODE: AtomicI32 = AtomicI32::new(0);
+static QUIET: AtomicBool = AtomicBool::new(false);
@@ -0,0 +1,59 @@
+use clap::Subcommand;
+use colored::Colorize;
+use invmst::api;
+
+mod add;
+mod check;
+mod list;
+mod remove;
+
+#[derive(Subcommand)]
+pub enum AlertsCommand {
+    #[command(about = "Add an alert rule on the metrics of a stock")]
+    Add(Box<add::AlertsAddCommand>),
+
+    #[command(about = "Check all alert rules once")]
+    Check(Box<check::AlertsCheckCommand>),
+
+    #[command(about = "List alert rules")]
+    List(Box<list::AlertsListCommand>),
+
+    #[command(about = "Remove an alert rule")]
+    #[clap(visible_aliases = &["rm"])]
+    Remove(Box<remove::AlertsRemoveCommand>),
+}
+
+impl AlertsCommand {
+    pub async fn exec(&self) {
+        match self {
+            AlertsCommand::Add(cmd) => {
+                cmd.exec().await;
+            }
+            AlertsCommand::Check(cmd) => {
+                cmd.exec().await;
+            }
+            AlertsCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            AlertsCommand::Remove(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
+
+pub fn render_trigger(trigger: &api::AlertTrigger) -> String {
+    let values: Vec<String> = trigger
+        .values
+        .iter()
+        .map(|(name, value)| format!("{name} = {value:.4}"))
+        .collect();
+
+    format!(
+        "[{}] Alert {} triggered: {} ({})",
+        trigger.rule.ticker.cyan(),
+        trigger.rule.id.to_string().cyan(),
+        trigger.rule.condition.yellow(),
+        values.join(", ")
+    )
+}
@@ -0,0 +1,29 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct AlertsAddCommand {
+    #[arg(
+        short = 'w',
+        long = "when",
+        required = true,
+        help = "Condition on metrics, e.g. \"pe < 12 && roe > 0.15\", percentages like 15% are fractions"
+    )]
+    condition: String,
+
+    #[arg(help = "Ticker to watch, e.g. 600900")]
+    ticker: String,
+}
+
+impl AlertsAddCommand {
+    pub async fn exec(&self) {
+        match api::alerts_add(&self.ticker, &self.condition).await {
+            Ok(rule) => {
+                println!("Alert rule {} has been added", rule.id.to_string().cyan());
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use colored::Colorize;
+use invmst::api;
+
+use crate::cli::alerts::render_trigger;
+
+#[derive(clap::Args)]
+pub struct AlertsCheckCommand;
+
+impl AlertsCheckCommand {
+    pub async fn exec(&self) {
+        match api::alerts_check().await {
+            Ok(triggers) => {
+                if triggers.is_empty() {
+                    println!("No alerts triggered");
+                    return;
+                }
+
+                for trigger in &triggers {
+                    println!("{}", render_trigger(trigger));
+                }
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
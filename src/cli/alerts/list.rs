@@ -0,0 +1,47 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct AlertsListCommand;
+
+impl AlertsListCommand {
+    pub async fn exec(&self) {
+        match api::alerts_list().await {
+            Ok(rules) => {
+                if rules.is_empty() {
+                    println!("No alert rules");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "ID".to_string(),
+                    "Ticker".to_string(),
+                    "Condition".to_string(),
+                    "Active".to_string(),
+                    "Last Triggered".to_string(),
+                ]];
+                for rule in rules {
+                    table_data.push(vec![
+                        rule.id.to_string(),
+                        rule.ticker.to_string(),
+                        rule.condition.to_string(),
+                        if rule.active { "Yes" } else { "" }.to_string(),
+                        rule.triggered_at
+                            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default(),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
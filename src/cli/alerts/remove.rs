@@ -0,0 +1,18 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct AlertsRemoveCommand {
+    #[arg(help = "ID of the alert rule")]
+    id: u64,
+}
+
+impl AlertsRemoveCommand {
+    pub async fn exec(&self) {
+        if let Err(err) = api::alerts_remove(self.id).await {
+            println!("{}", err.to_string().red());
+        } else {
+            println!("Alert rule {} has been removed", self.id);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{Write, stdout};
+
+use colored::Colorize;
+use invmst::{VecOptions, api, api::*};
+
+#[derive(clap::Args)]
+pub struct AskCommand {
+    #[arg(help = "ID of a saved evaluation")]
+    id: String,
+
+    #[arg(help = "Question about the evaluation")]
+    question: String,
+
+    #[arg(
+        short = 'L',
+        long = "llm-option",
+        help = "Additional option passed to LLM, e.g. -L temperature:0.6"
+    )]
+    llm_options: Vec<String>,
+}
+
+impl AskCommand {
+    pub async fn exec(&self) {
+        let mut chat_completion_options = ChatCompletionOptions::default();
+        let llm_options = VecOptions(&self.llm_options);
+        if let Some(temperature_str) = llm_options.get("temperature") {
+            if let Ok(temperature) = temperature_str.parse() {
+                chat_completion_options = chat_completion_options.with_temperature(temperature);
+            }
+        }
+
+        match api::history_ask(&self.id, &self.question, &chat_completion_options).await {
+            Ok(mut stream) => {
+                let mut has_content = false;
+                let mut has_reasoning_content = false;
+
+                while let Some(event) = stream.next().await {
+                    match event {
+                        ChatCompletionEvent::Content(delta) => {
+                            if !has_content && has_reasoning_content {
+                                print!("\n\n");
+                                stdout().flush().unwrap();
+                            }
+
+                            has_content = true;
+                            print!("{delta}");
+                            stdout().flush().unwrap();
+                        }
+                        ChatCompletionEvent::ReasoningContent(delta) => {
+                            has_reasoning_content = true;
+                            print!("{}", delta.bright_black());
+                            stdout().flush().unwrap();
+                        }
+                        // 这里不提供工具，不会收到工具调用
+                        ChatCompletionEvent::ToolCall(_) => {}
+                        ChatCompletionEvent::Error(err) => {
+                            println!("{}", err.to_string().red());
+                            break;
+                        }
+                    }
+                }
+
+                println!();
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use clap::Subcommand;
+
+mod get;
+mod list;
+mod set;
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    #[command(about = "Get the value of a config key")]
+    Get(Box<get::ConfigGetCommand>),
+
+    #[command(about = "List all config keys and values")]
+    #[clap(visible_aliases = &["ls"])]
+    List(Box<list::ConfigListCommand>),
+
+    #[command(about = "Set the value of a config key, an empty value resets it")]
+    Set(Box<set::ConfigSetCommand>),
+}
+
+impl ConfigCommand {
+    pub async fn exec(&self) {
+        match self {
+            ConfigCommand::Get(cmd) => {
+                cmd.exec().await;
+            }
+            ConfigCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            ConfigCommand::Set(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
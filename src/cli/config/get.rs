@@ -0,0 +1,24 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct ConfigGetCommand {
+    #[arg(help = "Config key, e.g. backward_days")]
+    key: String,
+}
+
+impl ConfigGetCommand {
+    pub async fn exec(&self) {
+        match api::config_get(&self.key).await {
+            Ok(Some(value)) => {
+                println!("{value}");
+            }
+            Ok(None) => {
+                println!("{}", "Not set".bright_black());
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct ConfigListCommand;
+
+impl ConfigListCommand {
+    pub async fn exec(&self) {
+        match api::config_list().await {
+            Ok(items) => {
+                let mut table_data: Vec<Vec<String>> = vec![];
+                for (key, value) in items {
+                    table_data.push(vec![key.to_string(), value.unwrap_or_default()]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct ConfigSetCommand {
+    #[arg(help = "Config key, e.g. backward_days")]
+    key: String,
+
+    #[arg(help = "Value of the key, masters are separated by comma, e.g. buffett,graham")]
+    value: String,
+}
+
+impl ConfigSetCommand {
+    pub async fn exec(&self) {
+        match api::config_set(&self.key, &self.value).await {
+            Ok(_) => {
+                println!("Config {} has been updated", self.key.cyan());
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use clap::Subcommand;
+
+mod export;
+
+#[derive(Subcommand)]
+pub enum DataCommand {
+    #[command(about = "Export fetched datasets of a stock to CSV or Parquet files")]
+    Export(Box<export::DataExportCommand>),
+}
+
+impl DataCommand {
+    pub async fn exec(&self) {
+        match self {
+            DataCommand::Export(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
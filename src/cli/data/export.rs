@@ -0,0 +1,135 @@
+use std::{path::PathBuf, str::FromStr};
+
+use chrono::Local;
+use colored::Colorize;
+use invmst::{api, utils};
+use strum::IntoEnumIterator;
+
+use crate::cli::new_spinner;
+
+#[derive(clap::Args)]
+pub struct DataExportCommand {
+    #[arg(
+        short = 'b',
+        long = "backward",
+        help = "Days to backward, the default value is 1100"
+    )]
+    backward_days: Option<i64>,
+
+    #[arg(
+        long = "charts",
+        help = "Directory to save charts of the price, the drawdown and the valuation to, requires the charts feature"
+    )]
+    charts_dir: Option<PathBuf>,
+
+    #[arg(
+        short = 'd',
+        long = "date",
+        help = "The end date of datasets, e.g. -d 2022-01-01"
+    )]
+    date: Option<String>,
+
+    #[arg(
+        short = 'f',
+        long = "format",
+        default_value = "csv",
+        help = "File format, csv or parquet"
+    )]
+    format: String,
+
+    #[arg(
+        short = 'o',
+        long = "out",
+        default_value = ".",
+        help = "Directory to write files to"
+    )]
+    out: PathBuf,
+
+    #[arg(
+        short = 'w',
+        long = "what",
+        value_delimiter = ',',
+        help = "Datasets to export, all if not given, e.g. -w prices,financials,statements,dividends,valuation_bands"
+    )]
+    what: Vec<String>,
+
+    #[arg(help = "Ticker to export, e.g. 600900")]
+    ticker: String,
+}
+
+impl DataExportCommand {
+    pub async fn exec(&self) {
+        let Ok(format) = api::ExportFormat::from_str(&self.format) else {
+            println!(
+                "Unknown format '{}', try '{}' or '{}'",
+                self.format.yellow(),
+                "csv".green(),
+                "parquet".green()
+            );
+            return;
+        };
+
+        let mut datasets: Vec<api::ExportDataset> = vec![];
+        for what in &self.what {
+            match api::ExportDataset::from_str(what.trim()) {
+                Ok(dataset) => datasets.push(dataset),
+                Err(_) => {
+                    println!(
+                        "Unknown dataset '{}', available values: {}",
+                        what.yellow(),
+                        api::ExportDataset::iter()
+                            .map(|dataset| dataset.to_string())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    );
+                    return;
+                }
+            }
+        }
+        if datasets.is_empty() {
+            datasets = api::ExportDataset::iter().collect();
+        }
+
+        let date = if let Some(date_str) = &self.date {
+            let parsed_date = utils::datetime::date_from_str(date_str);
+            if parsed_date.is_none() {
+                println!(
+                    "Can not parse '{}' as date, try format like '{}'",
+                    date_str.yellow(),
+                    Local::now()
+                        .date_naive()
+                        .format("%Y-%m-%d")
+                        .to_string()
+                        .green()
+                );
+                return;
+            }
+
+            parsed_date
+        } else {
+            None
+        };
+
+        let options = api::ExportOptions {
+            date,
+            backward_days: self.backward_days.unwrap_or(1100).abs(),
+            format,
+            charts_dir: self.charts_dir.clone(),
+        };
+
+        let spinner = new_spinner();
+        spinner.set_message(format!("[{}] Exporting", self.ticker.cyan()));
+
+        match api::data_export(&self.ticker, &datasets, &self.out, &options).await {
+            Ok(paths) => {
+                spinner.finish_with_message(format!("[{}] Exported", self.ticker.cyan()));
+                for path in paths {
+                    println!("{}", path.display());
+                }
+            }
+            Err(err) => {
+                spinner.finish_with_message(format!("[{}] {}", self.ticker, err.to_string().red()));
+            }
+        }
+    }
+}
@@ -1,20 +1,93 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
 use chrono::Local;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
-use invmst::{api, api::Prospect, error::InvmstError, utils};
-use strum::EnumMessage;
-use tabled::settings::{Color, Width, measurement::Percent, object::Columns, peaker::Priority};
-use tokio::time::Duration;
+use invmst::{
+    VecOptions, api,
+    api::{
+        BearCase, Evaluation, EvaluationEvent, FailureMode, IndexEvaluation, PriceSummary,
+        Prospect, RecentDevelopments, StockSnapshot,
+    },
+    error::{InvmstError, InvmstResult},
+    utils,
+};
+use serde_json::Value;
+use strum::{EnumMessage, IntoEnumIterator};
+use tabled::settings::{Width, measurement::Percent, object::Columns, peaker::Priority};
+
+use crate::cli::{
+    Outcome, highlight, is_quiet,
+    metrics::{format_multiple, format_percent},
+    new_spinner, set_outcome,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
+pub enum TableColumn {
+    Master,
+    Prospect,
+    Explanation,
+    Risks,
+    Catalysts,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
+pub enum SortBy {
+    Master,
+    Rating,
+    Ticker,
+}
+
+pub struct TableOptions {
+    pub columns: Vec<TableColumn>,
+    pub sort_by: SortBy,
+    /// Print explanations as raw markdown
+    pub plain: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            columns: TableColumn::iter().collect(),
+            sort_by: SortBy::Master,
+            plain: false,
+        }
+    }
+}
 
 #[derive(clap::Args)]
 pub struct EvaluateCommand {
+    #[arg(
+        long = "agentic",
+        help = "Let masters request metric series, announcements and industry peers on demand, within a budget of tool calls"
+    )]
+    agentic: bool,
+
     #[arg(
         short = 'b',
         long = "backward",
-        help = "Days to backward, the default value is 730"
+        help = "Days to backward, the default value is from config or 1100"
     )]
     backward_days: Option<i64>,
 
+    #[arg(
+        long = "bear-case",
+        help = "Construct the strongest bear argument after the masters finish"
+    )]
+    bear_case: bool,
+
+    #[arg(
+        long = "columns",
+        value_delimiter = ',',
+        help = "Table columns to show, all if not given, e.g. --columns master,prospect"
+    )]
+    columns: Vec<String>,
+
     #[arg(
         short = 'd',
         long = "date",
@@ -22,20 +95,215 @@ pub struct EvaluateCommand {
     )]
     date: Option<String>,
 
+    #[arg(
+        long = "focus",
+        help = "Aspect the masters should pay special attention to, e.g. --focus receivables --focus \"new capacity\""
+    )]
+    focus_areas: Vec<String>,
+
+    #[arg(
+        short = 'f',
+        long = "format",
+        help = "Output format, table or csv, the default value is from config or table"
+    )]
+    format: Option<String>,
+
+    #[arg(
+        long = "holding",
+        help = "Existing position to get an add/hold/trim/exit action, e.g. --holding cost:12.3"
+    )]
+    holding: Option<String>,
+
+    #[arg(
+        long = "index",
+        help = "Evaluate an index by aggregated constituent fundamentals, e.g. --index sh000300"
+    )]
+    index: bool,
+
+    #[arg(
+        short = 'L',
+        long = "llm-option",
+        help = "Option passed to LLM for this evaluation only, e.g. -L temperature:0.2 -L model:gpt-4o -L think:true"
+    )]
+    llm_options: Vec<String>,
+
     #[arg(
         short = 'm',
         long = "master",
-        help = "Investment master, e.g. -m buffett -m graham"
+        help = "Investment master or style group, e.g. -m buffett -m graham, or -m @value for all value masters"
     )]
     masters: Vec<String>,
 
-    #[arg(help = "Ticker to evaluate, e.g. 600900")]
+    #[arg(
+        short = 'x',
+        long = "exclude-master",
+        help = "Investment master or style group to leave out, e.g. -x livermore -x @macro"
+    )]
+    exclude_masters: Vec<String>,
+
+    #[arg(
+        long = "monte-carlo",
+        help = "Sample DCF assumptions to estimate a P10/P50/P90 fair value range"
+    )]
+    monte_carlo: bool,
+
+    #[arg(
+        long = "note",
+        help = "Context the data cannot capture, given to every master and saved with the evaluation, e.g. --note \"new capacity coming online in 2025\""
+    )]
+    note: Option<String>,
+
+    #[arg(
+        short = 'o',
+        long = "out",
+        help = "File to write the csv output to instead of stdout, e.g. -o result.csv"
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(
+        long = "pre-mortem",
+        help = "Imagine the investment failed in 3 years and list the likely causes"
+    )]
+    pre_mortem: bool,
+
+    #[arg(
+        long = "plain",
+        help = "Print explanations as raw markdown instead of rendering them"
+    )]
+    plain: bool,
+
+    #[arg(
+        short = 'p',
+        long = "preset",
+        help = "Preset of masters and options, explicit options take precedence, e.g. -p deep-value"
+    )]
+    preset: Option<String>,
+
+    #[arg(
+        long = "show-reasoning",
+        help = "Print the chain-of-thought of each master, if the model returns it"
+    )]
+    show_reasoning: bool,
+
+    #[arg(
+        long = "snapshot",
+        help = "Evaluate the data of a snapshot file or of a saved evaluation instead of fetching, e.g. --snapshot 20250101093000-600900"
+    )]
+    snapshot: Option<String>,
+
+    #[arg(
+        long = "sort-by",
+        default_value = "master",
+        help = "Sort table rows by rating, ticker or master"
+    )]
+    sort_by: String,
+
+    #[arg(
+        short = 's',
+        long = "sizing",
+        help = "Suggest position size by method, kelly or vol, e.g. -s kelly"
+    )]
+    sizing: Option<String>,
+
+    #[arg(help = "Ticker to evaluate, e.g. 600900, or index symbol if --index is set")]
     ticker: String,
 }
 
 impl EvaluateCommand {
     pub async fn exec(&self) {
-        let backward_days = self.backward_days.unwrap_or(1100).abs();
+        let preset = match &self.preset {
+            Some(name) => match api::preset(name).await {
+                Ok(preset) => Some(preset),
+                Err(err) => {
+                    println!("{}", err.to_string().red());
+                    set_outcome(Outcome::Failure);
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let backward_days = self
+            .backward_days
+            .or(preset.as_ref().and_then(|p| p.backward_days));
+        let masters = match &preset {
+            Some(preset) if self.masters.is_empty() => preset.masters.clone(),
+            _ => self.masters.clone(),
+        };
+
+        let Some(csv) = is_csv_format(self.format.as_deref()).await else {
+            return;
+        };
+        if csv && self.index {
+            println!("{}", "Index evaluation only supports table format".red());
+            set_outcome(Outcome::Failure);
+            return;
+        }
+
+        let mut columns: Vec<TableColumn> = vec![];
+        for column in &self.columns {
+            match TableColumn::from_str(column.trim()) {
+                Ok(column) => columns.push(column),
+                Err(_) => {
+                    println!(
+                        "Unknown column '{}', available values: {}",
+                        column.yellow(),
+                        TableColumn::iter()
+                            .map(|column| column.to_string())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    );
+                    set_outcome(Outcome::Failure);
+                    return;
+                }
+            }
+        }
+        if columns.is_empty() {
+            columns = TableColumn::iter().collect();
+        }
+
+        let Ok(sort_by) = SortBy::from_str(&self.sort_by) else {
+            println!(
+                "Unknown sort key '{}', available values: {}",
+                self.sort_by.yellow(),
+                SortBy::iter()
+                    .map(|sort_by| sort_by.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            );
+            set_outcome(Outcome::Failure);
+            return;
+        };
+        let table_options = TableOptions {
+            columns,
+            sort_by,
+            plain: self.plain,
+        };
+
+        let sizing_method = match self.sizing.as_deref().map(api::SizingMethod::from_str) {
+            Some(Ok(method)) => Some(method),
+            Some(Err(_)) => {
+                println!(
+                    "Unknown sizing method '{}', try '{}' or '{}'",
+                    self.sizing.as_deref().unwrap_or_default().yellow(),
+                    "kelly".green(),
+                    "vol".green()
+                );
+                set_outcome(Outcome::Failure);
+                return;
+            }
+            None => None,
+        };
+
+        let holding = match self.holding.as_deref().map(api::Holding::from_str) {
+            Some(Ok(holding)) => Some(holding),
+            Some(Err(err)) => {
+                println!("{}", err.to_string().red());
+                set_outcome(Outcome::Failure);
+                return;
+            }
+            None => None,
+        };
 
         let date = if let Some(date_str) = &self.date {
             let parsed_date = utils::datetime::date_from_str(date_str);
@@ -49,6 +317,7 @@ impl EvaluateCommand {
                         .to_string()
                         .green()
                 );
+                set_outcome(Outcome::Failure);
                 return;
             }
 
@@ -57,73 +326,216 @@ impl EvaluateCommand {
             None
         };
 
+        let llm_options = match api::ChatCompletionOptions::default()
+            .with_overrides(&VecOptions(&self.llm_options).into_map())
+        {
+            Ok(llm_options) => llm_options,
+            Err(err) => {
+                println!("{}", err.to_string().red());
+                set_outcome(Outcome::Failure);
+                return;
+            }
+        };
+
         let options = api::EvaluateOptions {
             backward_days,
             date,
-            masters: self.masters.clone(),
+            masters,
+            exclude_masters: self.exclude_masters.clone(),
+            monte_carlo: self.monte_carlo || preset.as_ref().is_some_and(|p| p.monte_carlo),
+            holding,
+            bear_case: self.bear_case || preset.as_ref().is_some_and(|p| p.bear_case),
+            pre_mortem: self.pre_mortem || preset.as_ref().is_some_and(|p| p.pre_mortem),
+            agentic: self.agentic,
+            focus_areas: self.focus_areas.clone(),
+            note: self.note.clone(),
+            llm_options,
         };
 
-        let spinner = ProgressBar::new_spinner();
-        spinner
-            .set_style(ProgressStyle::with_template("{msg} {spinner:.cyan} [{elapsed}]").unwrap());
-        spinner.enable_steady_tick(Duration::from_millis(100));
+        let spinner = new_spinner();
 
-        match api::evaluate(&self.ticker, &options).await {
-            Ok(evaluation) => {
-                spinner.finish_with_message(format!("[{}]", self.ticker.cyan()));
+        if self.index {
+            spinner.set_message(format!("[{}] Aggregating constituents", self.ticker.cyan()));
+            match api::evaluate_index(&self.ticker, &options).await {
+                Ok(index_evaluation) => {
+                    spinner.finish_with_message(format!(
+                        "[{}] {}",
+                        index_evaluation.symbol.cyan(),
+                        index_evaluation.date
+                    ));
+                    println!("{}", render_index_evaluation(&index_evaluation, self.plain));
+                }
+                Err(err) => {
+                    spinner.finish_with_message(format!(
+                        "[{}] {}",
+                        self.ticker,
+                        err.to_string().red()
+                    ));
+                    if is_quiet() {
+                        eprintln!("[{}] {err}", self.ticker);
+                    }
+                    set_outcome(Outcome::from(&err));
+                }
+            }
+            return;
+        }
 
-                let mut ratings: Vec<u64> = vec![];
-                let mut table_data: Vec<Vec<String>> = vec![];
-                for (master, master_analysis) in evaluation.master_analyses {
-                    ratings.push(master_analysis.rating);
+        let stream = match &self.snapshot {
+            Some(snapshot) => match load_snapshot(snapshot).await {
+                Ok(snapshot) => api::evaluate_stream_from_snapshot(snapshot, &options).await,
+                Err(err) => Err(err),
+            },
+            None => api::evaluate_stream(&self.ticker, &options).await,
+        };
+        let result: InvmstResult<Evaluation> = match stream {
+            Ok(mut stream) => {
+                let mut masters_count = 0;
+                let mut masters_finished_count = 0;
+                let mut result: Option<InvmstResult<Evaluation>> = None;
 
-                    let prospect_symbol = match master_analysis.prospect {
-                        Prospect::Bullish => "↑",
-                        Prospect::Bearish => "↓",
-                        Prospect::Neutral => "-",
-                    };
-                    let prospect = format!("{prospect_symbol} ({})", master_analysis.rating);
+                while let Some(event) = stream.next().await {
+                    match event {
+                        EvaluationEvent::Stage(stage) => {
+                            spinner.set_message(format!("[{}] {stage}", self.ticker.cyan()));
+                        }
+                        EvaluationEvent::MasterStarted(_) => {
+                            masters_count += 1;
+                        }
+                        EvaluationEvent::MasterFinished(_, _) => {
+                            masters_finished_count += 1;
+                            spinner.set_message(format!(
+                                "[{}] Analyzing ({masters_finished_count}/{masters_count})",
+                                self.ticker.cyan(),
+                            ));
+                        }
+                        EvaluationEvent::Finished(evaluation) => {
+                            result = Some(Ok(*evaluation));
+                            break;
+                        }
+                        EvaluationEvent::Error(err) => {
+                            result = Some(Err(err));
+                            break;
+                        }
+                    }
+                }
+
+                result.unwrap_or(Err(InvmstError::NoData(
+                    "EVALUATION_INTERRUPTED",
+                    "Evaluation ended without result".to_string(),
+                )))
+            }
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(evaluation) => {
+                spinner.finish_with_message(match &evaluation.price_summary {
+                    Some(price_summary) => format!(
+                        "[{}] {}",
+                        self.ticker.cyan(),
+                        render_price_summary(price_summary)
+                    ),
+                    None => format!("[{}]", self.ticker.cyan()),
+                });
 
-                    table_data.push(vec![
-                        master.get_message().unwrap_or_default().to_string(),
-                        prospect.to_string(),
-                        master_analysis.explanation.to_string(),
-                    ]);
+                // 部分大师或要求的附加分析失败时仍输出结果，但以退出码提示不完整
+                if !evaluation.master_errors.is_empty()
+                    || (options.bear_case && evaluation.bear_case.is_none())
+                    || (options.pre_mortem && evaluation.failure_modes.is_none())
+                {
+                    set_outcome(Outcome::Partial);
+                }
+
+                if csv {
+                    if let Err(err) =
+                        output_csv(std::slice::from_ref(&evaluation), self.out.as_deref()).await
+                    {
+                        println!("{}", err.to_string().red());
+                        set_outcome(Outcome::from(&err));
+                    }
+                    // 输出到标准输出时避免提示信息混入 CSV
+                    if !is_quiet() {
+                        eprintln!("[I] Saved as evaluation {}", evaluation.id.cyan());
+                    }
+                    return;
                 }
 
-                if !ratings.is_empty() {
-                    let rating_avg: u64 =
-                        (ratings.iter().sum::<u64>() as f64 / ratings.len() as f64).round() as u64;
+                if let Some(note) = &evaluation.note {
+                    println!("[I] Note: {}", note.cyan());
+                }
+                for (master, err) in &evaluation.master_errors {
+                    println!(
+                        "[W] {} failed: {}",
+                        master.get_message().unwrap_or_default(),
+                        err.yellow()
+                    );
+                }
+                for data_anomaly in &evaluation.data_anomalies {
+                    println!("[I] Data anomaly: {}", data_anomaly.assessment().yellow());
+                }
+                for data_discrepancy in &evaluation.data_discrepancies {
+                    println!(
+                        "[I] Data discrepancy: {}",
+                        data_discrepancy.assessment().yellow()
+                    );
+                }
+                if let Some(compliance_risk) = &evaluation.compliance_risk {
+                    println!(
+                        "[I] Compliance risk: {}",
+                        compliance_risk.assessment().yellow()
+                    );
+                }
+                println!("{}", render_evaluation(&evaluation, &table_options));
+                if self.show_reasoning {
+                    println!("{}", render_reasoning(&evaluation));
+                }
+                if let Some(recent_developments) = &evaluation.recent_developments {
+                    println!(
+                        "{}",
+                        render_recent_developments(recent_developments, self.plain)
+                    );
+                }
+                if let Some(bear_case) = &evaluation.bear_case {
+                    println!("{}", render_bear_case(bear_case, self.plain));
+                }
+                if let Some(failure_modes) = &evaluation.failure_modes {
+                    println!("{}", render_failure_modes(failure_modes));
+                }
 
-                    let prospect_symbol = if rating_avg < 40 {
-                        "↓"
-                    } else if rating_avg < 60 {
-                        "-"
-                    } else {
-                        "↑"
+                if let (Some(method), Some(rating_avg)) = (sizing_method, evaluation.rating_avg()) {
+                    let options = api::SizingOptions {
+                        method,
+                        ..Default::default()
                     };
-                    let prospect = format!("{prospect_symbol} ({rating_avg})");
-
-                    table_data.push(vec![
-                        "AVG".to_string(),
-                        prospect.to_string(),
-                        "".to_string(),
-                    ]);
-                }
-
-                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
-                table.modify(Columns::first(), Color::FG_CYAN);
-                table.with((
-                    Width::wrap(Percent(30)).priority(Priority::max(true)),
-                    Width::increase(Percent(30)).priority(Priority::min(true)),
-                ));
-                println!("{table}");
+                    match api::position_size(&evaluation.ticker, rating_avg, &options).await {
+                        Ok(Some(position)) => {
+                            println!(
+                                "[I] Suggested position ({method}): {}",
+                                format!("{:.1}%", position * 100.0).cyan()
+                            );
+                        }
+                        Ok(None) => {
+                            println!("[I] Not enough price history to suggest position size");
+                        }
+                        Err(err) => {
+                            println!("{}", err.to_string().red());
+                        }
+                    }
+                }
+
+                if !is_quiet() {
+                    println!("[I] Saved as evaluation {}", evaluation.id.cyan());
+                }
             }
             Err(err) => {
                 spinner.finish_with_message(format!("[{}] {}", self.ticker, err.to_string().red()));
+                if is_quiet() {
+                    eprintln!("[{}] {err}", self.ticker);
+                }
+                set_outcome(Outcome::from(&err));
 
                 if let InvmstError::NotExists(code, _) = err {
-                    if code == "MASTER_NOT_EXISTS" {
+                    if code == "MASTER_NOT_EXISTS" && !is_quiet() {
                         println!(
                             "[I] Run `{}` command to get master list",
                             "invmst masters".green()
@@ -134,3 +546,389 @@ impl EvaluateCommand {
         }
     }
 }
+
+/// One row per master, written to the file if given or printed to stdout
+/// Snapshot from a file, or of a saved evaluation if no such file exists
+async fn load_snapshot(snapshot: &str) -> InvmstResult<StockSnapshot> {
+    let path = Path::new(snapshot);
+    if path.is_file() {
+        StockSnapshot::from_json(&fs::read_to_string(path)?)
+    } else {
+        api::history_snapshot(snapshot).await
+    }
+}
+
+/// Whether to output CSV by the option or the config, None with a message if the format is unknown
+pub async fn is_csv_format(format: Option<&str>) -> Option<bool> {
+    let format = match format {
+        Some(format) => format.to_lowercase(),
+        None => api::config_get("format")
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or("table".to_string()),
+    };
+
+    match format.as_str() {
+        "table" => Some(false),
+        "csv" => Some(true),
+        _ => {
+            println!(
+                "Unknown format '{}', try '{}' or '{}'",
+                format.yellow(),
+                "table".green(),
+                "csv".green()
+            );
+            set_outcome(Outcome::Failure);
+            None
+        }
+    }
+}
+
+/// Write the evaluations as CSV to the file, or to stdout if no file is given
+pub async fn output_csv(evaluations: &[Evaluation], out: Option<&Path>) -> InvmstResult<()> {
+    let csv = api::evaluations_csv(evaluations).await?;
+
+    match out {
+        Some(path) => {
+            fs::write(path, csv)?;
+            println!("[I] Saved csv to {}", path.display().to_string().cyan());
+        }
+        None => print!("{csv}"),
+    }
+
+    Ok(())
+}
+
+/// One row per master with the columns and order of the options, explanations are markdown,
+/// rendered for the terminal unless plain
+pub fn render_evaluation(evaluation: &Evaluation, options: &TableOptions) -> String {
+    let mut master_analyses: Vec<_> = evaluation.master_analyses.iter().collect();
+    master_analyses.sort_by_key(|(master, _)| master.get_message().unwrap_or_default());
+    // 单次评估中股票代码相同，按股票代码排序时退化为按大师排序
+    if options.sort_by == SortBy::Rating {
+        master_analyses
+            .sort_by_key(|(_, master_analysis)| std::cmp::Reverse(master_analysis.rating));
+    }
+
+    let mut table_data: Vec<Vec<String>> = vec![
+        options
+            .columns
+            .iter()
+            .map(|column| match column {
+                TableColumn::Master => "Master",
+                TableColumn::Prospect => "Prospect",
+                TableColumn::Explanation => "Explanation",
+                TableColumn::Risks => "Risks",
+                TableColumn::Catalysts => "Catalysts",
+            })
+            .map(|title| title.to_string())
+            .collect(),
+    ];
+    for (master, master_analysis) in master_analyses {
+        let prospect_symbol = prospect_symbol(&master_analysis.prospect);
+        let mut prospect = format!("{prospect_symbol} ({})", master_analysis.rating);
+        if master_analysis.validation == Some(api::ValidationStatus::Inconsistent) {
+            prospect.push_str(" !");
+        }
+        if let Some(action) = &master_analysis.action {
+            prospect.push_str(&format!(" {}", action.to_string().to_uppercase()));
+        }
+        if let Some(horizon) = &master_analysis.horizon {
+            prospect.push_str(&format!(" {horizon}"));
+        }
+        if let Some(target_price) = &master_analysis.target_price {
+            prospect.push_str(&format!(
+                "\n{:.2} ~ {:.2}",
+                target_price.low, target_price.high
+            ));
+        }
+        if let Some(completeness) = master_analysis.completeness.filter(|c| *c < 1.0) {
+            prospect.push_str(&format!("\nData {:.0}%", completeness * 100.0));
+        }
+
+        table_data.push(
+            options
+                .columns
+                .iter()
+                .map(|column| match column {
+                    TableColumn::Master => master.get_message().unwrap_or_default().to_string(),
+                    TableColumn::Prospect => prospect.to_string(),
+                    TableColumn::Explanation => match &master_analysis.details {
+                        Some(details) => format!(
+                            "{}\n\n{}",
+                            render_markdown(&master_analysis.explanation, options.plain),
+                            render_details(details).join("\n")
+                        ),
+                        None => render_markdown(&master_analysis.explanation, options.plain),
+                    },
+                    TableColumn::Risks => render_bullets(&master_analysis.risks),
+                    TableColumn::Catalysts => render_bullets(&master_analysis.catalysts),
+                })
+                .collect(),
+        );
+    }
+
+    if let Some(rating_avg) = evaluation.rating_avg() {
+        let prospect_symbol = prospect_symbol(&Prospect::from_rating(rating_avg));
+        let prospect = format!("{prospect_symbol} ({rating_avg})");
+
+        table_data.push(
+            options
+                .columns
+                .iter()
+                .map(|column| match column {
+                    TableColumn::Master => "AVG".to_string(),
+                    TableColumn::Prospect => prospect.to_string(),
+                    _ => "".to_string(),
+                })
+                .collect(),
+        );
+    }
+
+    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+    table.modify(Columns::first(), highlight());
+    table.with((
+        Width::wrap(Percent(30)).priority(Priority::max(true)),
+        Width::increase(Percent(30)).priority(Priority::min(true)),
+    ));
+
+    table.to_string()
+}
+
+fn render_reasoning(evaluation: &Evaluation) -> String {
+    let mut sections: Vec<String> = vec![];
+    for (master, master_analysis) in &evaluation.master_analyses {
+        if let Some(reasoning) = &master_analysis.reasoning {
+            sections.push(format!(
+                "{}\n{}",
+                format!("[{} Reasoning]", master.get_message().unwrap_or_default()).green(),
+                reasoning.trim().bright_black()
+            ));
+        }
+    }
+
+    if sections.is_empty() {
+        "[I] No reasoning returned by the model".to_string()
+    } else {
+        sections.join("\n\n")
+    }
+}
+
+fn render_recent_developments(recent_developments: &RecentDevelopments, plain: bool) -> String {
+    let developments: Vec<String> = recent_developments
+        .developments
+        .iter()
+        .map(|development| {
+            format!(
+                "{} {}: {} {}",
+                development.date,
+                development.title,
+                development.detail,
+                development.url.bright_black()
+            )
+        })
+        .collect();
+
+    format!(
+        "{} {}\n{}",
+        "[Recent Developments]".green(),
+        render_markdown(&recent_developments.summary, plain),
+        render_bullets(&developments)
+    )
+}
+
+fn render_bear_case(bear_case: &BearCase, plain: bool) -> String {
+    format!(
+        "{} {}\n{}",
+        "[Bear Case]".green(),
+        render_markdown(&bear_case.thesis, plain),
+        render_bullets(&bear_case.arguments)
+    )
+}
+
+fn render_failure_modes(failure_modes: &[FailureMode]) -> String {
+    let mut table_data: Vec<Vec<String>> = vec![vec![
+        "Failure Mode".to_string(),
+        "Likelihood".to_string(),
+        "Warning Sign".to_string(),
+    ]];
+    for failure_mode in failure_modes {
+        table_data.push(vec![
+            failure_mode.cause.to_string(),
+            failure_mode.likelihood.to_string(),
+            failure_mode.warning_sign.to_string(),
+        ]);
+    }
+
+    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+    table.modify(Columns::first(), highlight());
+    table.with(Width::wrap(Percent(100)).priority(Priority::max(true)));
+
+    format!("{}\n{table}", "[Pre-mortem]".green())
+}
+
+fn render_index_evaluation(index_evaluation: &IndexEvaluation, plain: bool) -> String {
+    let fundamentals = &index_evaluation.fundamentals;
+    let table_data: Vec<Vec<String>> = vec![
+        vec![
+            "Constituents".to_string(),
+            format!(
+                "{} ({} of weight)",
+                fundamentals.constituents_count,
+                format_percent(Some(fundamentals.weight_coverage))
+            ),
+        ],
+        vec!["PE TTM".to_string(), format_multiple(fundamentals.pe_ttm)],
+        vec!["PB".to_string(), format_multiple(fundamentals.pb)],
+        vec![
+            "ROE".to_string(),
+            format_percent(fundamentals.return_on_equity),
+        ],
+        vec![
+            "Revenue Growth".to_string(),
+            format_percent(fundamentals.revenue_growth),
+        ],
+        vec![
+            "Bond Yield 10Y".to_string(),
+            format_percent(fundamentals.bond_yield_10y),
+        ],
+        vec![
+            "Equity Risk Premium".to_string(),
+            format_percent(fundamentals.equity_risk_premium),
+        ],
+        vec![
+            "Drawdown".to_string(),
+            format_percent(
+                index_evaluation
+                    .price_range
+                    .as_ref()
+                    .map(|price_range| price_range.drawdown),
+            ),
+        ],
+    ];
+
+    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+    table.modify(Columns::first(), highlight());
+
+    let analysis = &index_evaluation.analysis;
+    format!(
+        "{table}\n{} ({}) {}",
+        prospect_symbol(&analysis.prospect),
+        analysis.rating,
+        render_markdown(&analysis.explanation, plain)
+    )
+}
+
+fn render_markdown(s: &str, plain: bool) -> String {
+    if plain {
+        s.to_string()
+    } else {
+        utils::markdown::render_terminal(s)
+    }
+}
+
+fn render_details(details: &Value) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    if let Some(object) = details.as_object() {
+        for (key, value) in object {
+            if let Some(number) = value.as_f64() {
+                lines.push(format!("{key}: {number:.2}"));
+            } else if value.is_object() {
+                lines.extend(render_details(value));
+            }
+        }
+    }
+
+    lines
+}
+
+fn render_bullets(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("• {item}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn render_price_summary(price_summary: &PriceSummary) -> String {
+    let mut s = format!("Price {:.2}", price_summary.price);
+
+    if let (Some(change), Some(change_rate)) = (price_summary.change, price_summary.change_rate) {
+        let change = format!("{change:+.2} ({:+.2}%)", change_rate * 100.0);
+        s.push_str(&format!(
+            " {}",
+            if change_rate >= 0.0 {
+                change.red()
+            } else {
+                change.green()
+            }
+        ));
+    }
+
+    if !price_summary.is_quote {
+        s.push_str(" [close]");
+    }
+
+    if let (Some(low_52w), Some(high_52w)) = (price_summary.low_52w, price_summary.high_52w) {
+        s.push_str(&format!("  52W {low_52w:.2} ~ {high_52w:.2}"));
+    }
+
+    s
+}
+
+pub fn prospect_symbol(prospect: &Prospect) -> &'static str {
+    match prospect {
+        Prospect::Bullish => "↑",
+        Prospect::Bearish => "↓",
+        Prospect::Neutral => "-",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::{Cli, cli::Commands, expand_master_shorthand};
+
+    fn parse(args: &[&str]) -> EvaluateCommand {
+        let args = expand_master_shorthand(args.iter().map(|arg| arg.to_string()));
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Evaluate(cmd) => *cmd,
+            _ => panic!("Not the evaluate command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_masters() {
+        let cmd = parse(&["invmst", "evaluate", "600900", "-m", "@value"]);
+        assert_eq!(cmd.masters, vec!["@value"]);
+
+        let cmd = parse(&[
+            "invmst", "evaluate", "600900", "--master", "@value", "@buffett",
+        ]);
+        assert_eq!(cmd.masters, vec!["@value", "buffett"]);
+
+        let cmd = parse(&["invmst", "evaluate", "600900", "@value"]);
+        assert_eq!(cmd.masters, vec!["@value"]);
+    }
+
+    #[test]
+    fn test_parse_exclude_masters() {
+        let cmd = parse(&["invmst", "evaluate", "600900", "-x", "@macro"]);
+        assert_eq!(cmd.exclude_masters, vec!["@macro"]);
+        assert!(cmd.masters.is_empty());
+
+        let cmd = parse(&[
+            "invmst",
+            "evaluate",
+            "600900",
+            "@value",
+            "--exclude-master",
+            "@contrarian",
+        ]);
+        assert_eq!(cmd.masters, vec!["@value"]);
+        assert_eq!(cmd.exclude_masters, vec!["@contrarian"]);
+    }
+}
@@ -0,0 +1,27 @@
+use clap::Subcommand;
+
+mod diff;
+mod list;
+
+#[derive(Subcommand)]
+pub enum HistoryCommand {
+    #[command(about = "Compare two evaluations of the same ticker")]
+    Diff(Box<diff::HistoryDiffCommand>),
+
+    #[command(about = "List saved evaluations")]
+    #[clap(visible_aliases = &["ls"])]
+    List(Box<list::HistoryListCommand>),
+}
+
+impl HistoryCommand {
+    pub async fn exec(&self) {
+        match self {
+            HistoryCommand::Diff(cmd) => {
+                cmd.exec().await;
+            }
+            HistoryCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
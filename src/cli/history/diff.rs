@@ -0,0 +1,87 @@
+use colored::Colorize;
+use invmst::api;
+use strum::EnumMessage;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct HistoryDiffCommand {
+    #[arg(help = "ID of an evaluation")]
+    id_a: String,
+
+    #[arg(help = "ID of another evaluation of the same ticker")]
+    id_b: String,
+}
+
+impl HistoryDiffCommand {
+    pub async fn exec(&self) {
+        match api::history_diff(&self.id_a, &self.id_b).await {
+            Ok(diff) => {
+                println!(
+                    "[{}] {} -> {}",
+                    diff.ticker.cyan(),
+                    diff.from.date,
+                    diff.to.date
+                );
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Master".to_string(),
+                    diff.from.id.to_string(),
+                    diff.to.id.to_string(),
+                    "Δ".to_string(),
+                ]];
+                for master_diff in &diff.masters {
+                    let format_rating =
+                        |rating: Option<u64>| rating.map(|r| r.to_string()).unwrap_or_default();
+
+                    table_data.push(vec![
+                        master_diff
+                            .master
+                            .get_message()
+                            .unwrap_or_default()
+                            .to_string(),
+                        format_rating(master_diff.rating_from),
+                        format_rating(master_diff.rating_to),
+                        match master_diff.rating_delta() {
+                            Some(delta) if delta > 0 => format!("{delta:+}").red().to_string(),
+                            Some(delta) if delta < 0 => format!("{delta:+}").green().to_string(),
+                            Some(delta) => delta.to_string(),
+                            None => "".to_string(),
+                        },
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+
+                for master_diff in &diff.masters {
+                    if master_diff.assessments_added.is_empty()
+                        && master_diff.assessments_removed.is_empty()
+                    {
+                        continue;
+                    }
+
+                    println!(
+                        "\n{}",
+                        master_diff.master.get_message().unwrap_or_default().cyan()
+                    );
+                    for assessment in &master_diff.assessments_added {
+                        println!("  + {assessment}");
+                    }
+                    for assessment in &master_diff.assessments_removed {
+                        println!("  - {}", assessment.dimmed());
+                    }
+                }
+
+                if let Some(narrative) = &diff.narrative {
+                    println!("\n{narrative}");
+                }
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
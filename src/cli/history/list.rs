@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use colored::Colorize;
+use invmst::{api, api::Prospect};
+use tabled::settings::object::Columns;
+
+use crate::cli::{
+    Outcome,
+    evaluate::{is_csv_format, output_csv, prospect_symbol},
+    highlight, set_outcome,
+};
+
+#[derive(clap::Args)]
+pub struct HistoryListCommand {
+    #[arg(help = "Only list evaluations of the ticker, e.g. 600900")]
+    ticker: Option<String>,
+
+    #[arg(
+        short = 'f',
+        long = "format",
+        help = "Output format, table or csv with a row per master of each evaluation, the default value is from config or table"
+    )]
+    format: Option<String>,
+
+    #[arg(
+        short = 'o',
+        long = "out",
+        help = "File to write the csv output to instead of stdout, e.g. -o history.csv"
+    )]
+    out: Option<PathBuf>,
+}
+
+impl HistoryListCommand {
+    pub async fn exec(&self) {
+        let Some(csv) = is_csv_format(self.format.as_deref()).await else {
+            return;
+        };
+
+        match api::history_list(self.ticker.as_deref()).await {
+            Ok(evaluations) => {
+                if csv {
+                    if let Err(err) = output_csv(&evaluations, self.out.as_deref()).await {
+                        println!("{}", err.to_string().red());
+                        set_outcome(Outcome::from(&err));
+                    }
+                    return;
+                }
+
+                if evaluations.is_empty() {
+                    println!("No saved evaluations");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "ID".to_string(),
+                    "Ticker".to_string(),
+                    "Date".to_string(),
+                    "Masters".to_string(),
+                    "AVG".to_string(),
+                ]];
+                for evaluation in evaluations {
+                    let rating_avg = evaluation
+                        .rating_avg()
+                        .map(|rating_avg| {
+                            format!(
+                                "{} ({rating_avg})",
+                                prospect_symbol(&Prospect::from_rating(rating_avg))
+                            )
+                        })
+                        .unwrap_or_default();
+
+                    table_data.push(vec![
+                        evaluation.id.to_string(),
+                        evaluation.ticker.to_string(),
+                        evaluation.date.to_string(),
+                        evaluation.master_analyses.len().to_string(),
+                        rating_avg,
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
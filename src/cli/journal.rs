@@ -0,0 +1,42 @@
+use clap::Subcommand;
+
+mod add;
+mod list;
+mod remove;
+mod review;
+
+#[derive(Subcommand)]
+pub enum JournalCommand {
+    #[command(about = "Record a trade decision")]
+    Add(Box<add::JournalAddCommand>),
+
+    #[command(about = "List recorded trade decisions")]
+    #[clap(visible_aliases = &["ls"])]
+    List(Box<list::JournalListCommand>),
+
+    #[command(about = "Remove a recorded trade decision")]
+    #[clap(visible_aliases = &["rm"])]
+    Remove(Box<remove::JournalRemoveCommand>),
+
+    #[command(about = "Review trade decisions against subsequent development")]
+    Review(Box<review::JournalReviewCommand>),
+}
+
+impl JournalCommand {
+    pub async fn exec(&self) {
+        match self {
+            JournalCommand::Add(cmd) => {
+                cmd.exec().await;
+            }
+            JournalCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            JournalCommand::Remove(cmd) => {
+                cmd.exec().await;
+            }
+            JournalCommand::Review(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::Local;
+use colored::Colorize;
+use invmst::{api, utils};
+
+#[derive(clap::Args)]
+pub struct JournalAddCommand {
+    #[arg(short = 'a', long = "action", help = "Trade action, buy or sell")]
+    action: String,
+
+    #[arg(
+        short = 'd',
+        long = "date",
+        help = "The date of the trade, the default value is today, e.g. -d 2025-01-01"
+    )]
+    date: Option<String>,
+
+    #[arg(
+        short = 'e',
+        long = "evaluation",
+        help = "ID of the evaluation that motivated the trade, see `invmst history list`"
+    )]
+    evaluation: Option<String>,
+
+    #[arg(
+        short = 'p',
+        long = "price",
+        help = "Trade price, the default value is the close price of the date"
+    )]
+    price: Option<f64>,
+
+    #[arg(short = 's', long = "shares", help = "Number of shares")]
+    shares: f64,
+
+    #[arg(help = "Ticker of the trade, e.g. 600900")]
+    ticker: String,
+}
+
+impl JournalAddCommand {
+    pub async fn exec(&self) {
+        let date = if let Some(date_str) = &self.date {
+            let parsed_date = utils::datetime::date_from_str(date_str);
+            if parsed_date.is_none() {
+                println!(
+                    "Can not parse '{}' as date, try format like '{}'",
+                    date_str.yellow(),
+                    Local::now()
+                        .date_naive()
+                        .format("%Y-%m-%d")
+                        .to_string()
+                        .green()
+                );
+                return;
+            }
+
+            parsed_date
+        } else {
+            None
+        };
+
+        match api::journal_add(
+            &self.ticker,
+            &self.action,
+            self.shares,
+            self.price,
+            date,
+            self.evaluation.as_deref(),
+        )
+        .await
+        {
+            Ok(entry) => {
+                println!(
+                    "Journal entry {} has been added: {} {} {} @ {}",
+                    entry.id.to_string().cyan(),
+                    entry.action,
+                    entry.shares,
+                    entry.ticker,
+                    entry.price
+                );
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
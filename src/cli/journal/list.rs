@@ -0,0 +1,49 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct JournalListCommand;
+
+impl JournalListCommand {
+    pub async fn exec(&self) {
+        match api::journal_list().await {
+            Ok(entries) => {
+                if entries.is_empty() {
+                    println!("No journal entries");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "ID".to_string(),
+                    "Date".to_string(),
+                    "Ticker".to_string(),
+                    "Action".to_string(),
+                    "Shares".to_string(),
+                    "Price".to_string(),
+                    "Evaluation".to_string(),
+                ]];
+                for entry in entries {
+                    table_data.push(vec![
+                        entry.id.to_string(),
+                        entry.date.to_string(),
+                        entry.ticker.to_string(),
+                        entry.action.to_string(),
+                        entry.shares.to_string(),
+                        entry.price.to_string(),
+                        entry.evaluation_id.unwrap_or_default(),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
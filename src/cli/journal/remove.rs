@@ -0,0 +1,18 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct JournalRemoveCommand {
+    #[arg(help = "ID of the journal entry")]
+    id: u64,
+}
+
+impl JournalRemoveCommand {
+    pub async fn exec(&self) {
+        if let Err(err) = api::journal_remove(self.id).await {
+            println!("{}", err.to_string().red());
+        } else {
+            println!("Journal entry {} has been removed", self.id);
+        }
+    }
+}
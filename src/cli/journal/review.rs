@@ -0,0 +1,87 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::{evaluate::prospect_symbol, highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct JournalReviewCommand;
+
+impl JournalReviewCommand {
+    pub async fn exec(&self) {
+        let spinner = new_spinner();
+
+        match api::journal_review().await {
+            Ok(reviews) => {
+                spinner.finish_and_clear();
+
+                if reviews.is_empty() {
+                    println!("No journal entries");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "ID".to_string(),
+                    "Date".to_string(),
+                    "Ticker".to_string(),
+                    "Action".to_string(),
+                    "Thesis".to_string(),
+                    "Price".to_string(),
+                    "PE(TTM)".to_string(),
+                    "Return".to_string(),
+                ]];
+                for review in reviews {
+                    let thesis = review
+                        .thesis
+                        .map(|(prospect, rating)| {
+                            format!("{} ({rating})", prospect_symbol(&prospect))
+                        })
+                        .unwrap_or_default();
+
+                    let price = format!(
+                        "{} → {}",
+                        review.entry.price,
+                        format_option(review.price_current)
+                    );
+                    let pe = format!(
+                        "{} → {}",
+                        format_option(review.pe_at_entry),
+                        format_option(review.pe_current)
+                    );
+
+                    let return_rate = match review.return_rate {
+                        Some(return_rate) if return_rate >= 0.0 => {
+                            format!("{:.2}%", return_rate * 100.0).green().to_string()
+                        }
+                        Some(return_rate) => {
+                            format!("{:.2}%", return_rate * 100.0).red().to_string()
+                        }
+                        None => "".to_string(),
+                    };
+
+                    table_data.push(vec![
+                        review.entry.id.to_string(),
+                        review.entry.date.to_string(),
+                        review.entry.ticker.to_string(),
+                        review.entry.action.to_string(),
+                        thesis,
+                        price,
+                        pe,
+                        return_rate,
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+            }
+        }
+    }
+}
+
+fn format_option(value: Option<f64>) -> String {
+    value.map(|v| format!("{v:.2}")).unwrap_or("?".to_string())
+}
@@ -1,14 +1,22 @@
 use colored::Colorize;
 use invmst::{VecOptions, api};
 
-use crate::cli;
+use tabled::settings::object::Columns;
+
+use crate::{cli, cli::highlight};
 
 #[derive(clap::Args)]
 pub struct LlmConfigCommand {
+    #[arg(
+        long = "fallback",
+        help = "Append as a fallback provider, tried in order when the previous ones fail"
+    )]
+    fallback: bool,
+
     #[arg(
         short = 'O',
         long = "option",
-        help = "LLM provider's option, e.g. -O base_url:https://api.openai.com/v1 -O api_key:sk-xxx -O model:gpt-3.5-turbo"
+        help = "LLM provider's option, ${VAR} is read from environment variable, e.g. -O base_url:https://api.openai.com/v1 -O api_key:sk-xxx -O model:gpt-3.5-turbo -O context_window:128000"
     )]
     options: Vec<String>,
 
@@ -19,6 +27,12 @@ pub struct LlmConfigCommand {
     )]
     protocol: Option<String>,
 
+    #[arg(
+        long = "show",
+        help = "Print the effective config with the api_key masked instead of changing it"
+    )]
+    show: bool,
+
     #[arg(
         short = 't',
         long = "type",
@@ -45,12 +59,35 @@ impl LlmConfigCommand {
             return;
         }
 
+        if self.show {
+            match api::llm_config_show(r#type).await {
+                Ok(items) => {
+                    let table_data: Vec<Vec<String>> = items
+                        .into_iter()
+                        .map(|(key, value)| vec![key, value])
+                        .collect();
+
+                    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                    table.modify(Columns::first(), highlight());
+                    println!("{table}");
+                }
+                Err(err) => {
+                    println!("{}", err.to_string().red());
+                }
+            }
+            return;
+        }
+
         let options_map = VecOptions(&self.options).into_map();
 
-        if let Err(err) = api::llm_config(r#type, protocol, &options_map).await {
+        if let Err(err) = api::llm_config(r#type, protocol, &options_map, self.fallback).await {
             println!("{}", err.to_string().red());
         } else {
-            println!("LLM for '{type}' has been configured");
+            if self.fallback {
+                println!("Fallback LLM for '{type}' has been added");
+            } else {
+                println!("LLM for '{type}' has been configured");
+            }
         }
     }
 }
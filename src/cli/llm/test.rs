@@ -14,7 +14,7 @@ pub struct LlmTestCommand {
     #[arg(
         short = 'L',
         long = "llm-option",
-        help = "Additional option passed to LLM, e.g. -L temperature:0.6"
+        help = "Additional option passed to LLM, e.g. -L temperature:0.6 -L model:gpt-4o -L think:true"
     )]
     llm_options: Vec<String>,
 
@@ -36,13 +36,15 @@ impl LlmTestCommand {
             return;
         }
 
-        let mut chat_completion_options = ChatCompletionOptions::default();
-        let llm_options = VecOptions(&self.llm_options);
-        if let Some(temperature_str) = llm_options.get("temperature") {
-            if let Ok(temperature) = temperature_str.parse() {
-                chat_completion_options = chat_completion_options.with_temperature(temperature);
+        let chat_completion_options = match ChatCompletionOptions::default()
+            .with_overrides(&VecOptions(&self.llm_options).into_map())
+        {
+            Ok(chat_completion_options) => chat_completion_options,
+            Err(err) => {
+                println!("{}", err.to_string().red());
+                return;
             }
-        }
+        };
 
         let prompt = self.prompt.clone();
 
@@ -78,6 +80,8 @@ impl LlmTestCommand {
                             print!("{}", delta.bright_black());
                             stdout().flush().unwrap();
                         }
+                        // 这里不提供工具，不会收到工具调用
+                        ChatCompletionEvent::ToolCall(_) => {}
                         ChatCompletionEvent::Error(err) => {
                             println!("{}", err.to_string().red());
                             break;
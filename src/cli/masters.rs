@@ -1,23 +1,87 @@
+use colored::Colorize;
 use invmst::api;
 use strum::EnumMessage;
-use tabled::settings::{Color, object::Columns};
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
 
 #[derive(clap::Args)]
-pub struct MastersCommand;
+pub struct MastersCommand {
+    #[arg(
+        short = 'd',
+        long = "detail",
+        help = "Show the philosophy, data inputs and settings of a master, e.g. -d buffett"
+    )]
+    detail: Option<String>,
+
+    #[arg(
+        short = 't',
+        long = "tag",
+        help = "Only show masters of the style, e.g. -t value, styles are value/growth/macro/technical/quant/contrarian"
+    )]
+    tag: Option<api::MasterStyle>,
+}
 
 impl MastersCommand {
     pub async fn exec(&self) {
+        if let Some(name) = &self.detail {
+            match api::master_detail(name).await {
+                Ok(detail) => {
+                    let table_data: Vec<Vec<String>> = vec![
+                        vec!["Name".to_string(), detail.name],
+                        vec!["Keys".to_string(), detail.keys.join("/")],
+                        vec!["Styles".to_string(), join_styles(&detail.styles)],
+                        vec!["Philosophy".to_string(), detail.philosophy],
+                        vec!["Inputs".to_string(), detail.inputs.join(", ")],
+                        vec![
+                            "Settings".to_string(),
+                            detail
+                                .settings
+                                .iter()
+                                .map(|(key, value)| format!("{key} = {value}"))
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        ],
+                    ];
+
+                    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                    table.modify(Columns::first(), highlight());
+                    println!("{table}");
+                }
+                Err(err) => {
+                    println!("{}", err.to_string().red());
+                }
+            }
+
+            return;
+        }
+
         let mut table_data: Vec<Vec<String>> = vec![];
 
         let masters = api::masters().await;
         for master in masters {
+            let styles = master.styles();
+            if let Some(tag) = &self.tag {
+                if !styles.contains(tag) {
+                    continue;
+                }
+            }
+
             let name = master.get_message().unwrap_or_default().to_string();
             let keys = master.get_serializations().join("/");
-            table_data.push(vec![name, keys]);
+            table_data.push(vec![name, keys, join_styles(&styles)]);
         }
 
         let mut table = tabled::builder::Builder::from_iter(&table_data).build();
-        table.modify(Columns::first(), Color::FG_CYAN);
+        table.modify(Columns::first(), highlight());
         println!("{table}");
     }
 }
+
+fn join_styles(styles: &[api::MasterStyle]) -> String {
+    styles
+        .iter()
+        .map(|style| style.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
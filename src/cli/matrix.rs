@@ -0,0 +1,67 @@
+use colored::Colorize;
+use invmst::api;
+use strum::EnumMessage;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct MatrixCommand {
+    #[arg(
+        short = 'n',
+        long = "dates",
+        default_value_t = 8,
+        help = "Number of the latest evaluation dates to show, 0 for all"
+    )]
+    dates_limit: usize,
+
+    #[arg(help = "Ticker whose saved evaluations to compare, e.g. 600900")]
+    ticker: String,
+}
+
+impl MatrixCommand {
+    pub async fn exec(&self) {
+        let dates_limit = (self.dates_limit > 0).then_some(self.dates_limit);
+        match api::history_matrix(&self.ticker, dates_limit).await {
+            Ok(matrix) => {
+                if matrix.dates.is_empty() {
+                    println!("No saved evaluations of {}", matrix.ticker.cyan());
+                    return;
+                }
+
+                println!("[{}]", matrix.ticker.cyan());
+
+                let mut header = vec!["Master".to_string()];
+                header.extend(matrix.dates.iter().map(|date| date.to_string()));
+                let mut table_data: Vec<Vec<String>> = vec![header];
+                for row in &matrix.rows {
+                    let mut cells = vec![row.master.get_message().unwrap_or_default().to_string()];
+                    for cell in &row.cells {
+                        cells.push(match cell {
+                            // 与 history diff 一致，上调为红，下调为绿
+                            Some(cell) if cell.is_notable() => {
+                                let delta = cell.delta.unwrap_or_default();
+                                let text = format!("{} ({delta:+})", cell.rating);
+                                if delta > 0 {
+                                    text.red().to_string()
+                                } else {
+                                    text.green().to_string()
+                                }
+                            }
+                            Some(cell) => cell.rating.to_string(),
+                            None => "".to_string(),
+                        });
+                    }
+                    table_data.push(cells);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
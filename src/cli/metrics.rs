@@ -0,0 +1,202 @@
+use chrono::Local;
+use colored::Colorize;
+use invmst::{api, utils};
+use tabled::settings::object::Columns;
+
+use crate::cli::{highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct MetricsCommand {
+    #[arg(
+        short = 'b',
+        long = "benchmark",
+        default_value = "sh000300",
+        help = "Benchmark index to compute beta against"
+    )]
+    benchmark: String,
+
+    #[arg(
+        short = 'd',
+        long = "date",
+        help = "The date to compute metrics, e.g. -d 2022-01-01"
+    )]
+    date: Option<String>,
+
+    #[arg(
+        long = "rs",
+        num_args = 0..=1,
+        default_missing_value = "sh000300",
+        help = "Rank relative strength against the heaviest constituents of an index, sh000300 if no index is given"
+    )]
+    rs_universe: Option<String>,
+
+    #[arg(help = "Ticker to compute metrics, e.g. 600900")]
+    ticker: String,
+}
+
+impl MetricsCommand {
+    pub async fn exec(&self) {
+        let date = if let Some(date_str) = &self.date {
+            let parsed_date = utils::datetime::date_from_str(date_str);
+            if parsed_date.is_none() {
+                println!(
+                    "Can not parse '{}' as date, try format like '{}'",
+                    date_str.yellow(),
+                    Local::now()
+                        .date_naive()
+                        .format("%Y-%m-%d")
+                        .to_string()
+                        .green()
+                );
+                return;
+            }
+
+            parsed_date
+        } else {
+            None
+        };
+
+        let options = api::MetricsOptions {
+            date,
+            benchmark: Some(self.benchmark.clone()),
+            rs_universe: self.rs_universe.clone(),
+        };
+
+        let spinner = new_spinner();
+        spinner.set_message(format!("[{}]", self.ticker.cyan()));
+
+        match api::metrics(&self.ticker, &options).await {
+            Ok(metrics) => {
+                spinner.finish_with_message(format!(
+                    "[{}] {}",
+                    metrics.ticker.cyan(),
+                    metrics.date
+                ));
+
+                let momentum = &metrics.momentum;
+                let risk_stats = &metrics.risk_stats;
+                let enterprise_value = &metrics.enterprise_value;
+                let trends = &metrics.trends;
+                let table_data: Vec<Vec<String>> = vec![
+                    vec!["Return 1M".to_string(), format_percent(momentum.return_1m)],
+                    vec!["Return 3M".to_string(), format_percent(momentum.return_3m)],
+                    vec!["Return 6M".to_string(), format_percent(momentum.return_6m)],
+                    vec![
+                        "Return 12M".to_string(),
+                        format_percent(momentum.return_12m),
+                    ],
+                    vec![
+                        "Return 12M ex 1M".to_string(),
+                        format_percent(momentum.return_12m_ex_1m),
+                    ],
+                    vec!["RS Score".to_string(), format_percent(momentum.rs_score)],
+                    vec![
+                        "RS Rank".to_string(),
+                        momentum
+                            .rs_rank
+                            .map(|v| format!("{v:.0}"))
+                            .unwrap_or("-".to_string()),
+                    ],
+                    vec![
+                        "Volatility".to_string(),
+                        format_percent(risk_stats.volatility),
+                    ],
+                    vec![
+                        "Downside Deviation".to_string(),
+                        format_percent(risk_stats.downside_deviation),
+                    ],
+                    vec![
+                        format!("Beta ({})", self.benchmark),
+                        risk_stats
+                            .beta
+                            .map(|v| format!("{v:.2}"))
+                            .unwrap_or("-".to_string()),
+                    ],
+                    vec![
+                        "Max Drawdown".to_string(),
+                        format_percent(risk_stats.max_drawdown),
+                    ],
+                    vec![
+                        "Ulcer Index".to_string(),
+                        format_percent(risk_stats.ulcer_index),
+                    ],
+                    vec![
+                        "Recovery Days".to_string(),
+                        risk_stats
+                            .recovery_days
+                            .map(|v| format!("{v:.0}"))
+                            .unwrap_or("-".to_string()),
+                    ],
+                    vec![
+                        "Enterprise Value".to_string(),
+                        format_amount(enterprise_value.enterprise_value),
+                    ],
+                    vec![
+                        "EV/EBIT".to_string(),
+                        format_multiple(enterprise_value.ev_to_ebit),
+                    ],
+                    vec![
+                        "EV/EBITDA".to_string(),
+                        format_multiple(enterprise_value.ev_to_ebitda),
+                    ],
+                    vec![
+                        "Price 1Y".to_string(),
+                        if trends.prices.is_empty() {
+                            "-".to_string()
+                        } else {
+                            utils::chart::sparkline(&trends.prices, SPARKLINE_WIDTH)
+                        },
+                    ],
+                    vec![
+                        "ROE by Year".to_string(),
+                        format_annual_trend(&trends.return_on_equity),
+                    ],
+                    vec![
+                        "Revenue Growth by Year".to_string(),
+                        format_annual_trend(&trends.revenue_growth),
+                    ],
+                ];
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                spinner.finish_with_message(format!("[{}] {}", self.ticker, err.to_string().red()));
+            }
+        }
+    }
+}
+
+pub fn format_percent(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:.2}%", v * 100.0))
+        .unwrap_or("-".to_string())
+}
+
+pub fn format_amount(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:.2}B", v / 1e9))
+        .unwrap_or("-".to_string())
+}
+
+/// Sparkline of the yearly values with the range of years and the latest value
+fn format_annual_trend(values: &[(i32, f64)]) -> String {
+    match (values.first(), values.last()) {
+        (Some((first_year, _)), Some((last_year, latest))) => format!(
+            "{} {first_year}-{last_year} {}",
+            utils::chart::sparkline(
+                &values.iter().map(|(_, value)| *value).collect::<Vec<f64>>(),
+                SPARKLINE_WIDTH
+            ),
+            format_percent(Some(*latest))
+        ),
+        _ => "-".to_string(),
+    }
+}
+
+pub fn format_multiple(value: Option<f64>) -> String {
+    value.map(|v| format!("{v:.1}x")).unwrap_or("-".to_string())
+}
+
+static SPARKLINE_WIDTH: usize = 24;
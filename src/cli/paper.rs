@@ -0,0 +1,33 @@
+use clap::Subcommand;
+
+mod reset;
+mod status;
+mod step;
+
+#[derive(Subcommand)]
+pub enum PaperCommand {
+    #[command(about = "Reset the virtual portfolio with initial cash and trading rules")]
+    Reset(Box<reset::PaperResetCommand>),
+
+    #[command(about = "Show NAV, turnover and per-master attribution")]
+    Status(Box<status::PaperStatusCommand>),
+
+    #[command(about = "Evaluate tickers and trade on the consensus ratings")]
+    Step(Box<step::PaperStepCommand>),
+}
+
+impl PaperCommand {
+    pub async fn exec(&self) {
+        match self {
+            PaperCommand::Reset(cmd) => {
+                cmd.exec().await;
+            }
+            PaperCommand::Status(cmd) => {
+                cmd.exec().await;
+            }
+            PaperCommand::Step(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
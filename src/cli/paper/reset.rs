@@ -0,0 +1,59 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct PaperResetCommand {
+    #[arg(
+        long = "buy",
+        default_value_t = 75,
+        help = "Buy when the consensus rating is greater than or equal to this value"
+    )]
+    buy_rating: u64,
+
+    #[arg(
+        short = 'c',
+        long = "cash",
+        default_value_t = 1_000_000.0,
+        help = "Initial cash"
+    )]
+    cash: f64,
+
+    #[arg(
+        short = 'p',
+        long = "position",
+        default_value_t = 0.1,
+        help = "Fraction of NAV to allocate on each buy"
+    )]
+    position_ratio: f64,
+
+    #[arg(
+        long = "sell",
+        default_value_t = 40,
+        help = "Sell when the consensus rating is less than or equal to this value"
+    )]
+    sell_rating: u64,
+}
+
+impl PaperResetCommand {
+    pub async fn exec(&self) {
+        let rules = api::PaperRules {
+            buy_rating: self.buy_rating,
+            sell_rating: self.sell_rating,
+            position_ratio: self.position_ratio,
+        };
+
+        match api::paper_reset(self.cash, rules).await {
+            Ok(account) => {
+                println!(
+                    "Virtual portfolio has been reset with cash {}, buy ≥ {}, sell ≤ {}",
+                    account.cash.to_string().cyan(),
+                    account.rules.buy_rating,
+                    account.rules.sell_rating
+                );
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::{highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct PaperStatusCommand;
+
+impl PaperStatusCommand {
+    pub async fn exec(&self) {
+        let spinner = new_spinner();
+
+        match api::paper_report().await {
+            Ok(report) => {
+                spinner.finish_and_clear();
+
+                let return_rate = format!("{:.2}%", report.return_rate * 100.0);
+                println!(
+                    "NAV: {:.2}  Cash: {:.2}  Return: {}  Turnover: {:.2}",
+                    report.nav,
+                    report.account.cash,
+                    if report.return_rate >= 0.0 {
+                        return_rate.green()
+                    } else {
+                        return_rate.red()
+                    },
+                    report.turnover
+                );
+
+                if !report.account.positions.is_empty() {
+                    let mut table_data: Vec<Vec<String>> = vec![vec![
+                        "Ticker".to_string(),
+                        "Shares".to_string(),
+                        "Cost".to_string(),
+                        "Masters".to_string(),
+                    ]];
+                    for position in &report.account.positions {
+                        table_data.push(vec![
+                            position.ticker.to_string(),
+                            position.shares.to_string(),
+                            format!("{:.2}", position.cost),
+                            position.masters.join(", "),
+                        ]);
+                    }
+
+                    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                    table.modify(Columns::first(), highlight());
+                    println!("{table}");
+                }
+
+                if !report.attribution.is_empty() {
+                    let mut table_data: Vec<Vec<String>> =
+                        vec![vec!["Master".to_string(), "Profit".to_string()]];
+                    for (master, profit) in &report.attribution {
+                        table_data.push(vec![master.to_string(), format!("{profit:.2}")]);
+                    }
+
+                    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                    table.modify(Columns::first(), highlight());
+                    println!("{table}");
+                }
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::Local;
+use colored::Colorize;
+use invmst::{api, utils};
+use tabled::settings::object::Columns;
+
+use crate::cli::{Outcome, highlight, new_spinner, set_outcome};
+
+#[derive(clap::Args)]
+pub struct PaperStepCommand {
+    #[arg(
+        short = 'b',
+        long = "backward",
+        help = "Days to backward, the default value is from config or 1100"
+    )]
+    backward_days: Option<i64>,
+
+    #[arg(
+        short = 'd',
+        long = "date",
+        help = "The date to evaluate and trade, e.g. -d 2022-01-01"
+    )]
+    date: Option<String>,
+
+    #[arg(
+        short = 'm',
+        long = "master",
+        help = "Investment master, e.g. -m buffett -m graham"
+    )]
+    masters: Vec<String>,
+
+    #[arg(required = true, help = "Tickers to evaluate, e.g. 600900 00700")]
+    tickers: Vec<String>,
+}
+
+impl PaperStepCommand {
+    pub async fn exec(&self) {
+        let date = if let Some(date_str) = &self.date {
+            let parsed_date = utils::datetime::date_from_str(date_str);
+            if parsed_date.is_none() {
+                println!(
+                    "Can not parse '{}' as date, try format like '{}'",
+                    date_str.yellow(),
+                    Local::now()
+                        .date_naive()
+                        .format("%Y-%m-%d")
+                        .to_string()
+                        .green()
+                );
+                return;
+            }
+
+            parsed_date
+        } else {
+            None
+        };
+
+        let options = api::EvaluateOptions {
+            backward_days: self.backward_days,
+            date,
+            masters: self.masters.clone(),
+            exclude_masters: vec![],
+            monte_carlo: false,
+            holding: None,
+            bear_case: false,
+            pre_mortem: false,
+            agentic: false,
+            focus_areas: vec![],
+            note: None,
+            llm_options: api::ChatCompletionOptions::default(),
+        };
+
+        let spinner = new_spinner();
+        spinner.set_message("Evaluating");
+
+        match api::paper_step(&self.tickers, &options).await {
+            Ok(step) => {
+                spinner.finish_and_clear();
+
+                // 部分股票评估失败时仍交易其余股票，但以退出码提示不完整
+                for (ticker, err) in &step.errors {
+                    println!("[{}] {}", ticker, err.to_string().red());
+                }
+                if !step.errors.is_empty() {
+                    set_outcome(Outcome::Partial);
+                }
+
+                if step.trades.is_empty() {
+                    println!("No trades triggered");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Date".to_string(),
+                    "Ticker".to_string(),
+                    "Action".to_string(),
+                    "Shares".to_string(),
+                    "Price".to_string(),
+                    "Rating".to_string(),
+                ]];
+                for trade in step.trades {
+                    table_data.push(vec![
+                        trade.date.to_string(),
+                        trade.ticker.to_string(),
+                        trade.action.to_string(),
+                        trade.shares.to_string(),
+                        trade.price.to_string(),
+                        trade.rating.to_string(),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::single(1), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+                set_outcome(Outcome::from(&err));
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use clap::Subcommand;
+
+mod add;
+mod list;
+mod performance;
+mod remove;
+mod risk;
+mod show;
+
+#[derive(Subcommand)]
+pub enum PortfolioCommand {
+    #[command(about = "Add shares of a ticker to a portfolio, creating it if needed")]
+    Add(Box<add::PortfolioAddCommand>),
+
+    #[command(about = "List portfolios")]
+    #[clap(visible_aliases = &["ls"])]
+    List(Box<list::PortfolioListCommand>),
+
+    #[command(about = "Attribute performance against a benchmark to allocation and selection")]
+    Performance(Box<performance::PortfolioPerformanceCommand>),
+
+    #[command(about = "Remove a holding or a whole portfolio")]
+    #[clap(visible_aliases = &["rm"])]
+    Remove(Box<remove::PortfolioRemoveCommand>),
+
+    #[command(about = "Analyze correlation, concentration, beta and drawdown of a portfolio")]
+    Risk(Box<risk::PortfolioRiskCommand>),
+
+    #[command(about = "Show holdings with suggested position sizes")]
+    Show(Box<show::PortfolioShowCommand>),
+}
+
+impl PortfolioCommand {
+    pub async fn exec(&self) {
+        match self {
+            PortfolioCommand::Add(cmd) => {
+                cmd.exec().await;
+            }
+            PortfolioCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            PortfolioCommand::Performance(cmd) => {
+                cmd.exec().await;
+            }
+            PortfolioCommand::Remove(cmd) => {
+                cmd.exec().await;
+            }
+            PortfolioCommand::Risk(cmd) => {
+                cmd.exec().await;
+            }
+            PortfolioCommand::Show(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
+
+fn format_option<T>(value: Option<T>, format: impl Fn(T) -> String) -> String {
+    value.map(format).unwrap_or("-".to_string())
+}
+
+fn format_percent(value: f64) -> String {
+    format!("{:.1}%", value * 100.0)
+}
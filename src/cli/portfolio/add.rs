@@ -0,0 +1,31 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct PortfolioAddCommand {
+    #[arg(help = "Name of the portfolio")]
+    name: String,
+
+    #[arg(help = "Ticker of the holding, e.g. 600900")]
+    ticker: String,
+
+    #[arg(help = "Number of shares")]
+    shares: f64,
+}
+
+impl PortfolioAddCommand {
+    pub async fn exec(&self) {
+        match api::portfolio_add(&self.name, &self.ticker, self.shares).await {
+            Ok(portfolio) => {
+                println!(
+                    "Portfolio {} has {} holdings",
+                    portfolio.name.cyan(),
+                    portfolio.holdings.len()
+                );
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use super::format_option;
+
+use crate::cli::{highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct PortfolioListCommand {
+    #[arg(
+        short = 'i',
+        long = "income",
+        help = "Project dividend income of the next twelve months"
+    )]
+    income: bool,
+}
+
+impl PortfolioListCommand {
+    pub async fn exec(&self) {
+        match api::portfolio_list().await {
+            Ok(portfolios) => {
+                if portfolios.is_empty() {
+                    println!("No portfolios");
+                    return;
+                }
+
+                if self.income {
+                    for portfolio in portfolios {
+                        Self::print_income(&portfolio.name).await;
+                    }
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> =
+                    vec![vec!["Name".to_string(), "Holdings".to_string()]];
+                for portfolio in portfolios {
+                    table_data.push(vec![
+                        portfolio.name.to_string(),
+                        portfolio
+                            .holdings
+                            .iter()
+                            .map(|holding| format!("{} × {}", holding.ticker, holding.shares))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+
+    async fn print_income(name: &str) {
+        let spinner = new_spinner();
+        spinner.set_message(format!("[{}]", name.cyan()));
+
+        match api::portfolio_income(name).await {
+            Ok(incomes) => {
+                spinner.finish_with_message(format!("[{}]", name.cyan()));
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Ticker".to_string(),
+                    "Shares".to_string(),
+                    "DPS (TTM)".to_string(),
+                    "Growth".to_string(),
+                    "DPS (NTM)".to_string(),
+                    "Income (NTM)".to_string(),
+                    "Assumption".to_string(),
+                ]];
+                let mut income_total = 0.0;
+                for income in incomes {
+                    income_total += income.income;
+                    table_data.push(vec![
+                        income.holding.ticker.to_string(),
+                        income.holding.shares.to_string(),
+                        format!("{:.4}", income.projection.dividend_per_share_ttm),
+                        format_option(income.projection.growth, |v| format!("{:.1}%", v * 100.0)),
+                        format!("{:.4}", income.projection.dividend_per_share_ntm),
+                        format!("{:.2}", income.income),
+                        income.projection.assumption,
+                    ]);
+                }
+                table_data.push(vec![
+                    "Total".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    format!("{income_total:.2}"),
+                    "Amounts are in the trading currency of each ticker".to_string(),
+                ]);
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                spinner.finish_with_message(format!("[{}] {}", name, err.to_string().red()));
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use super::{format_option, format_percent};
+
+use crate::cli::{highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct PortfolioPerformanceCommand {
+    #[arg(
+        short = 'b',
+        long = "benchmark",
+        default_value = "sh000300",
+        help = "Benchmark index to compare with"
+    )]
+    benchmark: String,
+
+    #[arg(
+        short = 'd',
+        long = "days",
+        default_value_t = 365,
+        help = "Days of the period to track"
+    )]
+    days: i64,
+
+    #[arg(help = "Name of the portfolio")]
+    name: String,
+}
+
+impl PortfolioPerformanceCommand {
+    pub async fn exec(&self) {
+        let spinner = new_spinner();
+
+        match api::portfolio_performance(&self.name, &self.benchmark, self.days.abs()).await {
+            Ok(performance) => {
+                spinner.finish_and_clear();
+
+                println!(
+                    "{} ~ {}  Portfolio: {}  Benchmark ({}): {}  Excess: {}",
+                    performance.date_start,
+                    performance.date_end,
+                    format_percent(performance.portfolio_return).cyan(),
+                    performance.benchmark,
+                    format_option(performance.benchmark_return, format_percent),
+                    format_option(
+                        performance
+                            .benchmark_return
+                            .map(|r| performance.portfolio_return - r),
+                        format_percent
+                    )
+                );
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Ticker".to_string(),
+                    "Industry".to_string(),
+                    "Weight".to_string(),
+                    "Return".to_string(),
+                ]];
+                for holding in &performance.holdings {
+                    table_data.push(vec![
+                        holding.ticker.to_string(),
+                        holding.industry.clone().unwrap_or_default(),
+                        format_percent(holding.weight),
+                        format_percent(holding.return_rate),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Sector".to_string(),
+                    "Weight (P/B)".to_string(),
+                    "Return (P/B)".to_string(),
+                    "Allocation".to_string(),
+                    "Selection".to_string(),
+                ]];
+                for attribution in &performance.attributions {
+                    table_data.push(vec![
+                        attribution.sector.to_string(),
+                        format!(
+                            "{} / {}",
+                            format_option(attribution.portfolio.map(|p| p.weight), format_percent),
+                            format_option(attribution.benchmark.map(|b| b.weight), format_percent)
+                        ),
+                        format!(
+                            "{} / {}",
+                            format_option(
+                                attribution.portfolio.map(|p| p.return_rate),
+                                format_percent
+                            ),
+                            format_option(
+                                attribution.benchmark.map(|b| b.return_rate),
+                                format_percent
+                            )
+                        ),
+                        format_percent(attribution.allocation),
+                        format_percent(attribution.selection),
+                    ]);
+                }
+                table_data.push(vec![
+                    "Total".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    format_percent(performance.attributions.iter().map(|a| a.allocation).sum()),
+                    format_percent(performance.attributions.iter().map(|a| a.selection).sum()),
+                ]);
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+                println!(
+                    "[I] Attribution is against the heaviest constituents covering {} of the benchmark, which returned {}; returns of non-CNY holdings are converted to CNY",
+                    format_percent(performance.benchmark_coverage),
+                    format_percent(performance.benchmark_sample_return)
+                );
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct PortfolioRemoveCommand {
+    #[arg(help = "Name of the portfolio")]
+    name: String,
+
+    #[arg(help = "Ticker of the holding to remove, remove the whole portfolio if omitted")]
+    ticker: Option<String>,
+}
+
+impl PortfolioRemoveCommand {
+    pub async fn exec(&self) {
+        if let Err(err) = api::portfolio_remove(&self.name, self.ticker.as_deref()).await {
+            println!("{}", err.to_string().red());
+        } else if let Some(ticker) = &self.ticker {
+            println!("{} has been removed from portfolio {}", ticker, self.name);
+        } else {
+            println!("Portfolio {} has been removed", self.name);
+        }
+    }
+}
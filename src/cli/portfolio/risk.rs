@@ -0,0 +1,116 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use super::{format_option, format_percent};
+
+use crate::cli::{highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct PortfolioRiskCommand {
+    #[arg(
+        short = 'b',
+        long = "benchmark",
+        default_value = "sh000300",
+        help = "Benchmark index to compute beta against"
+    )]
+    benchmark: String,
+
+    #[arg(long = "no-commentary", help = "Skip LLM-generated commentary")]
+    no_commentary: bool,
+
+    #[arg(help = "Name of the portfolio")]
+    name: String,
+}
+
+impl PortfolioRiskCommand {
+    pub async fn exec(&self) {
+        let spinner = new_spinner();
+
+        match api::portfolio_risk(&self.name, &self.benchmark, !self.no_commentary).await {
+            Ok(risk) => {
+                spinner.finish_and_clear();
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Ticker".to_string(),
+                    "Name".to_string(),
+                    "Industry".to_string(),
+                    "Weight".to_string(),
+                    "Volatility".to_string(),
+                    "Downside Dev".to_string(),
+                    format!("Beta ({})", risk.benchmark),
+                    "Max Drawdown".to_string(),
+                ]];
+                for holding in &risk.holdings {
+                    table_data.push(vec![
+                        holding.ticker.to_string(),
+                        holding.name.clone().unwrap_or_default(),
+                        holding.industry.clone().unwrap_or_default(),
+                        format_option(holding.weight, format_percent),
+                        format_option(holding.volatility, format_percent),
+                        format_option(holding.downside_deviation, format_percent),
+                        format_option(holding.beta, |v| format!("{v:.2}")),
+                        format_option(holding.max_drawdown, format_percent),
+                    ]);
+                }
+                table_data.push(vec![
+                    "Portfolio".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    format_percent(1.0),
+                    format_option(risk.volatility, format_percent),
+                    format_option(risk.downside_deviation, format_percent),
+                    format_option(risk.beta, |v| format!("{v:.2}")),
+                    format_option(risk.max_drawdown, format_percent),
+                ]);
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+
+                let mut table_data: Vec<Vec<String>> =
+                    vec![vec!["Industry".to_string(), "Weight".to_string()]];
+                for (industry, weight) in &risk.industry_weights {
+                    table_data.push(vec![industry.to_string(), format_percent(*weight)]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+                println!("Concentration (HHI): {:.3}", risk.concentration);
+
+                if !risk.correlations.is_empty() {
+                    let mut table_data: Vec<Vec<String>> = vec![vec![
+                        "Ticker".to_string(),
+                        "Ticker".to_string(),
+                        "Correlation".to_string(),
+                    ]];
+                    for (a, b, correlation) in &risk.correlations {
+                        table_data.push(vec![
+                            a.to_string(),
+                            b.to_string(),
+                            format!("{correlation:.2}"),
+                        ]);
+                    }
+
+                    let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                    table.modify(Columns::first(), highlight());
+                    println!("{table}");
+                }
+
+                match &risk.commentary {
+                    Some(Ok(commentary)) => {
+                        println!("{commentary}");
+                    }
+                    Some(Err(err)) => {
+                        println!("[I] No commentary: {}", err.to_string().yellow());
+                    }
+                    None => {}
+                }
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+            }
+        }
+    }
+}
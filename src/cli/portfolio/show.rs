@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use super::{format_option, format_percent};
+
+use crate::cli::{highlight, new_spinner};
+
+#[derive(clap::Args)]
+pub struct PortfolioShowCommand {
+    #[arg(
+        short = 's',
+        long = "sizing",
+        default_value = "kelly",
+        help = "Position sizing method, kelly or vol"
+    )]
+    sizing: String,
+
+    #[arg(help = "Name of the portfolio")]
+    name: String,
+}
+
+impl PortfolioShowCommand {
+    pub async fn exec(&self) {
+        let Ok(method) = api::SizingMethod::from_str(&self.sizing) else {
+            println!(
+                "Unknown sizing method '{}', try '{}' or '{}'",
+                self.sizing.yellow(),
+                "kelly".green(),
+                "vol".green()
+            );
+            return;
+        };
+        let options = api::SizingOptions {
+            method,
+            ..Default::default()
+        };
+
+        let spinner = new_spinner();
+
+        match api::portfolio_show(&self.name, &options).await {
+            Ok(reports) => {
+                spinner.finish_and_clear();
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Ticker".to_string(),
+                    "Shares".to_string(),
+                    "Price".to_string(),
+                    "Weight".to_string(),
+                    "Rating".to_string(),
+                    "Volatility".to_string(),
+                    "Max Drawdown".to_string(),
+                    "52W High/Low".to_string(),
+                    format!("Suggested ({method})"),
+                ]];
+                for report in reports {
+                    table_data.push(vec![
+                        report.holding.ticker.to_string(),
+                        report.holding.shares.to_string(),
+                        format_option(report.price, |v| format!("{v:.2}")),
+                        format_option(report.weight, format_percent),
+                        format_option(report.rating, |v| v.to_string()),
+                        format_option(report.risk.map(|r| r.volatility), format_percent),
+                        format_option(report.risk.map(|r| r.max_drawdown), format_percent),
+                        format_option(report.price_range.as_ref(), |r| {
+                            format!(
+                                "{} / {}",
+                                format_percent(r.from_high_52w),
+                                format_percent(r.from_low_52w)
+                            )
+                        }),
+                        format_option(report.suggested_weight, format_percent),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+                println!(
+                    "[I] Rating is taken from the latest saved evaluation, run `{}` to refresh",
+                    "invmst evaluate <ticker>".green()
+                );
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+            }
+        }
+    }
+}
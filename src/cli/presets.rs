@@ -0,0 +1,52 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct PresetsCommand;
+
+impl PresetsCommand {
+    pub async fn exec(&self) {
+        match api::presets().await {
+            Ok(presets) => {
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Preset".to_string(),
+                    "Masters".to_string(),
+                    "Options".to_string(),
+                    "Description".to_string(),
+                ]];
+                for preset in presets {
+                    let mut options: Vec<String> = vec![];
+                    if let Some(backward_days) = preset.backward_days {
+                        options.push(format!("backward:{backward_days}"));
+                    }
+                    if preset.monte_carlo {
+                        options.push("monte-carlo".to_string());
+                    }
+                    if preset.bear_case {
+                        options.push("bear-case".to_string());
+                    }
+                    if preset.pre_mortem {
+                        options.push("pre-mortem".to_string());
+                    }
+
+                    table_data.push(vec![
+                        preset.name,
+                        preset.masters.join(", "),
+                        options.join(", "),
+                        preset.description,
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
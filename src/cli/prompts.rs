@@ -0,0 +1,34 @@
+use clap::Subcommand;
+
+mod edit;
+mod list;
+mod reset;
+
+#[derive(Subcommand)]
+pub enum PromptsCommand {
+    #[command(about = "Edit a prompt in the editor, the default is copied on first edit")]
+    Edit(Box<edit::PromptsEditCommand>),
+
+    #[command(about = "List prompts and whether they are customized")]
+    #[clap(visible_aliases = &["ls"])]
+    List(Box<list::PromptsListCommand>),
+
+    #[command(about = "Restore the default of a prompt, or of all prompts")]
+    Reset(Box<reset::PromptsResetCommand>),
+}
+
+impl PromptsCommand {
+    pub async fn exec(&self) {
+        match self {
+            PromptsCommand::Edit(cmd) => {
+                cmd.exec().await;
+            }
+            PromptsCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            PromptsCommand::Reset(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::process::Command;
+
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct PromptsEditCommand {
+    #[arg(help = "Name of the prompt, e.g. warren-buffett or analysis-json")]
+    name: String,
+}
+
+impl PromptsEditCommand {
+    pub async fn exec(&self) {
+        let path = match api::prompt_edit_path(&self.name).await {
+            Ok(path) => path,
+            Err(err) => {
+                println!("{}", err.to_string().red());
+                return;
+            }
+        };
+
+        // 优先使用 VISUAL/EDITOR 指定的编辑器
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .ok()
+            .filter(|editor| !editor.trim().is_empty())
+            .unwrap_or(if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+
+        // 编辑器命令可能带参数，例如 "code --wait"
+        let mut args = editor.split_whitespace();
+        let program = args.next().unwrap_or_default();
+        match Command::new(program).args(args).arg(&path).status() {
+            Ok(status) if status.success() => {
+                println!(
+                    "Prompt {} is saved at '{}', used from the next evaluation",
+                    self.name.cyan(),
+                    path.display()
+                );
+            }
+            Ok(status) => {
+                println!("{}", format!("Editor exited with {status}").red());
+            }
+            Err(err) => {
+                println!(
+                    "{}",
+                    format!(
+                        "Failed to run editor '{editor}': {err}, edit '{}' directly",
+                        path.display()
+                    )
+                    .red()
+                );
+            }
+        }
+    }
+}
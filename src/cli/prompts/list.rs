@@ -0,0 +1,45 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct PromptsListCommand;
+
+impl PromptsListCommand {
+    pub async fn exec(&self) {
+        match api::prompts().await {
+            Ok(statuses) => {
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "Prompt".to_string(),
+                    "Status".to_string(),
+                    "File".to_string(),
+                ]];
+                for status in statuses {
+                    let state = match (&status.path, status.outdated) {
+                        (None, _) => "Default".to_string(),
+                        (Some(_), false) => "Customized".to_string(),
+                        (Some(_), true) => "Customized, outdated".yellow().to_string(),
+                    };
+
+                    table_data.push(vec![
+                        status.prompt.to_string(),
+                        state,
+                        status
+                            .path
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default(),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct PromptsResetCommand {
+    #[arg(long = "all", help = "Reset all prompts", conflicts_with = "name")]
+    all: bool,
+
+    #[arg(
+        help = "Name of the prompt, e.g. warren-buffett or analysis-json",
+        required_unless_present = "all"
+    )]
+    name: Option<String>,
+}
+
+impl PromptsResetCommand {
+    pub async fn exec(&self) {
+        match api::prompt_reset(self.name.as_deref()).await {
+            Ok(names) => {
+                if names.is_empty() {
+                    println!("No customized prompt to reset");
+                } else {
+                    for name in names {
+                        println!("Prompt {} has been reset to default", name.cyan());
+                    }
+                }
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
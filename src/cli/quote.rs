@@ -0,0 +1,99 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::{
+    Outcome, highlight,
+    metrics::{format_amount, format_multiple},
+    new_spinner, set_outcome,
+};
+
+#[derive(clap::Args)]
+pub struct QuoteCommand {
+    #[arg(required = true, help = "Tickers to quote, e.g. 600900 00700")]
+    tickers: Vec<String>,
+}
+
+impl QuoteCommand {
+    pub async fn exec(&self) {
+        let spinner = new_spinner();
+
+        let mut table_data: Vec<Vec<String>> = vec![vec![
+            "Ticker".to_string(),
+            "Name".to_string(),
+            "Price".to_string(),
+            "Change".to_string(),
+            "52W Range".to_string(),
+            "PE TTM".to_string(),
+            "PB".to_string(),
+            "PS".to_string(),
+            "Market Cap".to_string(),
+            "Next Earnings".to_string(),
+        ]];
+        let mut errors: Vec<String> = vec![];
+        for ticker in &self.tickers {
+            spinner.set_message(format!("[{}]", ticker.cyan()));
+
+            match api::quote(ticker).await {
+                Ok(quote) => {
+                    let price_summary = quote.price_summary.as_ref();
+                    table_data.push(vec![
+                        quote.ticker.to_string(),
+                        quote.name.clone().unwrap_or("-".to_string()),
+                        price_summary
+                            .map(|s| {
+                                format!(
+                                    "{:.2}{}",
+                                    s.price,
+                                    if s.is_quote { "" } else { " [close]" }
+                                )
+                            })
+                            .unwrap_or("-".to_string()),
+                        price_summary
+                            .and_then(|s| s.change_rate)
+                            .map(|v| {
+                                let change = format!("{:+.2}%", v * 100.0);
+                                if v >= 0.0 {
+                                    change.red().to_string()
+                                } else {
+                                    change.green().to_string()
+                                }
+                            })
+                            .unwrap_or("-".to_string()),
+                        price_summary
+                            .and_then(|s| s.low_52w.zip(s.high_52w))
+                            .map(|(low, high)| format!("{low:.2} ~ {high:.2}"))
+                            .unwrap_or("-".to_string()),
+                        format_multiple(quote.pe_ttm),
+                        format_multiple(quote.pb),
+                        format_multiple(quote.ps),
+                        format_amount(quote.market_cap),
+                        quote
+                            .next_earnings_date
+                            .map(|date| date.to_string())
+                            .unwrap_or("-".to_string()),
+                    ]);
+                }
+                Err(err) => {
+                    errors.push(format!("[{}] {}", ticker, err.to_string().red()));
+                    set_outcome(Outcome::from(&err));
+                }
+            }
+        }
+        spinner.finish_and_clear();
+
+        if table_data.len() > 1 {
+            let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+            table.modify(Columns::first(), highlight());
+            println!("{table}");
+        }
+
+        // 部分股票获取失败时以部分成功退出
+        if !errors.is_empty() && table_data.len() > 1 {
+            set_outcome(Outcome::Partial);
+        }
+        for error in errors {
+            eprintln!("{error}");
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use clap::Subcommand;
+
+mod add;
+mod list;
+mod remove;
+
+#[derive(Subcommand)]
+pub enum ScheduleCommand {
+    #[command(about = "Add a scheduled evaluation job")]
+    Add(Box<add::ScheduleAddCommand>),
+
+    #[command(about = "List scheduled jobs")]
+    List(Box<list::ScheduleListCommand>),
+
+    #[command(about = "Remove a scheduled job")]
+    #[clap(visible_aliases = &["rm"])]
+    Remove(Box<remove::ScheduleRemoveCommand>),
+}
+
+impl ScheduleCommand {
+    pub async fn exec(&self) {
+        match self {
+            ScheduleCommand::Add(cmd) => {
+                cmd.exec().await;
+            }
+            ScheduleCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            ScheduleCommand::Remove(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct ScheduleAddCommand {
+    #[arg(
+        short = 'm',
+        long = "master",
+        help = "Investment master, e.g. -m buffett -m graham"
+    )]
+    masters: Vec<String>,
+
+    #[arg(
+        long = "portfolio",
+        help = "Portfolio whose holdings at run time are evaluated, e.g. --portfolio default"
+    )]
+    portfolio: Option<String>,
+
+    #[arg(
+        long = "watchlist",
+        help = "Watchlist whose tickers at run time are evaluated, e.g. --watchlist default"
+    )]
+    watchlist: Option<String>,
+
+    #[arg(
+        long = "screen",
+        help = "Screen the tickers by a condition over the metrics instead of evaluating them, e.g. --screen \"pe < 12 && roe > 15%\""
+    )]
+    screen: Option<String>,
+
+    #[arg(help = "Cron expression, e.g. \"0 18 * * 1-5\" for 18:00 on weekdays")]
+    cron: String,
+
+    #[arg(
+        required_unless_present_any = ["portfolio", "watchlist"],
+        help = "Tickers to evaluate, e.g. 600900 00700"
+    )]
+    tickers: Vec<String>,
+}
+
+impl ScheduleAddCommand {
+    pub async fn exec(&self) {
+        match api::schedule_add(
+            &self.cron,
+            &self.tickers,
+            self.portfolio.as_deref(),
+            self.watchlist.as_deref(),
+            self.screen.as_deref(),
+            &self.masters,
+        )
+        .await
+        {
+            Ok(job) => {
+                println!("Scheduled job {} has been added", job.id.to_string().cyan());
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::Local;
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct ScheduleListCommand;
+
+impl ScheduleListCommand {
+    pub async fn exec(&self) {
+        match api::schedule_list().await {
+            Ok(jobs) => {
+                if jobs.is_empty() {
+                    println!("No scheduled jobs");
+                    return;
+                }
+
+                let now = Local::now();
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "ID".to_string(),
+                    "Cron".to_string(),
+                    "Tickers".to_string(),
+                    "Portfolio".to_string(),
+                    "Watchlist".to_string(),
+                    "Screen".to_string(),
+                    "Masters".to_string(),
+                    "Next Run".to_string(),
+                ]];
+                for job in jobs {
+                    table_data.push(vec![
+                        job.id.to_string(),
+                        job.cron.to_string(),
+                        job.tickers.join(" "),
+                        job.portfolio.clone().unwrap_or_default(),
+                        job.watchlist.clone().unwrap_or_default(),
+                        job.screen.clone().unwrap_or_default(),
+                        job.masters.join(" "),
+                        job.next_run(&now)
+                            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default(),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct ScheduleRemoveCommand {
+    #[arg(help = "ID of the scheduled job")]
+    id: u64,
+}
+
+impl ScheduleRemoveCommand {
+    pub async fn exec(&self) {
+        if let Err(err) = api::schedule_remove(self.id).await {
+            println!("{}", err.to_string().red());
+        } else {
+            println!("Scheduled job {} has been removed", self.id);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use clap::Subcommand;
+
+mod run;
+
+#[derive(Subcommand)]
+pub enum SchedulerCommand {
+    #[command(about = "Run scheduled jobs and check alert rules until interrupted")]
+    Run(Box<run::SchedulerRunCommand>),
+}
+
+impl SchedulerCommand {
+    pub async fn exec(&self) {
+        match self {
+            SchedulerCommand::Run(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
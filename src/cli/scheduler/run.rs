@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+
+use chrono::Local;
+use colored::Colorize;
+use invmst::api;
+use tokio::time::{Duration, Instant};
+
+use crate::cli::{
+    alerts::render_trigger,
+    evaluate::{TableOptions, output_csv, render_evaluation, render_price_summary},
+};
+
+#[derive(clap::Args)]
+pub struct SchedulerRunCommand {
+    #[arg(
+        long = "csv-dir",
+        help = "Directory to also write the evaluations of each job run to as a csv file named '<job>_<time>.csv'"
+    )]
+    csv_dir: Option<PathBuf>,
+}
+
+impl SchedulerRunCommand {
+    pub async fn exec(&self) {
+        println!("Scheduler is running, press Ctrl+C to stop");
+
+        let mut last_check = Local::now();
+        let mut alerts_checked_at: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_TICK_SECS)).await;
+
+            // Reload jobs on every tick so that changes take effect without restarting
+            let jobs = match api::schedule_list().await {
+                Ok(jobs) => jobs,
+                Err(err) => {
+                    println!("{}", err.to_string().red());
+                    continue;
+                }
+            };
+
+            let now = Local::now();
+            for job in jobs {
+                let is_due = job.next_run(&last_check).is_some_and(|t| t <= now);
+                if !is_due {
+                    continue;
+                }
+
+                println!(
+                    "[{}] Running scheduled job {}",
+                    now.format("%Y-%m-%d %H:%M"),
+                    job.id.to_string().cyan()
+                );
+
+                // 组合持仓在运行时解析，添加任务后的调仓也会生效
+                let tickers = match job.resolve_tickers().await {
+                    Ok(tickers) => tickers,
+                    Err(err) => {
+                        println!("{}", err.to_string().red());
+                        continue;
+                    }
+                };
+
+                if let Some(condition) = &job.screen {
+                    match api::screen(&tickers, condition).await {
+                        Ok(matches) => {
+                            println!(
+                                "{} of {} tickers meet {}",
+                                matches.len(),
+                                tickers.len(),
+                                condition.yellow()
+                            );
+                            for screen_match in &matches {
+                                println!("{}", render_screen_match(screen_match));
+                            }
+                        }
+                        Err(err) => {
+                            println!("{}", err.to_string().red());
+                        }
+                    }
+                    continue;
+                }
+
+                let options = api::EvaluateOptions {
+                    backward_days: None,
+                    date: None,
+                    masters: job.masters.clone(),
+                    exclude_masters: vec![],
+                    monte_carlo: false,
+                    holding: None,
+                    bear_case: false,
+                    pre_mortem: false,
+                    agentic: false,
+                    focus_areas: vec![],
+                    note: None,
+                    llm_options: api::ChatCompletionOptions::default(),
+                };
+
+                let mut evaluations: Vec<api::Evaluation> = vec![];
+                for ticker in &tickers {
+                    match api::evaluate(ticker, &options).await {
+                        Ok(evaluation) => {
+                            match &evaluation.price_summary {
+                                Some(price_summary) => println!(
+                                    "[{}] {}",
+                                    ticker.cyan(),
+                                    render_price_summary(price_summary)
+                                ),
+                                None => println!("[{}]", ticker.cyan()),
+                            }
+                            println!(
+                                "{}",
+                                render_evaluation(&evaluation, &TableOptions::default())
+                            );
+                            evaluations.push(evaluation);
+                        }
+                        Err(err) => {
+                            println!("[{}] {}", ticker, err.to_string().red());
+                        }
+                    }
+                }
+
+                if let Some(csv_dir) = &self.csv_dir {
+                    if !evaluations.is_empty() {
+                        let path =
+                            csv_dir.join(format!("{}_{}.csv", job.id, now.format("%Y%m%d%H%M")));
+                        if let Err(err) = std::fs::create_dir_all(csv_dir) {
+                            println!("{}", err.to_string().red());
+                        } else if let Err(err) = output_csv(&evaluations, Some(&path)).await {
+                            println!("{}", err.to_string().red());
+                        }
+                    }
+                }
+            }
+            last_check = now;
+
+            // 告警需要拉取行情和指标，按较长的间隔检查
+            if alerts_checked_at
+                .is_none_or(|t| t.elapsed() >= Duration::from_secs(ALERTS_CHECK_SECS))
+            {
+                alerts_checked_at = Some(Instant::now());
+                match api::alerts_check().await {
+                    Ok(triggers) => {
+                        for trigger in &triggers {
+                            println!(
+                                "[{}] {}",
+                                Local::now().format("%Y-%m-%d %H:%M"),
+                                render_trigger(trigger)
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        println!("{}", err.to_string().red());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn render_screen_match(screen_match: &api::ScreenMatch) -> String {
+    let values: Vec<String> = screen_match
+        .values
+        .iter()
+        .map(|(name, value)| format!("{name} = {value:.4}"))
+        .collect();
+
+    format!("[{}] {}", screen_match.ticker.cyan(), values.join(", "))
+}
+
+static ALERTS_CHECK_SECS: u64 = 600;
+static SCHEDULER_TICK_SECS: u64 = 20;
@@ -0,0 +1,110 @@
+use chrono::Local;
+use colored::Colorize;
+use invmst::{api, utils};
+use tabled::settings::object::Columns;
+
+use crate::cli::{
+    highlight,
+    metrics::{format_multiple, format_percent},
+    new_spinner,
+};
+
+#[derive(clap::Args)]
+pub struct SectorsCommand {
+    #[arg(
+        short = 'b',
+        long = "backward",
+        help = "Days of history to rank valuations against, the default value is 1825"
+    )]
+    backward_days: Option<i64>,
+
+    #[arg(
+        short = 'c',
+        long = "comment",
+        help = "Let a macro-oriented master comment on which sectors the current cycle favors"
+    )]
+    comment: bool,
+
+    #[arg(
+        short = 'd',
+        long = "date",
+        help = "The date of the overview, e.g. -d 2022-01-01"
+    )]
+    date: Option<String>,
+}
+
+impl SectorsCommand {
+    pub async fn exec(&self) {
+        let backward_days = self.backward_days.unwrap_or(1825).abs();
+
+        let date = if let Some(date_str) = &self.date {
+            let parsed_date = utils::datetime::date_from_str(date_str);
+            if parsed_date.is_none() {
+                println!(
+                    "Can not parse '{}' as date, try format like '{}'",
+                    date_str.yellow(),
+                    Local::now()
+                        .date_naive()
+                        .format("%Y-%m-%d")
+                        .to_string()
+                        .green()
+                );
+                return;
+            }
+
+            parsed_date
+        } else {
+            None
+        };
+
+        let spinner = new_spinner();
+        spinner.set_message("Fetching sectors");
+
+        match api::sectors(date, backward_days, self.comment).await {
+            Ok((sector_overviews, comment)) => {
+                spinner.finish_and_clear();
+
+                if sector_overviews.is_empty() {
+                    println!("No sector data");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> = vec![vec![
+                    "#".to_string(),
+                    "Sector".to_string(),
+                    "Return 1M".to_string(),
+                    "Return 3M".to_string(),
+                    "Return 12M".to_string(),
+                    "PE".to_string(),
+                    "PE Pct".to_string(),
+                    "PB".to_string(),
+                    "PB Pct".to_string(),
+                ]];
+                for (i, sector_overview) in sector_overviews.iter().enumerate() {
+                    table_data.push(vec![
+                        (i + 1).to_string(),
+                        sector_overview.name.to_string(),
+                        format_percent(sector_overview.return_1m),
+                        format_percent(sector_overview.return_3m),
+                        format_percent(sector_overview.return_12m),
+                        format_multiple(sector_overview.pe),
+                        format_percent(sector_overview.pe_percentile),
+                        format_multiple(sector_overview.pb),
+                        format_percent(sector_overview.pb_percentile),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+
+                if let Some(comment) = comment {
+                    println!("\n{comment}");
+                }
+            }
+            Err(err) => {
+                spinner.finish_with_message(err.to_string().red().to_string());
+            }
+        }
+    }
+}
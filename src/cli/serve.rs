@@ -0,0 +1,42 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct ServeCommand {
+    #[arg(
+        short = 'b',
+        long = "bind",
+        default_value = "127.0.0.1:3000",
+        help = "Address to listen on, the default value is 127.0.0.1:3000"
+    )]
+    bind: String,
+
+    #[cfg(feature = "grpc")]
+    #[arg(
+        long = "grpc",
+        help = "Also serve gRPC API on the address, e.g. --grpc 127.0.0.1:50051"
+    )]
+    grpc: Option<String>,
+}
+
+impl ServeCommand {
+    pub async fn exec(&self) {
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            println!("Listening gRPC on {}", grpc.cyan());
+
+            let grpc = grpc.clone();
+            tokio::spawn(async move {
+                if let Err(err) = api::serve_grpc(&grpc).await {
+                    println!("{}", err.to_string().red());
+                }
+            });
+        }
+
+        println!("Listening on {}", self.bind.cyan());
+
+        if let Err(err) = api::serve(&self.bind).await {
+            println!("{}", err.to_string().red());
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use clap::Subcommand;
+
+mod add;
+mod list;
+mod remove;
+
+#[derive(Subcommand)]
+pub enum WatchlistCommand {
+    #[command(about = "Add tickers to a watchlist, creating it if needed")]
+    Add(Box<add::WatchlistAddCommand>),
+
+    #[command(about = "List watchlists")]
+    #[clap(visible_aliases = &["ls"])]
+    List(Box<list::WatchlistListCommand>),
+
+    #[command(about = "Remove a ticker or a whole watchlist")]
+    #[clap(visible_aliases = &["rm"])]
+    Remove(Box<remove::WatchlistRemoveCommand>),
+}
+
+impl WatchlistCommand {
+    pub async fn exec(&self) {
+        match self {
+            WatchlistCommand::Add(cmd) => {
+                cmd.exec().await;
+            }
+            WatchlistCommand::List(cmd) => {
+                cmd.exec().await;
+            }
+            WatchlistCommand::Remove(cmd) => {
+                cmd.exec().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct WatchlistAddCommand {
+    #[arg(help = "Name of the watchlist")]
+    name: String,
+
+    #[arg(required = true, help = "Tickers to add, e.g. 600900 00700")]
+    tickers: Vec<String>,
+}
+
+impl WatchlistAddCommand {
+    pub async fn exec(&self) {
+        match api::watchlist_add(&self.name, &self.tickers).await {
+            Ok(watchlist) => {
+                println!(
+                    "Watchlist {} has {} tickers",
+                    watchlist.name.cyan(),
+                    watchlist.tickers.len()
+                );
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use colored::Colorize;
+use invmst::api;
+use tabled::settings::object::Columns;
+
+use crate::cli::highlight;
+
+#[derive(clap::Args)]
+pub struct WatchlistListCommand;
+
+impl WatchlistListCommand {
+    pub async fn exec(&self) {
+        match api::watchlist_list().await {
+            Ok(watchlists) => {
+                if watchlists.is_empty() {
+                    println!("No watchlists");
+                    return;
+                }
+
+                let mut table_data: Vec<Vec<String>> =
+                    vec![vec!["Name".to_string(), "Tickers".to_string()]];
+                for watchlist in watchlists {
+                    table_data.push(vec![
+                        watchlist.name.to_string(),
+                        watchlist.tickers.join(" "),
+                    ]);
+                }
+
+                let mut table = tabled::builder::Builder::from_iter(&table_data).build();
+                table.modify(Columns::first(), highlight());
+                println!("{table}");
+            }
+            Err(err) => {
+                println!("{}", err.to_string().red());
+            }
+        }
+    }
+}
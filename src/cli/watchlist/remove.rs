@@ -0,0 +1,23 @@
+use colored::Colorize;
+use invmst::api;
+
+#[derive(clap::Args)]
+pub struct WatchlistRemoveCommand {
+    #[arg(help = "Name of the watchlist")]
+    name: String,
+
+    #[arg(help = "Ticker to remove, remove the whole watchlist if omitted")]
+    ticker: Option<String>,
+}
+
+impl WatchlistRemoveCommand {
+    pub async fn exec(&self) {
+        if let Err(err) = api::watchlist_remove(&self.name, self.ticker.as_deref()).await {
+            println!("{}", err.to_string().red());
+        } else if let Some(ticker) = &self.ticker {
+            println!("{} has been removed from watchlist {}", ticker, self.name);
+        } else {
+            println!("Watchlist {} has been removed", self.name);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::{path::PathBuf, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    APP_CONFIG_PATH,
+    error::{InvmstError, InvmstResult},
+    financial::reconcile::DataSource,
+    master::Master,
+    ticker::Ticker,
+};
+
+/// Global settings, used as defaults when options are not given explicitly
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppConfig {
+    /// Download the full texts of recent announcements for the summary of recent developments,
+    /// otherwise only the titles are summarized
+    #[serde(default)]
+    pub announcement_full_text: bool,
+    /// Days to backward of evaluations
+    #[serde(default)]
+    pub backward_days: Option<i64>,
+    /// Overrides the directory of data and history, unless INVMST_DATA_DIR or --data-dir is set
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+    /// Sources to cross-check key figures between, the first two are compared if more than one
+    #[serde(default)]
+    pub data_sources: Vec<String>,
+    /// Masters never to evaluate, even if selected by a style group
+    #[serde(default)]
+    pub exclude_masters: Vec<String>,
+    /// Tickers whose statements are parsed from the official filings downloaded under the
+    /// filings directory, rather than fetched from the aggregator
+    #[serde(default)]
+    pub filing_tickers: Vec<String>,
+    /// Output format of commands supporting it
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Language of LLM answers
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Masters to evaluate if none is specified
+    #[serde(default)]
+    pub masters: Vec<String>,
+    /// Skip fetching penalties and ESG ratings for the compliance risk
+    #[serde(default)]
+    pub skip_compliance: bool,
+}
+
+#[derive(Clone, Copy, Debug, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
+pub enum ConfigKey {
+    AnnouncementFullText,
+    BackwardDays,
+    DataDir,
+    DataSources,
+    ExcludeMasters,
+    FilingTickers,
+    Format,
+    Language,
+    Masters,
+    SkipCompliance,
+}
+
+pub fn load() -> InvmstResult<AppConfig> {
+    Ok(confy::load_path(&*APP_CONFIG_PATH)?)
+}
+
+pub async fn get(key: &str) -> InvmstResult<Option<String>> {
+    Ok(value_of(&load()?, parse_key(key)?))
+}
+
+pub async fn list() -> InvmstResult<Vec<(ConfigKey, Option<String>)>> {
+    let cfg = load()?;
+
+    Ok(ConfigKey::iter()
+        .map(|key| (key, value_of(&cfg, key)))
+        .collect())
+}
+
+/// Set the value of a key, an empty value resets the key to default
+pub async fn set(key: &str, value: &str) -> InvmstResult<()> {
+    let key = parse_key(key)?;
+    let value = value.trim();
+
+    let mut cfg = load()?;
+    match key {
+        ConfigKey::AnnouncementFullText => {
+            cfg.announcement_full_text = !value.is_empty() && parse_bool(value)?;
+        }
+        ConfigKey::BackwardDays => {
+            cfg.backward_days = if value.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .parse::<i64>()
+                        .map_err(|_| {
+                            InvmstError::Invalid(
+                                "INVALID_CONFIG_VALUE",
+                                format!("Invalid days '{value}'"),
+                            )
+                        })?
+                        .abs(),
+                )
+            };
+        }
+        ConfigKey::DataDir => {
+            cfg.data_dir = (!value.is_empty()).then(|| PathBuf::from(value));
+        }
+        ConfigKey::DataSources => {
+            cfg.data_sources = parse_data_sources(value)?
+                .iter()
+                .map(|source| source.to_string())
+                .collect();
+        }
+        ConfigKey::FilingTickers => {
+            cfg.filing_tickers = parse_tickers(value)?
+                .iter()
+                .map(|ticker| ticker.to_string())
+                .collect();
+        }
+        ConfigKey::Format => {
+            cfg.format = (!value.is_empty()).then(|| value.to_lowercase());
+        }
+        ConfigKey::Language => {
+            cfg.language = (!value.is_empty()).then(|| value.to_string());
+        }
+        ConfigKey::Masters => {
+            cfg.masters = parse_masters(value)?;
+        }
+        ConfigKey::ExcludeMasters => {
+            cfg.exclude_masters = parse_masters(value)?;
+        }
+        ConfigKey::SkipCompliance => {
+            cfg.skip_compliance = !value.is_empty() && parse_bool(value)?;
+        }
+    }
+
+    confy::store_path(&*APP_CONFIG_PATH, &cfg)?;
+
+    Ok(())
+}
+
+/// Data sources configured, unknown ones are skipped
+pub fn data_sources(cfg: &AppConfig) -> Vec<DataSource> {
+    cfg.data_sources
+        .iter()
+        .filter_map(|s| DataSource::from_str(s).ok())
+        .collect()
+}
+
+/// Whether the statements of the ticker are parsed from the downloaded official filings
+pub fn is_filing_ticker(cfg: &AppConfig, ticker: &Ticker) -> bool {
+    cfg.filing_tickers
+        .iter()
+        .filter_map(|s| Ticker::from_str(s).ok())
+        .any(|t| t.exchange == ticker.exchange && t.symbol == ticker.symbol)
+}
+
+fn parse_bool(value: &str) -> InvmstResult<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(InvmstError::Invalid(
+            "INVALID_CONFIG_VALUE",
+            format!("Invalid boolean '{value}'"),
+        )),
+    }
+}
+
+/// Comma separated data sources, all must exist
+fn parse_data_sources(value: &str) -> InvmstResult<Vec<DataSource>> {
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            DataSource::from_str(s).map_err(|_| {
+                InvmstError::NotExists(
+                    "DATA_SOURCE_NOT_EXISTS",
+                    format!(
+                        "Data source '{s}' not exists, available sources: {}",
+                        DataSource::iter()
+                            .map(|source| source.to_string())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Comma separated masters or style groups like '@value', all must exist
+fn parse_masters(value: &str) -> InvmstResult<Vec<String>> {
+    let masters: Vec<String> = value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if let Some(master_str) = masters.iter().find(|s| Master::select(s).is_none()) {
+        return Err(InvmstError::NotExists(
+            "MASTER_NOT_EXISTS",
+            format!("Master '{master_str}' not exists"),
+        ));
+    }
+
+    Ok(masters)
+}
+
+/// Comma separated tickers, exchanges are determined if omitted
+fn parse_tickers(value: &str) -> InvmstResult<Vec<Ticker>> {
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(Ticker::from_str)
+        .collect()
+}
+
+fn parse_key(key: &str) -> InvmstResult<ConfigKey> {
+    ConfigKey::from_str(&key.trim().replace('-', "_")).map_err(|_| {
+        InvmstError::NotExists(
+            "CONFIG_KEY_NOT_EXISTS",
+            format!(
+                "Config key '{key}' not exists, available keys: {}",
+                ConfigKey::iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            ),
+        )
+    })
+}
+
+fn value_of(cfg: &AppConfig, key: ConfigKey) -> Option<String> {
+    match key {
+        ConfigKey::AnnouncementFullText => cfg.announcement_full_text.then(|| "true".to_string()),
+        ConfigKey::BackwardDays => cfg.backward_days.map(|days| days.to_string()),
+        ConfigKey::DataDir => cfg
+            .data_dir
+            .as_ref()
+            .map(|dir| dir.to_string_lossy().to_string()),
+        ConfigKey::DataSources => {
+            (!cfg.data_sources.is_empty()).then(|| cfg.data_sources.join(","))
+        }
+        ConfigKey::ExcludeMasters => {
+            (!cfg.exclude_masters.is_empty()).then(|| cfg.exclude_masters.join(","))
+        }
+        ConfigKey::FilingTickers => {
+            (!cfg.filing_tickers.is_empty()).then(|| cfg.filing_tickers.join(","))
+        }
+        ConfigKey::Format => cfg.format.clone(),
+        ConfigKey::Language => cfg.language.clone(),
+        ConfigKey::Masters => (!cfg.masters.is_empty()).then(|| cfg.masters.join(",")),
+        ConfigKey::SkipCompliance => cfg.skip_compliance.then(|| "true".to_string()),
+    }
+}
@@ -1,9 +1,10 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display, io::Cursor, marker::PhantomData};
 
 use chrono::NaiveDate;
+use log::warn;
 use num_traits::NumCast;
 use polars::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
 use crate::{
@@ -11,19 +12,217 @@ use crate::{
     utils,
 };
 
-#[derive(Clone, Debug, Serialize)]
-pub struct DailyDataset {
+/// Field names of a kind of daily dataset, implemented by the field enum of each dataset so that
+/// the accessors only take the fields the dataset has
+pub trait DailyField: Display {}
+
+#[derive(Clone, Debug)]
+pub struct DailyDataset<F> {
+    /// Sorted by date ascending, so that lookups can binary search the dates
     df: DataFrame,
+    /// Dates of the rows of the dataframe
+    dates: Vec<NaiveDate>,
 
     date_field_name: String,
     value_field_names: HashMap<String, String>,
+    /// Values older than this many days before the requested date are not used, unlimited if None
+    max_staleness_days: Option<i64>,
+
+    field: PhantomData<F>,
 }
 
-impl DailyDataset {
+impl<F: DailyField> DailyDataset<F> {
+    /// Each field is read from the column of the origin name in the JSON objects
     pub fn from_json(
         json: &Value,
         date_field_name: &str,
-        value_field_names: &HashMap<String, String>,
+        value_field_names: &[(F, &str)],
+    ) -> InvmstResult<Self> {
+        Self::from_json_columns(
+            json,
+            date_field_name,
+            value_field_names
+                .iter()
+                .map(|(field, origin_field_name)| {
+                    (field.to_string(), origin_field_name.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    pub fn with_max_staleness(mut self, days: i64) -> Self {
+        self.max_staleness_days = Some(days.abs());
+        self
+    }
+
+    /// The latest non-null value on or before the date, i.e. forward-filled over non-trading days
+    /// and gaps, within the max staleness
+    pub fn get_latest_value<T: NumCast>(&self, date: &NaiveDate, field: F) -> Option<T> {
+        self.find_value(date, &field, false).map(|(_, value)| value)
+    }
+
+    /// The non-null value whose date is nearest to the date on either side within the max
+    /// staleness, the earlier one wins a tie
+    pub fn get_nearest_value<T: NumCast>(&self, date: &NaiveDate, field: F) -> Option<T> {
+        match (
+            self.find_value::<T>(date, &field, false),
+            self.find_value::<T>(date, &field, true),
+        ) {
+            (Some((date_before, value_before)), Some((date_after, value_after))) => {
+                if *date - date_before <= date_after - *date {
+                    Some(value_before)
+                } else {
+                    Some(value_after)
+                }
+            }
+            (Some((_, value)), None) | (None, Some((_, value))) => Some(value),
+            (None, None) => None,
+        }
+    }
+
+    /// Rows sorted by date ascending, each row is an object of the date and the field values, which
+    /// can be loaded back with the field names unchanged by deserializing
+    pub fn to_json(&self) -> Value {
+        let mut field_names: Vec<&String> = self.value_field_names.keys().collect();
+        field_names.sort();
+
+        let mut rows: Vec<Value> = vec![];
+        for (i, date) in self.dates.iter().enumerate() {
+            let mut row = serde_json::Map::new();
+            row.insert("date".to_string(), Value::from(date.to_string()));
+
+            for field_name in &field_names {
+                let value = match self
+                    .df
+                    .column(&self.value_field_names[*field_name])
+                    .and_then(|col| col.get(i))
+                {
+                    Ok(AnyValue::Boolean(b)) => Value::from(b),
+                    Ok(AnyValue::Int64(n)) => Value::from(n),
+                    Ok(AnyValue::String(s)) => Value::from(s),
+                    Ok(val) => val.extract::<f64>().map(Value::from).unwrap_or(Value::Null),
+                    Err(_) => Value::Null,
+                };
+                row.insert(field_name.to_string(), value);
+            }
+
+            rows.push(Value::Object(row));
+        }
+
+        Value::Array(rows)
+    }
+
+    /// Rows between the dates (inclusive) sorted by date ascending, columns are renamed to the
+    /// field names and the date column is named 'date'
+    pub fn to_dataframe(
+        &self,
+        date_start: &NaiveDate,
+        date_end: &NaiveDate,
+    ) -> InvmstResult<DataFrame> {
+        Ok(self
+            .normalized()
+            .filter(
+                col("date")
+                    .gt_eq(lit(*date_start))
+                    .and(col("date").lt_eq(lit(*date_end))),
+            )
+            .collect()?)
+    }
+
+    /// All rows as Parquet in the layout of to_dataframe, the dtypes are kept unlike to_json
+    pub fn to_parquet(&self) -> InvmstResult<Vec<u8>> {
+        let mut df = self.normalized().collect()?;
+
+        let mut buf: Vec<u8> = vec![];
+        ParquetWriter::new(&mut buf).finish(&mut df)?;
+
+        Ok(buf)
+    }
+
+    /// Load the Parquet written by to_parquet, the columns other than 'date' are the field names
+    pub fn from_parquet(bytes: &[u8]) -> InvmstResult<Self> {
+        let df = ParquetReader::new(Cursor::new(bytes)).finish()?;
+        if !matches!(df.column("date").map(|col| col.dtype()), Ok(DataType::Date)) {
+            return Err(InvmstError::Invalid(
+                "PARQUET_DATE_MISSING",
+                "Parquet has no date column".to_string(),
+            ));
+        }
+
+        let value_field_names: HashMap<String, String> = df
+            .get_column_names()
+            .into_iter()
+            .filter(|name| name.as_str() != "date")
+            .map(|name| (name.to_string(), name.to_string()))
+            .collect();
+
+        Self::new(df, "date", value_field_names)
+    }
+
+    /// Values of the field between the dates (inclusive), sorted by date ascending, nulls skipped
+    pub fn get_values<T: NumCast>(
+        &self,
+        date_start: &NaiveDate,
+        date_end: &NaiveDate,
+        field: F,
+    ) -> Vec<(NaiveDate, T)> {
+        let Some(value_col) = self.value_column(&field) else {
+            return vec![];
+        };
+
+        let start = self.dates.partition_point(|date| date < date_start);
+        let end = self.dates.partition_point(|date| date <= date_end);
+        (start..end)
+            .filter_map(|i| {
+                let value = value_col.get(i).ok().and_then(|v| v.extract::<T>())?;
+                Some((self.dates[i], value))
+            })
+            .collect()
+    }
+
+    /// Set the values of the field failing the check to null, returns the dates and values
+    /// dropped
+    pub fn drop_values(
+        &mut self,
+        field: F,
+        is_valid: impl Fn(f64) -> bool,
+    ) -> Vec<(NaiveDate, f64)> {
+        let mut dropped: Vec<(NaiveDate, f64)> = vec![];
+
+        let Some(origin_field_name) = self.value_field_names.get(&field.to_string()) else {
+            return dropped;
+        };
+        let Ok(value_col) = self.df.column(origin_field_name) else {
+            return dropped;
+        };
+
+        let mut values: Vec<Option<f64>> = Vec::with_capacity(self.df.height());
+        for i in 0..self.df.height() {
+            let value = value_col.get(i).ok().and_then(|v| v.extract::<f64>());
+            match value {
+                Some(value) if !is_valid(value) => {
+                    dropped.push((self.dates[i], value));
+                    values.push(None);
+                }
+                _ => values.push(value),
+            }
+        }
+
+        if !dropped.is_empty() {
+            let column = Column::new(origin_field_name.into(), values);
+            if let Err(err) = self.df.with_column(column) {
+                warn!("[Daily Dataset] Unable to drop values of '{field}': {err}");
+                return vec![];
+            }
+        }
+
+        dropped
+    }
+
+    fn from_json_columns(
+        json: &Value,
+        date_field_name: &str,
+        value_field_names: HashMap<String, String>,
     ) -> InvmstResult<Self> {
         if let Some(array) = json.as_array() {
             let column_names: Vec<String> = [
@@ -88,13 +287,7 @@ impl DailyDataset {
                 series.push(Column::new(column_name.into(), values));
             }
 
-            let df = DataFrame::new(series)?;
-
-            Ok(Self {
-                df,
-                date_field_name: date_field_name.to_string(),
-                value_field_names: value_field_names.clone(),
-            })
+            Self::new(DataFrame::new(series)?, date_field_name, value_field_names)
         } else {
             Err(InvmstError::Invalid(
                 "JSON_IS_NOT_ARRAY",
@@ -103,27 +296,305 @@ impl DailyDataset {
         }
     }
 
-    pub fn get_latest_value<T: NumCast>(&self, date: &NaiveDate, field_name: &str) -> Option<T> {
-        if let Some(origin_field_name) = self.value_field_names.get(field_name) {
-            if let Ok(df) = self
-                .df
-                .clone()
-                .lazy()
-                .filter(col(&self.date_field_name).lt_eq(lit(*date)))
-                .sort(
-                    [&self.date_field_name],
-                    SortMultipleOptions::default().with_order_descending(true),
-                )
-                .collect()
-            {
-                if let Ok(col) = df.column(origin_field_name) {
-                    if let Ok(val) = col.get(0) {
-                        return val.extract::<T>();
-                    }
-                }
+    /// Columns renamed to the field names and 'date'
+    fn normalized(&self) -> LazyFrame {
+        let mut field_names: Vec<&String> = self.value_field_names.keys().collect();
+        field_names.sort();
+
+        let mut exprs: Vec<Expr> = vec![col(&self.date_field_name).alias("date")];
+        for field_name in field_names {
+            exprs.push(col(&self.value_field_names[field_name]).alias(field_name));
+        }
+
+        self.df.clone().lazy().select(exprs)
+    }
+
+    /// The nearest non-null value on or before the date, or on or after the date if forward
+    fn find_value<T: NumCast>(
+        &self,
+        date: &NaiveDate,
+        field: &F,
+        forward: bool,
+    ) -> Option<(NaiveDate, T)> {
+        let value_col = self.value_column(field)?;
+
+        let value_at = |i: usize| {
+            let value = value_col.get(i).ok().and_then(|v| v.extract::<T>())?;
+            Some((self.dates[i], value))
+        };
+        let is_fresh = |i: &usize| {
+            self.max_staleness_days
+                .is_none_or(|days| (self.dates[*i] - *date).num_days().abs() <= days)
+        };
+
+        // 日期已排序，二分定位后只需跳过空值
+        if forward {
+            (self.dates.partition_point(|d| d < date)..self.dates.len())
+                .take_while(is_fresh)
+                .find_map(value_at)
+        } else {
+            (0..self.dates.partition_point(|d| d <= date))
+                .rev()
+                .take_while(is_fresh)
+                .find_map(value_at)
+        }
+    }
+
+    /// Sort the rows by date and drop the rows without dates
+    fn new(
+        df: DataFrame,
+        date_field_name: &str,
+        value_field_names: HashMap<String, String>,
+    ) -> InvmstResult<Self> {
+        // 没有日期值时列的类型为空，需转为日期类型
+        let mut df = df
+            .lazy()
+            .with_column(col(date_field_name).cast(DataType::Date))
+            .filter(col(date_field_name).is_not_null())
+            .sort([date_field_name], SortMultipleOptions::default())
+            .collect()?;
+        df.as_single_chunk();
+
+        let dates: Vec<NaiveDate> = df
+            .column(date_field_name)?
+            .date()?
+            .physical()
+            .into_iter()
+            .flatten()
+            .filter_map(utils::datetime::date_from_days_after_epoch)
+            .collect();
+        if dates.len() != df.height() {
+            return Err(InvmstError::Invalid(
+                "DATE_OUT_OF_RANGE",
+                format!("Dates of column '{date_field_name}' out of range"),
+            ));
+        }
+
+        Ok(Self {
+            df,
+            dates,
+            date_field_name: date_field_name.to_string(),
+            value_field_names,
+            max_staleness_days: None,
+            field: PhantomData,
+        })
+    }
+
+    fn value_column(&self, field: &F) -> Option<&Column> {
+        self.df
+            .column(self.value_field_names.get(&field.to_string())?)
+            .ok()
+    }
+}
+
+impl<F: DailyField> Serialize for DailyDataset<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de, F: DailyField> Deserialize<'de> for DailyDataset<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = Value::deserialize(deserializer)?;
+
+        let mut value_field_names: HashMap<String, String> = HashMap::new();
+        for obj in json
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_object())
+        {
+            for key in obj.keys().filter(|key| *key != "date") {
+                value_field_names.insert(key.to_string(), key.to_string());
             }
         }
 
-        None
+        Self::from_json_columns(&json, "date", value_field_names).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, strum::Display)]
+    #[strum(serialize_all = "snake_case")]
+    enum Field {
+        Price,
+        Pe,
+        Volume,
+    }
+
+    impl DailyField for Field {}
+
+    fn sparse_dataset() -> DailyDataset<Field> {
+        let json = json!([
+            {"date": "2024-01-02", "price": 10.0},
+            {"date": "2024-01-05", "price": null},
+            {"date": "2024-01-10", "price": 12.0},
+        ]);
+
+        DailyDataset::from_json(&json, "date", &[(Field::Price, "price")]).unwrap()
+    }
+
+    #[test]
+    fn test_get_latest_value_forward_fill() {
+        let dataset = sparse_dataset();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        // Non-trading day and null gap are filled by the previous value
+        assert_eq!(
+            dataset.get_latest_value::<f64>(&date(4), Field::Price),
+            Some(10.0)
+        );
+        assert_eq!(
+            dataset.get_latest_value::<f64>(&date(5), Field::Price),
+            Some(10.0)
+        );
+        assert_eq!(
+            dataset.get_latest_value::<f64>(&date(10), Field::Price),
+            Some(12.0)
+        );
+        assert_eq!(
+            dataset.get_latest_value::<f64>(&date(1), Field::Price),
+            None
+        );
+        assert_eq!(dataset.get_latest_value::<f64>(&date(5), Field::Pe), None);
+
+        let dataset = dataset.with_max_staleness(2);
+        assert_eq!(
+            dataset.get_latest_value::<f64>(&date(4), Field::Price),
+            Some(10.0)
+        );
+        assert_eq!(
+            dataset.get_latest_value::<f64>(&date(5), Field::Price),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_nearest_value() {
+        let dataset = sparse_dataset();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        assert_eq!(
+            dataset.get_nearest_value::<f64>(&date(1), Field::Price),
+            Some(10.0)
+        );
+        assert_eq!(
+            dataset.get_nearest_value::<f64>(&date(6), Field::Price),
+            Some(10.0)
+        );
+        assert_eq!(
+            dataset.get_nearest_value::<f64>(&date(8), Field::Price),
+            Some(12.0)
+        );
+        assert_eq!(
+            dataset.get_nearest_value::<f64>(&date(20), Field::Price),
+            Some(12.0)
+        );
+
+        let dataset = dataset.with_max_staleness(3);
+        assert_eq!(
+            dataset.get_nearest_value::<f64>(&date(6), Field::Price),
+            None
+        );
+        assert_eq!(
+            dataset.get_nearest_value::<f64>(&date(8), Field::Price),
+            Some(12.0)
+        );
+    }
+
+    #[test]
+    fn test_get_values() {
+        let dataset = DailyDataset::from_json(
+            &json!([
+                {"date": "2024-01-10", "price": 12.0},
+                {"date": "2024-01-02", "price": 10.0},
+                {"date": "2024-01-05", "price": null},
+                {"date": "2024-01-08", "price": 11.0},
+            ]),
+            "date",
+            &[(Field::Price, "price")],
+        )
+        .unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        assert_eq!(
+            dataset.get_values::<f64>(&date(2), &date(8), Field::Price),
+            vec![(date(2), 10.0), (date(8), 11.0)]
+        );
+        assert_eq!(
+            dataset.get_values::<f64>(&date(3), &date(31), Field::Price),
+            vec![(date(8), 11.0), (date(10), 12.0)]
+        );
+        assert!(
+            dataset
+                .get_values::<f64>(&date(9), &date(3), Field::Price)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let json = json!([
+            {"trade_date": "2024-01-03", "close": 11.0, "pe": 20.5},
+            {"trade_date": "2024-01-02", "close": 10.0, "pe": null},
+        ]);
+        let dataset = DailyDataset::from_json(
+            &json,
+            "trade_date",
+            &[(Field::Price, "close"), (Field::Pe, "pe")],
+        )
+        .unwrap();
+
+        let serialized = serde_json::to_value(&dataset).unwrap();
+        assert_eq!(
+            serialized,
+            json!([
+                {"date": "2024-01-02", "pe": null, "price": 10.0},
+                {"date": "2024-01-03", "pe": 20.5, "price": 11.0},
+            ])
+        );
+
+        let deserialized: DailyDataset<Field> = serde_json::from_value(serialized).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        assert_eq!(
+            deserialized.get_latest_value::<f64>(&date, Field::Price),
+            Some(11.0)
+        );
+        assert_eq!(
+            deserialized.get_latest_value::<f64>(&date, Field::Pe),
+            Some(20.5)
+        );
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let json = json!([
+            {"trade_date": "2024-01-03", "close": 11.0, "volume": 3000},
+            {"trade_date": "2024-01-02", "close": null, "volume": 2000},
+        ]);
+        let dataset = DailyDataset::from_json(
+            &json,
+            "trade_date",
+            &[(Field::Price, "close"), (Field::Volume, "volume")],
+        )
+        .unwrap();
+
+        let loaded = DailyDataset::<Field>::from_parquet(&dataset.to_parquet().unwrap()).unwrap();
+        let date_start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let date_end = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let df = loaded.to_dataframe(&date_start, &date_end).unwrap();
+        assert_eq!(df, dataset.to_dataframe(&date_start, &date_end).unwrap());
+        assert_eq!(df.column("volume").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(
+            loaded.get_latest_value::<f64>(&date_end, Field::Price),
+            Some(11.0)
+        );
+
+        assert!(DailyDataset::<Field>::from_parquet(b"not parquet").is_err());
     }
 }
@@ -1,31 +1,154 @@
-use chrono::NaiveDate;
-use serde::Serialize;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{data::daily::DailyDataset, utils::datetime::FiscalQuarter};
+use crate::{
+    data::daily::DailyDataset,
+    error::{InvmstError, InvmstResult},
+    financial::{
+        DAILY_VALUATIONS_MAX_STALENESS,
+        price_action::Bar,
+        price_range,
+        price_range::PriceRange,
+        stock::{StockMarginFieldName, StockNorthboundFieldName, StockValuationFieldName},
+    },
+    utils::datetime::FiscalQuarter,
+};
 
 pub type StockFiscalMetricset = (FiscalQuarter, StockMetricset);
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StockDailyData {
-    pub daily_valuations: DailyDataset,
+    /// The max staleness is not serialized and is set again when loaded
+    #[serde(deserialize_with = "deserialize_daily_valuations")]
+    pub daily_valuations: DailyDataset<StockValuationFieldName>,
+    /// Real-time or delayed quote, only present when evaluating as of today
+    #[serde(default)]
+    pub quote: Option<StockQuote>,
+    /// Daily OHLCV bars sorted by date ascending, empty if unavailable
+    #[serde(default)]
+    pub daily_bars: Vec<Bar>,
+    /// Daily balances of margin trading, None if unavailable, e.g. not a margin trading target
+    #[serde(default)]
+    pub margin_balances: Option<DailyDataset<StockMarginFieldName>>,
+    /// Holdings of northbound investors, None if unavailable, e.g. not in the Stock Connect
+    #[serde(default)]
+    pub northbound_holdings: Option<DailyDataset<StockNorthboundFieldName>>,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StockDividend {
     pub date_announce: NaiveDate,
     pub date_record: NaiveDate,
     pub dividend_per_share: f64,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+/// Topics of announcements that may reveal governance problems or interrupted trading
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, strum::Display, strum::EnumIter)]
+pub enum AnnouncementTopic {
+    RelatedParty,
+    Guarantee,
+    AuditorChange,
+    ShareholderSale,
+    TradingHalt,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockAnnouncement {
+    pub date: NaiveDate,
+    pub title: String,
+    pub topic: AnnouncementTopic,
+}
+
+/// Disclosure of any topic with the link to it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockDisclosure {
+    pub date: NaiveDate,
+    pub title: String,
+    pub url: String,
+    /// Full text, only downloaded if enabled in the config
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Kinds of penalties and sanctions, told apart by the announcement titles
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, strum::Display, strum::EnumIter,
+)]
+pub enum PenaltyCategory {
+    Environmental,
+    Regulatory,
+    Investigation,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockPenalty {
+    pub date: NaiveDate,
+    pub title: String,
+    pub category: PenaltyCategory,
+}
+
+/// ESG rating of a third-party agency, grades range from AAA down to C
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StockEsgRating {
+    pub date: Option<NaiveDate>,
+    pub score: Option<f64>,
+    pub grade: Option<String>,
+    pub environmental_grade: Option<String>,
+    pub social_grade: Option<String>,
+    pub governance_grade: Option<String>,
+}
+
+/// Data for the compliance risk, fetched unless disabled in the config
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StockCompliance {
+    pub esg_rating: Option<StockEsgRating>,
+    /// Penalties and sanctions, latest first
+    pub penalties: Vec<StockPenalty>,
+}
+
+/// Forecast of a sell-side research report
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockAnalystForecast {
+    pub date: NaiveDate,
+    pub institution: String,
+    pub rating: Option<String>,
+    /// Fiscal year of the EPS estimate, the next year is estimated as well
+    pub fiscal_year: i32,
+    pub eps: Option<f64>,
+    pub eps_next_year: Option<f64>,
+    pub target_price: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockShareChange {
+    pub date: NaiveDate,
+    /// Total shares after the change
+    pub total_shares: f64,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StockEvents {
     pub dividends: Vec<StockDividend>,
+    pub announcements: Vec<StockAnnouncement>,
+    /// Recent disclosures of all topics, latest first
+    pub disclosures: Vec<StockDisclosure>,
+    /// Changes of total shares sorted by date ascending
+    pub share_changes: Vec<StockShareChange>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StockFinancialSummary {
     pub asset_turnover: Option<f64>,
     pub book_value_per_share: Option<f64>,
+    /// Cash paid for fixed, intangible and other long-term assets, accumulated within the fiscal
+    /// year
+    pub capital_expenditure: Option<f64>,
+    pub cash_and_equivalents: Option<f64>,
     pub cash_ratio: Option<f64>,
     pub cost_of_profit: Option<f64>,
     pub cost_of_revenue: Option<f64>,
@@ -33,14 +156,25 @@ pub struct StockFinancialSummary {
     pub current_ratio: Option<f64>,
     pub days_asset_outstanding: Option<f64>,
     pub days_inventory_outstanding: Option<f64>,
+    pub days_payable_outstanding: Option<f64>,
     pub days_sales_outstanding: Option<f64>,
     pub debt_to_assets: Option<f64>,
     pub debt_to_equity: Option<f64>,
+    /// Depreciation and amortization, accumulated within the fiscal year
+    pub depreciation_amortization: Option<f64>,
     pub earnings_per_share: Option<f64>,
+    /// Earnings before interest and taxes, accumulated within the fiscal year
+    pub ebit: Option<f64>,
+    /// EBIT plus depreciation and amortization, accumulated within the fiscal year
+    pub ebitda: Option<f64>,
     pub free_cash_flow_per_share: Option<f64>,
     pub goodwill: Option<f64>,
     pub gross_margin: Option<f64>,
+    /// Interest expense within finance expenses, accumulated within the fiscal year
+    pub interest_expense: Option<f64>,
     pub inventory_turnover: Option<f64>,
+    /// Long-term borrowings and bonds payable
+    pub long_term_debt: Option<f64>,
     pub net_assets: Option<f64>,
     pub net_margin: Option<f64>,
     pub net_profit: Option<f64>,
@@ -50,19 +184,446 @@ pub struct StockFinancialSummary {
     pub operating_revenue: Option<f64>,
     pub quick_ratio: Option<f64>,
     pub receivables_turnover: Option<f64>,
+    /// Research and development expense, accumulated within the fiscal year
+    pub research_expense: Option<f64>,
     pub return_on_assets: Option<f64>,
     pub return_on_equity: Option<f64>,
     pub return_on_invested_capital: Option<f64>,
     pub revenue_growth: Option<f64>,
+    /// Short-term borrowings and non-current liabilities due within one year
+    pub short_term_debt: Option<f64>,
+    /// Cash paid to and for employees, accumulated within the fiscal year
+    pub staff_compensation: Option<f64>,
+    /// Interest-bearing debt, including borrowings and bonds payable
+    pub total_debt: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockQuote {
+    pub price: f64,
+    pub change: Option<f64>,
+    pub change_rate: Option<f64>,
+    pub prev_close: Option<f64>,
+    pub time: DateTime<Local>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StockInfo {
     pub name: Option<String>,
     pub industry: Option<String>,
+    pub listing_date: Option<NaiveDate>,
+    /// Stake of the largest shareholder, a proxy of insider ownership
+    pub largest_holder_ratio: Option<f64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct StockMetricset {
     pub financial_summary: StockFinancialSummary,
+    /// Empty if the full statements are unavailable, e.g. for HKEX
+    #[serde(default)]
+    pub statements: StockStatements,
+}
+
+/// Amounts are accumulated within the fiscal year
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IncomeStatement {
+    pub operating_revenue: Option<f64>,
+    pub operating_cost: Option<f64>,
+    pub selling_expense: Option<f64>,
+    pub administrative_expense: Option<f64>,
+    pub research_expense: Option<f64>,
+    pub finance_expense: Option<f64>,
+    /// Interest expense within finance expenses
+    pub interest_expense: Option<f64>,
+    pub operating_profit: Option<f64>,
+    pub total_profit: Option<f64>,
+    pub income_tax: Option<f64>,
+    pub net_profit: Option<f64>,
+    /// Net profit attributable to shareholders of the parent company
+    pub net_profit_attributable: Option<f64>,
+}
+
+/// Balances at the end of the fiscal quarter
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BalanceSheet {
+    pub cash: Option<f64>,
+    pub accounts_receivable: Option<f64>,
+    pub inventory: Option<f64>,
+    pub total_current_assets: Option<f64>,
+    pub fixed_assets: Option<f64>,
+    pub intangible_assets: Option<f64>,
+    pub goodwill: Option<f64>,
+    pub total_assets: Option<f64>,
+    pub short_term_borrowings: Option<f64>,
+    pub accounts_payable: Option<f64>,
+    pub noncurrent_liabilities_due_within_one_year: Option<f64>,
+    pub total_current_liabilities: Option<f64>,
+    pub long_term_borrowings: Option<f64>,
+    pub bonds_payable: Option<f64>,
+    pub total_liabilities: Option<f64>,
+    /// Equity attributable to shareholders of the parent company
+    pub equity_attributable: Option<f64>,
+    pub total_equity: Option<f64>,
+}
+
+/// Amounts are accumulated within the fiscal year
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CashFlowStatement {
+    pub operating_cash_flow: Option<f64>,
+    pub investing_cash_flow: Option<f64>,
+    pub financing_cash_flow: Option<f64>,
+    /// Cash paid for fixed, intangible and other long-term assets
+    pub capital_expenditure: Option<f64>,
+    /// Depreciation of fixed assets, oil and gas assets and productive biological assets
+    pub depreciation: Option<f64>,
+    pub intangible_amortization: Option<f64>,
+    pub prepaid_expense_amortization: Option<f64>,
+    pub staff_cash_paid: Option<f64>,
+    /// Cash paid for dividends, profits and interest
+    pub dividends_interest_paid: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StockStatements {
+    pub income: IncomeStatement,
+    pub balance_sheet: BalanceSheet,
+    pub cash_flow: CashFlowStatement,
+}
+
+/// Data of a stock the masters analyze, fiscal metricsets are sorted by fiscal quarter descending
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockSnapshot {
+    pub ticker: String,
+    #[serde(default)]
+    pub info: StockInfo,
+    #[serde(default)]
+    pub events: StockEvents,
+    pub daily_data: StockDailyData,
+    #[serde(default)]
+    pub fiscal_metricsets: Vec<StockFiscalMetricset>,
+}
+
+impl StockSnapshot {
+    /// Snapshot saved by to_json, fiscal metricsets are sorted again in case they were edited
+    pub fn from_json(json_str: &str) -> InvmstResult<Self> {
+        let mut snapshot: StockSnapshot = serde_json::from_str(json_str)?;
+        if snapshot.ticker.trim().is_empty() {
+            return Err(InvmstError::Required(
+                "TICKER_REQUIRED",
+                "Missing ticker of the snapshot".to_string(),
+            ));
+        }
+
+        snapshot
+            .fiscal_metricsets
+            .sort_by_key(|(fiscal_quarter, _)| std::cmp::Reverse(fiscal_quarter.end_date()));
+
+        Ok(snapshot)
+    }
+
+    pub fn to_json(&self) -> InvmstResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl StockFinancialSummary {
+    /// No field is present, e.g. for a fiscal quarter before the company listed
+    pub fn is_empty(&self) -> bool {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| {
+                value
+                    .as_object()
+                    .map(|fields| fields.values().all(|v| v.is_null()))
+            })
+            .unwrap_or(true)
+    }
+
+    /// Fill the capital structure, operating profit and cash flow fields from the full statements
+    pub fn with_statements(mut self, statements: &StockStatements) -> Self {
+        let balance_sheet = &statements.balance_sheet;
+        let income = &statements.income;
+        let cash_flow = &statements.cash_flow;
+
+        self.cash_and_equivalents = balance_sheet.cash;
+
+        // 只计入有息负债，应付款属于营运资本
+        self.short_term_debt = sum_present(&[
+            balance_sheet.short_term_borrowings,
+            balance_sheet.noncurrent_liabilities_due_within_one_year,
+        ]);
+        self.long_term_debt = sum_present(&[
+            balance_sheet.long_term_borrowings,
+            balance_sheet.bonds_payable,
+        ]);
+        self.total_debt = sum_present(&[self.short_term_debt, self.long_term_debt]);
+        // 资产负债表存在但没有有息负债科目时，视为无有息负债而非缺失
+        if self.total_debt.is_none() && balance_sheet.total_liabilities.is_some() {
+            self.total_debt = Some(0.0);
+        }
+
+        self.ebit = income
+            .total_profit
+            .map(|v| v + income.interest_expense.unwrap_or(0.0));
+        self.interest_expense = income.interest_expense;
+        self.research_expense = income.research_expense;
+
+        self.capital_expenditure = cash_flow.capital_expenditure;
+        self.staff_compensation = cash_flow.staff_cash_paid;
+        self.depreciation_amortization = statements.depreciation_amortization();
+        // 缺少折旧摊销时 EBITDA 会被低估，宁可留空
+        self.ebitda = self
+            .ebit
+            .zip(self.depreciation_amortization)
+            .map(|(ebit, depreciation_amortization)| ebit + depreciation_amortization);
+
+        self
+    }
+}
+
+impl PenaltyCategory {
+    /// Category of a penalty announcement by its title
+    pub fn classify(title: &str) -> Self {
+        if title.contains("立案") {
+            PenaltyCategory::Investigation
+        } else if ["环保", "环境", "排污", "生态"]
+            .iter()
+            .any(|word| title.contains(word))
+        {
+            PenaltyCategory::Environmental
+        } else {
+            PenaltyCategory::Regulatory
+        }
+    }
+}
+
+impl StockStatements {
+    /// Depreciation and amortization, None if none of them is reported
+    pub fn depreciation_amortization(&self) -> Option<f64> {
+        sum_present(&[
+            self.cash_flow.depreciation,
+            self.cash_flow.intangible_amortization,
+            self.cash_flow.prepaid_expense_amortization,
+        ])
+    }
+
+    /// Operating cash flow minus capital expenditure
+    pub fn free_cash_flow(&self) -> Option<f64> {
+        Some(
+            self.cash_flow.operating_cash_flow? - self.cash_flow.capital_expenditure.unwrap_or(0.0),
+        )
+    }
+
+    /// Net current asset value of Graham, current assets minus all liabilities
+    pub fn net_current_asset_value(&self) -> Option<f64> {
+        Some(self.balance_sheet.total_current_assets? - self.balance_sheet.total_liabilities?)
+    }
+
+    /// Owner earnings of Buffett, net profit attributable plus depreciation and amortization minus
+    /// capital expenditure, which stands in for the maintenance capital expenditure
+    pub fn owner_earnings(&self) -> Option<f64> {
+        Some(
+            self.income.net_profit_attributable? + self.depreciation_amortization().unwrap_or(0.0)
+                - self.cash_flow.capital_expenditure.unwrap_or(0.0),
+        )
+    }
+}
+
+impl StockDailyData {
+    /// Valuation of the date, rescaled to the quote price if the date is not before the quote.
+    /// All valuation fields are proportional to the price, so the latest daily row is scaled by the
+    /// ratio of the quote price to its close price.
+    pub fn get_valuation(
+        &self,
+        date: &NaiveDate,
+        field_name: &StockValuationFieldName,
+    ) -> Option<f64> {
+        let value = self
+            .daily_valuations
+            .get_latest_value::<f64>(date, *field_name)?;
+
+        if let Some(quote) = &self.quote {
+            if *date >= quote.time.date_naive() {
+                if let Some(close) = self
+                    .daily_valuations
+                    .get_latest_value::<f64>(date, StockValuationFieldName::Price)
+                {
+                    if close > 0.0 {
+                        return Some(value * quote.price / close);
+                    }
+                }
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Position of the price of the date within the prices of the backward window
+    pub fn get_price_range(&self, date: &NaiveDate, backward_days: i64) -> Option<PriceRange> {
+        let prices = self.daily_valuations.get_values::<f64>(
+            &(*date - chrono::Duration::days(backward_days)),
+            date,
+            StockValuationFieldName::Price,
+        );
+        let price = self.get_valuation(date, &StockValuationFieldName::Price)?;
+
+        price_range::compute(&prices, date, price)
+    }
+}
+
+/// Sum of the present values, None if none is present
+fn sum_present(values: &[Option<f64>]) -> Option<f64> {
+    let present: Vec<f64> = values.iter().flatten().copied().collect();
+    (!present.is_empty()).then(|| present.iter().sum())
+}
+
+fn deserialize_daily_valuations<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DailyDataset<StockValuationFieldName>, D::Error> {
+    Ok(DailyDataset::deserialize(deserializer)?.with_max_staleness(DAILY_VALUATIONS_MAX_STALENESS))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::utils::datetime::Quarter;
+
+    #[test]
+    fn test_snapshot_json() {
+        let snapshot = StockSnapshot {
+            ticker: "SSE:600519".to_string(),
+            info: StockInfo {
+                name: Some("贵州茅台".to_string()),
+                ..Default::default()
+            },
+            events: StockEvents::default(),
+            daily_data: StockDailyData {
+                daily_valuations: DailyDataset::from_json(
+                    &json!([
+                        {"date": "2024-12-30", "close": 1500.0},
+                        {"date": "2024-12-31", "close": 1525.0},
+                    ]),
+                    "date",
+                    &[(StockValuationFieldName::Price, "close")],
+                )
+                .unwrap(),
+                quote: None,
+                daily_bars: vec![],
+                margin_balances: None,
+                northbound_holdings: None,
+            },
+            fiscal_metricsets: [Quarter::Q3, Quarter::Q4]
+                .into_iter()
+                .map(|quarter| {
+                    (
+                        FiscalQuarter {
+                            year: 2024,
+                            quarter,
+                        },
+                        StockMetricset {
+                            financial_summary: StockFinancialSummary {
+                                gross_margin: Some(0.9),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+        };
+
+        let loaded = StockSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.ticker, snapshot.ticker);
+        assert_eq!(loaded.info.name, snapshot.info.name);
+        assert_eq!(
+            loaded.daily_data.get_valuation(
+                &NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+                &StockValuationFieldName::Price
+            ),
+            Some(1525.0)
+        );
+        assert_eq!(
+            loaded.daily_data.get_valuation(
+                &NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+                &StockValuationFieldName::Price
+            ),
+            None
+        );
+        assert_eq!(loaded.fiscal_metricsets[0].0.quarter, Quarter::Q4);
+        assert_eq!(
+            loaded.fiscal_metricsets[1].1.financial_summary.gross_margin,
+            Some(0.9)
+        );
+
+        assert!(
+            StockSnapshot::from_json(r#"{"ticker": "", "daily_data": {"daily_valuations": []}}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_statements() {
+        let statements = StockStatements {
+            income: IncomeStatement {
+                total_profit: Some(120.0),
+                interest_expense: Some(10.0),
+                net_profit_attributable: Some(90.0),
+                ..Default::default()
+            },
+            balance_sheet: BalanceSheet {
+                cash: Some(50.0),
+                total_current_assets: Some(300.0),
+                short_term_borrowings: Some(40.0),
+                long_term_borrowings: Some(60.0),
+                total_liabilities: Some(200.0),
+                ..Default::default()
+            },
+            cash_flow: CashFlowStatement {
+                operating_cash_flow: Some(110.0),
+                capital_expenditure: Some(30.0),
+                depreciation: Some(15.0),
+                intangible_amortization: Some(5.0),
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(statements.free_cash_flow(), Some(80.0));
+        assert_eq!(statements.net_current_asset_value(), Some(100.0));
+        assert_eq!(statements.owner_earnings(), Some(80.0));
+        assert_eq!(StockStatements::default().owner_earnings(), None);
+
+        let summary = StockFinancialSummary::default().with_statements(&statements);
+        assert_eq!(summary.short_term_debt, Some(40.0));
+        assert_eq!(summary.long_term_debt, Some(60.0));
+        assert_eq!(summary.total_debt, Some(100.0));
+        assert_eq!(summary.ebit, Some(130.0));
+        assert_eq!(summary.ebitda, Some(150.0));
+
+        let summary = StockFinancialSummary::default().with_statements(&StockStatements {
+            cash_flow: CashFlowStatement::default(),
+            ..statements
+        });
+        assert_eq!(summary.ebit, Some(130.0));
+        assert_eq!(summary.ebitda, None);
+
+        let summary = StockFinancialSummary::default().with_statements(&StockStatements {
+            balance_sheet: BalanceSheet {
+                total_liabilities: Some(20.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert_eq!(summary.total_debt, Some(0.0));
+
+        let summary = StockFinancialSummary::default().with_statements(&StockStatements::default());
+        assert_eq!(summary.total_debt, None);
+        assert_eq!(summary.ebitda, None);
+    }
 }
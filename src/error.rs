@@ -2,6 +2,10 @@ pub type InvmstResult<T> = Result<T, InvmstError>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum InvmstError {
+    #[cfg(feature = "charts")]
+    #[error("[Chart Error] {0}")]
+    ChartError(String),
+
     #[error("[Concurrent Error] {0}")]
     ConcurrentError(#[from] ::tokio::task::JoinError),
 
@@ -11,6 +15,10 @@ pub enum InvmstError {
     #[error("[Dataframe Error] {0}")]
     DataframeError(#[from] ::polars::error::PolarsError),
 
+    #[cfg(feature = "grpc")]
+    #[error("[gRPC Error] {0}")]
+    GrpcError(#[from] ::tonic::transport::Error),
+
     #[error("[HTTP Request Error] {0}")]
     HttpRequestError(#[from] ::reqwest::Error),
 
@@ -23,6 +31,13 @@ pub enum InvmstError {
     #[error("[IO Error] {0}")]
     IoError(#[from] std::io::Error),
 
+    #[cfg(feature = "keyring")]
+    #[error("[Keyring Error] {0}")]
+    KeyringError(#[from] ::keyring::Error),
+
+    #[error("[LLM Error] {0}")]
+    LlmError(String),
+
     #[error("[No Data] {1}")]
     NoData(&'static str, String),
 
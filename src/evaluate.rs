@@ -1,67 +1,818 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
-use chrono::NaiveDate;
-use log::debug;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use futures::{StreamExt, stream::FuturesUnordered};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-use tokio::task::JoinHandle;
+use tokio::sync::mpsc::{self, Receiver, Sender};
 
 use crate::{
-    data::stock::StockDailyData,
+    CHANNEL_BUFFER_DEFAULT, config,
+    data::stock::{StockDailyData, StockFiscalMetricset, StockSnapshot},
     error::*,
     financial::*,
-    master::{Master, MasterAnalysis, MasterAnalyzeOptions},
+    history,
+    llm::ChatCompletionOptions,
+    master::{
+        Holding, Master, MasterAnalysis, MasterAnalyzeOptions, ShortHistory, agent, bear_case,
+        digest, management, market, pre_mortem, prompt::PromptContext,
+    },
     ticker::Ticker,
     utils,
 };
 
+#[derive(Clone)]
 pub struct EvaluateOptions {
-    pub backward_days: i64,
+    /// Days to backward, falls back to the config
+    pub backward_days: Option<i64>,
     pub date: Option<NaiveDate>,
     pub masters: Vec<String>,
+    /// Masters or style groups to leave out of the selected masters, falls back to the config
+    pub exclude_masters: Vec<String>,
+    /// Sample valuation assumptions to get a fair value distribution rather than a point
+    pub monte_carlo: bool,
+    /// Existing position, masters return an explicit action if present
+    pub holding: Option<Holding>,
+    /// Construct the strongest bear argument after the masters finish
+    pub bear_case: bool,
+    /// Imagine the investment failed in 3 years and list the likely causes
+    pub pre_mortem: bool,
+    /// Let the masters request more data through tools, within a budget of calls
+    pub agentic: bool,
+    /// Aspects the masters should pay special attention to, e.g. "receivables"
+    pub focus_areas: Vec<String>,
+    /// Comment of the user on context the data cannot capture, referenced by the masters
+    pub note: Option<String>,
+    /// LLM options of this evaluation only, e.g. a lower temperature or another model
+    pub llm_options: ChatCompletionOptions,
 }
 
+#[derive(Clone, Default)]
+pub struct FinancialsOptions {
+    /// Days to backward, falls back to the config
+    pub backward_days: Option<i64>,
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Evaluation {
+    pub id: String,
+    pub ticker: String,
+    pub date: NaiveDate,
+    pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub price_summary: Option<PriceSummary>,
     pub master_analyses: HashMap<Master, MasterAnalysis>,
+    /// Masters whose analysis failed with the error, the analyses of the others are kept
+    #[serde(default)]
+    pub master_errors: HashMap<Master, String>,
+    #[serde(default)]
+    pub bear_case: Option<bear_case::BearCase>,
+    #[serde(default)]
+    pub failure_modes: Option<Vec<pre_mortem::FailureMode>>,
+    /// Comment of the user given to the masters
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Implausible values of the data source, dropped or flagged before the analysis
+    #[serde(default)]
+    pub data_anomalies: Vec<anomaly::DataAnomaly>,
+    /// Key figures differing between the configured data sources
+    #[serde(default)]
+    pub data_discrepancies: Vec<reconcile::Discrepancy>,
+    /// Summary of the recent disclosures, also given to the masters
+    #[serde(default)]
+    pub recent_developments: Option<digest::RecentDevelopments>,
+    /// Penalties and ESG grades, None if skipped in the config
+    #[serde(default)]
+    pub compliance_risk: Option<compliance::ComplianceRisk>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IndexEvaluation {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub fundamentals: index_fundamentals::IndexFundamentals,
+    pub price_range: Option<price_range::PriceRange>,
+    pub analysis: MasterAnalysis,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PriceSummary {
+    pub price: f64,
+    pub change: Option<f64>,
+    pub change_rate: Option<f64>,
+    /// Whether the price is from a real-time or delayed quote rather than a daily close
+    pub is_quote: bool,
+    pub low_52w: Option<f64>,
+    pub high_52w: Option<f64>,
+}
+
+#[derive(Debug)]
+pub enum EvaluationEvent {
+    Stage(EvaluationStage),
+    MasterStarted(Master),
+    MasterFinished(Master, MasterAnalysis),
+    Finished(Box<Evaluation>),
+    Error(InvmstError),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
+pub enum EvaluationStage {
+    #[strum(serialize = "Fetching stock info")]
+    FetchStockInfo,
+
+    #[strum(serialize = "Fetching stock events")]
+    FetchStockEvents,
+
+    #[strum(serialize = "Fetching daily valuations")]
+    FetchDailyValuations,
+
+    #[strum(serialize = "Fetching fiscal metrics")]
+    FetchFiscalMetrics,
+
+    #[strum(serialize = "Validating data")]
+    ValidateData,
+
+    #[strum(serialize = "Reconciling data sources")]
+    ReconcileData,
+
+    #[strum(serialize = "Assessing compliance risk")]
+    AssessCompliance,
+
+    #[strum(serialize = "Summarizing announcements")]
+    SummarizeAnnouncements,
+
+    #[strum(serialize = "Analyzing management discussion")]
+    AnalyzeManagementDiscussion,
+
+    #[strum(serialize = "Fetching analyst consensus")]
+    FetchAnalystConsensus,
+
+    #[strum(serialize = "Analyzing")]
+    Analyze,
+
+    #[strum(serialize = "Constructing bear case")]
+    ConstructBearCase,
+
+    #[strum(serialize = "Running pre-mortem")]
+    RunPreMortem,
+}
+
+pub struct EvaluationStream {
+    id: String,
+    receiver: Receiver<EvaluationEvent>,
 }
 
 pub async fn run(ticker: &str, options: &EvaluateOptions) -> InvmstResult<Evaluation> {
+    wait_finished(run_stream(ticker, options).await?).await
+}
+
+/// Evaluate with the data of the snapshot instead of fetching it, masters needing data beyond the
+/// snapshot, e.g. macro data or benchmark indexes, still fetch that part
+pub async fn run_snapshot(
+    snapshot: StockSnapshot,
+    options: &EvaluateOptions,
+) -> InvmstResult<Evaluation> {
+    let ticker = Ticker::from_str(&snapshot.ticker)?;
+
+    wait_finished(start(ticker, Some(snapshot), options)?).await
+}
+
+pub async fn run_stream(ticker: &str, options: &EvaluateOptions) -> InvmstResult<EvaluationStream> {
+    start(Ticker::from_str(ticker)?, None, options)
+}
+
+pub async fn run_snapshot_stream(
+    snapshot: StockSnapshot,
+    options: &EvaluateOptions,
+) -> InvmstResult<EvaluationStream> {
+    let ticker = Ticker::from_str(&snapshot.ticker)?;
+
+    start(ticker, Some(snapshot), options)
+}
+
+/// Fetch the data of the stock the masters analyze, without evaluating
+pub async fn run_financials(
+    ticker: &str,
+    options: &FinancialsOptions,
+) -> InvmstResult<StockSnapshot> {
     let ticker = Ticker::from_str(ticker)?;
-    debug!("{ticker:?}");
 
-    let stock_info = get_stock_info(&ticker).await?;
+    let mut options = options.clone();
+    if options.backward_days.is_none() {
+        options.backward_days = config::load()?.backward_days;
+    }
+
+    fetch_snapshot(&ticker, &options, None).await
+}
+
+/// Evaluate an index by its aggregated constituent fundamentals, symbol is in the form of 'sh000300'
+pub async fn run_index(symbol: &str, options: &EvaluateOptions) -> InvmstResult<IndexEvaluation> {
+    let options = with_config_defaults(options)?;
+    let backward_days = options.backward_days.unwrap_or(BACKWARD_DAYS_DEFAULT);
+    let date = options.date.unwrap_or(Local::now().date_naive());
+
+    let fundamentals = get_index_fundamentals(symbol, &date).await?;
+    debug!("{fundamentals:?}");
+    if fundamentals.constituents_count == 0 {
+        return Err(InvmstError::NoData(
+            "NO_INDEX_CONSTITUENTS",
+            format!("No constituents of index '{symbol}'"),
+        ));
+    }
+
+    let daily_prices = get_index_daily_prices(symbol).await?;
+    let price_range = daily_prices
+        .get_latest_value::<f64>(&date, index::IndexDailyFieldName::Close)
+        .and_then(|price| {
+            let prices = daily_prices.get_values::<f64>(
+                &(date - Duration::days(backward_days)),
+                &date,
+                index::IndexDailyFieldName::Close,
+            );
+            price_range::compute(&prices, &date, price)
+        });
+
+    let prompt_context = PromptContext {
+        backward_days: Some(backward_days),
+        focus_areas: options.focus_areas.clone(),
+        note: options.note.clone(),
+        ..PromptContext::from_config()
+    };
+    let analysis = market::analyze_index(
+        symbol,
+        &fundamentals,
+        price_range.as_ref(),
+        &prompt_context,
+        &options.llm_options,
+    )
+    .await?;
+
+    Ok(IndexEvaluation {
+        symbol: symbol.to_string(),
+        date,
+        fundamentals,
+        price_range,
+        analysis,
+    })
+}
+
+impl Evaluation {
+    pub fn rating_avg(&self) -> Option<u64> {
+        if self.master_analyses.is_empty() {
+            return None;
+        }
+
+        let sum = self
+            .master_analyses
+            .values()
+            .map(|master_analysis| master_analysis.rating)
+            .sum::<u64>();
+
+        Some((sum as f64 / self.master_analyses.len() as f64).round() as u64)
+    }
+}
+
+#[cfg(test)]
+impl Evaluation {
+    /// Evaluation of 'SSE:600900' with only the ratings of the masters, shared by tests
+    pub fn with_ratings(id: &str, date: NaiveDate, ratings: &[(Master, u64)]) -> Self {
+        Evaluation {
+            id: id.to_string(),
+            ticker: "SSE:600900".to_string(),
+            date,
+            created_at: Local::now(),
+            price_summary: None,
+            master_analyses: ratings
+                .iter()
+                .map(|(master, rating)| (*master, MasterAnalysis::with_rating(*rating)))
+                .collect(),
+            master_errors: HashMap::new(),
+            bear_case: None,
+            failure_modes: None,
+            note: None,
+            data_anomalies: vec![],
+            data_discrepancies: vec![],
+            recent_developments: None,
+            compliance_risk: None,
+        }
+    }
+}
+
+impl EvaluationStream {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn close(&mut self) {
+        self.receiver.close()
+    }
+
+    pub async fn next(&mut self) -> Option<EvaluationEvent> {
+        self.receiver.recv().await
+    }
+}
+
+pub static BACKWARD_DAYS_DEFAULT: i64 = 1100;
+
+async fn evaluate(
+    ticker: &Ticker,
+    snapshot: Option<StockSnapshot>,
+    masters: &[Master],
+    options: &EvaluateOptions,
+    mut evaluation: Evaluation,
+    sender: &Sender<EvaluationEvent>,
+) -> InvmstResult<(Evaluation, Arc<StockSnapshot>)> {
+    let backward_days = options.backward_days.unwrap_or(BACKWARD_DAYS_DEFAULT);
+    let is_snapshot = snapshot.is_some();
+
+    // Cyclicality is detected from the fetched earnings history, a snapshot is analyzed as is
+    let (mut snapshot, cyclicality) = match snapshot {
+        Some(snapshot) => (snapshot, None),
+        None => {
+            let financials_options = FinancialsOptions {
+                backward_days: Some(backward_days),
+                date: options.date,
+            };
+            let snapshot = fetch_snapshot(ticker, &financials_options, Some(sender)).await?;
+            let cyclicality = match get_stock_cyclicality(ticker, &evaluation.date).await {
+                Ok(cyclicality) => Some(cyclicality),
+                Err(err) => {
+                    warn!("[Cyclicality] {err}");
+                    None
+                }
+            };
+            debug!("{cyclicality:?}");
+
+            (snapshot, cyclicality)
+        }
+    };
+    // 数据源偶有明显错误的数值，在分析前剔除或标记
+    let _ = sender
+        .send(EvaluationEvent::Stage(EvaluationStage::ValidateData))
+        .await;
+    evaluation.data_anomalies =
+        anomaly::sanitize(&mut snapshot.daily_data, &mut snapshot.fiscal_metricsets);
+    for data_anomaly in &evaluation.data_anomalies {
+        warn!("[Data Anomaly] {}", data_anomaly.assessment());
+    }
+
+    // 配置了多个数据源时交叉核对关键数据，提示单一数据源可能过时或有误
+    let data_sources = config::data_sources(&config::load()?);
+    if let Some((fiscal_quarter, _)) = snapshot
+        .fiscal_metricsets
+        .first()
+        .filter(|_| data_sources.len() > 1)
+    {
+        let _ = sender
+            .send(EvaluationEvent::Stage(EvaluationStage::ReconcileData))
+            .await;
+        match get_stock_discrepancies(ticker, &data_sources, fiscal_quarter, &evaluation.date).await
+        {
+            Ok(data_discrepancies) => evaluation.data_discrepancies = data_discrepancies,
+            Err(err) => {
+                warn!("[Reconcile] {err}");
+            }
+        }
+    }
+    // 合规风险供关注治理的大师以及看空论证、事前验尸参考，用户可在配置中关闭
+    if !is_snapshot
+        && !config::load()?.skip_compliance
+        && (options.bear_case
+            || options.pre_mortem
+            || masters.iter().any(|master| master.uses_compliance()))
+    {
+        let _ = sender
+            .send(EvaluationEvent::Stage(EvaluationStage::AssessCompliance))
+            .await;
+        let stock_compliance = get_stock_compliance(ticker, &evaluation.date).await;
+        evaluation.compliance_risk = Some(compliance::assess(&stock_compliance, &evaluation.date));
+    }
+    debug!("{:?}", evaluation.compliance_risk);
+
+    // 各位大师共享同一份数据，不必逐一复制
+    let snapshot = Arc::new(snapshot);
+
+    evaluation.price_summary = summarize_price(&snapshot.daily_data, &evaluation.date);
+
+    let requested_quarters = fiscal_quarters_count(backward_days);
+    let short_history =
+        (snapshot.fiscal_metricsets.len() < requested_quarters).then_some(ShortHistory {
+            listing_date: snapshot.info.listing_date,
+            quarters: snapshot.fiscal_metricsets.len(),
+            requested_quarters,
+        });
+
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let suspension = suspension::detect(
+        &snapshot.daily_data.daily_valuations.get_values::<f64>(
+            &(date - Duration::days(backward_days)),
+            &date,
+            stock::StockValuationFieldName::Price,
+        ),
+        &snapshot.events.announcements,
+        &date,
+    );
+    debug!("{suspension:?}");
+
+    // 公告较多时先汇总为摘要，各位大师共用，不必各自读取全部公告；近期动态在同一轮中一并总结
+    let with_digest = masters.iter().any(|master| master.uses_announcements());
+    let announcement_digest = if (with_digest && !snapshot.events.announcements.is_empty())
+        || !snapshot.events.disclosures.is_empty()
+    {
+        let _ = sender
+            .send(EvaluationEvent::Stage(
+                EvaluationStage::SummarizeAnnouncements,
+            ))
+            .await;
+        match digest::summarize_announcements(
+            &snapshot.events.announcements,
+            &snapshot.events.disclosures,
+            with_digest,
+            &options.llm_options,
+        )
+        .await
+        {
+            Ok((announcement_digest, recent_developments)) => {
+                evaluation.recent_developments = recent_developments;
+                announcement_digest
+            }
+            Err(err) => {
+                warn!("[Digest] {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    debug!("{announcement_digest:?}");
+
+    // 管理层讨论与分析需要读取定期报告，快照按原样分析时跳过
+    let management_discussion = if !is_snapshot
+        && masters
+            .iter()
+            .any(|master| master.uses_management_discussion())
+    {
+        let _ = sender
+            .send(EvaluationEvent::Stage(
+                EvaluationStage::AnalyzeManagementDiscussion,
+            ))
+            .await;
+        let result = match get_stock_management_discussion(ticker, &evaluation.date).await {
+            Ok(Some(report)) => management::analyze_discussion(&report, &options.llm_options).await,
+            Ok(None) => Ok(None),
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(management_discussion) => management_discussion,
+            Err(err) => {
+                warn!("[Management Discussion] {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    debug!("{management_discussion:?}");
+
+    // 卖方预期只提供给关注预期的大师，快照按原样分析时跳过
+    let analyst_consensus =
+        if !is_snapshot && masters.iter().any(|master| master.uses_analyst_consensus()) {
+            let _ = sender
+                .send(EvaluationEvent::Stage(
+                    EvaluationStage::FetchAnalystConsensus,
+                ))
+                .await;
+            match get_stock_analyst_forecasts(ticker, &evaluation.date).await {
+                Ok(forecasts) => consensus::compute(
+                    &forecasts,
+                    snapshot
+                        .daily_data
+                        .daily_valuations
+                        .get_latest_value::<f64>(&date, stock::StockValuationFieldName::Price),
+                    &evaluation.date,
+                ),
+                Err(err) => {
+                    warn!("[Analyst Consensus] {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+    debug!("{analyst_consensus:?}");
+
+    let toolbox = options.agentic.then(|| {
+        Arc::new(agent::Toolbox {
+            snapshot: snapshot.clone(),
+            date,
+            budget: agent::TOOL_CALLS_BUDGET_DEFAULT,
+        })
+    });
+
+    let prompt_context = PromptContext {
+        industry: snapshot.info.industry.clone(),
+        backward_days: Some(backward_days),
+        focus_areas: options.focus_areas.clone(),
+        note: options.note.clone(),
+        data_anomalies: evaluation
+            .data_anomalies
+            .iter()
+            .filter(|data_anomaly| !data_anomaly.dropped)
+            .map(|data_anomaly| data_anomaly.assessment())
+            .collect(),
+        ..PromptContext::from_config()
+    };
+
+    let _ = sender
+        .send(EvaluationEvent::Stage(EvaluationStage::Analyze))
+        .await;
+    let mut handles = FuturesUnordered::new();
+    for master in masters.iter().copied() {
+        let options = MasterAnalyzeOptions {
+            backward_days,
+            date: options.date,
+            monte_carlo: options.monte_carlo,
+            cyclicality: cyclicality.clone(),
+            holding: options.holding.clone(),
+            short_history: short_history.clone(),
+            suspension: suspension.clone(),
+            announcement_digest: announcement_digest.clone(),
+            recent_developments: evaluation.recent_developments.clone(),
+            management_discussion: management_discussion.clone(),
+            analyst_consensus: analyst_consensus
+                .clone()
+                .filter(|_| master.uses_analyst_consensus()),
+            compliance_risk: evaluation.compliance_risk.clone(),
+            toolbox: toolbox.clone(),
+            prompt_context: prompt_context.clone(),
+            llm_options: options.llm_options.clone(),
+        };
+
+        let snapshot = snapshot.clone();
+        let handle = tokio::spawn(async move { master.analyze(&snapshot, &options).await });
+        handles.push(async move { (master, handle.await) });
+
+        let _ = sender.send(EvaluationEvent::MasterStarted(master)).await;
+    }
+
+    // 单个大师失败时保留其他大师的分析，全部失败才作为评估失败
+    let mut last_err: Option<InvmstError> = None;
+    while let Some((master, result)) = handles.next().await {
+        match result.map_err(InvmstError::from).and_then(|result| result) {
+            Ok(master_analysis) => {
+                evaluation
+                    .master_analyses
+                    .insert(master, master_analysis.clone());
+
+                let _ = sender
+                    .send(EvaluationEvent::MasterFinished(master, master_analysis))
+                    .await;
+            }
+            Err(err) => {
+                warn!("[{master}] {err}");
+                evaluation.master_errors.insert(master, err.to_string());
+                last_err = Some(err);
+            }
+        }
+    }
+    if evaluation.master_analyses.is_empty() {
+        if let Some(err) = last_err {
+            return Err(err);
+        }
+    }
+
+    if options.bear_case {
+        let _ = sender
+            .send(EvaluationEvent::Stage(EvaluationStage::ConstructBearCase))
+            .await;
+        match bear_case::construct(
+            &snapshot.info,
+            &snapshot.daily_data,
+            &snapshot.fiscal_metricsets,
+            &evaluation.master_analyses,
+            evaluation.compliance_risk.as_ref(),
+            &evaluation.date,
+            &options.llm_options,
+        )
+        .await
+        {
+            Ok(bear_case) => evaluation.bear_case = Some(bear_case),
+            Err(err) => {
+                warn!("[Bear Case] {err}");
+            }
+        }
+    }
+
+    if options.pre_mortem {
+        let _ = sender
+            .send(EvaluationEvent::Stage(EvaluationStage::RunPreMortem))
+            .await;
+        match pre_mortem::analyze(
+            &snapshot.info,
+            &snapshot.daily_data,
+            &snapshot.fiscal_metricsets,
+            &evaluation.master_analyses,
+            evaluation.compliance_risk.as_ref(),
+            &evaluation.date,
+            &options.llm_options,
+        )
+        .await
+        {
+            Ok(failure_modes) => evaluation.failure_modes = Some(failure_modes),
+            Err(err) => {
+                warn!("[Pre-mortem] {err}");
+            }
+        }
+    }
+
+    Ok((evaluation, snapshot))
+}
+
+/// Stages are sent to the sender if present, e.g. to report the progress of an evaluation
+async fn fetch_snapshot(
+    ticker: &Ticker,
+    options: &FinancialsOptions,
+    sender: Option<&Sender<EvaluationEvent>>,
+) -> InvmstResult<StockSnapshot> {
+    let backward_days = options.backward_days.unwrap_or(BACKWARD_DAYS_DEFAULT);
+    let date = options.date.unwrap_or(Local::now().date_naive());
+
+    send_stage(sender, EvaluationStage::FetchStockInfo).await;
+    let stock_info = get_stock_info(ticker).await?;
     debug!("{stock_info:?}");
 
-    let stock_events =
-        get_stock_events(&ticker, options.date.as_ref(), options.backward_days).await?;
+    send_stage(sender, EvaluationStage::FetchStockEvents).await;
+    let stock_events = get_stock_events(ticker, options.date.as_ref(), backward_days).await?;
     debug!("{stock_events:?}");
 
-    let daily_valuations = get_stock_daily_valuations(&ticker).await?;
-    let stock_daily_data = StockDailyData { daily_valuations };
+    send_stage(sender, EvaluationStage::FetchDailyValuations).await;
+    let daily_valuations = get_stock_daily_valuations(ticker).await?;
+    let quote = if options.date.is_none() {
+        match get_stock_quote(ticker).await {
+            Ok(quote) => Some(quote),
+            Err(err) => {
+                warn!("[Quote] {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let daily_bars =
+        match get_stock_daily_bars(ticker, &(date - Duration::days(backward_days)), &date).await {
+            Ok(daily_bars) => daily_bars,
+            Err(err) => {
+                warn!("[Daily Bars] {err}");
+                vec![]
+            }
+        };
+    let margin_balances = match get_stock_margin_balances(ticker).await {
+        Ok(margin_balances) => Some(margin_balances),
+        Err(err) => {
+            warn!("[Margin Balances] {err}");
+            None
+        }
+    };
+    let northbound_holdings = match get_stock_northbound_holdings(ticker).await {
+        Ok(northbound_holdings) => Some(northbound_holdings),
+        Err(err) => {
+            warn!("[Northbound Holdings] {err}");
+            None
+        }
+    };
+    let stock_daily_data = StockDailyData {
+        daily_valuations,
+        quote,
+        daily_bars,
+        margin_balances,
+        northbound_holdings,
+    };
     debug!("{stock_daily_data:?}");
 
-    let mut stock_fiscal_metricsets = vec![];
-    let fiscal_count = options.backward_days / 91;
+    send_stage(sender, EvaluationStage::FetchFiscalMetrics).await;
+    let mut stock_fiscal_metricsets: Vec<StockFiscalMetricset> = vec![];
     let mut fiscal_quarter = utils::datetime::prev_fiscal_quarter(options.date.as_ref());
-    for _ in 0..fiscal_count {
-        let stock_fiscal_metricset =
-            get_stock_fiscal_metricset(&ticker, Some(fiscal_quarter.clone())).await?;
-        stock_fiscal_metricsets.push(stock_fiscal_metricset);
+    for _ in 0..fiscal_quarters_count(backward_days) {
+        // No reports before listing, so a recent IPO has a shorter history than requested
+        if stock_info
+            .listing_date
+            .is_some_and(|listing_date| fiscal_quarter.end_date() < listing_date)
+        {
+            break;
+        }
+
+        match get_stock_fiscal_metricset(ticker, Some(fiscal_quarter.clone())).await {
+            Ok((_, stock_metricset)) if stock_metricset.financial_summary.is_empty() => {
+                if !stock_fiscal_metricsets.is_empty() {
+                    break;
+                }
+            }
+            Ok(stock_fiscal_metricset) => stock_fiscal_metricsets.push(stock_fiscal_metricset),
+            Err(err) if !stock_fiscal_metricsets.is_empty() => {
+                warn!("[Fiscal Metrics] {fiscal_quarter}: {err}");
+                break;
+            }
+            Err(err) => return Err(err),
+        }
 
         fiscal_quarter = fiscal_quarter.prev();
     }
     debug!("{stock_fiscal_metricsets:?}");
 
-    let mut masters: Vec<Master> = vec![];
+    Ok(StockSnapshot {
+        ticker: ticker.to_string(),
+        info: stock_info,
+        events: stock_events,
+        daily_data: stock_daily_data,
+        fiscal_metricsets: stock_fiscal_metricsets,
+    })
+}
+
+/// Fiscal quarters covered by the backward days
+fn fiscal_quarters_count(backward_days: i64) -> usize {
+    (backward_days / 91).max(0) as usize
+}
+
+pub fn summarize_price(
+    stock_daily_data: &StockDailyData,
+    date: &NaiveDate,
+) -> Option<PriceSummary> {
+    let price_range = stock_daily_data.get_price_range(date, 365);
+
+    let (price, change, change_rate, is_quote) = match &stock_daily_data.quote {
+        Some(quote) => (quote.price, quote.change, quote.change_rate, true),
+        None => {
+            let prices = stock_daily_data.daily_valuations.get_values::<f64>(
+                &(*date - Duration::days(30)),
+                date,
+                stock::StockValuationFieldName::Price,
+            );
+            let (_, price) = *prices.last()?;
+            let prev_close = prices.iter().rev().nth(1).map(|(_, price)| *price);
+
+            (
+                price,
+                prev_close.map(|prev_close| price - prev_close),
+                prev_close
+                    .filter(|prev_close| *prev_close > 0.0)
+                    .map(|prev_close| price / prev_close - 1.0),
+                false,
+            )
+        }
+    };
+
+    Some(PriceSummary {
+        price,
+        change,
+        change_rate,
+        is_quote,
+        low_52w: price_range.as_ref().map(|r| r.low_52w),
+        high_52w: price_range.as_ref().map(|r| r.high_52w),
+    })
+}
+
+/// Fill the options not given with the global config
+fn with_config_defaults(options: &EvaluateOptions) -> InvmstResult<EvaluateOptions> {
+    let cfg = config::load()?;
+
+    let mut options = options.clone();
+    options.backward_days = Some(
+        options
+            .backward_days
+            .or(cfg.backward_days)
+            .unwrap_or(BACKWARD_DAYS_DEFAULT)
+            .abs(),
+    );
     if options.masters.is_empty() {
-        // Use all masters if no master is specified in options
-        masters = Master::iter().collect();
-    } else {
-        for master_str in &options.masters {
-            match Master::from_str(master_str) {
-                Ok(master) => {
-                    masters.push(master);
+        options.masters = cfg.masters;
+    }
+    if options.exclude_masters.is_empty() {
+        options.exclude_masters = cfg.exclude_masters;
+    }
+    options.note = options
+        .note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    Ok(options)
+}
+
+fn select_masters(master_strs: &[String], exclude_strs: &[String]) -> InvmstResult<Vec<Master>> {
+    let expand = |master_strs: &[String]| -> InvmstResult<Vec<Master>> {
+        let mut masters: Vec<Master> = vec![];
+        for master_str in master_strs {
+            match Master::select(master_str) {
+                Some(selected) => {
+                    // 风格分组可能与单独指定的大师重叠
+                    for master in selected {
+                        if !masters.contains(&master) {
+                            masters.push(master);
+                        }
+                    }
                 }
-                Err(_) => {
+                None => {
                     return Err(InvmstError::NotExists(
                         "MASTER_NOT_EXISTS",
                         format!("Master '{master_str}' not exists"),
@@ -69,39 +820,115 @@ pub async fn run(ticker: &str, options: &EvaluateOptions) -> InvmstResult<Evalua
                 }
             }
         }
-    }
 
-    let mut handles: HashMap<Master, JoinHandle<InvmstResult<MasterAnalysis>>> = HashMap::new();
-    for master in masters {
-        let options = MasterAnalyzeOptions {
-            backward_days: options.backward_days,
-            date: options.date,
-        };
+        Ok(masters)
+    };
 
-        let stock_info = stock_info.clone();
-        let stock_events = stock_events.clone();
-        let stock_daily_data = stock_daily_data.clone();
-        let stock_fiscal_metricsets = stock_fiscal_metricsets.clone();
-
-        let handle = tokio::spawn(async move {
-            master
-                .analyze(
-                    &stock_info,
-                    &stock_events,
-                    &stock_daily_data,
-                    &stock_fiscal_metricsets,
-                    &options,
-                )
-                .await
-        });
-        handles.insert(master, handle);
+    // Use all masters if no master is specified in options
+    let mut masters = if master_strs.is_empty() {
+        Master::iter().collect()
+    } else {
+        expand(master_strs)?
+    };
+
+    let excluded = expand(exclude_strs)?;
+    masters.retain(|master| !excluded.contains(master));
+    if masters.is_empty() {
+        return Err(InvmstError::Invalid(
+            "NO_MASTER_SELECTED",
+            "All selected masters are excluded".to_string(),
+        ));
     }
 
-    let mut master_analyses: HashMap<Master, MasterAnalysis> = HashMap::new();
-    for (master, handle) in handles {
-        let result = handle.await??;
-        master_analyses.insert(master, result);
+    Ok(masters)
+}
+
+fn start(
+    ticker: Ticker,
+    snapshot: Option<StockSnapshot>,
+    options: &EvaluateOptions,
+) -> InvmstResult<EvaluationStream> {
+    debug!("{ticker:?}");
+
+    // 用户提供的快照未必有序，按约定将季度数据倒序排列
+    let snapshot = snapshot.map(|mut snapshot| {
+        snapshot
+            .fiscal_metricsets
+            .sort_by_key(|(fiscal_quarter, _)| std::cmp::Reverse(fiscal_quarter.end_date()));
+        snapshot
+    });
+
+    let options = with_config_defaults(options)?;
+    let masters = select_masters(&options.masters, &options.exclude_masters)?;
+
+    let created_at = Local::now();
+    let id = history::new_id(&created_at, &ticker.symbol);
+
+    let (sender, receiver) = mpsc::channel(CHANNEL_BUFFER_DEFAULT);
+
+    let evaluation = Evaluation {
+        id: id.clone(),
+        ticker: ticker.to_string(),
+        date: options.date.unwrap_or(created_at.date_naive()),
+        created_at,
+        price_summary: None,
+        master_analyses: HashMap::new(),
+        master_errors: HashMap::new(),
+        bear_case: None,
+        failure_modes: None,
+        note: options.note.clone(),
+        data_anomalies: vec![],
+        data_discrepancies: vec![],
+        recent_developments: None,
+        compliance_risk: None,
+    };
+    tokio::spawn(async move {
+        match evaluate(&ticker, snapshot, &masters, &options, evaluation, &sender).await {
+            Ok((evaluation, snapshot)) => {
+                if let Err(err) = history::save(&evaluation).await {
+                    warn!("Unable to save evaluation '{}': {err}", evaluation.id);
+                }
+                if let Err(err) = history::save_snapshot(&evaluation.id, &snapshot).await {
+                    warn!(
+                        "Unable to save snapshot of evaluation '{}': {err}",
+                        evaluation.id
+                    );
+                }
+
+                let _ = sender
+                    .send(EvaluationEvent::Finished(Box::new(evaluation)))
+                    .await;
+            }
+            Err(err) => {
+                let _ = sender.send(EvaluationEvent::Error(err)).await;
+            }
+        }
+    });
+
+    Ok(EvaluationStream { id, receiver })
+}
+
+async fn wait_finished(mut stream: EvaluationStream) -> InvmstResult<Evaluation> {
+    while let Some(event) = stream.next().await {
+        match event {
+            EvaluationEvent::Finished(evaluation) => {
+                return Ok(*evaluation);
+            }
+            EvaluationEvent::Error(err) => {
+                return Err(err);
+            }
+            _ => {}
+        }
     }
 
-    Ok(Evaluation { master_analyses })
+    Err(InvmstError::NoData(
+        "EVALUATION_INTERRUPTED",
+        "Evaluation ended without result".to_string(),
+    ))
+}
+
+async fn send_stage(sender: Option<&Sender<EvaluationEvent>>, stage: EvaluationStage) {
+    if let Some(sender) = sender {
+        let _ = sender.send(EvaluationEvent::Stage(stage)).await;
+    }
 }
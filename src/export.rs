@@ -0,0 +1,558 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{Duration, Local, NaiveDate};
+#[cfg(not(feature = "charts"))]
+use log::warn;
+use polars::prelude::*;
+use serde_json::{Value, json};
+use strum::IntoEnumIterator;
+
+use crate::{
+    data::stock::{StockDividend, StockFiscalMetricset},
+    error::InvmstResult,
+    evaluate::Evaluation,
+    financial::{
+        get_stock_daily_valuations, get_stock_fiscal_metricset,
+        stock::fetch_stock_dividends,
+        valuation_band,
+        valuation_band::{BandMultiple, BandPoint, ValuationBand},
+    },
+    master::MasterAnalysis,
+    ticker::Ticker,
+    utils,
+};
+#[cfg(feature = "charts")]
+use crate::{financial::stock::StockValuationFieldName, report::charts};
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
+pub enum ExportDataset {
+    /// Daily prices and valuation multiples
+    Prices,
+    /// Financial summaries of fiscal quarters
+    Financials,
+    /// Itemized income statements, balance sheets and cash flow statements of fiscal quarters
+    Statements,
+    Dividends,
+    /// Prices implied by the rolling PE and PB bands, with a band chart in SVG for each multiple
+    ValuationBands,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Clone)]
+pub struct ExportOptions {
+    pub date: Option<NaiveDate>,
+    pub backward_days: i64,
+    pub format: ExportFormat,
+    /// Directory to save charts of the price, the drawdown and the valuation to, if given
+    pub charts_dir: Option<PathBuf>,
+}
+
+/// Write each dataset of the ticker to a file named '<symbol>_<dataset>.<format>' in the directory,
+/// returns paths of the written files
+pub async fn export(
+    ticker: &str,
+    datasets: &[ExportDataset],
+    dir: &Path,
+    options: &ExportOptions,
+) -> InvmstResult<Vec<PathBuf>> {
+    let ticker = Ticker::from_str(ticker)?;
+    let date_end = options.date.unwrap_or(Local::now().date_naive());
+    let date_start = date_end - Duration::days(options.backward_days);
+
+    fs::create_dir_all(dir)?;
+
+    let mut paths: Vec<PathBuf> = vec![];
+    for dataset in datasets {
+        let mut df = match dataset {
+            ExportDataset::Prices => get_stock_daily_valuations(&ticker)
+                .await?
+                .to_dataframe(&date_start, &date_end)?,
+            ExportDataset::Financials => financials_dataframe(
+                &fetch_fiscal_metricsets(&ticker, &date_end, options.backward_days).await?,
+            )?,
+            ExportDataset::Statements => statements_dataframe(
+                &fetch_fiscal_metricsets(&ticker, &date_end, options.backward_days).await?,
+            )?,
+            ExportDataset::Dividends => {
+                dividends_dataframe(&fetch_stock_dividends(&ticker, &date_start, &date_end).await?)?
+            }
+            ExportDataset::ValuationBands => {
+                let daily_valuations = get_stock_daily_valuations(&ticker).await?;
+                let valuation_bands: Vec<ValuationBand> = BandMultiple::iter()
+                    .filter_map(|multiple| {
+                        valuation_band::compute(&daily_valuations, multiple, &date_start, &date_end)
+                    })
+                    .collect();
+
+                for valuation_band in &valuation_bands {
+                    let path = dir.join(format!(
+                        "{}_{dataset}_{}.svg",
+                        ticker.symbol, valuation_band.multiple
+                    ));
+                    fs::write(
+                        &path,
+                        valuation_band.to_svg(&format!(
+                            "{} {} band",
+                            ticker.symbol,
+                            valuation_band.multiple.to_string().to_uppercase()
+                        )),
+                    )?;
+                    paths.push(path);
+                }
+
+                valuation_bands_dataframe(&valuation_bands)?
+            }
+        };
+
+        let path = dir.join(format!("{}_{dataset}.{}", ticker.symbol, options.format));
+        let file = File::create(&path)?;
+        match options.format {
+            ExportFormat::Csv => {
+                CsvWriter::new(file).finish(&mut df)?;
+            }
+            ExportFormat::Parquet => {
+                ParquetWriter::new(file).finish(&mut df)?;
+            }
+        }
+
+        paths.push(path);
+    }
+
+    #[cfg(feature = "charts")]
+    if let Some(charts_dir) = &options.charts_dir {
+        paths.extend(save_charts(&ticker, charts_dir, &date_start, &date_end).await?);
+    }
+
+    #[cfg(not(feature = "charts"))]
+    if options.charts_dir.is_some() {
+        warn!("[Charts] Charts of {ticker} are not saved as the charts feature is not enabled");
+    }
+
+    Ok(paths)
+}
+
+/// Save charts as SVG files named '<symbol>_<chart>.svg' in the directory
+#[cfg(feature = "charts")]
+async fn save_charts(
+    ticker: &Ticker,
+    dir: &Path,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    let daily_valuations = get_stock_daily_valuations(ticker).await?;
+    let values = |field: StockValuationFieldName| {
+        daily_valuations.get_values::<f64>(date_start, date_end, field)
+    };
+    let prices = values(StockValuationFieldName::Price);
+
+    let mut paths: Vec<PathBuf> = vec![];
+    for (name, chart) in [
+        ("price", charts::price_history(&ticker.symbol, &prices)),
+        ("drawdown", charts::drawdown(&ticker.symbol, &prices)),
+        (
+            "valuation",
+            charts::metric_trend(
+                &ticker.symbol,
+                "Valuation",
+                vec![
+                    charts::ChartSeries {
+                        name: "PE TTM".to_string(),
+                        points: values(StockValuationFieldName::PeTtm),
+                    },
+                    charts::ChartSeries {
+                        name: "PB".to_string(),
+                        points: values(StockValuationFieldName::Pb),
+                    },
+                ],
+                false,
+            ),
+        ),
+    ] {
+        let path = dir.join(format!("{}_{name}.svg", ticker.symbol));
+        chart.save(&path)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Evaluation, master name and the analysis of the master
+type EvaluationRow<'a> = (&'a Evaluation, String, &'a MasterAnalysis);
+
+/// Evaluations as CSV, see evaluations_dataframe for the columns
+pub fn evaluations_csv(evaluations: &[Evaluation]) -> InvmstResult<String> {
+    let mut df = evaluations_dataframe(evaluations)?;
+
+    let mut buf: Vec<u8> = vec![];
+    CsvWriter::new(&mut buf).finish(&mut df)?;
+
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+/// One row per ticker and master sorted by ticker, date then master, with the prospect, the rating, the
+/// data quality of the analysis, the price summary and the master specific details as JSON
+fn evaluations_dataframe(evaluations: &[Evaluation]) -> InvmstResult<DataFrame> {
+    let mut rows: Vec<EvaluationRow> = evaluations
+        .iter()
+        .flat_map(|evaluation| {
+            evaluation
+                .master_analyses
+                .iter()
+                .map(move |(master, analysis)| (evaluation, master.to_string(), analysis))
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.0.ticker
+            .cmp(&b.0.ticker)
+            .then(a.0.date.cmp(&b.0.date))
+            .then(a.1.cmp(&b.1))
+    });
+
+    let text = |f: &dyn Fn(&EvaluationRow) -> Option<String>| {
+        rows.iter().map(f).collect::<Vec<Option<String>>>()
+    };
+    let number = |f: &dyn Fn(&EvaluationRow) -> Option<f64>| {
+        rows.iter().map(f).collect::<Vec<Option<f64>>>()
+    };
+
+    Ok(DataFrame::new(vec![
+        Column::new(
+            "ticker".into(),
+            text(&|(evaluation, _, _)| Some(evaluation.ticker.clone())),
+        ),
+        Column::new(
+            "date".into(),
+            text(&|(evaluation, _, _)| Some(evaluation.date.to_string())),
+        ),
+        Column::new(
+            "master".into(),
+            text(&|(_, master, _)| Some(master.clone())),
+        ),
+        Column::new(
+            "prospect".into(),
+            text(&|(_, _, analysis)| Some(analysis.prospect.to_string())),
+        ),
+        Column::new(
+            "rating".into(),
+            rows.iter()
+                .map(|(_, _, analysis)| analysis.rating)
+                .collect::<Vec<u64>>(),
+        ),
+        Column::new(
+            "action".into(),
+            text(&|(_, _, analysis)| analysis.action.as_ref().map(|a| a.to_string())),
+        ),
+        Column::new(
+            "horizon".into(),
+            text(&|(_, _, analysis)| analysis.horizon.as_ref().map(|h| h.to_string())),
+        ),
+        Column::new(
+            "target_price_low".into(),
+            number(&|(_, _, analysis)| analysis.target_price.as_ref().map(|t| t.low)),
+        ),
+        Column::new(
+            "target_price_high".into(),
+            number(&|(_, _, analysis)| analysis.target_price.as_ref().map(|t| t.high)),
+        ),
+        Column::new(
+            "validation".into(),
+            text(&|(_, _, analysis)| analysis.validation.map(|v| v.to_string())),
+        ),
+        Column::new(
+            "completeness".into(),
+            number(&|(_, _, analysis)| analysis.completeness),
+        ),
+        Column::new(
+            "price".into(),
+            number(&|(evaluation, _, _)| evaluation.price_summary.as_ref().map(|s| s.price)),
+        ),
+        Column::new(
+            "change_rate".into(),
+            number(&|(evaluation, _, _)| {
+                evaluation
+                    .price_summary
+                    .as_ref()
+                    .and_then(|s| s.change_rate)
+            }),
+        ),
+        Column::new(
+            "low_52w".into(),
+            number(&|(evaluation, _, _)| evaluation.price_summary.as_ref().and_then(|s| s.low_52w)),
+        ),
+        Column::new(
+            "high_52w".into(),
+            number(&|(evaluation, _, _)| {
+                evaluation.price_summary.as_ref().and_then(|s| s.high_52w)
+            }),
+        ),
+        Column::new(
+            "details".into(),
+            text(&|(_, _, analysis)| analysis.details.as_ref().map(|d| d.to_string())),
+        ),
+    ])?)
+}
+
+/// Fiscal quarters within the backward days before the date, latest first
+async fn fetch_fiscal_metricsets(
+    ticker: &Ticker,
+    date_end: &NaiveDate,
+    backward_days: i64,
+) -> InvmstResult<Vec<StockFiscalMetricset>> {
+    let mut stock_fiscal_metricsets = vec![];
+    let mut fiscal_quarter = utils::datetime::prev_fiscal_quarter(Some(date_end));
+    for _ in 0..backward_days / 91 {
+        stock_fiscal_metricsets
+            .push(get_stock_fiscal_metricset(ticker, Some(fiscal_quarter.clone())).await?);
+
+        fiscal_quarter = fiscal_quarter.prev();
+    }
+
+    Ok(stock_fiscal_metricsets)
+}
+
+/// One row per fiscal quarter sorted ascending, one column per field of the financial summary
+fn financials_dataframe(
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+) -> InvmstResult<DataFrame> {
+    let mut rows: Vec<(String, serde_json::Map<String, Value>)> = vec![];
+    for (fiscal_quarter, metricset) in stock_fiscal_metricsets.iter().rev() {
+        if let Value::Object(obj) = serde_json::to_value(&metricset.financial_summary)? {
+            rows.push((fiscal_quarter.to_string(), obj));
+        }
+    }
+
+    fiscal_quarters_dataframe(&rows)
+}
+
+/// One row per fiscal quarter sorted ascending, with the fields of the statements named
+/// '<statement>.<field>' and the metrics derived from the statements
+fn statements_dataframe(
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+) -> InvmstResult<DataFrame> {
+    let mut rows: Vec<(String, serde_json::Map<String, Value>)> = vec![];
+    for (fiscal_quarter, metricset) in stock_fiscal_metricsets.iter().rev() {
+        let statements = &metricset.statements;
+
+        let mut row = serde_json::Map::new();
+        if let Value::Object(obj) = serde_json::to_value(statements)? {
+            for (statement, fields) in obj {
+                if let Value::Object(fields) = fields {
+                    for (field, value) in fields {
+                        row.insert(format!("{statement}.{field}"), value);
+                    }
+                }
+            }
+        }
+        for (metric, value) in [
+            ("free_cash_flow", statements.free_cash_flow()),
+            (
+                "net_current_asset_value",
+                statements.net_current_asset_value(),
+            ),
+            ("owner_earnings", statements.owner_earnings()),
+        ] {
+            row.insert(metric.to_string(), json!(value));
+        }
+
+        rows.push((fiscal_quarter.to_string(), row));
+    }
+
+    fiscal_quarters_dataframe(&rows)
+}
+
+fn fiscal_quarters_dataframe(
+    rows: &[(String, serde_json::Map<String, Value>)],
+) -> InvmstResult<DataFrame> {
+    let mut columns: Vec<Column> = vec![Column::new(
+        "fiscal_quarter".into(),
+        rows.iter()
+            .map(|(fiscal_quarter, _)| fiscal_quarter.as_str())
+            .collect::<Vec<_>>(),
+    )];
+    if let Some((_, first)) = rows.first() {
+        for field_name in first.keys() {
+            let values: Vec<Option<f64>> = rows
+                .iter()
+                .map(|(_, obj)| obj.get(field_name).and_then(|v| v.as_f64()))
+                .collect();
+            columns.push(Column::new(field_name.into(), values));
+        }
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
+
+fn dividends_dataframe(dividends: &[StockDividend]) -> InvmstResult<DataFrame> {
+    let date_value = |date: &NaiveDate| {
+        utils::datetime::days_after_epoch(date)
+            .map(AnyValue::Date)
+            .unwrap_or(AnyValue::Null)
+    };
+
+    Ok(DataFrame::new(vec![
+        Column::new(
+            "date_announce".into(),
+            dividends
+                .iter()
+                .map(|dividend| date_value(&dividend.date_announce))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "date_record".into(),
+            dividends
+                .iter()
+                .map(|dividend| date_value(&dividend.date_record))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "dividend_per_share".into(),
+            dividends
+                .iter()
+                .map(|dividend| dividend.dividend_per_share)
+                .collect::<Vec<_>>(),
+        ),
+    ])?)
+}
+
+/// One row per date with the multiple and the implied prices of each band, e.g. 'pe_lower'
+fn valuation_bands_dataframe(valuation_bands: &[ValuationBand]) -> InvmstResult<DataFrame> {
+    let mut dates: Vec<NaiveDate> = valuation_bands
+        .iter()
+        .flat_map(|valuation_band| valuation_band.points.iter().map(|point| point.date))
+        .collect();
+    dates.sort();
+    dates.dedup();
+
+    let mut columns = vec![Column::new(
+        "date".into(),
+        dates
+            .iter()
+            .map(|date| {
+                utils::datetime::days_after_epoch(date)
+                    .map(AnyValue::Date)
+                    .unwrap_or(AnyValue::Null)
+            })
+            .collect::<Vec<_>>(),
+    )];
+    for valuation_band in valuation_bands {
+        let points: HashMap<NaiveDate, &BandPoint> = valuation_band
+            .points
+            .iter()
+            .map(|point| (point.date, point))
+            .collect();
+        let value = |f: &dyn Fn(&BandPoint) -> f64| {
+            dates
+                .iter()
+                .map(|date| points.get(date).map(|point| f(point)))
+                .collect::<Vec<Option<f64>>>()
+        };
+
+        let multiple = valuation_band.multiple;
+        columns.extend([
+            Column::new(format!("{multiple}").into(), value(&|point| point.multiple)),
+            Column::new(
+                format!("{multiple}_lower").into(),
+                value(&|point| point.lower),
+            ),
+            Column::new(
+                format!("{multiple}_middle").into(),
+                value(&|point| point.middle),
+            ),
+            Column::new(
+                format!("{multiple}_upper").into(),
+                value(&|point| point.upper),
+            ),
+        ]);
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::stock::{StockFinancialSummary, StockMetricset},
+        utils::datetime::{FiscalQuarter, Quarter},
+    };
+
+    #[test]
+    fn test_financials_dataframe() {
+        let metricset = |net_profit: f64| StockMetricset {
+            financial_summary: StockFinancialSummary {
+                net_profit: Some(net_profit),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let stock_fiscal_metricsets = vec![
+            (
+                FiscalQuarter {
+                    year: 2024,
+                    quarter: Quarter::Q2,
+                },
+                metricset(20.0),
+            ),
+            (
+                FiscalQuarter {
+                    year: 2024,
+                    quarter: Quarter::Q1,
+                },
+                metricset(10.0),
+            ),
+        ];
+
+        let df = financials_dataframe(&stock_fiscal_metricsets).unwrap();
+        assert_eq!(df.height(), 2);
+
+        let net_profits: Vec<Option<f64>> = df
+            .column("net_profit")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(net_profits, vec![Some(10.0), Some(20.0)]);
+        assert!(df.column("return_on_equity").unwrap().is_null().all());
+    }
+
+    #[test]
+    fn test_evaluations_csv() {
+        let evaluation = |ticker: &str| -> Evaluation {
+            serde_json::from_value(serde_json::json!({
+                "id": ticker,
+                "ticker": ticker,
+                "date": "2024-06-28",
+                "created_at": "2024-06-28T15:00:00+08:00",
+                "master_analyses": {
+                    "WarrenBuffett": {"prospect": "Bullish", "rating": 75, "explanation": ""},
+                    "BenjaminGraham": {"prospect": "Neutral", "rating": 50, "explanation": ""},
+                },
+            }))
+            .unwrap()
+        };
+
+        let csv = evaluations_csv(&[evaluation("SH600900"), evaluation("SH600036")]).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("ticker,date,master,prospect,rating"));
+        assert!(lines[1].starts_with("SH600036,2024-06-28,benjamin-graham,Neutral,50,"));
+        assert!(lines[4].starts_with("SH600900,2024-06-28,warren-buffett,Bullish,75,"));
+
+        assert_eq!(evaluations_csv(&[]).unwrap().lines().count(), 1);
+    }
+}
@@ -1,6 +1,15 @@
-use chrono::{Duration, Local, NaiveDate};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use log::warn;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    APP_DATA_DIR, config,
     data::{daily::*, stock::*},
     error::*,
     financial::stock::*,
@@ -8,9 +17,52 @@ use crate::{
     utils::datetime::*,
 };
 
+pub mod acceleration;
+pub mod anomaly;
+pub mod capital_intensity;
+pub mod compensation;
+pub mod compliance;
+pub mod consensus;
+pub mod cyclicality;
+pub mod dcf;
+pub mod dilution;
+pub mod enterprise_value;
+pub mod factors;
+#[cfg(feature = "xbrl")]
+pub mod filing;
+pub mod fiscal_series;
+pub mod forensic;
+pub mod fx;
+pub mod growth;
+pub mod index;
+pub mod index_fundamentals;
+pub mod macro_economy;
+pub mod management_discussion;
+pub mod margin;
+pub mod momentum;
+pub mod northbound;
+pub mod price_action;
+pub mod price_range;
+pub mod reconcile;
+pub mod research;
+pub mod risk_stats;
+pub mod sector;
+pub mod solvency;
 pub mod stock;
+pub mod suspension;
+pub mod valuation_band;
+pub mod working_capital;
 
-#[derive(Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
 #[strum(ascii_case_insensitive)]
 pub enum Prospect {
     Bullish,
@@ -18,8 +70,235 @@ pub enum Prospect {
     Neutral,
 }
 
-pub async fn get_stock_daily_valuations(ticker: &Ticker) -> InvmstResult<DailyDataset> {
-    fetch_stock_daily_valuations(ticker).await
+impl Prospect {
+    pub fn from_rating(rating: u64) -> Self {
+        if rating < 40 {
+            Prospect::Bearish
+        } else if rating < 60 {
+            Prospect::Neutral
+        } else {
+            Prospect::Bullish
+        }
+    }
+}
+
+pub async fn get_cny_central_parity() -> InvmstResult<DailyDataset<fx::Currency>> {
+    fx::fetch_cny_central_parity().await
+}
+
+pub async fn get_index_constituent_weights(symbol: &str) -> InvmstResult<Vec<(Ticker, f64)>> {
+    index::fetch_index_constituent_weights(symbol).await
+}
+
+pub async fn get_index_daily_prices(
+    symbol: &str,
+) -> InvmstResult<DailyDataset<index::IndexDailyFieldName>> {
+    index::fetch_index_daily_prices(symbol).await
+}
+
+/// Fundamentals of an index as of the date aggregated from its heaviest constituents, ROE and
+/// growth are from the latest annual reports. Constituents failing to fetch are skipped.
+pub async fn get_index_fundamentals(
+    symbol: &str,
+    date: &NaiveDate,
+) -> InvmstResult<index_fundamentals::IndexFundamentals> {
+    let mut constituents = get_index_constituent_weights(symbol).await?;
+    constituents.sort_by(|a, b| b.1.total_cmp(&a.1));
+    constituents.truncate(INDEX_FUNDAMENTALS_CONSTITUENTS_SIZE);
+
+    let fiscal_quarter = prev_fiscal_quarter(Some(date));
+    let annual_quarter = if fiscal_quarter.quarter == Quarter::Q4 {
+        fiscal_quarter
+    } else {
+        FiscalQuarter::new(fiscal_quarter.year - 1, Quarter::Q4)
+    };
+
+    let mut constituent_fundamentals = vec![];
+    for (constituent, weight) in &constituents {
+        let daily_valuations = match get_stock_daily_valuations(constituent).await {
+            Ok(daily_valuations) => daily_valuations,
+            Err(err) => {
+                warn!("[Index Fundamentals] {constituent}: {err}");
+                continue;
+            }
+        };
+        let financial_summary =
+            match get_stock_fiscal_metricset(constituent, Some(annual_quarter.clone())).await {
+                Ok((_, stock_metricset)) => stock_metricset.financial_summary,
+                Err(err) => {
+                    warn!("[Index Fundamentals] {constituent}: {err}");
+                    StockFinancialSummary::default()
+                }
+            };
+
+        constituent_fundamentals.push(index_fundamentals::ConstituentFundamentals {
+            weight: *weight,
+            pe_ttm: daily_valuations.get_latest_value::<f64>(date, StockValuationFieldName::PeTtm),
+            pb: daily_valuations.get_latest_value::<f64>(date, StockValuationFieldName::Pb),
+            return_on_equity: financial_summary.return_on_equity,
+            revenue_growth: financial_summary.revenue_growth,
+        });
+    }
+
+    let bond_yield_10y =
+        match macro_economy::fetch_cn_bond_yield_10y(&(*date - Duration::days(30))).await {
+            Ok(yields) => yields
+                .iter()
+                .rev()
+                .find(|(d, _)| d <= date)
+                .map(|(_, v)| v / 100.0),
+            Err(err) => {
+                warn!("[Bond Yield] {err}");
+                None
+            }
+        };
+
+    Ok(index_fundamentals::aggregate(
+        &constituent_fundamentals,
+        bond_yield_10y,
+    ))
+}
+
+/// Overviews of Shenwan level-1 industries as of the date, ranked by 3-month return
+pub async fn get_sector_overviews(
+    date: &NaiveDate,
+    backward_days: i64,
+) -> InvmstResult<Vec<sector::SectorOverview>> {
+    let date_start = *date - Duration::days(backward_days);
+    let sector_dailies = sector::fetch_sector_dailies(&date_start, date).await?;
+
+    let mut overviews: Vec<sector::SectorOverview> = sector_dailies
+        .iter()
+        .filter_map(|((code, name), dailies)| sector::summarize(code, name, dailies, date))
+        .collect();
+    overviews.sort_by(|a, b| {
+        b.return_3m
+            .unwrap_or(f64::NEG_INFINITY)
+            .total_cmp(&a.return_3m.unwrap_or(f64::NEG_INFINITY))
+    });
+
+    Ok(overviews)
+}
+
+/// Momentum of the stock as of the date, ranked against the heaviest constituents of the index
+/// if rs_universe is given
+pub async fn get_stock_momentum(
+    ticker: &Ticker,
+    date: &NaiveDate,
+    rs_universe: Option<&str>,
+) -> InvmstResult<momentum::Momentum> {
+    let daily_valuations = get_stock_daily_valuations(ticker).await?;
+    let mut result =
+        momentum::compute_from_dataset(&daily_valuations, date, StockValuationFieldName::Price);
+
+    if let (Some(index_symbol), Some(rs_score)) = (rs_universe, result.rs_score) {
+        let mut constituents = get_index_constituent_weights(index_symbol).await?;
+        constituents.sort_by(|a, b| b.1.total_cmp(&a.1));
+        constituents.truncate(MOMENTUM_RS_UNIVERSE_SIZE);
+
+        let mut scores: Vec<f64> = vec![];
+        for (constituent, _) in &constituents {
+            let daily_valuations = get_stock_daily_valuations(constituent).await?;
+            if let Some(score) = momentum::compute_from_dataset(
+                &daily_valuations,
+                date,
+                StockValuationFieldName::Price,
+            )
+            .rs_score
+            {
+                scores.push(score);
+            }
+        }
+
+        result.rs_rank = momentum::rank(rs_score, &scores);
+    }
+
+    Ok(result)
+}
+
+/// Risk stats of the stock over the backward window ending at the date, with beta against the
+/// benchmark index if given
+pub async fn get_stock_risk_stats(
+    ticker: &Ticker,
+    date: &NaiveDate,
+    backward_days: i64,
+    benchmark: Option<&str>,
+) -> InvmstResult<risk_stats::RiskStats> {
+    let date_start = *date - Duration::days(backward_days);
+    let prices = get_stock_daily_valuations(ticker).await?.get_values::<f64>(
+        &date_start,
+        date,
+        StockValuationFieldName::Price,
+    );
+    let values: Vec<f64> = prices.iter().map(|(_, price)| *price).collect();
+
+    let mut result = risk_stats::compute(&values, None);
+    if let Some(benchmark) = benchmark {
+        let benchmark_prices = get_index_daily_prices(benchmark).await?.get_values::<f64>(
+            &date_start,
+            date,
+            index::IndexDailyFieldName::Close,
+        );
+
+        let (_, aligned) = risk_stats::align(&[prices, benchmark_prices]);
+        result.beta = risk_stats::compute(&aligned[0], Some(&aligned[1])).beta;
+    }
+
+    Ok(result)
+}
+
+/// Key figures of the first two sources compared, nothing to compare with fewer sources
+pub async fn get_stock_discrepancies(
+    ticker: &Ticker,
+    sources: &[reconcile::DataSource],
+    fiscal_quarter: &FiscalQuarter,
+    date: &NaiveDate,
+) -> InvmstResult<Vec<reconcile::Discrepancy>> {
+    let [source_a, source_b, ..] = sources else {
+        return Ok(vec![]);
+    };
+
+    let figures_a = reconcile::fetch_key_figures(ticker, *source_a, fiscal_quarter, date).await?;
+    let figures_b = reconcile::fetch_key_figures(ticker, *source_b, fiscal_quarter, date).await?;
+
+    Ok(reconcile::compare(
+        (*source_a, &figures_a),
+        (*source_b, &figures_b),
+        fiscal_quarter,
+        date,
+        RECONCILE_TOLERANCE,
+    ))
+}
+
+/// Cached as Parquet for the day, the history does not change and today's values are only
+/// updated after the close
+pub async fn get_stock_daily_valuations(
+    ticker: &Ticker,
+) -> InvmstResult<DailyDataset<StockValuationFieldName>> {
+    let path =
+        DAILY_VALUATIONS_CACHE_DIR.join(format!("{}_{}.parquet", ticker.exchange, ticker.symbol));
+
+    if is_modified_today(&path) {
+        match fs::read(&path) {
+            Ok(bytes) => match DailyDataset::from_parquet(&bytes) {
+                Ok(daily_valuations) => {
+                    return Ok(daily_valuations.with_max_staleness(DAILY_VALUATIONS_MAX_STALENESS));
+                }
+                Err(err) => warn!("[Cache] Unable to load daily valuations of {ticker}: {err}"),
+            },
+            Err(err) => warn!("[Cache] Unable to load daily valuations of {ticker}: {err}"),
+        }
+    }
+
+    let daily_valuations = fetch_stock_daily_valuations(ticker).await?;
+    if let Err(err) = daily_valuations
+        .to_parquet()
+        .and_then(|bytes| save_cache(&path, &bytes))
+    {
+        warn!("[Cache] Unable to save daily valuations of {ticker}: {err}");
+    }
+
+    Ok(daily_valuations.with_max_staleness(DAILY_VALUATIONS_MAX_STALENESS))
 }
 
 pub async fn get_stock_events(
@@ -32,7 +311,43 @@ pub async fn get_stock_events(
 
     let dividends = fetch_stock_dividends(ticker, &date_start, &date_end).await?;
 
-    Ok(StockEvents { dividends })
+    // Announcements only add governance and trading halt context, so failures do not fail the evaluation
+    let announcements =
+        match fetch_stock_governance_announcements(ticker, &date_start, &date_end).await {
+            Ok(announcements) => announcements,
+            Err(err) => {
+                warn!("[Announcements] {err}");
+                vec![]
+            }
+        };
+
+    let full_text = config::load().is_ok_and(|cfg| cfg.announcement_full_text);
+    let disclosures = match fetch_announcements(ticker, &date_start, &date_end, full_text).await {
+        Ok(disclosures) => disclosures,
+        Err(err) => {
+            warn!("[Announcements] {err}");
+            vec![]
+        }
+    };
+
+    // Share changes of the years before the window are needed to measure dilution within it
+    let share_changes =
+        match fetch_stock_share_changes(ticker, &(date_start - Duration::days(365)), &date_end)
+            .await
+        {
+            Ok(share_changes) => share_changes,
+            Err(err) => {
+                warn!("[Share Changes] {err}");
+                vec![]
+            }
+        };
+
+    Ok(StockEvents {
+        dividends,
+        announcements,
+        disclosures,
+        share_changes,
+    })
 }
 
 pub async fn get_stock_fiscal_metricset(
@@ -40,11 +355,208 @@ pub async fn get_stock_fiscal_metricset(
     quater: Option<FiscalQuarter>,
 ) -> InvmstResult<StockFiscalMetricset> {
     let fiscal_quater = quater.unwrap_or_else(|| prev_fiscal_quarter(None));
-    let financial_summary = fetch_stock_financial_summary(ticker, &fiscal_quater).await?;
+    // 报表只用于补充摘要之外的字段，获取失败时留空
+    let statements = match get_stock_statements(ticker, &fiscal_quater).await {
+        Ok(statements) => statements,
+        Err(err) => {
+            warn!("[Statements] Unable to get statements of {ticker} for {fiscal_quater}: {err}");
+            StockStatements::default()
+        }
+    };
+    let financial_summary = fetch_stock_financial_summary(ticker, &fiscal_quater)
+        .await?
+        .with_statements(&statements);
 
-    Ok((fiscal_quater, StockMetricset { financial_summary }))
+    Ok((
+        fiscal_quater,
+        StockMetricset {
+            financial_summary,
+            statements,
+        },
+    ))
+}
+
+/// Statements parsed from the downloaded official filing if the ticker is configured so and the
+/// filing of the quarter is found, otherwise fetched from the aggregator
+pub async fn get_stock_statements(
+    ticker: &Ticker,
+    fiscal_quarter: &FiscalQuarter,
+) -> InvmstResult<StockStatements> {
+    if config::load().is_ok_and(|cfg| config::is_filing_ticker(&cfg, ticker)) {
+        #[cfg(feature = "xbrl")]
+        match filing::load_statements(ticker, fiscal_quarter) {
+            Ok(Some(statements)) => return Ok(statements),
+            Ok(None) => warn!("[Filing] No filing of {ticker} for {fiscal_quarter}, fetch instead"),
+            Err(err) => warn!("[Filing] Unable to load the filing of {ticker}: {err}"),
+        }
+
+        #[cfg(not(feature = "xbrl"))]
+        warn!("[Filing] Filings of {ticker} are ignored as the xbrl feature is not enabled");
+    }
+
+    fetch_stock_statements(ticker, fiscal_quarter).await
+}
+
+/// Cyclicality of annual earnings reported before the date, the PPI is optional as it only
+/// strengthens the detection
+pub async fn get_stock_cyclicality(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<cyclicality::Cyclicality> {
+    // Annual reports are published by the end of April
+    let year_reported = if date.month() > 4 {
+        date.year() - 1
+    } else {
+        date.year() - 2
+    };
+    let annual_eps: Vec<(i32, f64)> = fetch_stock_annual_eps(ticker)
+        .await?
+        .into_iter()
+        .filter(|(year, _)| *year <= year_reported)
+        .collect();
+
+    let ppi = match macro_economy::fetch_ppi_yearly().await {
+        Ok(ppi) => ppi,
+        Err(err) => {
+            warn!("[PPI] {err}");
+            vec![]
+        }
+    };
+
+    Ok(cyclicality::detect(&annual_eps, &ppi))
+}
+
+/// Enterprise value and EV multiples as of the date, using the latest report before the date
+pub async fn get_stock_enterprise_value(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<enterprise_value::EnterpriseValue> {
+    let market_cap = get_stock_daily_valuations(ticker)
+        .await?
+        .get_latest_value::<f64>(date, StockValuationFieldName::MarketCap);
+
+    let fiscal_quarter = prev_fiscal_quarter(Some(date));
+    let (_, latest) = get_stock_fiscal_metricset(ticker, Some(fiscal_quarter.clone())).await?;
+    let annual = if fiscal_quarter.quarter == Quarter::Q4 {
+        latest.clone()
+    } else {
+        let fiscal_quarter = FiscalQuarter::new(fiscal_quarter.year - 1, Quarter::Q4);
+        get_stock_fiscal_metricset(ticker, Some(fiscal_quarter))
+            .await?
+            .1
+    };
+
+    Ok(enterprise_value::compute(
+        market_cap,
+        &latest.financial_summary,
+        &annual.financial_summary,
+    ))
+}
+
+pub async fn get_stock_daily_bars(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<price_action::Bar>> {
+    fetch_stock_daily_bars(ticker, date_start, date_end).await
+}
+
+pub async fn get_stock_next_earnings_date(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<Option<NaiveDate>> {
+    fetch_stock_next_earnings_date(ticker, date).await
+}
+
+pub async fn get_stock_quote(ticker: &Ticker) -> InvmstResult<StockQuote> {
+    fetch_stock_quote(ticker).await
 }
 
 pub async fn get_stock_info(ticker: &Ticker) -> InvmstResult<StockInfo> {
     fetch_stock_info(ticker).await
 }
+
+pub async fn get_stock_margin_balances(
+    ticker: &Ticker,
+) -> InvmstResult<DailyDataset<StockMarginFieldName>> {
+    fetch_stock_margin_balances(ticker).await
+}
+
+pub async fn get_stock_northbound_holdings(
+    ticker: &Ticker,
+) -> InvmstResult<DailyDataset<StockNorthboundFieldName>> {
+    fetch_stock_northbound_holdings(ticker).await
+}
+
+/// Forecasts of the research reports within the window of the consensus as of the date
+pub async fn get_stock_analyst_forecasts(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<Vec<StockAnalystForecast>> {
+    let date_start = *date - Duration::days(consensus::WINDOW_DAYS);
+    fetch_stock_analyst_forecasts(ticker, &date_start, date).await
+}
+
+/// Penalties of the recent years and the ESG rating as of the date, a missing part is left empty
+/// rather than failing the other
+pub async fn get_stock_compliance(ticker: &Ticker, date: &NaiveDate) -> StockCompliance {
+    let date_start = *date - Duration::days(compliance::PENALTY_BACKWARD_DAYS);
+    let penalties = match fetch_stock_penalties(ticker, &date_start, date).await {
+        Ok(penalties) => penalties,
+        Err(err) => {
+            warn!("[Penalties] {err}");
+            vec![]
+        }
+    };
+
+    let esg_rating = match fetch_stock_esg_rating(ticker).await {
+        Ok(esg_rating) => esg_rating,
+        Err(err) => {
+            warn!("[ESG] {err}");
+            None
+        }
+    };
+
+    StockCompliance {
+        esg_rating,
+        penalties,
+    }
+}
+
+/// Management discussion and analysis of the latest periodic report as of the date
+pub async fn get_stock_management_discussion(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<Option<StockDisclosure>> {
+    fetch_stock_management_discussion(ticker, date).await
+}
+
+fn is_modified_today(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| {
+            DateTime::<Local>::from(modified).date_naive() == Local::now().date_naive()
+        })
+}
+
+fn save_cache(path: &Path, bytes: &[u8]) -> InvmstResult<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, bytes)?;
+
+    Ok(())
+}
+
+static DAILY_VALUATIONS_CACHE_DIR: LazyLock<PathBuf> =
+    LazyLock::new(|| APP_DATA_DIR.join("cache").join("daily_valuations"));
+
+/// Days a valuation is carried forward over suspensions and gaps, older values are not used
+pub static DAILY_VALUATIONS_MAX_STALENESS: i64 = 30;
+
+static INDEX_FUNDAMENTALS_CONSTITUENTS_SIZE: usize = 50;
+
+static MOMENTUM_RS_UNIVERSE_SIZE: usize = 50;
+
+/// Relative difference of a key figure between sources to report
+static RECONCILE_TOLERANCE: f64 = 0.05;
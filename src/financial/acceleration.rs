@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+use crate::{
+    data::stock::StockFiscalMetricset,
+    financial::fiscal_series::{FiscalSeries, SummaryField},
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Acceleration {
+    /// Year over year growth of single quarters, latest first
+    pub growths: Vec<f64>,
+    /// Consecutive latest quarters whose growth is higher than the quarter before
+    pub accelerating_quarters: usize,
+    /// Consecutive latest quarters whose growth is lower than the quarter before
+    pub decelerating_quarters: usize,
+}
+
+/// Detect acceleration of the year over year growth of a field, from consecutive fiscal
+/// metricsets sorted latest first
+pub fn detect(
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    field: SummaryField,
+) -> Option<Acceleration> {
+    let series = FiscalSeries::new(stock_fiscal_metricsets);
+
+    // Only the consecutive latest quarters count
+    let mut growths: Vec<f64> = vec![];
+    let mut expected_quarter = series.latest_quarter()?.clone();
+    for (fiscal_quarter, growth) in series.single_quarter_yoy(field) {
+        if fiscal_quarter != expected_quarter {
+            break;
+        }
+        growths.push(growth);
+        expected_quarter = fiscal_quarter.prev();
+    }
+
+    if growths.len() < 2 {
+        return None;
+    }
+
+    let streak = |is_faster: fn(f64, f64) -> bool| {
+        growths
+            .windows(2)
+            .take_while(|w| is_faster(w[0], w[1]))
+            .count()
+    };
+
+    Some(Acceleration {
+        accelerating_quarters: streak(|a, b| a > b),
+        decelerating_quarters: streak(|a, b| a < b),
+        growths,
+    })
+}
+
+impl Acceleration {
+    /// Describe the trend of growth if it has lasted for at least two quarters
+    pub fn assessment(&self, name: &str) -> Option<String> {
+        if self.accelerating_quarters >= 2 {
+            Some(format!(
+                "{name} growth accelerating for {} consecutive quarters",
+                self.accelerating_quarters
+            ))
+        } else if self.decelerating_quarters >= 2 {
+            Some(format!(
+                "{name} growth decelerating for {} consecutive quarters",
+                self.decelerating_quarters
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::stock::{StockFinancialSummary, StockMetricset},
+        utils::datetime::{FiscalQuarter, Quarter},
+    };
+
+    #[test]
+    fn test_detect() {
+        // Single quarter EPS of 2023 are all 1.0, then 2024 grows 10%, 20%, 30%, 40%
+        let mut fiscal_quarter = FiscalQuarter::new(2024, Quarter::Q4);
+        let mut stock_fiscal_metricsets: Vec<StockFiscalMetricset> = vec![];
+        for ytd in [5.0, 3.6, 2.3, 1.1, 4.0, 3.0, 2.0, 1.0] {
+            stock_fiscal_metricsets.push((
+                fiscal_quarter.clone(),
+                StockMetricset {
+                    financial_summary: StockFinancialSummary {
+                        earnings_per_share: Some(ytd),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ));
+            fiscal_quarter = fiscal_quarter.prev();
+        }
+
+        let acceleration = detect(&stock_fiscal_metricsets, |summary| {
+            summary.earnings_per_share
+        })
+        .unwrap();
+        assert_eq!(acceleration.growths.len(), 4);
+        assert_eq!(acceleration.accelerating_quarters, 3);
+        assert_eq!(acceleration.decelerating_quarters, 0);
+        assert_eq!(
+            acceleration.assessment("EPS").unwrap(),
+            "EPS growth accelerating for 3 consecutive quarters"
+        );
+    }
+}
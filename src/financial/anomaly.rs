@@ -0,0 +1,335 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    data::stock::{StockDailyData, StockFinancialSummary, StockFiscalMetricset},
+    financial::stock::StockValuationFieldName,
+};
+
+/// Implausible value from the data source
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DataAnomaly {
+    /// Fiscal quarter or date of the value
+    pub period: String,
+    pub field: String,
+    pub value: Option<f64>,
+    pub reason: String,
+    /// Removed before the analysis, otherwise kept and only flagged to the masters
+    pub dropped: bool,
+}
+
+/// Drop the values out of plausible ranges and the duplicate quarters, and flag the values
+/// inconsistent with each other, returns the anomalies found. Fiscal metricsets are sorted by
+/// fiscal quarter descending.
+pub fn sanitize(
+    stock_daily_data: &mut StockDailyData,
+    stock_fiscal_metricsets: &mut Vec<StockFiscalMetricset>,
+) -> Vec<DataAnomaly> {
+    let mut anomalies: Vec<DataAnomaly> = vec![];
+
+    // 同一财季重复出现时保留第一条
+    let mut seen_quarters = vec![];
+    stock_fiscal_metricsets.retain(|(fiscal_quarter, _)| {
+        if seen_quarters.contains(fiscal_quarter) {
+            anomalies.push(DataAnomaly {
+                period: fiscal_quarter.to_string(),
+                field: "fiscal_quarter".to_string(),
+                value: None,
+                reason: "Duplicate fiscal quarter".to_string(),
+                dropped: true,
+            });
+            false
+        } else {
+            seen_quarters.push(fiscal_quarter.clone());
+            true
+        }
+    });
+
+    for (fiscal_quarter, stock_metricset) in stock_fiscal_metricsets.iter_mut() {
+        let period = fiscal_quarter.to_string();
+        let summary = &mut stock_metricset.financial_summary;
+
+        for (field, value) in drop_out_of_range(summary) {
+            anomalies.push(DataAnomaly {
+                period: period.clone(),
+                field,
+                value: Some(value),
+                reason: "Out of plausible range".to_string(),
+                dropped: true,
+            });
+        }
+
+        for (field, value, reason) in check_consistency(summary) {
+            anomalies.push(DataAnomaly {
+                period: period.clone(),
+                field: field.to_string(),
+                value,
+                reason,
+                dropped: false,
+            });
+        }
+    }
+
+    for (field_name, field) in DAILY_POSITIVE_FIELDS {
+        for (date, value) in stock_daily_data
+            .daily_valuations
+            .drop_values(*field_name, |value| value > 0.0)
+        {
+            anomalies.push(DataAnomaly {
+                period: date.to_string(),
+                field: field.to_string(),
+                value: Some(value),
+                reason: "Not positive".to_string(),
+                dropped: true,
+            });
+        }
+    }
+
+    anomalies
+}
+
+impl DataAnomaly {
+    pub fn assessment(&self) -> String {
+        let value = self
+            .value
+            .map(|value| format!(" ({value})"))
+            .unwrap_or_default();
+        let action = if self.dropped { "dropped" } else { "flagged" };
+
+        format!(
+            "{} {}{value}: {}, {action}",
+            self.period, self.field, self.reason
+        )
+    }
+}
+
+/// Set the fields out of their ranges to None, returns the fields and values dropped
+fn drop_out_of_range(summary: &mut StockFinancialSummary) -> Vec<(String, f64)> {
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(&*summary) else {
+        return vec![];
+    };
+
+    let mut dropped: Vec<(String, f64)> = vec![];
+    for (field, min, max) in FISCAL_FIELD_RANGES {
+        if let Some(value) = fields.get(*field).and_then(|value| value.as_f64()) {
+            if !value.is_finite() || value < *min || value > *max {
+                fields.insert(field.to_string(), Value::Null);
+                dropped.push((field.to_string(), value));
+            }
+        }
+    }
+
+    if !dropped.is_empty() {
+        if let Ok(sanitized) = serde_json::from_value(Value::Object(fields)) {
+            *summary = sanitized;
+        }
+    }
+
+    dropped
+}
+
+/// Values contradicting each other, which one is wrong is unknown so both are kept
+fn check_consistency(summary: &StockFinancialSummary) -> Vec<(&'static str, Option<f64>, String)> {
+    let mut inconsistencies: Vec<(&'static str, Option<f64>, String)> = vec![];
+
+    // 速动资产是流动资产的一部分，现金又是速动资产的一部分
+    if let (Some(quick_ratio), Some(current_ratio)) = (summary.quick_ratio, summary.current_ratio) {
+        if quick_ratio > current_ratio * (1.0 + RATIO_TOLERANCE) {
+            inconsistencies.push((
+                "quick_ratio",
+                Some(quick_ratio),
+                format!("Above the current ratio {current_ratio}"),
+            ));
+        }
+    }
+    if let (Some(cash_ratio), Some(quick_ratio)) = (summary.cash_ratio, summary.quick_ratio) {
+        if cash_ratio > quick_ratio * (1.0 + RATIO_TOLERANCE) {
+            inconsistencies.push((
+                "cash_ratio",
+                Some(cash_ratio),
+                format!("Above the quick ratio {quick_ratio}"),
+            ));
+        }
+    }
+
+    if let (Some(ebitda), Some(ebit)) = (summary.ebitda, summary.ebit) {
+        if ebitda < ebit - ebit.abs() * RATIO_TOLERANCE {
+            inconsistencies.push(("ebitda", Some(ebitda), format!("Below the EBIT {ebit}")));
+        }
+    }
+
+    if let (Some(net_profit), Some(net_margin), Some(operating_revenue)) = (
+        summary.net_profit,
+        summary.net_margin,
+        summary.operating_revenue,
+    ) {
+        let implied_net_profit = net_margin * operating_revenue;
+        if operating_revenue > 0.0
+            && (net_profit - implied_net_profit).abs()
+                > net_profit.abs().max(implied_net_profit.abs()) * NET_PROFIT_TOLERANCE
+        {
+            inconsistencies.push((
+                "net_margin",
+                Some(net_margin),
+                format!(
+                    "Implies a net profit of {implied_net_profit:.0} rather than {net_profit:.0}"
+                ),
+            ));
+        }
+    }
+
+    if let (Some(net_assets), Some(return_on_equity)) =
+        (summary.net_assets, summary.return_on_equity)
+    {
+        if net_assets <= 0.0 && return_on_equity > 0.0 {
+            inconsistencies.push((
+                "return_on_equity",
+                Some(return_on_equity),
+                format!("Positive with non-positive net assets {net_assets}"),
+            ));
+        }
+    }
+
+    inconsistencies
+}
+
+/// Named in snake case like the fiscal fields, rather than by the display names of the fields
+static DAILY_POSITIVE_FIELDS: &[(StockValuationFieldName, &str)] = &[
+    (StockValuationFieldName::Price, "price"),
+    (StockValuationFieldName::MarketCap, "market_cap"),
+];
+
+/// Plausible ranges of the fields, ratios are fractions rather than percentages
+static FISCAL_FIELD_RANGES: &[(&str, f64, f64)] = &[
+    ("asset_turnover", 0.0, 100.0),
+    ("capital_expenditure", 0.0, f64::MAX),
+    ("cash_and_equivalents", 0.0, f64::MAX),
+    ("cash_ratio", 0.0, 1000.0),
+    ("current_ratio", 0.0, 1000.0),
+    ("days_inventory_outstanding", 0.0, f64::MAX),
+    ("days_payable_outstanding", 0.0, f64::MAX),
+    ("days_sales_outstanding", 0.0, f64::MAX),
+    ("debt_to_assets", 0.0, 5.0),
+    ("goodwill", 0.0, f64::MAX),
+    ("gross_margin", -5.0, 1.0),
+    ("inventory_turnover", 0.0, f64::MAX),
+    ("net_margin", -50.0, 5.0),
+    ("operating_margin", -50.0, 5.0),
+    ("operating_revenue", 0.0, f64::MAX),
+    ("quick_ratio", 0.0, 1000.0),
+    ("receivables_turnover", 0.0, f64::MAX),
+    ("return_on_assets", -5.0, 5.0),
+    ("return_on_equity", -5.0, 5.0),
+    ("return_on_invested_capital", -5.0, 5.0),
+    ("revenue_growth", -1.0, 100.0),
+];
+
+/// Net profit may differ from net margin times revenue by minority interests, but not by half
+static NET_PROFIT_TOLERANCE: f64 = 0.5;
+static RATIO_TOLERANCE: f64 = 0.01;
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{
+        data::{daily::DailyDataset, stock::StockMetricset},
+        utils::datetime::{FiscalQuarter, Quarter},
+    };
+
+    #[test]
+    fn test_sanitize() {
+        let metricset = |return_on_equity: f64, quick_ratio: f64| StockMetricset {
+            financial_summary: StockFinancialSummary {
+                return_on_equity: Some(return_on_equity),
+                current_ratio: Some(1.5),
+                quick_ratio: Some(quick_ratio),
+                gross_margin: Some(0.4),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut stock_fiscal_metricsets = vec![
+            (
+                FiscalQuarter {
+                    year: 2024,
+                    quarter: Quarter::Q4,
+                },
+                metricset(45.0, 1.0),
+            ),
+            (
+                FiscalQuarter {
+                    year: 2024,
+                    quarter: Quarter::Q3,
+                },
+                metricset(0.12, 2.0),
+            ),
+            (
+                FiscalQuarter {
+                    year: 2024,
+                    quarter: Quarter::Q3,
+                },
+                metricset(0.12, 1.0),
+            ),
+        ];
+        let mut stock_daily_data = StockDailyData {
+            daily_valuations: DailyDataset::from_json(
+                &serde_json::json!([
+                    {"date": "2024-12-30", "close": 10.0, "cap": 1e9},
+                    {"date": "2024-12-31", "close": 10.2, "cap": -1e9},
+                ]),
+                "date",
+                &[
+                    (StockValuationFieldName::Price, "close"),
+                    (StockValuationFieldName::MarketCap, "cap"),
+                ],
+            )
+            .unwrap(),
+            quote: None,
+            daily_bars: vec![],
+            margin_balances: None,
+            northbound_holdings: None,
+        };
+
+        let anomalies = sanitize(&mut stock_daily_data, &mut stock_fiscal_metricsets);
+
+        assert_eq!(stock_fiscal_metricsets.len(), 2);
+        assert_eq!(
+            stock_fiscal_metricsets[0]
+                .1
+                .financial_summary
+                .return_on_equity,
+            None
+        );
+        assert_eq!(
+            stock_fiscal_metricsets[0].1.financial_summary.gross_margin,
+            Some(0.4)
+        );
+        assert_eq!(
+            stock_fiscal_metricsets[1].1.financial_summary.quick_ratio,
+            Some(2.0)
+        );
+        assert_eq!(
+            stock_daily_data.daily_valuations.get_latest_value::<f64>(
+                &NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                StockValuationFieldName::MarketCap
+            ),
+            Some(1e9)
+        );
+
+        let fields: Vec<(&str, bool)> = anomalies
+            .iter()
+            .map(|anomaly| (anomaly.field.as_str(), anomaly.dropped))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("fiscal_quarter", true),
+                ("return_on_equity", true),
+                ("quick_ratio", false),
+                ("market_cap", true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use serde::Serialize;
+
+use crate::data::stock::StockFinancialSummary;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CapitalIntensity {
+    pub capex_to_sales: Option<f64>,
+    /// Above 1 means investing beyond maintaining the existing assets
+    pub capex_to_depreciation: Option<f64>,
+    /// Growth of after-tax operating profit divided by growth of invested capital
+    pub incremental_return_on_capital: Option<f64>,
+}
+
+/// Compute capital intensity from annual summaries sorted latest first, the incremental return is
+/// measured between the latest and the earliest
+pub fn compute(annual_summaries: &[&StockFinancialSummary]) -> CapitalIntensity {
+    let Some(latest) = annual_summaries.first() else {
+        return CapitalIntensity::default();
+    };
+
+    let ratio = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) if b > 0.0 => Some(a / b),
+        _ => None,
+    };
+
+    let incremental_return_on_capital = match annual_summaries.last() {
+        Some(earliest) if annual_summaries.len() > 1 => {
+            match (
+                nopat(latest),
+                nopat(earliest),
+                invested_capital(latest),
+                invested_capital(earliest),
+            ) {
+                (Some(nopat), Some(nopat_prev), Some(capital), Some(capital_prev))
+                    if capital > capital_prev =>
+                {
+                    Some((nopat - nopat_prev) / (capital - capital_prev))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    CapitalIntensity {
+        capex_to_sales: ratio(latest.capital_expenditure, latest.operating_revenue),
+        capex_to_depreciation: ratio(latest.capital_expenditure, latest.depreciation_amortization),
+        incremental_return_on_capital,
+    }
+}
+
+/// Net operating profit after tax, using the statutory income tax rate of China
+pub fn nopat(summary: &StockFinancialSummary) -> Option<f64> {
+    summary.ebit.map(|ebit| ebit * (1.0 - INCOME_TAX_RATE))
+}
+
+/// Equity plus interest-bearing debt, net of cash, None if any of them is missing
+pub fn invested_capital(summary: &StockFinancialSummary) -> Option<f64> {
+    Some(summary.net_assets? + summary.total_debt? - summary.cash_and_equivalents?)
+}
+
+/// Statutory corporate income tax rate of China
+pub static INCOME_TAX_RATE: f64 = 0.25;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let latest = StockFinancialSummary {
+            capital_expenditure: Some(10.0),
+            depreciation_amortization: Some(5.0),
+            operating_revenue: Some(100.0),
+            ebit: Some(40.0),
+            net_assets: Some(200.0),
+            total_debt: Some(30.0),
+            cash_and_equivalents: Some(30.0),
+            ..Default::default()
+        };
+        let earliest = StockFinancialSummary {
+            ebit: Some(20.0),
+            net_assets: Some(150.0),
+            total_debt: Some(10.0),
+            cash_and_equivalents: Some(10.0),
+            ..Default::default()
+        };
+
+        let capital_intensity = compute(&[&latest, &earliest]);
+        assert_eq!(capital_intensity.capex_to_sales, Some(0.1));
+        assert_eq!(capital_intensity.capex_to_depreciation, Some(2.0));
+        assert_eq!(capital_intensity.incremental_return_on_capital, Some(0.3));
+
+        // 缺少资产负债表的报告期不与完整的报告期相减
+        let earliest = StockFinancialSummary {
+            total_debt: None,
+            cash_and_equivalents: None,
+            ..earliest
+        };
+        assert_eq!(
+            compute(&[&latest, &earliest]).incremental_return_on_capital,
+            None
+        );
+    }
+}
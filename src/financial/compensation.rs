@@ -0,0 +1,91 @@
+use serde::Serialize;
+
+use crate::data::stock::StockFinancialSummary;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CompensationAlignment {
+    /// Growth of compensation from the earliest to the latest year
+    pub compensation_growth: Option<f64>,
+    pub net_profit_growth: Option<f64>,
+    /// Price change plus dividends over the window
+    pub shareholder_return: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alignment {
+    /// Compensation grows no faster than profit
+    Aligned,
+    Neutral,
+    /// Compensation outgrows both profit and shareholder return by a wide margin
+    Misaligned,
+}
+
+/// Compare compensation growth with profit growth of annual summaries sorted latest first
+pub fn compute(
+    annual_summaries: &[&StockFinancialSummary],
+    shareholder_return: Option<f64>,
+) -> CompensationAlignment {
+    let growth = |field: fn(&StockFinancialSummary) -> Option<f64>| match (
+        annual_summaries.first().and_then(|s| field(s)),
+        annual_summaries.last().and_then(|s| field(s)),
+    ) {
+        (Some(latest), Some(earliest)) if annual_summaries.len() > 1 && earliest > 0.0 => {
+            Some(latest / earliest - 1.0)
+        }
+        _ => None,
+    };
+
+    CompensationAlignment {
+        compensation_growth: growth(|s| s.staff_compensation),
+        net_profit_growth: growth(|s| s.net_profit),
+        shareholder_return,
+    }
+}
+
+impl CompensationAlignment {
+    pub fn alignment(&self) -> Option<Alignment> {
+        let compensation_growth = self.compensation_growth?;
+        let net_profit_growth = self.net_profit_growth?;
+
+        if compensation_growth <= net_profit_growth {
+            Some(Alignment::Aligned)
+        } else if compensation_growth > net_profit_growth + MISALIGNMENT_MARGIN
+            && self
+                .shareholder_return
+                .is_none_or(|r| compensation_growth > r + MISALIGNMENT_MARGIN)
+        {
+            Some(Alignment::Misaligned)
+        } else {
+            Some(Alignment::Neutral)
+        }
+    }
+}
+
+static MISALIGNMENT_MARGIN: f64 = 0.2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alignment() {
+        let latest = StockFinancialSummary {
+            staff_compensation: Some(150.0),
+            net_profit: Some(100.0),
+            ..Default::default()
+        };
+        let earliest = StockFinancialSummary {
+            staff_compensation: Some(100.0),
+            net_profit: Some(100.0),
+            ..Default::default()
+        };
+
+        let compensation = compute(&[&latest, &earliest], Some(0.0));
+        assert_eq!(compensation.compensation_growth, Some(0.5));
+        assert_eq!(compensation.net_profit_growth, Some(0.0));
+        assert_eq!(compensation.alignment(), Some(Alignment::Misaligned));
+
+        let compensation = compute(&[&latest, &earliest], Some(0.4));
+        assert_eq!(compensation.alignment(), Some(Alignment::Neutral));
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::data::stock::{PenaltyCategory, StockCompliance, StockPenalty};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, strum::Display,
+)]
+pub enum ComplianceRiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// Environmental and regulatory risk from the penalties and the ESG rating of the company
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComplianceRisk {
+    pub level: ComplianceRiskLevel,
+    pub esg_grade: Option<String>,
+    pub governance_grade: Option<String>,
+    /// Penalties within the recent years, latest first
+    pub penalties: Vec<StockPenalty>,
+    pub reasons: Vec<String>,
+}
+
+impl ComplianceRisk {
+    pub fn assessment(&self) -> String {
+        if self.reasons.is_empty() {
+            format!("{}, no penalties or poor ESG grades", self.level)
+        } else {
+            format!("{}, {}", self.level, self.reasons.join("; "))
+        }
+    }
+}
+
+/// Pending investigations and repeated penalties are high risks, a single penalty or a poor ESG
+/// grade is a medium risk. Penalties before the recent years are not counted.
+pub fn assess(compliance: &StockCompliance, date: &NaiveDate) -> ComplianceRisk {
+    let date_start = *date - Duration::days(PENALTY_BACKWARD_DAYS);
+    let penalties: Vec<StockPenalty> = compliance
+        .penalties
+        .iter()
+        .filter(|penalty| penalty.date >= date_start && penalty.date <= *date)
+        .cloned()
+        .collect();
+
+    let mut level = ComplianceRiskLevel::Low;
+    let mut reasons: Vec<String> = vec![];
+
+    let investigations = penalties
+        .iter()
+        .filter(|penalty| penalty.category == PenaltyCategory::Investigation)
+        .count();
+    if investigations > 0 {
+        level = ComplianceRiskLevel::High;
+        reasons.push(format!("{investigations} investigations by regulators"));
+    }
+
+    for category in [PenaltyCategory::Environmental, PenaltyCategory::Regulatory] {
+        let count = penalties
+            .iter()
+            .filter(|penalty| penalty.category == category)
+            .count();
+        if count > 0 {
+            level = level.max(if count > 1 {
+                ComplianceRiskLevel::High
+            } else {
+                ComplianceRiskLevel::Medium
+            });
+            reasons.push(format!(
+                "{count} {} penalties",
+                category.to_string().to_lowercase()
+            ));
+        }
+    }
+
+    let esg_rating = compliance.esg_rating.as_ref();
+    for (name, grade) in [
+        ("ESG", esg_rating.and_then(|rating| rating.grade.as_deref())),
+        (
+            "Governance",
+            esg_rating.and_then(|rating| rating.governance_grade.as_deref()),
+        ),
+    ] {
+        if let Some(grade) = grade.filter(|grade| is_poor_grade(grade)) {
+            level = level.max(ComplianceRiskLevel::Medium);
+            reasons.push(format!("{name} grade {grade}"));
+        }
+    }
+
+    ComplianceRisk {
+        level,
+        esg_grade: esg_rating.and_then(|rating| rating.grade.clone()),
+        governance_grade: esg_rating.and_then(|rating| rating.governance_grade.clone()),
+        penalties,
+        reasons,
+    }
+}
+
+/// Grades of the C tier, i.e. CCC, CC and C
+fn is_poor_grade(grade: &str) -> bool {
+    grade.trim().starts_with('C')
+}
+
+/// Penalties older than the days are considered remedied
+pub static PENALTY_BACKWARD_DAYS: i64 = 3 * 365;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::stock::StockEsgRating;
+
+    #[test]
+    fn test_assess() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+        let penalty = |date: &str, title: &str| StockPenalty {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            title: title.to_string(),
+            category: PenaltyCategory::classify(title),
+        };
+
+        let mut compliance = StockCompliance {
+            esg_rating: Some(StockEsgRating {
+                grade: Some("BBB".to_string()),
+                governance_grade: Some("A".to_string()),
+                ..Default::default()
+            }),
+            penalties: vec![
+                penalty("2025-03-01", "关于子公司收到环保行政处罚决定书的公告"),
+                penalty("2020-01-01", "关于收到中国证监会立案告知书的公告"),
+            ],
+        };
+        let compliance_risk = assess(&compliance, &date);
+        assert_eq!(compliance_risk.level, ComplianceRiskLevel::Medium);
+        assert_eq!(compliance_risk.penalties.len(), 1);
+        assert_eq!(compliance_risk.reasons, vec!["1 environmental penalties"]);
+
+        compliance
+            .penalties
+            .push(penalty("2024-11-01", "关于收到中国证监会立案告知书的公告"));
+        compliance.esg_rating = Some(StockEsgRating {
+            grade: Some("CCC".to_string()),
+            ..Default::default()
+        });
+        let compliance_risk = assess(&compliance, &date);
+        assert_eq!(compliance_risk.level, ComplianceRiskLevel::High);
+        assert_eq!(compliance_risk.reasons.len(), 3);
+
+        assert_eq!(
+            assess(&StockCompliance::default(), &date).level,
+            ComplianceRiskLevel::Low
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{data::stock::StockAnalystForecast, utils::stats};
+
+/// Sell-side expectations of the fiscal year as of the date
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnalystConsensus {
+    pub fiscal_year: i32,
+    /// Institutions with a forecast within the window
+    pub institutions: usize,
+    pub eps_estimate: Option<f64>,
+    pub target_price: Option<f64>,
+    /// Target price relative to the current price
+    pub implied_upside: Option<f64>,
+    /// Change of the average EPS estimate of the recent months from the months before
+    pub eps_revision: Option<f64>,
+    /// Ratings and their counts, most common first
+    pub ratings: Vec<(String, usize)>,
+}
+
+impl AnalystConsensus {
+    pub fn assessment(&self) -> String {
+        let mut parts = vec![format!("{} institutions", self.institutions)];
+        if let Some(eps_estimate) = self.eps_estimate {
+            parts.push(format!(
+                "EPS {} estimate {eps_estimate:.2}",
+                self.fiscal_year
+            ));
+        }
+        if let Some(implied_upside) = self.implied_upside {
+            parts.push(format!("implied upside {implied_upside:+.2}"));
+        }
+        if let Some(eps_revision) = self.eps_revision {
+            parts.push(format!("EPS revised {eps_revision:+.2}"));
+        }
+
+        parts.join(", ")
+    }
+}
+
+/// Consensus of the latest forecast of each institution within the window as of the date, None if
+/// no institution covers the stock. Estimates of the current fiscal year are taken from either the
+/// current year of the reports this year or the next year of the reports last year.
+pub fn compute(
+    forecasts: &[StockAnalystForecast],
+    price: Option<f64>,
+    date: &NaiveDate,
+) -> Option<AnalystConsensus> {
+    let fiscal_year = date.year();
+    let eps_of = |forecast: &StockAnalystForecast| {
+        if forecast.fiscal_year == fiscal_year {
+            forecast.eps
+        } else if forecast.fiscal_year == fiscal_year - 1 {
+            forecast.eps_next_year
+        } else {
+            None
+        }
+    };
+
+    let date_start = *date - Duration::days(WINDOW_DAYS);
+    let mut recent: Vec<&StockAnalystForecast> = forecasts
+        .iter()
+        .filter(|forecast| forecast.date > date_start && forecast.date <= *date)
+        .collect();
+    recent.sort_by_key(|forecast| std::cmp::Reverse(forecast.date));
+
+    let mut latest: Vec<&StockAnalystForecast> = vec![];
+    for forecast in recent {
+        if !latest
+            .iter()
+            .any(|other| other.institution == forecast.institution)
+        {
+            latest.push(forecast);
+        }
+    }
+    if latest.is_empty() {
+        return None;
+    }
+
+    let eps_estimates: Vec<f64> = latest.iter().filter_map(|f| eps_of(f)).collect();
+    let target_prices: Vec<f64> = latest.iter().filter_map(|f| f.target_price).collect();
+    let target_price = stats::median(&target_prices);
+
+    // 比较近几个月与之前几个月的平均预测，反映预期的上修或下修
+    let revision_date = *date - Duration::days(REVISION_DAYS);
+    let mean_eps = |from: NaiveDate, to: NaiveDate| {
+        stats::mean(
+            &forecasts
+                .iter()
+                .filter(|forecast| forecast.date > from && forecast.date <= to)
+                .filter_map(eps_of)
+                .collect::<Vec<f64>>(),
+        )
+    };
+    let eps_revision = mean_eps(revision_date, *date)
+        .zip(mean_eps(
+            revision_date - Duration::days(REVISION_DAYS),
+            revision_date,
+        ))
+        .filter(|(_, base)| *base > 0.0)
+        .map(|(eps, base)| eps / base - 1.0);
+
+    let mut ratings: HashMap<&str, usize> = HashMap::new();
+    for rating in latest.iter().filter_map(|f| f.rating.as_deref()) {
+        *ratings.entry(rating).or_default() += 1;
+    }
+    let mut ratings: Vec<(String, usize)> = ratings
+        .into_iter()
+        .map(|(rating, count)| (rating.to_string(), count))
+        .collect();
+    ratings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Some(AnalystConsensus {
+        fiscal_year,
+        institutions: latest.len(),
+        eps_estimate: stats::mean(&eps_estimates),
+        target_price,
+        implied_upside: target_price
+            .zip(price.filter(|price| *price > 0.0))
+            .map(|(target_price, price)| target_price / price - 1.0),
+        eps_revision,
+        ratings,
+    })
+}
+
+static REVISION_DAYS: i64 = 90;
+
+/// Forecasts older than the days are considered stale
+pub static WINDOW_DAYS: i64 = 180;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let forecast = |date: &str, institution: &str, eps: [f64; 2], target_price: Option<f64>| {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+            StockAnalystForecast {
+                date,
+                institution: institution.to_string(),
+                rating: Some("买入".to_string()),
+                fiscal_year: date.year(),
+                eps: Some(eps[0]),
+                eps_next_year: Some(eps[1]),
+                target_price,
+            }
+        };
+        let forecasts = vec![
+            forecast("2025-03-20", "A", [1.2, 1.5], Some(30.0)),
+            forecast("2025-02-10", "B", [1.1, 1.3], Some(25.0)),
+            forecast("2024-12-01", "A", [0.8, 1.0], Some(24.0)),
+            forecast("2024-11-01", "C", [0.7, 0.9], None),
+        ];
+        let date = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+
+        let consensus = compute(&forecasts, Some(25.0), &date).unwrap();
+        assert_eq!(consensus.institutions, 3);
+        assert!((consensus.eps_estimate.unwrap() - (1.2 + 1.1 + 0.9) / 3.0).abs() < 1e-9);
+        assert_eq!(consensus.target_price, Some(27.5));
+        assert!((consensus.implied_upside.unwrap() - 0.1).abs() < 1e-9);
+        assert!((consensus.eps_revision.unwrap() - (1.15 / 0.95 - 1.0)).abs() < 1e-9);
+        assert_eq!(consensus.ratings, vec![("买入".to_string(), 3)]);
+
+        assert!(compute(&forecasts, None, &(date + Duration::days(365))).is_none());
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+use crate::utils::stats;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Cyclicality {
+    /// Annual EPS by fiscal year, latest first
+    pub annual_eps: Vec<(i32, f64)>,
+    /// Standard deviation of annual EPS divided by the absolute mean
+    pub variation: Option<f64>,
+    /// Correlation of annual EPS growth with the average PPI year over year change
+    pub ppi_correlation: Option<f64>,
+    pub is_cyclical: bool,
+    /// Average EPS through the cycle, only present if earnings are cyclical
+    pub normalized_eps: Option<f64>,
+}
+
+/// Detect cyclical earnings from annual EPS sorted latest first, and the average PPI year over
+/// year change of each year
+pub fn detect(annual_eps: &[(i32, f64)], ppi: &[(i32, f64)]) -> Cyclicality {
+    let annual_eps: Vec<(i32, f64)> = annual_eps.iter().take(CYCLE_YEARS).copied().collect();
+    if annual_eps.len() < CYCLE_YEARS_MIN {
+        return Cyclicality {
+            annual_eps,
+            ..Default::default()
+        };
+    }
+
+    let values: Vec<f64> = annual_eps.iter().map(|(_, eps)| *eps).collect();
+    let mean = stats::mean(&values);
+    let variation = match (mean, stats::std(&values)) {
+        (Some(mean), Some(std)) if mean != 0.0 => Some(std / mean.abs()),
+        _ => None,
+    };
+
+    let mut growths: Vec<f64> = vec![];
+    let mut ppi_changes: Vec<f64> = vec![];
+    for w in annual_eps.windows(2) {
+        let ((year, eps), (_, eps_prev)) = (w[0], w[1]);
+        if let Some((_, ppi_change)) = ppi.iter().find(|(ppi_year, _)| *ppi_year == year) {
+            if let Some(growth) = stats::growth_rate(eps, eps_prev) {
+                growths.push(growth);
+                ppi_changes.push(*ppi_change);
+            }
+        }
+    }
+    let ppi_correlation = stats::correlation(&growths, &ppi_changes);
+
+    // Losses between profitable years are the most obvious sign of a cycle
+    let has_loss = values.iter().any(|eps| *eps < 0.0) && values.iter().any(|eps| *eps > 0.0);
+    let is_cyclical = has_loss
+        || variation.is_some_and(|v| v > 0.5)
+        || (variation.is_some_and(|v| v > 0.3) && ppi_correlation.is_some_and(|c| c > 0.6));
+
+    Cyclicality {
+        annual_eps,
+        variation,
+        ppi_correlation,
+        is_cyclical,
+        normalized_eps: if is_cyclical { mean } else { None },
+    }
+}
+
+impl Cyclicality {
+    pub fn assessment(&self) -> Option<String> {
+        match (self.is_cyclical, self.normalized_eps) {
+            (true, Some(normalized_eps)) => Some(format!(
+                "Earnings are cyclical, valued on {}-year average EPS ({normalized_eps:.2}) rather than the latest",
+                self.annual_eps.len()
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Years of a full cycle to average earnings over
+pub static CYCLE_YEARS: usize = 7;
+
+static CYCLE_YEARS_MIN: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let stable: Vec<(i32, f64)> = (0..7).map(|i| (2024 - i, 1.0 + i as f64 * 0.01)).collect();
+        let cyclicality = detect(&stable, &[]);
+        assert!(!cyclicality.is_cyclical);
+        assert_eq!(cyclicality.normalized_eps, None);
+
+        let cyclical = [
+            (2024, 2.0),
+            (2023, -0.5),
+            (2022, 1.0),
+            (2021, 3.0),
+            (2020, 0.5),
+            (2019, 1.5),
+            (2018, 1.0),
+            (2017, 9.0),
+        ];
+        let cyclicality = detect(&cyclical, &[]);
+        assert!(cyclicality.is_cyclical);
+        assert_eq!(cyclicality.annual_eps.len(), 7);
+        assert!((cyclicality.normalized_eps.unwrap() - 8.5 / 7.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,231 @@
+use std::{path::PathBuf, sync::LazyLock};
+
+use log::warn;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{APP_DATA_DIR, utils::stats};
+
+/// Distribution to sample an assumption from
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Distribution {
+    Fixed { value: f64 },
+    Uniform { low: f64, high: f64 },
+    Normal { mean: f64, std: f64 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DcfAssumptions {
+    /// Annual growth of cash flow during the forecast years
+    pub growth: Distribution,
+    pub discount_rate: Distribution,
+    /// Perpetual growth after the forecast years
+    pub terminal_growth: Distribution,
+    /// Multiplier on the base cash flow, modelling margin expansion or compression
+    pub margin: Distribution,
+    pub years: u32,
+    pub simulations: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FairValue {
+    /// Fair value using the mean of every assumption
+    pub point: f64,
+    pub p10: Option<f64>,
+    pub p50: Option<f64>,
+    pub p90: Option<f64>,
+    /// Simulations whose sampled discount rate did not exceed the terminal growth, left out of the
+    /// percentiles
+    pub rejected_simulations: usize,
+}
+
+/// Load assumptions from the config, which is created with defaults on first use. An unreadable
+/// config falls back to the defaults with a warning rather than failing the evaluation.
+pub fn load_assumptions() -> DcfAssumptions {
+    confy::load_path(&*DCF_CONFIG_PATH).unwrap_or_else(|err| {
+        warn!(
+            "[DCF] Unable to load '{}', use the default assumptions: {err}",
+            DCF_CONFIG_PATH.display()
+        );
+        DcfAssumptions::default()
+    })
+}
+
+/// Estimate the fair value per share from the annual cash flow per share, sampling the assumptions
+/// to get the P10/P50/P90 of fair values if monte_carlo is enabled
+pub fn estimate(
+    cash_flow: f64,
+    assumptions: &DcfAssumptions,
+    monte_carlo: bool,
+) -> Option<FairValue> {
+    let point = intrinsic_value(
+        cash_flow * assumptions.margin.mean(),
+        assumptions.growth.mean(),
+        assumptions.discount_rate.mean(),
+        assumptions.terminal_growth.mean(),
+        assumptions.years,
+    )?;
+
+    let (p10, p50, p90, rejected_simulations) = if monte_carlo {
+        let values = simulate(cash_flow, assumptions, &mut StdRng::from_os_rng());
+        (
+            stats::quantile(&values, 0.1),
+            stats::quantile(&values, 0.5),
+            stats::quantile(&values, 0.9),
+            assumptions.simulations - values.len(),
+        )
+    } else {
+        (None, None, None, 0)
+    };
+
+    Some(FairValue {
+        point,
+        p10,
+        p50,
+        p90,
+        rejected_simulations,
+    })
+}
+
+/// Present value of cash flows growing for years then perpetually at the terminal growth, None if
+/// the discount rate does not exceed the terminal growth
+pub fn intrinsic_value(
+    cash_flow: f64,
+    growth: f64,
+    discount_rate: f64,
+    terminal_growth: f64,
+    years: u32,
+) -> Option<f64> {
+    if discount_rate <= terminal_growth || discount_rate <= -1.0 {
+        return None;
+    }
+
+    let mut value = 0.0;
+    let mut flow = cash_flow;
+    for year in 1..=years {
+        flow *= 1.0 + growth;
+        value += flow / (1.0 + discount_rate).powi(year as i32);
+    }
+
+    let terminal_value = flow * (1.0 + terminal_growth) / (discount_rate - terminal_growth);
+    value += terminal_value / (1.0 + discount_rate).powi(years as i32);
+
+    Some(value)
+}
+
+impl Default for DcfAssumptions {
+    fn default() -> Self {
+        Self {
+            growth: Distribution::Normal {
+                mean: 0.05,
+                std: 0.03,
+            },
+            discount_rate: Distribution::Normal {
+                mean: 0.09,
+                std: 0.01,
+            },
+            terminal_growth: Distribution::Uniform {
+                low: 0.01,
+                high: 0.03,
+            },
+            margin: Distribution::Normal {
+                mean: 1.0,
+                std: 0.1,
+            },
+            years: 10,
+            simulations: 2000,
+        }
+    }
+}
+
+impl std::fmt::Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed { value } => write!(f, "{value}"),
+            Self::Uniform { low, high } => write!(f, "uniform({low}, {high})"),
+            Self::Normal { mean, std } => write!(f, "normal({mean}, {std})"),
+        }
+    }
+}
+
+impl Distribution {
+    pub fn mean(&self) -> f64 {
+        match self {
+            Self::Fixed { value } => *value,
+            Self::Uniform { low, high } => (low + high) / 2.0,
+            Self::Normal { mean, .. } => *mean,
+        }
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            Self::Fixed { value } => *value,
+            Self::Uniform { low, high } => low + (high - low) * rng.random::<f64>(),
+            Self::Normal { mean, std } => {
+                // Box-Muller transform
+                let u1 = 1.0 - rng.random::<f64>();
+                let u2 = rng.random::<f64>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+
+                mean + std * z
+            }
+        }
+    }
+}
+
+static DCF_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("dcf.toml"));
+
+/// Fair values of the simulations, those with invalid sampled rates are rejected rather than clamped
+/// so that the distribution is not piled up at the boundary
+fn simulate<R: Rng>(cash_flow: f64, assumptions: &DcfAssumptions, rng: &mut R) -> Vec<f64> {
+    (0..assumptions.simulations)
+        .filter_map(|_| {
+            intrinsic_value(
+                cash_flow * assumptions.margin.sample(rng),
+                assumptions.growth.sample(rng),
+                assumptions.discount_rate.sample(rng),
+                assumptions.terminal_growth.sample(rng),
+                assumptions.years,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intrinsic_value() {
+        // Without growth the value is a perpetuity
+        let value = intrinsic_value(1.0, 0.0, 0.1, 0.0, 5).unwrap();
+        assert!((value - 10.0).abs() < 1e-9);
+        assert_eq!(intrinsic_value(1.0, 0.05, 0.03, 0.03, 5), None);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let assumptions = DcfAssumptions::default();
+        let values = simulate(1.0, &assumptions, &mut StdRng::seed_from_u64(0));
+        assert_eq!(values.len(), assumptions.simulations);
+
+        let p10 = stats::quantile(&values, 0.1).unwrap();
+        let p90 = stats::quantile(&values, 0.9).unwrap();
+        let point = estimate(1.0, &assumptions, false).unwrap().point;
+        assert!(p10 < point && point < p90);
+
+        // 折现率与永续增长率区间重叠时，部分模拟被剔除
+        let assumptions = DcfAssumptions {
+            discount_rate: Distribution::Uniform {
+                low: 0.01,
+                high: 0.07,
+            },
+            terminal_growth: Distribution::Fixed { value: 0.02 },
+            ..DcfAssumptions::default()
+        };
+        let fair_value = estimate(1.0, &assumptions, true).unwrap();
+        assert!(fair_value.rejected_simulations > 0);
+        assert!(fair_value.rejected_simulations < assumptions.simulations);
+    }
+}
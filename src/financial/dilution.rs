@@ -0,0 +1,120 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::data::stock::StockShareChange;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Dilution {
+    /// Annualized growth of shares excluding bonus shares and splits, negative means buybacks
+    pub annual_dilution_rate: Option<f64>,
+    /// Secondary offerings and rights issues within the window
+    pub offerings: usize,
+}
+
+/// Measure dilution between the dates from share changes sorted by date ascending
+pub fn compute(
+    share_changes: &[StockShareChange],
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> Dilution {
+    if share_changes.is_empty() {
+        return Dilution::default();
+    }
+
+    let mut shares_prev = share_changes
+        .iter()
+        .rev()
+        .find(|share_change| share_change.date <= *date_start)
+        .map(|share_change| share_change.total_shares);
+
+    let mut factor = 1.0;
+    let mut offerings = 0;
+    for share_change in share_changes
+        .iter()
+        .filter(|share_change| share_change.date > *date_start && share_change.date <= *date_end)
+    {
+        if let Some(shares_prev) = shares_prev {
+            if shares_prev > 0.0 && !is_split(&share_change.reason) {
+                factor *= share_change.total_shares / shares_prev;
+            }
+        }
+        if is_offering(&share_change.reason) {
+            offerings += 1;
+        }
+
+        shares_prev = Some(share_change.total_shares);
+    }
+
+    let years = (*date_end - *date_start).num_days() as f64 / 365.25;
+
+    Dilution {
+        annual_dilution_rate: (years > 0.0).then(|| factor.powf(1.0 / years) - 1.0),
+        offerings,
+    }
+}
+
+/// Growth of shares from bonus shares and splits between the dates, per-share values before the
+/// changes should be divided by it to be comparable
+pub fn split_factor(share_changes: &[StockShareChange], from: &NaiveDate, to: &NaiveDate) -> f64 {
+    let mut factor = 1.0;
+    let mut shares_prev: Option<f64> = None;
+    for share_change in share_changes {
+        if share_change.date > *from && share_change.date <= *to && is_split(&share_change.reason) {
+            if let Some(shares_prev) = shares_prev.filter(|v| *v > 0.0) {
+                factor *= share_change.total_shares / shares_prev;
+            }
+        }
+
+        shares_prev = Some(share_change.total_shares);
+    }
+
+    factor
+}
+
+fn is_offering(reason: &str) -> bool {
+    ["增发", "配股", "定向发行"]
+        .iter()
+        .any(|keyword| reason.contains(keyword))
+}
+
+fn is_split(reason: &str) -> bool {
+    ["送股", "转增", "拆细", "送转"]
+        .iter()
+        .any(|keyword| reason.contains(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let d = |year, month| NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        let share_changes = vec![
+            StockShareChange {
+                date: d(2020, 1),
+                total_shares: 100.0,
+                reason: "定期报告".to_string(),
+            },
+            StockShareChange {
+                date: d(2021, 6),
+                total_shares: 200.0,
+                reason: "资本公积金转增股本".to_string(),
+            },
+            StockShareChange {
+                date: d(2022, 6),
+                total_shares: 220.0,
+                reason: "非公开增发".to_string(),
+            },
+        ];
+
+        let dilution = compute(&share_changes, &d(2021, 1), &d(2023, 1));
+        assert_eq!(dilution.offerings, 1);
+        let years = (d(2023, 1) - d(2021, 1)).num_days() as f64 / 365.25;
+        let expected = 1.1f64.powf(1.0 / years) - 1.0;
+        assert!((dilution.annual_dilution_rate.unwrap() - expected).abs() < 1e-9);
+
+        assert_eq!(split_factor(&share_changes, &d(2021, 1), &d(2023, 1)), 2.0);
+        assert_eq!(split_factor(&share_changes, &d(2022, 1), &d(2023, 1)), 1.0);
+    }
+}
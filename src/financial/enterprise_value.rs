@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::data::stock::StockFinancialSummary;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EnterpriseValue {
+    pub market_cap: Option<f64>,
+    /// Market cap plus interest-bearing debt minus cash
+    pub enterprise_value: Option<f64>,
+    pub ev_to_ebit: Option<f64>,
+    pub ev_to_ebitda: Option<f64>,
+}
+
+/// Compute enterprise value from the capital structure of the latest report, and the multiples
+/// from the operating profit of the latest annual report, None if debt or cash is missing
+pub fn compute(
+    market_cap: Option<f64>,
+    latest: &StockFinancialSummary,
+    annual: &StockFinancialSummary,
+) -> EnterpriseValue {
+    // 缺少资产负债表时不以市值代替企业价值
+    let enterprise_value = match (market_cap, latest.total_debt, latest.cash_and_equivalents) {
+        (Some(market_cap), Some(total_debt), Some(cash)) => Some(market_cap + total_debt - cash),
+        _ => None,
+    };
+
+    EnterpriseValue {
+        market_cap,
+        enterprise_value,
+        ev_to_ebit: multiple(enterprise_value, annual.ebit),
+        ev_to_ebitda: multiple(enterprise_value, annual.ebitda),
+    }
+}
+
+/// Multiple of enterprise value to profit, None if the profit is not positive
+fn multiple(enterprise_value: Option<f64>, profit: Option<f64>) -> Option<f64> {
+    match (enterprise_value, profit) {
+        (Some(enterprise_value), Some(profit)) if profit > 0.0 => Some(enterprise_value / profit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let latest = StockFinancialSummary {
+            cash_and_equivalents: Some(30.0),
+            total_debt: Some(50.0),
+            ..Default::default()
+        };
+        let annual = StockFinancialSummary {
+            ebit: Some(12.0),
+            ebitda: Some(-1.0),
+            ..Default::default()
+        };
+
+        let ev = compute(Some(100.0), &latest, &annual);
+        assert_eq!(ev.enterprise_value, Some(120.0));
+        assert_eq!(ev.ev_to_ebit, Some(10.0));
+        assert_eq!(ev.ev_to_ebitda, None);
+
+        let ev = compute(Some(100.0), &StockFinancialSummary::default(), &annual);
+        assert_eq!(ev.market_cap, Some(100.0));
+        assert_eq!(ev.enterprise_value, None);
+        assert_eq!(ev.ev_to_ebit, None);
+    }
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+use crate::utils::stats;
+
+#[derive(Clone, Debug, Default)]
+pub struct FactorInputs {
+    pub pe_ttm: Option<f64>,
+    /// Historical PE TTM of the stock within the window, positive values only
+    pub pe_ttm_history: Vec<f64>,
+    pub return_on_equity: Option<f64>,
+    pub gross_margin: Option<f64>,
+    pub debt_to_assets: Option<f64>,
+    pub return_12m_ex_1m: Option<f64>,
+    pub volatility: Option<f64>,
+    pub market_cap: Option<f64>,
+}
+
+/// Factor z-scores, positive means the stock is favored by the factor
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FactorScores {
+    /// Cheapness of PE against its own history
+    pub value: Option<f64>,
+    /// Profitability and balance sheet strength against typical A-share levels
+    pub quality: Option<f64>,
+    /// 12-month return excluding the latest month, scaled by volatility
+    pub momentum: Option<f64>,
+    pub low_volatility: Option<f64>,
+    /// Smallness of market cap, for the small-cap premium
+    pub size: Option<f64>,
+}
+
+impl FactorScores {
+    /// Equal-weighted average of available factors
+    pub fn composite(&self) -> Option<f64> {
+        let scores: Vec<f64> = self.scores().into_iter().flatten().collect();
+
+        stats::mean(&scores)
+    }
+
+    /// Fraction of the factors which could be computed
+    pub fn completeness(&self) -> f64 {
+        let scores = self.scores();
+
+        scores.iter().flatten().count() as f64 / scores.len() as f64
+    }
+
+    fn scores(&self) -> [Option<f64>; 5] {
+        [
+            self.value,
+            self.quality,
+            self.momentum,
+            self.low_volatility,
+            self.size,
+        ]
+    }
+}
+
+pub fn compute(inputs: &FactorInputs) -> FactorScores {
+    let value = match (inputs.pe_ttm, stats::mean(&inputs.pe_ttm_history)) {
+        (Some(pe_ttm), Some(mean)) if pe_ttm > 0.0 => stats::std(&inputs.pe_ttm_history)
+            .filter(|std| *std > 0.0)
+            .map(|std| clamp(-(pe_ttm - mean) / std)),
+        _ => None,
+    };
+
+    let quality_scores: Vec<f64> = [
+        inputs
+            .return_on_equity
+            .map(|v| z(v, REFERENCE_RETURN_ON_EQUITY)),
+        inputs.gross_margin.map(|v| z(v, REFERENCE_GROSS_MARGIN)),
+        inputs
+            .debt_to_assets
+            .map(|v| -z(v, REFERENCE_DEBT_TO_ASSETS)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let momentum = match (inputs.return_12m_ex_1m, inputs.volatility) {
+        (Some(r), Some(volatility)) if volatility > 0.0 => {
+            Some(clamp(r / (volatility * (11.0f64 / 12.0).sqrt())))
+        }
+        _ => None,
+    };
+
+    FactorScores {
+        value,
+        quality: stats::mean(&quality_scores),
+        momentum,
+        low_volatility: inputs.volatility.map(|v| -z(v, REFERENCE_VOLATILITY)),
+        size: inputs
+            .market_cap
+            .filter(|v| *v > 0.0)
+            .map(|v| -z(v.ln(), REFERENCE_LN_MARKET_CAP)),
+    }
+}
+
+/// Map a composite z-score to a rating in 0 to 100 by the logistic approximation of normal CDF
+pub fn rating(composite: f64) -> u64 {
+    (100.0 / (1.0 + (-1.702 * composite).exp())).round() as u64
+}
+
+fn z(value: f64, (mean, std): (f64, f64)) -> f64 {
+    clamp((value - mean) / std)
+}
+
+fn clamp(z: f64) -> f64 {
+    z.clamp(-3.0, 3.0)
+}
+
+// Typical cross-sectional (mean, std) of A-share stocks, used in place of a full universe
+static REFERENCE_RETURN_ON_EQUITY: (f64, f64) = (0.08, 0.08);
+static REFERENCE_GROSS_MARGIN: (f64, f64) = (0.25, 0.15);
+static REFERENCE_DEBT_TO_ASSETS: (f64, f64) = (0.45, 0.2);
+static REFERENCE_VOLATILITY: (f64, f64) = (0.35, 0.12);
+static REFERENCE_LN_MARKET_CAP: (f64, f64) = (23.0, 1.0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let scores = compute(&FactorInputs {
+            pe_ttm: Some(10.0),
+            pe_ttm_history: vec![10.0, 30.0],
+            return_on_equity: Some(0.16),
+            volatility: Some(0.35),
+            return_12m_ex_1m: Some(0.0),
+            ..Default::default()
+        });
+        assert!((scores.value.unwrap() - 1.0).abs() < 1e-9);
+        assert!((scores.quality.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(scores.momentum, Some(0.0));
+        assert_eq!(scores.low_volatility, Some(0.0));
+        assert!(scores.size.is_none());
+        assert!((scores.composite().unwrap() - 0.5).abs() < 1e-9);
+
+        assert_eq!(rating(0.0), 50);
+        assert!(rating(3.0) > 95);
+    }
+}
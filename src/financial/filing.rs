@@ -0,0 +1,342 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use chrono::NaiveDate;
+use log::warn;
+use roxmltree::{Document, Node};
+use serde_json::{Map, Value};
+
+use crate::{
+    APP_DATA_DIR,
+    data::stock::StockStatements,
+    error::{InvmstError, InvmstResult},
+    ticker::Ticker,
+    utils::datetime::FiscalQuarter,
+};
+
+/// Statements of the fiscal quarter in the XBRL instances downloaded to
+/// 'filings/<exchange>_<symbol>/' under the data directory, None if no instance reports the quarter
+pub fn load_statements(
+    ticker: &Ticker,
+    fiscal_quarter: &FiscalQuarter,
+) -> InvmstResult<Option<StockStatements>> {
+    let dir = filings_dir(ticker);
+    if !dir.exists() {
+        return Ok(None);
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_xbrl_instance(path))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match parse_instance(&fs::read_to_string(&path)?, fiscal_quarter) {
+            Ok(Some(statements)) => return Ok(Some(statements)),
+            Ok(None) => {}
+            Err(err) => warn!("[Filing] Unable to parse '{}': {err}", path.display()),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Facts of the consolidated statements for the fiscal quarter, i.e. balances at the end of the
+/// quarter and amounts accumulated from the beginning of the fiscal year, None if there is none
+pub fn parse_instance(
+    xml: &str,
+    fiscal_quarter: &FiscalQuarter,
+) -> InvmstResult<Option<StockStatements>> {
+    let doc = Document::parse(xml)
+        .map_err(|err| InvmstError::Invalid("INVALID_XBRL", format!("Invalid XBRL: {err}")))?;
+
+    let end_date = fiscal_quarter.end_date();
+    let year_start = NaiveDate::from_ymd_opt(fiscal_quarter.year, 1, 1);
+
+    let mut context_ids: Vec<&str> = vec![];
+    for context in doc
+        .descendants()
+        .filter(|node| node.tag_name().name() == "context")
+    {
+        // 带维度的上下文是分部或母公司报表，只取合并报表
+        if context
+            .descendants()
+            .any(|node| matches!(node.tag_name().name(), "segment" | "scenario"))
+        {
+            continue;
+        }
+
+        let matched = match (
+            child_date(&context, "instant"),
+            child_date(&context, "startDate"),
+            child_date(&context, "endDate"),
+        ) {
+            (Some(instant), _, _) => instant == end_date,
+            (None, Some(start), Some(end)) => Some(start) == year_start && end == end_date,
+            _ => false,
+        };
+        if matched {
+            if let Some(id) = context.attribute("id") {
+                context_ids.push(id);
+            }
+        }
+    }
+
+    let mut facts: HashMap<&str, f64> = HashMap::new();
+    for node in doc.descendants().filter(|node| node.is_element()) {
+        if let (Some(context_ref), Some(text)) = (node.attribute("contextRef"), node.text()) {
+            if context_ids.contains(&context_ref) {
+                if let Ok(value) = text.trim().parse::<f64>() {
+                    facts.entry(node.tag_name().name()).or_insert(value);
+                }
+            }
+        }
+    }
+
+    let mut statements = Map::new();
+    for (statement, field, concepts) in CONCEPTS {
+        if let Some(value) = concepts.iter().find_map(|concept| facts.get(concept)) {
+            if let Value::Object(fields) = statements
+                .entry(statement.to_string())
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                fields.insert(field.to_string(), Value::from(*value));
+            }
+        }
+    }
+    if statements.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_value(Value::Object(statements))?))
+}
+
+fn child_date(node: &Node, name: &str) -> Option<NaiveDate> {
+    node.descendants()
+        .find(|child| child.tag_name().name() == name)
+        .and_then(|child| child.text())
+        .and_then(|text| NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok())
+}
+
+fn filings_dir(ticker: &Ticker) -> PathBuf {
+    FILINGS_DIR.join(format!("{}_{}", ticker.exchange, ticker.symbol))
+}
+
+fn is_xbrl_instance(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xml") || ext.eq_ignore_ascii_case("xbrl"))
+}
+
+/// Statement, field and the local names of the concepts reporting it, in the IFRS taxonomy which
+/// the taxonomy of the Chinese accounting standards extends, the first concept found wins
+static CONCEPTS: &[(&str, &str, &[&str])] = &[
+    (
+        "income",
+        "operating_revenue",
+        &["OperatingRevenue", "Revenue"],
+    ),
+    (
+        "income",
+        "operating_cost",
+        &["OperatingCosts", "CostOfSales"],
+    ),
+    (
+        "income",
+        "selling_expense",
+        &["SellingExpenses", "DistributionCosts"],
+    ),
+    (
+        "income",
+        "administrative_expense",
+        &["AdministrativeExpense"],
+    ),
+    (
+        "income",
+        "research_expense",
+        &["ResearchAndDevelopmentExpense"],
+    ),
+    (
+        "income",
+        "finance_expense",
+        &["FinancialExpenses", "FinanceCosts"],
+    ),
+    ("income", "interest_expense", &["InterestExpense"]),
+    (
+        "income",
+        "operating_profit",
+        &["OperatingProfit", "ProfitLossFromOperatingActivities"],
+    ),
+    (
+        "income",
+        "total_profit",
+        &["TotalProfit", "ProfitLossBeforeTax"],
+    ),
+    (
+        "income",
+        "income_tax",
+        &["IncomeTaxExpenseContinuingOperations"],
+    ),
+    ("income", "net_profit", &["NetProfit", "ProfitLoss"]),
+    (
+        "income",
+        "net_profit_attributable",
+        &["ProfitLossAttributableToOwnersOfParent"],
+    ),
+    ("balance_sheet", "cash", &["CashAndCashEquivalents"]),
+    (
+        "balance_sheet",
+        "accounts_receivable",
+        &["AccountsReceivable", "TradeAndOtherCurrentReceivables"],
+    ),
+    ("balance_sheet", "inventory", &["Inventories"]),
+    ("balance_sheet", "total_current_assets", &["CurrentAssets"]),
+    (
+        "balance_sheet",
+        "fixed_assets",
+        &["PropertyPlantAndEquipment"],
+    ),
+    (
+        "balance_sheet",
+        "intangible_assets",
+        &["IntangibleAssetsOtherThanGoodwill"],
+    ),
+    ("balance_sheet", "goodwill", &["Goodwill"]),
+    ("balance_sheet", "total_assets", &["Assets"]),
+    (
+        "balance_sheet",
+        "short_term_borrowings",
+        &["ShorttermBorrowings"],
+    ),
+    (
+        "balance_sheet",
+        "accounts_payable",
+        &["AccountsPayable", "TradeAndOtherCurrentPayables"],
+    ),
+    (
+        "balance_sheet",
+        "noncurrent_liabilities_due_within_one_year",
+        &["NoncurrentLiabilitiesDueWithinOneYear"],
+    ),
+    (
+        "balance_sheet",
+        "total_current_liabilities",
+        &["CurrentLiabilities"],
+    ),
+    (
+        "balance_sheet",
+        "long_term_borrowings",
+        &["LongtermBorrowings"],
+    ),
+    ("balance_sheet", "bonds_payable", &["BondsIssued"]),
+    ("balance_sheet", "total_liabilities", &["Liabilities"]),
+    (
+        "balance_sheet",
+        "equity_attributable",
+        &["EquityAttributableToOwnersOfParent"],
+    ),
+    ("balance_sheet", "total_equity", &["Equity"]),
+    (
+        "cash_flow",
+        "operating_cash_flow",
+        &["CashFlowsFromUsedInOperatingActivities"],
+    ),
+    (
+        "cash_flow",
+        "investing_cash_flow",
+        &["CashFlowsFromUsedInInvestingActivities"],
+    ),
+    (
+        "cash_flow",
+        "financing_cash_flow",
+        &["CashFlowsFromUsedInFinancingActivities"],
+    ),
+    (
+        "cash_flow",
+        "capital_expenditure",
+        &[
+            "PaymentsToAcquireFixedIntangibleAndOtherLongtermAssets",
+            "PurchaseOfPropertyPlantAndEquipmentClassifiedAsInvestingActivities",
+        ],
+    ),
+    (
+        "cash_flow",
+        "depreciation",
+        &["DepreciationPropertyPlantAndEquipment"],
+    ),
+    (
+        "cash_flow",
+        "intangible_amortization",
+        &["AmortisationIntangibleAssetsOtherThanGoodwill"],
+    ),
+    (
+        "cash_flow",
+        "prepaid_expense_amortization",
+        &["AmortisationOfLongtermPrepaidExpenses"],
+    ),
+    (
+        "cash_flow",
+        "staff_cash_paid",
+        &["PaymentsToAndOnBehalfOfEmployees"],
+    ),
+    (
+        "cash_flow",
+        "dividends_interest_paid",
+        &["DividendsAndInterestPaid"],
+    ),
+];
+
+static FILINGS_DIR: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("filings"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::datetime::Quarter;
+
+    #[test]
+    fn test_parse_instance() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbrli:xbrl xmlns:xbrli="http://www.xbrl.org/2003/instance" xmlns:ifrs-full="http://xbrl.ifrs.org/taxonomy/2023-03-23/ifrs-full" xmlns:xbrldi="http://xbrl.org/2006/xbrldi">
+  <xbrli:context id="ytd">
+    <xbrli:period><xbrli:startDate>2024-01-01</xbrli:startDate><xbrli:endDate>2024-09-30</xbrli:endDate></xbrli:period>
+  </xbrli:context>
+  <xbrli:context id="q3">
+    <xbrli:period><xbrli:startDate>2024-07-01</xbrli:startDate><xbrli:endDate>2024-09-30</xbrli:endDate></xbrli:period>
+  </xbrli:context>
+  <xbrli:context id="end">
+    <xbrli:period><xbrli:instant>2024-09-30</xbrli:instant></xbrli:period>
+  </xbrli:context>
+  <xbrli:context id="end-parent">
+    <xbrli:entity><xbrli:segment><xbrldi:explicitMember dimension="ifrs-full:ConsolidatedAndSeparateFinancialStatementsAxis">ifrs-full:SeparateMember</xbrldi:explicitMember></xbrli:segment></xbrli:entity>
+    <xbrli:period><xbrli:instant>2024-09-30</xbrli:instant></xbrli:period>
+  </xbrli:context>
+  <ifrs-full:Revenue contextRef="q3" unitRef="CNY" decimals="0">300</ifrs-full:Revenue>
+  <ifrs-full:Revenue contextRef="ytd" unitRef="CNY" decimals="0">900</ifrs-full:Revenue>
+  <ifrs-full:ProfitLossAttributableToOwnersOfParent contextRef="ytd" unitRef="CNY" decimals="0">120</ifrs-full:ProfitLossAttributableToOwnersOfParent>
+  <ifrs-full:CurrentAssets contextRef="end-parent" unitRef="CNY" decimals="0">100</ifrs-full:CurrentAssets>
+  <ifrs-full:CurrentAssets contextRef="end" unitRef="CNY" decimals="0">500</ifrs-full:CurrentAssets>
+  <ifrs-full:Liabilities contextRef="end" unitRef="CNY" decimals="0">350</ifrs-full:Liabilities>
+</xbrli:xbrl>"#;
+
+        let statements = parse_instance(xml, &FiscalQuarter::new(2024, Quarter::Q3))
+            .unwrap()
+            .unwrap();
+        assert_eq!(statements.income.operating_revenue, Some(900.0));
+        assert_eq!(statements.income.net_profit_attributable, Some(120.0));
+        assert_eq!(statements.balance_sheet.total_current_assets, Some(500.0));
+        assert_eq!(statements.net_current_asset_value(), Some(150.0));
+        assert_eq!(statements.cash_flow.operating_cash_flow, None);
+
+        assert!(
+            parse_instance(xml, &FiscalQuarter::new(2024, Quarter::Q2))
+                .unwrap()
+                .is_none()
+        );
+        assert!(parse_instance("<xbrl>", &FiscalQuarter::new(2024, Quarter::Q3)).is_err());
+    }
+}
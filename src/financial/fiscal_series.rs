@@ -0,0 +1,179 @@
+use crate::{
+    data::stock::{StockFinancialSummary, StockFiscalMetricset, StockShareChange},
+    financial::dilution,
+    utils::{
+        datetime::{FiscalQuarter, Quarter},
+        stats,
+    },
+};
+
+/// Field of the financial summary to look up in the series
+pub type SummaryField = fn(&StockFinancialSummary) -> Option<f64>;
+
+/// Fiscal metricsets sorted latest first, looked up by fiscal quarter so that comparisons line up
+/// the right quarters even if some reports are missing. Fiscal metrics accumulate within the year,
+/// so single quarters and TTM values are derived from the year-to-date values
+pub struct FiscalSeries<'a> {
+    stock_fiscal_metricsets: &'a [StockFiscalMetricset],
+    share_changes: Option<&'a [StockShareChange]>,
+}
+
+impl<'a> FiscalSeries<'a> {
+    pub fn new(stock_fiscal_metricsets: &'a [StockFiscalMetricset]) -> Self {
+        Self {
+            stock_fiscal_metricsets,
+            share_changes: None,
+        }
+    }
+
+    /// Restate values before bonus shares and splits to the share base of the latest quarter, for
+    /// per-share fields
+    pub fn with_share_changes(mut self, share_changes: &'a [StockShareChange]) -> Self {
+        self.share_changes = Some(share_changes);
+        self
+    }
+
+    pub fn latest_quarter(&self) -> Option<&FiscalQuarter> {
+        self.stock_fiscal_metricsets
+            .first()
+            .map(|(fiscal_quarter, _)| fiscal_quarter)
+    }
+
+    pub fn quarters(&self) -> impl Iterator<Item = &FiscalQuarter> {
+        self.stock_fiscal_metricsets
+            .iter()
+            .map(|(fiscal_quarter, _)| fiscal_quarter)
+    }
+
+    /// Value as reported, which is year-to-date for flow items like revenue and profit
+    pub fn value(&self, fiscal_quarter: &FiscalQuarter, field: SummaryField) -> Option<f64> {
+        let (_, stock_metrics) = self
+            .stock_fiscal_metricsets
+            .iter()
+            .find(|(q, _)| q == fiscal_quarter)?;
+        let value = field(&stock_metrics.financial_summary)?;
+
+        match (self.share_changes, self.latest_quarter()) {
+            (Some(share_changes), Some(latest_quarter)) => Some(
+                value
+                    / dilution::split_factor(
+                        share_changes,
+                        &fiscal_quarter.end_date(),
+                        &latest_quarter.end_date(),
+                    ),
+            ),
+            _ => Some(value),
+        }
+    }
+
+    /// Value of the single quarter, the year-to-date value minus that of the previous quarter
+    pub fn single_quarter(
+        &self,
+        fiscal_quarter: &FiscalQuarter,
+        field: SummaryField,
+    ) -> Option<f64> {
+        let value = self.value(fiscal_quarter, field)?;
+        if fiscal_quarter.quarter == Quarter::Q1 {
+            return Some(value);
+        }
+
+        Some(value - self.value(&fiscal_quarter.prev(), field)?)
+    }
+
+    /// Value of the trailing twelve months ending at the quarter
+    pub fn ttm(&self, fiscal_quarter: &FiscalQuarter, field: SummaryField) -> Option<f64> {
+        let value = self.value(fiscal_quarter, field)?;
+        if fiscal_quarter.quarter == Quarter::Q4 {
+            return Some(value);
+        }
+
+        let prev_year = fiscal_quarter.prev_year();
+        let prev_annual = self.value(&FiscalQuarter::new(prev_year.year, Quarter::Q4), field)?;
+
+        Some(value + prev_annual - self.value(&prev_year, field)?)
+    }
+
+    /// Year over year growth of the value of each quarter, latest first, comparing Q3 to Q3 rather
+    /// than Q3 to Q2 to avoid seasonality
+    pub fn yoy(&self, field: SummaryField) -> Vec<(FiscalQuarter, f64)> {
+        self.growths(|fiscal_quarter| {
+            stats::growth_rate(
+                self.value(fiscal_quarter, field)?,
+                self.value(&fiscal_quarter.prev_year(), field)?,
+            )
+        })
+    }
+
+    /// Year over year growth of the single quarter value of each quarter, latest first
+    pub fn single_quarter_yoy(&self, field: SummaryField) -> Vec<(FiscalQuarter, f64)> {
+        self.growths(|fiscal_quarter| {
+            stats::growth_rate(
+                self.single_quarter(fiscal_quarter, field)?,
+                self.single_quarter(&fiscal_quarter.prev_year(), field)?,
+            )
+        })
+    }
+
+    /// Growth of the single quarter value of each quarter over the quarter before, latest first,
+    /// which is distorted by seasonality so only suits momentum within a year
+    pub fn qoq(&self, field: SummaryField) -> Vec<(FiscalQuarter, f64)> {
+        self.growths(|fiscal_quarter| {
+            stats::growth_rate(
+                self.single_quarter(fiscal_quarter, field)?,
+                self.single_quarter(&fiscal_quarter.prev(), field)?,
+            )
+        })
+    }
+
+    fn growths(&self, growth: impl Fn(&FiscalQuarter) -> Option<f64>) -> Vec<(FiscalQuarter, f64)> {
+        self.quarters()
+            .filter_map(|fiscal_quarter| Some((fiscal_quarter.clone(), growth(fiscal_quarter)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::stock::StockMetricset;
+
+    #[test]
+    fn test_fiscal_series() {
+        // Single quarter revenue of 2023 are 1, 2, 3, 4 and of 2024 are 2, 4, 6, 8
+        let mut fiscal_quarter = FiscalQuarter::new(2024, Quarter::Q4);
+        let mut stock_fiscal_metricsets: Vec<StockFiscalMetricset> = vec![];
+        for ytd in [20.0, 12.0, 6.0, 2.0, 10.0, 6.0, 3.0, 1.0] {
+            stock_fiscal_metricsets.push((
+                fiscal_quarter.clone(),
+                StockMetricset {
+                    financial_summary: StockFinancialSummary {
+                        operating_revenue: Some(ytd),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ));
+            fiscal_quarter = fiscal_quarter.prev();
+        }
+
+        let series = FiscalSeries::new(&stock_fiscal_metricsets);
+        let field: SummaryField = |summary| summary.operating_revenue;
+        let q3 = FiscalQuarter::new(2024, Quarter::Q3);
+
+        assert_eq!(series.single_quarter(&q3, field), Some(6.0));
+        assert_eq!(series.ttm(&q3, field), Some(16.0));
+        assert_eq!(
+            series.yoy(field),
+            vec![
+                (FiscalQuarter::new(2024, Quarter::Q4), 1.0),
+                (q3.clone(), 1.0),
+                (FiscalQuarter::new(2024, Quarter::Q2), 1.0),
+                (FiscalQuarter::new(2024, Quarter::Q1), 1.0),
+            ]
+        );
+        assert_eq!(series.single_quarter_yoy(field).len(), 4);
+        let (fiscal_quarter, growth) = &series.qoq(field)[0];
+        assert_eq!(*fiscal_quarter, FiscalQuarter::new(2024, Quarter::Q4));
+        assert!((growth - 2.0 / 6.0).abs() < 1e-9);
+    }
+}
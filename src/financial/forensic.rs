@@ -0,0 +1,127 @@
+use serde::Serialize;
+
+use crate::data::stock::StockFinancialSummary;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ForensicSignals {
+    /// Beneish M-Score, indices without data are taken as neutral 1
+    pub m_score: Option<f64>,
+    /// Net profit not backed by operating cash flow, divided by total assets
+    pub accruals_ratio: Option<f64>,
+    /// Growth of days sales outstanding, receivables outgrowing revenue hints aggressive
+    /// recognition
+    pub receivables_index: Option<f64>,
+    pub revenue_growth: Option<f64>,
+    /// Growth of operating cash flow over the same year as the revenue growth
+    pub operating_cash_flow_growth: Option<f64>,
+}
+
+/// Compute forensic accounting signals from the latest two annual summaries sorted latest first
+pub fn compute(annual_summaries: &[&StockFinancialSummary]) -> ForensicSignals {
+    let (Some(latest), prev) = (annual_summaries.first(), annual_summaries.get(1)) else {
+        return ForensicSignals::default();
+    };
+
+    let accruals_ratio = accruals_ratio(latest);
+
+    let Some(prev) = prev else {
+        return ForensicSignals {
+            accruals_ratio,
+            ..Default::default()
+        };
+    };
+
+    let ratio = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) if b > 0.0 => Some(a / b),
+        _ => None,
+    };
+    let growth = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) if b > 0.0 => Some(a / b - 1.0),
+        _ => None,
+    };
+
+    let receivables_index = ratio(latest.days_sales_outstanding, prev.days_sales_outstanding);
+    let gross_margin_index = ratio(prev.gross_margin, latest.gross_margin);
+    let sales_growth_index = ratio(latest.operating_revenue, prev.operating_revenue);
+    let leverage_index = ratio(latest.debt_to_assets, prev.debt_to_assets);
+
+    let m_score = match (
+        receivables_index,
+        gross_margin_index,
+        sales_growth_index,
+        accruals_ratio,
+    ) {
+        (Some(dsri), Some(gmi), Some(sgi), Some(tata)) => {
+            let aqi = 1.0;
+            let depi = 1.0;
+            let sgai = 1.0;
+            let lvgi = leverage_index.unwrap_or(1.0);
+            Some(
+                -4.84 + 0.92 * dsri + 0.528 * gmi + 0.404 * aqi + 0.892 * sgi + 0.115 * depi
+                    - 0.172 * sgai
+                    + 4.679 * tata
+                    - 0.327 * lvgi,
+            )
+        }
+        _ => None,
+    };
+
+    ForensicSignals {
+        m_score,
+        accruals_ratio,
+        receivables_index,
+        revenue_growth: growth(latest.operating_revenue, prev.operating_revenue),
+        operating_cash_flow_growth: growth(latest.operating_cash_flow, prev.operating_cash_flow),
+    }
+}
+
+/// Accruals over total assets, which are derived from net assets and the debt to assets ratio
+fn accruals_ratio(summary: &StockFinancialSummary) -> Option<f64> {
+    let total_assets = match (summary.net_assets, summary.debt_to_assets) {
+        (Some(net_assets), Some(debt_to_assets)) if debt_to_assets < 1.0 => {
+            net_assets / (1.0 - debt_to_assets)
+        }
+        _ => return None,
+    };
+    if total_assets <= 0.0 {
+        return None;
+    }
+
+    Some((summary.net_profit? - summary.operating_cash_flow?) / total_assets)
+}
+
+/// M-Score above this value suggests a likely earnings manipulator
+pub static M_SCORE_THRESHOLD: f64 = -1.78;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let latest = StockFinancialSummary {
+            days_sales_outstanding: Some(90.0),
+            gross_margin: Some(0.3),
+            operating_revenue: Some(150.0),
+            debt_to_assets: Some(0.5),
+            net_assets: Some(100.0),
+            net_profit: Some(30.0),
+            operating_cash_flow: Some(10.0),
+            ..Default::default()
+        };
+        let prev = StockFinancialSummary {
+            days_sales_outstanding: Some(60.0),
+            gross_margin: Some(0.3),
+            operating_revenue: Some(100.0),
+            debt_to_assets: Some(0.5),
+            operating_cash_flow: Some(20.0),
+            ..Default::default()
+        };
+
+        let signals = compute(&[&latest, &prev]);
+        assert_eq!(signals.accruals_ratio, Some(0.1));
+        assert_eq!(signals.receivables_index, Some(1.5));
+        assert_eq!(signals.operating_cash_flow_growth, Some(-0.5));
+        assert!(signals.m_score.unwrap() > M_SCORE_THRESHOLD);
+    }
+}
@@ -0,0 +1,40 @@
+use serde_json::json;
+
+use crate::{
+    data::daily::{DailyDataset, DailyField},
+    ds::aktools,
+    error::*,
+    ticker::Ticker,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
+pub enum Currency {
+    #[strum(serialize = "CNY")]
+    Cny,
+
+    #[strum(serialize = "HKD")]
+    Hkd,
+
+    #[strum(serialize = "USD")]
+    Usd,
+}
+
+impl DailyField for Currency {}
+
+impl Currency {
+    pub fn of(ticker: &Ticker) -> Self {
+        match ticker.exchange.as_str() {
+            "HKEX" => Currency::Hkd,
+            _ => Currency::Cny,
+        }
+    }
+}
+
+/// Fetch daily CNY central parity rates, the value of each currency field is CNY per 100 units
+pub async fn fetch_cny_central_parity() -> InvmstResult<DailyDataset<Currency>> {
+    let json = aktools::call_public_api("/currency_boc_safe", &json!({})).await?;
+
+    let value_field_names = [(Currency::Hkd, "港元"), (Currency::Usd, "美元")];
+
+    DailyDataset::from_json(&json, "日期", &value_field_names)
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+
+use crate::{
+    data::stock::{StockFiscalMetricset, StockShareChange},
+    financial::fiscal_series::{FiscalSeries, SummaryField},
+    utils::stats,
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Growth {
+    /// Compound annual growth from the earliest report of the same fiscal quarter as the latest
+    pub cagr: Option<f64>,
+    /// Years the CAGR spans
+    pub cagr_years: Option<i32>,
+    /// Year over year growth of the year-to-date value of each quarter, latest first
+    pub rolling_growths: Vec<f64>,
+}
+
+/// Growth of a field from fiscal metricsets sorted latest first, per-share fields should give
+/// share changes to restate earlier values for bonus shares and splits
+pub fn compute(
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    field: SummaryField,
+    share_changes: Option<&[StockShareChange]>,
+) -> Growth {
+    let mut series = FiscalSeries::new(stock_fiscal_metricsets);
+    if let Some(share_changes) = share_changes {
+        series = series.with_share_changes(share_changes);
+    }
+
+    let Some(latest_quarter) = series.latest_quarter() else {
+        return Growth::default();
+    };
+
+    // 年初至今的累计值只能和其他年份的同一季度比较
+    let cagr = series.value(latest_quarter, field).and_then(|latest| {
+        let quarters: Vec<_> = series.quarters().collect();
+        quarters
+            .into_iter()
+            .rev()
+            .filter(|q| q.quarter == latest_quarter.quarter && q.year < latest_quarter.year)
+            .find_map(|q| {
+                let years = latest_quarter.year - q.year;
+                stats::cagr(series.value(q, field)?, latest, years as f64).map(|cagr| (cagr, years))
+            })
+    });
+
+    Growth {
+        cagr: cagr.map(|(cagr, _)| cagr),
+        cagr_years: cagr.map(|(_, years)| years),
+        rolling_growths: series
+            .yoy(field)
+            .into_iter()
+            .map(|(_, growth)| growth)
+            .collect(),
+    }
+}
+
+impl Growth {
+    /// Fraction of the rolling growths which are positive
+    pub fn positive_ratio(&self) -> Option<f64> {
+        if self.rolling_growths.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.rolling_growths.iter().filter(|g| **g > 0.0).count() as f64
+                / self.rolling_growths.len() as f64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::stock::{StockFinancialSummary, StockMetricset},
+        utils::datetime::{FiscalQuarter, Quarter},
+    };
+
+    #[test]
+    fn test_compute() {
+        let quarters = [Quarter::Q4, Quarter::Q3, Quarter::Q2, Quarter::Q1];
+        let stock_fiscal_metricsets: Vec<StockFiscalMetricset> = (0..9)
+            .map(|i| {
+                let fiscal_quarter =
+                    FiscalQuarter::new(2024 - i / 4, quarters[i as usize % 4].clone());
+                // YTD revenue doubles every two years and grows along the year
+                let revenue = 2f64.powf(-(i / 4) as f64 / 2.0) * (4 - i % 4) as f64;
+                let stock_metricset = StockMetricset {
+                    financial_summary: StockFinancialSummary {
+                        operating_revenue: Some(revenue),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+
+                (fiscal_quarter, stock_metricset)
+            })
+            .collect();
+
+        let growth = compute(&stock_fiscal_metricsets, |s| s.operating_revenue, None);
+        assert_eq!(growth.cagr_years, Some(2));
+        assert!((growth.cagr.unwrap() - (2f64.sqrt() - 1.0)).abs() < 1e-9);
+        assert_eq!(growth.rolling_growths.len(), 5);
+        assert_eq!(growth.positive_ratio(), Some(1.0));
+    }
+}
@@ -0,0 +1,69 @@
+use serde_json::json;
+
+use crate::{
+    data::daily::{DailyDataset, DailyField},
+    ds::aktools,
+    error::*,
+    ticker::Ticker,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
+pub enum IndexDailyFieldName {
+    Close,
+}
+
+impl DailyField for IndexDailyFieldName {}
+
+/// Fetch daily prices of an index, symbol is in the form of 'sh000300'
+pub async fn fetch_index_daily_prices(
+    symbol: &str,
+) -> InvmstResult<DailyDataset<IndexDailyFieldName>> {
+    let json = aktools::call_public_api(
+        "/stock_zh_index_daily",
+        &json!({
+            "symbol": symbol,
+        }),
+    )
+    .await?;
+
+    let value_field_names = [(IndexDailyFieldName::Close, "close")];
+
+    DailyDataset::from_json(&json, "date", &value_field_names)
+}
+
+/// Fetch constituents with weights of a CSI index, symbol is in the form of 'sh000300'
+pub async fn fetch_index_constituent_weights(symbol: &str) -> InvmstResult<Vec<(Ticker, f64)>> {
+    let code = symbol.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+
+    let json = aktools::call_public_api(
+        "/index_stock_cons_weight_csindex",
+        &json!({
+            "symbol": code,
+        }),
+    )
+    .await?;
+
+    let mut result = vec![];
+    if let Some(array) = json.as_array() {
+        for item in array {
+            let symbol = item["成分券代码"].as_str().unwrap_or_default();
+            let exchange = match item["交易所"].as_str().unwrap_or_default() {
+                "上海证券交易所" => "SSE",
+                "深圳证券交易所" => "SZSE",
+                _ => continue,
+            };
+
+            if let Some(weight) = item["权重"].as_f64() {
+                result.push((
+                    Ticker {
+                        exchange: exchange.to_string(),
+                        symbol: symbol.to_string(),
+                    },
+                    weight / 100.0,
+                ));
+            }
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+
+#[derive(Clone, Debug, Default)]
+pub struct ConstituentFundamentals {
+    /// Weight in the index, in [0, 1]
+    pub weight: f64,
+    pub pe_ttm: Option<f64>,
+    pub pb: Option<f64>,
+    pub return_on_equity: Option<f64>,
+    pub revenue_growth: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct IndexFundamentals {
+    pub constituents_count: usize,
+    /// Index weight covered by the constituents aggregated
+    pub weight_coverage: f64,
+    /// Cap-weighted PE, the weighted harmonic mean so that loss makers lower the earnings yield
+    pub pe_ttm: Option<f64>,
+    pub pb: Option<f64>,
+    pub return_on_equity: Option<f64>,
+    pub revenue_growth: Option<f64>,
+    pub bond_yield_10y: Option<f64>,
+    /// Earnings yield minus the 10-year government bond yield
+    pub equity_risk_premium: Option<f64>,
+}
+
+/// Aggregate fundamentals of constituents weighted by their weights in the index
+pub fn aggregate(
+    constituents: &[ConstituentFundamentals],
+    bond_yield_10y: Option<f64>,
+) -> IndexFundamentals {
+    let harmonic = |field: fn(&ConstituentFundamentals) -> Option<f64>| {
+        let (sum_weights, sum_yields) = constituents
+            .iter()
+            .filter_map(|c| {
+                field(c)
+                    .filter(|v| *v != 0.0)
+                    .map(|v| (c.weight, c.weight / v))
+            })
+            .fold((0.0, 0.0), |(w, y), (cw, cy)| (w + cw, y + cy));
+        (sum_yields > 0.0).then(|| sum_weights / sum_yields)
+    };
+
+    let arithmetic = |field: fn(&ConstituentFundamentals) -> Option<f64>| {
+        let (sum_weights, sum_values) = constituents
+            .iter()
+            .filter_map(|c| field(c).map(|v| (c.weight, c.weight * v)))
+            .fold((0.0, 0.0), |(w, s), (cw, cs)| (w + cw, s + cs));
+        (sum_weights > 0.0).then(|| sum_values / sum_weights)
+    };
+
+    let pe_ttm = harmonic(|c| c.pe_ttm);
+
+    IndexFundamentals {
+        constituents_count: constituents.len(),
+        weight_coverage: constituents.iter().map(|c| c.weight).sum(),
+        pe_ttm,
+        pb: harmonic(|c| c.pb),
+        return_on_equity: arithmetic(|c| c.return_on_equity),
+        revenue_growth: arithmetic(|c| c.revenue_growth),
+        bond_yield_10y,
+        equity_risk_premium: match (pe_ttm, bond_yield_10y) {
+            (Some(pe_ttm), Some(bond_yield_10y)) => Some(1.0 / pe_ttm - bond_yield_10y),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate() {
+        let constituents = [
+            ConstituentFundamentals {
+                weight: 0.3,
+                pe_ttm: Some(10.0),
+                return_on_equity: Some(0.2),
+                ..Default::default()
+            },
+            ConstituentFundamentals {
+                weight: 0.1,
+                pe_ttm: Some(20.0),
+                return_on_equity: None,
+                ..Default::default()
+            },
+        ];
+
+        let fundamentals = aggregate(&constituents, Some(0.02));
+        assert!((fundamentals.weight_coverage - 0.4).abs() < 1e-9);
+        // 0.4 / (0.3 / 10 + 0.1 / 20) = 11.43
+        assert!((fundamentals.pe_ttm.unwrap() - 0.4 / 0.035).abs() < 1e-9);
+        assert_eq!(fundamentals.return_on_equity, Some(0.2));
+        assert!((fundamentals.equity_risk_premium.unwrap() - (0.035 / 0.4 - 0.02)).abs() < 1e-9);
+        assert!(fundamentals.pb.is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use serde_json::json;
+
+use crate::{ds::aktools, error::*, utils::datetime::date_from_str};
+
+/// Fetch the average year over year change of China PPI of each year, in percent
+pub async fn fetch_ppi_yearly() -> InvmstResult<Vec<(i32, f64)>> {
+    let json = aktools::call_public_api("/macro_china_ppi_yearly", &json!({})).await?;
+
+    let mut changes: BTreeMap<i32, Vec<f64>> = BTreeMap::new();
+    if let Some(array) = json.as_array() {
+        for item in array {
+            if let (Some(date), Some(change)) = (
+                date_from_str(item["日期"].as_str().unwrap_or_default()),
+                item["今值"].as_f64(),
+            ) {
+                changes.entry(date.year()).or_default().push(change);
+            }
+        }
+    }
+
+    Ok(changes
+        .into_iter()
+        .map(|(year, values)| (year, values.iter().sum::<f64>() / values.len() as f64))
+        .collect())
+}
+
+/// Fetch the 10-year China government bond yield of each day since the date, in percent
+pub async fn fetch_cn_bond_yield_10y(
+    date_start: &NaiveDate,
+) -> InvmstResult<Vec<(NaiveDate, f64)>> {
+    let json = aktools::call_public_api(
+        "/bond_zh_us_rate",
+        &json!({
+            "start_date": date_start.format("%Y%m%d").to_string(),
+        }),
+    )
+    .await?;
+
+    let mut yields: Vec<(NaiveDate, f64)> = vec![];
+    if let Some(array) = json.as_array() {
+        for item in array {
+            if let (Some(date), Some(value)) = (
+                date_from_str(item["日期"].as_str().unwrap_or_default()),
+                item["中国国债收益率10年"].as_f64(),
+            ) {
+                yields.push((date, value));
+            }
+        }
+    }
+    yields.sort_by_key(|(date, _)| *date);
+
+    Ok(yields)
+}
+
+/// Fetch year over year growth of China M1 and M2 of each month, in percent, as (month, M1, M2)
+pub async fn fetch_money_supply() -> InvmstResult<Vec<(NaiveDate, f64, f64)>> {
+    let json = aktools::call_public_api("/macro_china_money_supply", &json!({})).await?;
+
+    let mut growths: Vec<(NaiveDate, f64, f64)> = vec![];
+    if let Some(array) = json.as_array() {
+        for item in array {
+            // 月份形如 2024年12月份
+            let month = item["月份"]
+                .as_str()
+                .unwrap_or_default()
+                .trim_end_matches('份');
+            if let (Ok(date), Some(m1), Some(m2)) = (
+                NaiveDate::parse_from_str(&format!("{month}1日"), "%Y年%m月%d日"),
+                item["货币(M1)-同比增长"].as_f64(),
+                item["货币和准货币(M2)-同比增长"].as_f64(),
+            ) {
+                growths.push((date, m1, m2));
+            }
+        }
+    }
+    growths.sort_by_key(|(date, _, _)| *date);
+
+    Ok(growths)
+}
@@ -0,0 +1,69 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Whether the title is of a full annual or semi-annual report, which has the section of
+/// management discussion and analysis, rather than a summary, a correction or a quarterly report
+pub fn is_periodic_report(title: &str) -> bool {
+    (title.contains("年度报告") || title.contains("年报"))
+        && !EXCLUDED_TITLE_WORDS.iter().any(|word| title.contains(word))
+}
+
+/// Section of management discussion and analysis in the text of a periodic report, None if the
+/// section is not complete in the text. The table of contents also lists the heading, so the
+/// longest span between the heading and the next section is taken.
+pub fn extract_section(text: &str) -> Option<&str> {
+    let headings: Vec<(usize, usize, bool)> = SECTION_HEADING_REGEX
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let heading = captures.get(0)?;
+            let is_target = captures.get(1)?.as_str().contains("管理层讨论与分析");
+
+            Some((heading.start(), heading.end(), is_target))
+        })
+        .collect();
+
+    headings
+        .windows(2)
+        .filter(|pair| pair[0].2)
+        .map(|pair| text[pair[0].1..pair[1].0].trim())
+        .filter(|section| section.chars().count() >= SECTION_MIN_CHARS)
+        .max_by_key(|section| section.len())
+}
+
+static EXCLUDED_TITLE_WORDS: &[&str] = &["摘要", "英文", "更正", "补充", "取消", "季度"];
+
+/// Below which a span is an entry of the table of contents rather than the section
+static SECTION_MIN_CHARS: usize = 200;
+
+static SECTION_HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"第[一二三四五六七八九十]+节\s*([^\s.…·\d]+)").unwrap());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_periodic_report() {
+        assert!(is_periodic_report("贵州茅台2024年年度报告"));
+        assert!(is_periodic_report("2025年半年度报告"));
+        assert!(!is_periodic_report("2024年年度报告摘要"));
+        assert!(!is_periodic_report("2025年第一季度报告"));
+        assert!(!is_periodic_report("关于召开2024年度股东大会的通知"));
+    }
+
+    #[test]
+    fn test_extract_section() {
+        let discussion = "报告期内公司营业收入增长15%，".repeat(20);
+        let text = format!(
+            "目录\n第一节 释义......2\n第二节 公司简介和主要财务指标......4\n第三节 管理层讨论与分析......8\n第四节 公司治理......30\n\
+             第一节 释义\n本报告中……\n第三节 管理层讨论与分析\n{discussion}\n第四节 公司治理\n公司严格按照……"
+        );
+
+        assert_eq!(extract_section(&text), Some(discussion.as_str()));
+        assert_eq!(
+            extract_section(&format!("第三节 管理层讨论与分析\n{discussion}")),
+            None
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::{
+    data::stock::StockDailyData,
+    financial::stock::{StockMarginFieldName, StockValuationFieldName},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, strum::Display)]
+pub enum MarginSignal {
+    /// Margin buying piling up quickly, leveraged bulls are chasing the stock
+    BuildUp,
+    /// Margin positions closed quickly, possibly forced liquidations
+    Unwind,
+    Stable,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MarginTrend {
+    /// Date of the latest balances
+    pub date: NaiveDate,
+    pub financing_balance: f64,
+    /// Change of the financing balance over 5 trading days
+    pub change_5d: Option<f64>,
+    /// Change of the financing balance over 20 trading days
+    pub change_20d: Option<f64>,
+    pub financing_to_market_cap: Option<f64>,
+    /// Short selling balance relative to the market cap, i.e. short interest
+    pub short_to_market_cap: Option<f64>,
+    pub signal: MarginSignal,
+}
+
+impl MarginTrend {
+    pub fn assessment(&self) -> String {
+        let change = |change: Option<f64>| {
+            change.map_or("N/A".to_string(), |change| format!("{change:+.2}"))
+        };
+
+        format!(
+            "{} (financing balance {} in 5 days, {} in 20 days)",
+            self.signal,
+            change(self.change_5d),
+            change(self.change_20d)
+        )
+    }
+}
+
+/// Trend of the margin balances as of the date, None if there are no recent balances
+pub fn analyze(stock_daily_data: &StockDailyData, date: &NaiveDate) -> Option<MarginTrend> {
+    let margin_balances = stock_daily_data.margin_balances.as_ref()?;
+
+    let financing_balances: Vec<(NaiveDate, f64)> = margin_balances.get_values(
+        &(*date - Duration::days(WINDOW_DAYS)),
+        date,
+        StockMarginFieldName::FinancingBalance,
+    );
+    let (latest_date, financing_balance) = *financing_balances.last()?;
+    if (*date - latest_date).num_days() > STALENESS_DAYS_MAX {
+        return None;
+    }
+
+    let change = |days: usize| {
+        let (_, base) = financing_balances.iter().rev().nth(days)?;
+        (*base > 0.0).then(|| financing_balance / base - 1.0)
+    };
+    let change_5d = change(5);
+    let change_20d = change(20);
+
+    let signal = if change_5d.is_some_and(|c| c > RAPID_CHANGE_5D)
+        || change_20d.is_some_and(|c| c > RAPID_CHANGE_20D)
+    {
+        MarginSignal::BuildUp
+    } else if change_5d.is_some_and(|c| c < -RAPID_CHANGE_5D)
+        || change_20d.is_some_and(|c| c < -RAPID_CHANGE_20D)
+    {
+        MarginSignal::Unwind
+    } else {
+        MarginSignal::Stable
+    };
+
+    let market_cap = stock_daily_data
+        .daily_valuations
+        .get_latest_value::<f64>(date, StockValuationFieldName::MarketCap)
+        .filter(|market_cap| *market_cap > 0.0);
+    let short_balance =
+        margin_balances.get_latest_value::<f64>(date, StockMarginFieldName::ShortBalance);
+
+    Some(MarginTrend {
+        date: latest_date,
+        financing_balance,
+        change_5d,
+        change_20d,
+        financing_to_market_cap: market_cap.map(|market_cap| financing_balance / market_cap),
+        short_to_market_cap: market_cap
+            .zip(short_balance)
+            .map(|(market_cap, short_balance)| short_balance / market_cap),
+        signal,
+    })
+}
+
+static RAPID_CHANGE_5D: f64 = 0.1;
+static RAPID_CHANGE_20D: f64 = 0.2;
+static STALENESS_DAYS_MAX: i64 = 10;
+static WINDOW_DAYS: i64 = 60;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::data::daily::DailyDataset;
+
+    #[test]
+    fn test_analyze() {
+        let date_start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let margin_balances = |growth: f64| {
+            let items: Vec<Value> = (0..30)
+                .map(|i| {
+                    json!({
+                        "date": (date_start + Duration::days(i)).to_string(),
+                        "rz": 1e9 * (1.0 + growth).powi(i as i32),
+                        "rq": 1e7,
+                    })
+                })
+                .collect();
+
+            DailyDataset::from_json(
+                &Value::Array(items),
+                "date",
+                &[
+                    (StockMarginFieldName::FinancingBalance, "rz"),
+                    (StockMarginFieldName::ShortBalance, "rq"),
+                ],
+            )
+            .unwrap()
+        };
+        let mut stock_daily_data = StockDailyData {
+            daily_valuations: DailyDataset::from_json(
+                &json!([{"date": "2025-01-30", "cap": 1e11}]),
+                "date",
+                &[(StockValuationFieldName::MarketCap, "cap")],
+            )
+            .unwrap(),
+            quote: None,
+            daily_bars: vec![],
+            margin_balances: Some(margin_balances(0.02)),
+            northbound_holdings: None,
+        };
+        let date = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+
+        let margin_trend = analyze(&stock_daily_data, &date).unwrap();
+        assert_eq!(margin_trend.signal, MarginSignal::BuildUp);
+        assert!((margin_trend.change_5d.unwrap() - (1.02f64.powi(5) - 1.0)).abs() < 1e-9);
+        assert_eq!(margin_trend.short_to_market_cap, Some(1e-4));
+
+        stock_daily_data.margin_balances = Some(margin_balances(-0.03));
+        assert_eq!(
+            analyze(&stock_daily_data, &date).unwrap().signal,
+            MarginSignal::Unwind
+        );
+
+        stock_daily_data.margin_balances = Some(margin_balances(0.0));
+        assert_eq!(
+            analyze(&stock_daily_data, &date).unwrap().signal,
+            MarginSignal::Stable
+        );
+        assert!(analyze(&stock_daily_data, &(date + Duration::days(30))).is_none());
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::data::daily::{DailyDataset, DailyField};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Momentum {
+    pub return_1m: Option<f64>,
+    pub return_3m: Option<f64>,
+    pub return_6m: Option<f64>,
+    pub return_12m: Option<f64>,
+    /// 12-month return skipping the latest month, the classic momentum factor
+    pub return_12m_ex_1m: Option<f64>,
+    /// Weighted relative strength score, 40% of 3-month plus 20% each of 6, 9 and 12-month returns
+    pub rs_score: Option<f64>,
+    /// Percentile of the relative strength score within the universe, from 0 to 100
+    pub rs_rank: Option<f64>,
+}
+
+/// Compute momentum measures from prices sorted by date ascending
+pub fn compute(prices: &[(NaiveDate, f64)], date: &NaiveDate) -> Momentum {
+    let price_at = |days: i64| -> Option<f64> {
+        let target = *date - Duration::days(days);
+        prices
+            .iter()
+            .rev()
+            .find(|(d, _)| *d <= target)
+            .map(|(_, p)| *p)
+            .filter(|p| *p > 0.0)
+    };
+    let return_since = |days: i64| -> Option<f64> { Some(price_at(0)? / price_at(days)? - 1.0) };
+
+    let return_3m = return_since(91);
+    let return_6m = return_since(182);
+    let return_9m = return_since(273);
+    let return_12m = return_since(365);
+
+    Momentum {
+        return_1m: return_since(30),
+        return_3m,
+        return_6m,
+        return_12m,
+        return_12m_ex_1m: price_at(30)
+            .zip(price_at(365))
+            .map(|(p_1m, p_12m)| p_1m / p_12m - 1.0),
+        rs_score: match (return_3m, return_6m, return_9m, return_12m) {
+            (Some(r3), Some(r6), Some(r9), Some(r12)) => {
+                Some(0.4 * r3 + 0.2 * r6 + 0.2 * r9 + 0.2 * r12)
+            }
+            _ => None,
+        },
+        rs_rank: None,
+    }
+}
+
+/// Compute momentum from the price field of a daily dataset
+pub fn compute_from_dataset<F: DailyField>(
+    dataset: &DailyDataset<F>,
+    date: &NaiveDate,
+    price_field: F,
+) -> Momentum {
+    let prices = dataset.get_values::<f64>(&(*date - Duration::days(400)), date, price_field);
+
+    compute(&prices, date)
+}
+
+/// Percentile rank of the score within scores of the universe, from 0 to 100
+pub fn rank(score: f64, universe: &[f64]) -> Option<f64> {
+    if universe.is_empty() {
+        return None;
+    }
+
+    let below = universe.iter().filter(|s| **s < score).count();
+
+    Some(below as f64 / universe.len() as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let prices: Vec<(NaiveDate, f64)> = (0..=400)
+            .rev()
+            .map(|days| (date - Duration::days(days), 100.0 + (400 - days) as f64))
+            .collect();
+
+        let momentum = compute(&prices, &date);
+        assert!((momentum.return_1m.unwrap() - 30.0 / 470.0).abs() < 1e-9);
+        assert!((momentum.return_12m.unwrap() - 365.0 / 135.0).abs() < 1e-9);
+        assert!(momentum.rs_score.is_some());
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(rank(0.5, &[0.1, 0.2, 0.6, 0.7]), Some(50.0));
+        assert_eq!(rank(0.5, &[]), None);
+    }
+}
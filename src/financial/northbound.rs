@@ -0,0 +1,156 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::{data::stock::StockDailyData, financial::stock::StockNorthboundFieldName};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, strum::Display)]
+pub enum NorthboundSignal {
+    /// Foreign investors raising their stake through the Stock Connect
+    Accumulation,
+    /// Foreign investors reducing their stake through the Stock Connect
+    Distribution,
+    Neutral,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NorthboundTrend {
+    /// Date of the latest holdings
+    pub date: NaiveDate,
+    /// Fraction of the A-shares held by northbound investors
+    pub holding_ratio: f64,
+    /// Change of the holding ratio in percentage points over about a month
+    pub change_1m: Option<f64>,
+    /// Change of the holding ratio in percentage points over about a quarter
+    pub change_3m: Option<f64>,
+    pub signal: NorthboundSignal,
+}
+
+impl NorthboundTrend {
+    pub fn assessment(&self) -> String {
+        let change = |change: Option<f64>| {
+            change.map_or("N/A".to_string(), |change| format!("{change:+.2}pp"))
+        };
+
+        format!(
+            "{} (holding {:.2}% of A-shares, {} in 1 month, {} in 3 months)",
+            self.signal,
+            self.holding_ratio * 100.0,
+            change(self.change_1m),
+            change(self.change_3m)
+        )
+    }
+}
+
+/// Trend of the northbound holdings as of the date, None if there are no recent holdings. Changes
+/// are measured by calendar days since holdings are disclosed quarterly rather than daily lately.
+pub fn analyze(stock_daily_data: &StockDailyData, date: &NaiveDate) -> Option<NorthboundTrend> {
+    let northbound_holdings = stock_daily_data.northbound_holdings.as_ref()?;
+
+    let holding_ratios: Vec<(NaiveDate, f64)> = northbound_holdings.get_values(
+        &(*date - Duration::days(WINDOW_DAYS)),
+        date,
+        StockNorthboundFieldName::HoldingRatio,
+    );
+    let (latest_date, holding_ratio) = *holding_ratios.last()?;
+    if (*date - latest_date).num_days() > STALENESS_DAYS_MAX {
+        return None;
+    }
+
+    let change = |days: i64| {
+        let (_, base) = holding_ratios
+            .iter()
+            .rev()
+            .find(|(date, _)| *date <= latest_date - Duration::days(days))?;
+        Some((holding_ratio - base) * 100.0)
+    };
+    let change_1m = change(30);
+    let change_3m = change(90);
+
+    let signal = if change_1m.is_some_and(|c| c > NOTABLE_CHANGE_1M)
+        || change_3m.is_some_and(|c| c > NOTABLE_CHANGE_3M)
+    {
+        NorthboundSignal::Accumulation
+    } else if change_1m.is_some_and(|c| c < -NOTABLE_CHANGE_1M)
+        || change_3m.is_some_and(|c| c < -NOTABLE_CHANGE_3M)
+    {
+        NorthboundSignal::Distribution
+    } else {
+        NorthboundSignal::Neutral
+    };
+
+    Some(NorthboundTrend {
+        date: latest_date,
+        holding_ratio,
+        change_1m,
+        change_3m,
+        signal,
+    })
+}
+
+/// In percentage points of the A-shares
+static NOTABLE_CHANGE_1M: f64 = 0.3;
+static NOTABLE_CHANGE_3M: f64 = 0.5;
+/// Holdings are disclosed at the end of each quarter
+static STALENESS_DAYS_MAX: i64 = 120;
+static WINDOW_DAYS: i64 = 240;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{data::daily::DailyDataset, financial::stock::StockValuationFieldName};
+
+    #[test]
+    fn test_analyze() {
+        let date_start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let northbound_holdings = |daily_change: f64| {
+            let items: Vec<Value> = (0..100)
+                .map(|i| {
+                    json!({
+                        "date": (date_start + Duration::days(i)).to_string(),
+                        "ratio": 0.05 + daily_change * i as f64,
+                    })
+                })
+                .collect();
+
+            DailyDataset::from_json(
+                &Value::Array(items),
+                "date",
+                &[(StockNorthboundFieldName::HoldingRatio, "ratio")],
+            )
+            .unwrap()
+        };
+        let mut stock_daily_data = StockDailyData {
+            daily_valuations: DailyDataset::from_json(
+                &json!([{"date": "2025-04-10", "cap": 1e11}]),
+                "date",
+                &[(StockValuationFieldName::MarketCap, "cap")],
+            )
+            .unwrap(),
+            quote: None,
+            daily_bars: vec![],
+            margin_balances: None,
+            northbound_holdings: Some(northbound_holdings(0.0002)),
+        };
+        let date = date_start + Duration::days(99);
+
+        let northbound_trend = analyze(&stock_daily_data, &date).unwrap();
+        assert_eq!(northbound_trend.signal, NorthboundSignal::Accumulation);
+        assert!((northbound_trend.change_1m.unwrap() - 0.6).abs() < 1e-9);
+        assert!((northbound_trend.change_3m.unwrap() - 1.8).abs() < 1e-9);
+
+        stock_daily_data.northbound_holdings = Some(northbound_holdings(-0.0001));
+        assert_eq!(
+            analyze(&stock_daily_data, &date).unwrap().signal,
+            NorthboundSignal::Distribution
+        );
+
+        stock_daily_data.northbound_holdings = Some(northbound_holdings(0.0));
+        assert_eq!(
+            analyze(&stock_daily_data, &date).unwrap().signal,
+            NorthboundSignal::Neutral
+        );
+        assert!(analyze(&stock_daily_data, &(date + Duration::days(200))).is_none());
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::stats;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bar {
+    pub date: NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, strum::Display)]
+pub enum Trend {
+    Up,
+    Down,
+    Sideways,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Pivot {
+    pub date: NaiveDate,
+    pub price: f64,
+    pub is_high: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Breakout {
+    /// Positive for breaking above the base, negative for breaking below
+    pub direction: i8,
+    /// Level of the pivotal point broken through
+    pub level: f64,
+    /// Volume of the breakout day divided by the average volume of the base
+    pub volume_ratio: Option<f64>,
+    /// Volume expanded enough to confirm the breakout
+    pub confirmed: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PriceAction {
+    pub trend: Trend,
+    pub last_pivot_high: Option<Pivot>,
+    pub last_pivot_low: Option<Pivot>,
+    pub breakout: Option<Breakout>,
+    /// Fraction of recent days closing above the moving average
+    pub above_ma_ratio: Option<f64>,
+    /// Change of the moving average over the recent days
+    pub ma_slope: Option<f64>,
+}
+
+/// Analyze price action of bars sorted by date ascending
+pub fn analyze(bars: &[Bar]) -> PriceAction {
+    let pivots = pivots(bars, PIVOT_SPAN);
+    let pivot_highs: Vec<&Pivot> = pivots.iter().filter(|p| p.is_high).collect();
+    let pivot_lows: Vec<&Pivot> = pivots.iter().filter(|p| !p.is_high).collect();
+
+    // 高点和低点同步抬高为上升趋势，同步降低为下降趋势
+    let trend = match (pivot_highs.as_slice(), pivot_lows.as_slice()) {
+        ([.., h1, h2], [.., l1, l2]) if h2.price > h1.price && l2.price > l1.price => Trend::Up,
+        ([.., h1, h2], [.., l1, l2]) if h2.price < h1.price && l2.price < l1.price => Trend::Down,
+        _ => Trend::Sideways,
+    };
+
+    let (above_ma_ratio, ma_slope) = ma_persistence(bars, MA_DAYS, PERSISTENCE_DAYS);
+
+    PriceAction {
+        trend,
+        last_pivot_high: pivot_highs.last().map(|p| (*p).clone()),
+        last_pivot_low: pivot_lows.last().map(|p| (*p).clone()),
+        breakout: breakout(bars, BASE_DAYS),
+        above_ma_ratio,
+        ma_slope,
+    }
+}
+
+/// Swing highs and lows, whose high or low is the extreme of the span of bars on each side
+pub fn pivots(bars: &[Bar], span: usize) -> Vec<Pivot> {
+    let mut pivots = vec![];
+    if bars.len() < span * 2 + 1 {
+        return pivots;
+    }
+
+    for i in span..bars.len() - span {
+        let window = &bars[i - span..=i + span];
+        if window.iter().all(|b| b.high <= bars[i].high) {
+            pivots.push(Pivot {
+                date: bars[i].date,
+                price: bars[i].high,
+                is_high: true,
+            });
+        } else if window.iter().all(|b| b.low >= bars[i].low) {
+            pivots.push(Pivot {
+                date: bars[i].date,
+                price: bars[i].low,
+                is_high: false,
+            });
+        }
+    }
+
+    pivots
+}
+
+/// Whether the latest bar closes beyond the range of the base formed by the previous bars
+pub fn breakout(bars: &[Bar], base_days: usize) -> Option<Breakout> {
+    let (latest, rest) = bars.split_last()?;
+    if rest.len() < base_days {
+        return None;
+    }
+    let base = &rest[rest.len() - base_days..];
+
+    let high = base
+        .iter()
+        .map(|b| b.high)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let low = base.iter().map(|b| b.low).fold(f64::INFINITY, f64::min);
+    let (direction, level) = if latest.close > high {
+        (1, high)
+    } else if latest.close < low {
+        (-1, low)
+    } else {
+        return None;
+    };
+
+    let volume_ratio = stats::mean(&base.iter().map(|b| b.volume).collect::<Vec<_>>())
+        .filter(|v| *v > 0.0)
+        .map(|v| latest.volume / v);
+
+    Some(Breakout {
+        direction,
+        level,
+        volume_ratio,
+        confirmed: volume_ratio.is_some_and(|r| r >= BREAKOUT_VOLUME_RATIO),
+    })
+}
+
+fn ma_persistence(bars: &[Bar], ma_days: usize, days: usize) -> (Option<f64>, Option<f64>) {
+    if bars.len() < ma_days + days {
+        return (None, None);
+    }
+
+    let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+    let ma = |end: usize| closes[end + 1 - ma_days..=end].iter().sum::<f64>() / ma_days as f64;
+
+    let last = closes.len() - 1;
+    let above = (last + 1 - days..=last)
+        .filter(|i| closes[*i] > ma(*i))
+        .count();
+
+    let ma_start = ma(last - days);
+    let ma_slope = (ma_start > 0.0).then(|| ma(last) / ma_start - 1.0);
+
+    (Some(above as f64 / days as f64), ma_slope)
+}
+
+static BASE_DAYS: usize = 20;
+static BREAKOUT_VOLUME_RATIO: f64 = 1.5;
+static MA_DAYS: usize = 50;
+static PERSISTENCE_DAYS: usize = 60;
+static PIVOT_SPAN: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bars(closes: &[f64], volumes: &[f64]) -> Vec<Bar> {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        closes
+            .iter()
+            .zip(volumes)
+            .enumerate()
+            .map(|(i, (close, volume))| Bar {
+                date: date + chrono::Duration::days(i as i64),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                volume: *volume,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_breakout() {
+        let mut closes = vec![10.0; 20];
+        let mut volumes = vec![100.0; 20];
+        closes.push(11.0);
+        volumes.push(200.0);
+
+        let breakout = breakout(&bars(&closes, &volumes), 20).unwrap();
+        assert_eq!(breakout.direction, 1);
+        assert_eq!(breakout.level, 10.0);
+        assert_eq!(breakout.volume_ratio, Some(2.0));
+        assert!(breakout.confirmed);
+    }
+
+    #[test]
+    fn test_pivots() {
+        let closes = [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 4.0, 3.0, 2.0];
+        let pivots = pivots(&bars(&closes, &[1.0; 9]), 2);
+        assert_eq!(pivots.len(), 3);
+        assert!(pivots[0].is_high && pivots[0].price == 3.0);
+        assert!(!pivots[1].is_high && pivots[1].price == 1.0);
+        assert!(pivots[2].is_high && pivots[2].price == 4.0);
+    }
+}
@@ -0,0 +1,63 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PriceRange {
+    pub low_52w: f64,
+    pub high_52w: f64,
+    /// Distance of the price above the 52-week low, e.g. 0.2 means 20% above
+    pub from_low_52w: f64,
+    /// Distance of the price below the 52-week high, e.g. -0.2 means 20% below
+    pub from_high_52w: f64,
+    /// Decline of the price from the highest price of the whole window, as a positive fraction
+    pub drawdown: f64,
+}
+
+/// Compute the position of the price within prices sorted by date ascending, the price of the date
+/// is included in the range
+pub fn compute(prices: &[(NaiveDate, f64)], date: &NaiveDate, price: f64) -> Option<PriceRange> {
+    if price <= 0.0 {
+        return None;
+    }
+
+    let date_52w = *date - Duration::days(365);
+    let prices_52w = prices
+        .iter()
+        .filter(|(d, _)| *d > date_52w && d <= date)
+        .map(|(_, p)| *p)
+        .chain(std::iter::once(price));
+    let low_52w = prices_52w.clone().fold(f64::INFINITY, f64::min);
+    let high_52w = prices_52w.fold(f64::NEG_INFINITY, f64::max);
+
+    let peak = prices
+        .iter()
+        .filter(|(d, _)| d <= date)
+        .map(|(_, p)| *p)
+        .fold(price, f64::max);
+
+    Some(PriceRange {
+        low_52w,
+        high_52w,
+        from_low_52w: price / low_52w - 1.0,
+        from_high_52w: price / high_52w - 1.0,
+        drawdown: 1.0 - price / peak,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let d = |y, m| NaiveDate::from_ymd_opt(y, m, 1).unwrap();
+        let prices = [(d(2023, 1), 20.0), (d(2024, 6), 10.0), (d(2024, 12), 16.0)];
+
+        let range = compute(&prices, &d(2025, 3), 12.0).unwrap();
+        assert_eq!(range.low_52w, 10.0);
+        assert_eq!(range.high_52w, 16.0);
+        assert!((range.from_low_52w - 0.2).abs() < 1e-9);
+        assert!((range.from_high_52w + 0.25).abs() < 1e-9);
+        assert!((range.drawdown - 0.4).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,260 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    ds::aktools,
+    error::*,
+    financial::stock::{StockValuationFieldName, fetch_stock_daily_valuations},
+    ticker::Ticker,
+    utils::datetime::{FiscalQuarter, Quarter},
+};
+
+/// Upstream feeds behind aktools that key figures can be cross-checked against
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum DataSource {
+    Eastmoney,
+    Sina,
+    Xueqiu,
+}
+
+/// Figures of a source, None if the source does not provide it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyFigures {
+    /// Net profit attributable to shareholders of the fiscal quarter, accumulated within the
+    /// fiscal year
+    pub net_profit: Option<f64>,
+    pub market_cap: Option<f64>,
+}
+
+/// Figure differing between two sources beyond the tolerance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Discrepancy {
+    pub figure: String,
+    /// Fiscal quarter or date of the figure
+    pub period: String,
+    pub values: Vec<(String, f64)>,
+    /// Difference relative to the larger of the absolute values
+    pub deviation: f64,
+}
+
+impl Discrepancy {
+    pub fn assessment(&self) -> String {
+        format!(
+            "{} {} differs by {:.1}% between {}",
+            self.period,
+            self.figure,
+            self.deviation * 100.0,
+            self.values
+                .iter()
+                .map(|(source, value)| format!("{source} ({value:.0})"))
+                .collect::<Vec<_>>()
+                .join(" and ")
+        )
+    }
+}
+
+/// Figures present in both sources differing by more than the tolerance
+pub fn compare(
+    (source_a, figures_a): (DataSource, &KeyFigures),
+    (source_b, figures_b): (DataSource, &KeyFigures),
+    fiscal_quarter: &FiscalQuarter,
+    date: &NaiveDate,
+    tolerance: f64,
+) -> Vec<Discrepancy> {
+    let mut discrepancies: Vec<Discrepancy> = vec![];
+
+    for (figure, period, a, b) in [
+        (
+            "net_profit",
+            fiscal_quarter.to_string(),
+            figures_a.net_profit,
+            figures_b.net_profit,
+        ),
+        (
+            "market_cap",
+            date.to_string(),
+            figures_a.market_cap,
+            figures_b.market_cap,
+        ),
+    ] {
+        let (Some(a), Some(b)) = (a, b) else {
+            continue;
+        };
+
+        let scale = a.abs().max(b.abs());
+        let deviation = if scale > 0.0 {
+            (a - b).abs() / scale
+        } else {
+            0.0
+        };
+        if deviation > tolerance {
+            discrepancies.push(Discrepancy {
+                figure: figure.to_string(),
+                period,
+                values: vec![(source_a.to_string(), a), (source_b.to_string(), b)],
+                deviation,
+            });
+        }
+    }
+
+    discrepancies
+}
+
+pub async fn fetch_key_figures(
+    ticker: &Ticker,
+    source: DataSource,
+    fiscal_quarter: &FiscalQuarter,
+    date: &NaiveDate,
+) -> InvmstResult<KeyFigures> {
+    if !matches!(ticker.exchange.as_str(), "SSE" | "SZSE") {
+        return Err(InvmstError::Invalid(
+            "EXCHANGE_NOT_SUPPORTED",
+            format!("Not yet supported exchange '{}'", ticker.exchange),
+        ));
+    }
+
+    let prefixed_symbol = format!(
+        "{}{}",
+        if ticker.exchange == "SSE" { "SH" } else { "SZ" },
+        ticker.symbol
+    );
+
+    match source {
+        DataSource::Eastmoney => {
+            let profit_sheet = aktools::call_public_api(
+                "/stock_profit_sheet_by_report_em",
+                &json!({
+                    "symbol": prefixed_symbol,
+                }),
+            )
+            .await?;
+            let report_date = fiscal_quarter.end_date().to_string();
+            let net_profit = profit_sheet.as_array().and_then(|array| {
+                array
+                    .iter()
+                    .find(|item| {
+                        item["REPORT_DATE"]
+                            .as_str()
+                            .is_some_and(|s| s.starts_with(&report_date))
+                    })
+                    .and_then(|item| item["PARENT_NETPROFIT"].as_f64())
+            });
+
+            let market_cap = fetch_stock_daily_valuations(ticker)
+                .await?
+                .get_latest_value::<f64>(date, StockValuationFieldName::MarketCap);
+
+            Ok(KeyFigures {
+                net_profit,
+                market_cap,
+            })
+        }
+        DataSource::Sina => {
+            let json = aktools::call_public_api(
+                "/stock_financial_abstract",
+                &json!({
+                    "symbol": ticker.symbol,
+                }),
+            )
+            .await?;
+            let quarter_key = format!(
+                "{}{}",
+                fiscal_quarter.year,
+                match fiscal_quarter.quarter {
+                    Quarter::Q1 => "0331",
+                    Quarter::Q2 => "0630",
+                    Quarter::Q3 => "0930",
+                    Quarter::Q4 => "1231",
+                }
+            );
+            let net_profit = json.as_array().and_then(|array| {
+                array
+                    .iter()
+                    .find(|item| item["指标"].as_str() == Some("归母净利润"))
+                    .and_then(|item| item[&quarter_key].as_f64())
+            });
+
+            Ok(KeyFigures {
+                net_profit,
+                market_cap: None,
+            })
+        }
+        DataSource::Xueqiu => {
+            // 雪球只提供实时行情，评估历史日期时没有可比的市值
+            if *date != Local::now().date_naive() {
+                return Ok(KeyFigures::default());
+            }
+
+            let json = aktools::call_public_api(
+                "/stock_individual_spot_xq",
+                &json!({
+                    "symbol": prefixed_symbol,
+                }),
+            )
+            .await?;
+            let market_cap = json.as_array().and_then(|array| {
+                array
+                    .iter()
+                    .find(|item| item["item"].as_str() == Some("资产净值/总市值"))
+                    .and_then(|item| match &item["value"] {
+                        Value::Number(n) => n.as_f64(),
+                        Value::String(s) => s.parse().ok(),
+                        _ => None,
+                    })
+            });
+
+            Ok(KeyFigures {
+                net_profit: None,
+                market_cap,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let fiscal_quarter = FiscalQuarter::new(2024, Quarter::Q4);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        let eastmoney = KeyFigures {
+            net_profit: Some(862.28e8),
+            market_cap: Some(1.95e12),
+        };
+        let sina = KeyFigures {
+            net_profit: Some(862.28e8),
+            market_cap: None,
+        };
+        let xueqiu = KeyFigures {
+            net_profit: None,
+            market_cap: Some(1.75e12),
+        };
+
+        assert!(
+            compare(
+                (DataSource::Eastmoney, &eastmoney),
+                (DataSource::Sina, &sina),
+                &fiscal_quarter,
+                &date,
+                0.05
+            )
+            .is_empty()
+        );
+
+        let discrepancies = compare(
+            (DataSource::Eastmoney, &eastmoney),
+            (DataSource::Xueqiu, &xueqiu),
+            &fiscal_quarter,
+            &date,
+            0.05,
+        );
+        assert_eq!(discrepancies.len(), 1);
+        assert_eq!(discrepancies[0].figure, "market_cap");
+        assert_eq!(discrepancies[0].values[1].0, "xueqiu");
+        assert!((discrepancies[0].deviation - 0.2e12 / 1.95e12).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+
+use crate::{
+    data::stock::StockFinancialSummary,
+    financial::capital_intensity::{INCOME_TAX_RATE, invested_capital, nopat},
+    utils::stats::{self, LinearFit},
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ResearchIntensity {
+    /// R&D expense divided by revenue of each fiscal year, latest first
+    pub research_to_sales: Vec<(i32, f64)>,
+    /// Linear fit of R&D to sales over the years, the slope is per year
+    pub trend: Option<LinearFit>,
+    /// Return on invested capital with R&D capitalized as an asset and amortized
+    pub adjusted_return_on_capital: Option<f64>,
+}
+
+/// Compute R&D intensity from annual summaries sorted latest first
+pub fn compute(annual_summaries: &[(i32, &StockFinancialSummary)]) -> ResearchIntensity {
+    let research_to_sales: Vec<(i32, f64)> = annual_summaries
+        .iter()
+        .filter_map(
+            |(year, summary)| match (summary.research_expense, summary.operating_revenue) {
+                (Some(research_expense), Some(revenue)) if revenue > 0.0 => {
+                    Some((*year, research_expense / revenue))
+                }
+                _ => None,
+            },
+        )
+        .collect();
+
+    let trend = stats::linear_regression(
+        &research_to_sales
+            .iter()
+            .map(|(year, ratio)| (*year as f64, *ratio))
+            .collect::<Vec<_>>(),
+    );
+
+    ResearchIntensity {
+        research_to_sales,
+        trend,
+        adjusted_return_on_capital: adjusted_return_on_capital(annual_summaries),
+    }
+}
+
+/// Treat R&D as an investment amortized straight-line over years, years before the earliest
+/// summary count as no R&D so the research asset is understated with short histories
+fn adjusted_return_on_capital(annual_summaries: &[(i32, &StockFinancialSummary)]) -> Option<f64> {
+    let (_, latest) = annual_summaries.first()?;
+    let research_expenses: Vec<f64> = annual_summaries
+        .iter()
+        .take(AMORTIZATION_YEARS + 1)
+        .map(|(_, summary)| summary.research_expense.unwrap_or(0.0))
+        .collect();
+
+    let research_asset: f64 = research_expenses
+        .iter()
+        .take(AMORTIZATION_YEARS)
+        .enumerate()
+        .map(|(k, expense)| expense * (1.0 - k as f64 / AMORTIZATION_YEARS as f64))
+        .sum();
+    let amortization: f64 = research_expenses
+        .iter()
+        .skip(1)
+        .map(|expense| expense / AMORTIZATION_YEARS as f64)
+        .sum();
+
+    let adjusted_nopat =
+        nopat(latest)? + (latest.research_expense? - amortization) * (1.0 - INCOME_TAX_RATE);
+    let adjusted_capital = invested_capital(latest)? + research_asset;
+
+    if adjusted_capital > 0.0 {
+        Some(adjusted_nopat / adjusted_capital)
+    } else {
+        None
+    }
+}
+
+static AMORTIZATION_YEARS: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let latest = StockFinancialSummary {
+            research_expense: Some(10.0),
+            operating_revenue: Some(100.0),
+            ebit: Some(20.0),
+            net_assets: Some(100.0),
+            total_debt: Some(0.0),
+            cash_and_equivalents: Some(0.0),
+            ..Default::default()
+        };
+        let earliest = StockFinancialSummary {
+            research_expense: Some(5.0),
+            operating_revenue: Some(100.0),
+            ..Default::default()
+        };
+
+        let research_intensity = compute(&[(2024, &latest), (2023, &earliest)]);
+        assert_eq!(
+            research_intensity.research_to_sales,
+            vec![(2024, 0.1), (2023, 0.05)]
+        );
+        assert!((research_intensity.trend.unwrap().slope - 0.05).abs() < 1e-9);
+
+        // NOPAT 15 + (10 - 1) * 0.75, capital 100 + 10 + 5 * 0.8
+        let expected = (15.0 + 9.0 * 0.75) / 114.0;
+        assert!((research_intensity.adjusted_return_on_capital.unwrap() - expected).abs() < 1e-9);
+    }
+}
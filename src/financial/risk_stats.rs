@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::utils::stats;
+
+/// Trading days in a year, used to annualize daily statistics
+pub static TRADING_DAYS_PER_YEAR: f64 = 244.0;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RiskStats {
+    /// Annualized standard deviation of daily returns
+    pub volatility: Option<f64>,
+    /// Annualized deviation of daily returns below zero
+    pub downside_deviation: Option<f64>,
+    /// Beta against the benchmark, computed on returns of the dates both have prices
+    pub beta: Option<f64>,
+    /// Maximum peak-to-trough decline, as a positive fraction
+    pub max_drawdown: Option<f64>,
+    /// Root mean square of percentage drawdowns from the running peak
+    pub ulcer_index: Option<f64>,
+    /// Average trading days from a peak back to it, over drawdowns that have recovered
+    pub recovery_days: Option<f64>,
+}
+
+/// Compute risk stats from prices sorted by date ascending, benchmark prices should be aligned with
+/// prices day by day
+pub fn compute(prices: &[f64], benchmark_prices: Option<&[f64]>) -> RiskStats {
+    let returns = daily_returns(prices);
+
+    RiskStats {
+        volatility: annualized_volatility(&returns),
+        downside_deviation: downside_deviation(&returns),
+        beta: benchmark_prices
+            .and_then(|benchmark_prices| beta(&returns, &daily_returns(benchmark_prices))),
+        max_drawdown: max_drawdown(prices),
+        ulcer_index: ulcer_index(prices),
+        recovery_days: recovery_days(prices),
+    }
+}
+
+pub fn daily_returns(prices: &[f64]) -> Vec<f64> {
+    prices
+        .windows(2)
+        .filter(|w| w[0] > 0.0)
+        .map(|w| w[1] / w[0] - 1.0)
+        .collect()
+}
+
+pub fn annualized_volatility(returns: &[f64]) -> Option<f64> {
+    stats::std(returns).map(|std| std * TRADING_DAYS_PER_YEAR.sqrt())
+}
+
+/// Annualized root mean square of negative daily returns, with zero as the target return
+pub fn downside_deviation(returns: &[f64]) -> Option<f64> {
+    if returns.is_empty() {
+        return None;
+    }
+
+    let sum_squares: f64 = returns.iter().map(|r| r.min(0.0).powi(2)).sum();
+
+    Some((sum_squares / returns.len() as f64).sqrt() * TRADING_DAYS_PER_YEAR.sqrt())
+}
+
+/// Keep only the dates present in every series, returning values in the same order as series.
+/// A date repeated within a series takes its last value.
+pub fn align(series: &[Vec<(NaiveDate, f64)>]) -> (Vec<NaiveDate>, Vec<Vec<f64>>) {
+    // 同一序列内重复的日期只计一次
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for values in series {
+        let dates: HashSet<NaiveDate> = values.iter().map(|(date, _)| *date).collect();
+        for date in dates {
+            *counts.entry(date).or_insert(0) += 1;
+        }
+    }
+    let dates: Vec<NaiveDate> = counts
+        .into_iter()
+        .filter(|(_, count)| *count == series.len())
+        .map(|(date, _)| date)
+        .collect();
+
+    let aligned = series
+        .iter()
+        .map(|values| {
+            let map: HashMap<NaiveDate, f64> = values.iter().copied().collect();
+            dates.iter().map(|date| map[date]).collect()
+        })
+        .collect();
+
+    (dates, aligned)
+}
+
+pub fn beta(returns: &[f64], benchmark_returns: &[f64]) -> Option<f64> {
+    let variance = stats::std(benchmark_returns)?.powi(2);
+    if variance > 0.0 {
+        Some(stats::covariance(returns, benchmark_returns)? / variance)
+    } else {
+        None
+    }
+}
+
+/// Maximum peak-to-trough decline of prices sorted by date ascending, as a positive fraction
+pub fn max_drawdown(prices: &[f64]) -> Option<f64> {
+    drawdowns(prices).map(|drawdowns| drawdowns.into_iter().fold(0.0, f64::max))
+}
+
+/// Ulcer index of prices sorted by date ascending, penalizing both depth and duration of drawdowns
+pub fn ulcer_index(prices: &[f64]) -> Option<f64> {
+    let drawdowns = drawdowns(prices)?;
+    let sum_squares: f64 = drawdowns.iter().map(|d| d.powi(2)).sum();
+
+    Some((sum_squares / drawdowns.len() as f64).sqrt())
+}
+
+/// Average trading days taken to regain the previous peak, None if no drawdown has recovered
+pub fn recovery_days(prices: &[f64]) -> Option<f64> {
+    let mut peak = *prices.first()?;
+    let mut peak_index = 0;
+    let mut in_drawdown = false;
+    let mut durations: Vec<f64> = vec![];
+    for (i, price) in prices.iter().enumerate() {
+        if *price >= peak {
+            if in_drawdown {
+                durations.push((i - peak_index) as f64);
+                in_drawdown = false;
+            }
+            peak = *price;
+            peak_index = i;
+        } else {
+            in_drawdown = true;
+        }
+    }
+
+    stats::mean(&durations)
+}
+
+/// Decline from the running peak of each price, as positive fractions
+pub fn drawdowns(prices: &[f64]) -> Option<Vec<f64>> {
+    let mut peak = *prices.first()?;
+
+    Some(
+        prices
+            .iter()
+            .map(|price| {
+                peak = peak.max(*price);
+                if peak > 0.0 { 1.0 - price / peak } else { 0.0 }
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align() {
+        let d = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let (dates, aligned) = align(&[
+            vec![(d(1), 1.0), (d(2), 2.0), (d(3), 3.0)],
+            vec![(d(2), 20.0), (d(3), 30.0), (d(4), 40.0)],
+        ]);
+        assert_eq!(dates, vec![d(2), d(3)]);
+        assert_eq!(aligned, vec![vec![2.0, 3.0], vec![20.0, 30.0]]);
+
+        // A date repeated in one series and missing from the other is dropped
+        let (dates, aligned) = align(&[
+            vec![(d(1), 1.0), (d(2), 2.0), (d(2), 2.5)],
+            vec![(d(1), 10.0)],
+        ]);
+        assert_eq!(dates, vec![d(1)]);
+        assert_eq!(aligned, vec![vec![1.0], vec![10.0]]);
+    }
+
+    #[test]
+    fn test_beta() {
+        let benchmark_returns = [0.01, -0.02, 0.03];
+        let returns: Vec<f64> = benchmark_returns.iter().map(|r| r * 2.0).collect();
+        assert!((beta(&returns, &benchmark_returns).unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_max_drawdown() {
+        assert!((max_drawdown(&[10.0, 12.0, 9.0, 11.0]).unwrap() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_recovery_days() {
+        assert_eq!(recovery_days(&[10.0, 12.0, 9.0, 11.0]), None);
+        assert_eq!(
+            recovery_days(&[10.0, 9.0, 10.0, 12.0, 11.0, 10.0, 12.0]),
+            Some(2.5)
+        );
+    }
+
+    #[test]
+    fn test_ulcer_index() {
+        assert_eq!(ulcer_index(&[10.0, 11.0, 12.0]), Some(0.0));
+        let expected = (0.25f64.powi(2) / 2.0).sqrt();
+        assert!((ulcer_index(&[12.0, 9.0]).unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_downside_deviation() {
+        assert_eq!(downside_deviation(&[0.01, 0.02]), Some(0.0));
+        let expected = (0.0004f64 / 2.0).sqrt() * TRADING_DAYS_PER_YEAR.sqrt();
+        assert!((downside_deviation(&[0.01, -0.02]).unwrap() - expected).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    ds::aktools,
+    error::*,
+    utils::{datetime::date_from_str, stats},
+};
+
+#[derive(Clone, Debug)]
+pub struct SectorDaily {
+    pub date: NaiveDate,
+    pub close: f64,
+    pub pe: Option<f64>,
+    pub pb: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SectorOverview {
+    pub code: String,
+    pub name: String,
+    pub return_1m: Option<f64>,
+    pub return_3m: Option<f64>,
+    pub return_12m: Option<f64>,
+    pub pe: Option<f64>,
+    /// Rank of the current PE within the window, in [0, 1]
+    pub pe_percentile: Option<f64>,
+    pub pb: Option<f64>,
+    /// Rank of the current PB within the window, in [0, 1]
+    pub pb_percentile: Option<f64>,
+}
+
+/// Fetch daily prices and valuations of Shenwan level-1 industry indices, keyed by (code, name)
+pub async fn fetch_sector_dailies(
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<BTreeMap<(String, String), Vec<SectorDaily>>> {
+    let json = aktools::call_public_api(
+        "/index_analysis_daily_sw",
+        &json!({
+            "symbol": "一级行业",
+            "start_date": date_start.format("%Y%m%d").to_string(),
+            "end_date": date_end.format("%Y%m%d").to_string(),
+        }),
+    )
+    .await?;
+
+    let mut sectors: BTreeMap<(String, String), Vec<SectorDaily>> = BTreeMap::new();
+    if let Some(array) = json.as_array() {
+        for item in array {
+            let code = item["指数代码"].as_str().unwrap_or_default();
+            let name = item["指数名称"].as_str().unwrap_or_default();
+            if let (Some(date), Some(close)) = (
+                date_from_str(item["发布日期"].as_str().unwrap_or_default()),
+                item["收盘指数"].as_f64(),
+            ) {
+                sectors
+                    .entry((code.to_string(), name.to_string()))
+                    .or_default()
+                    .push(SectorDaily {
+                        date,
+                        close,
+                        pe: item["市盈率"].as_f64().filter(|v| *v > 0.0),
+                        pb: item["市净率"].as_f64().filter(|v| *v > 0.0),
+                    });
+            }
+        }
+    }
+
+    for dailies in sectors.values_mut() {
+        dailies.sort_by_key(|daily| daily.date);
+    }
+
+    Ok(sectors)
+}
+
+/// Summarize performance and valuation percentiles of a sector from dailies sorted by date ascending
+pub fn summarize(
+    code: &str,
+    name: &str,
+    dailies: &[SectorDaily],
+    date: &NaiveDate,
+) -> Option<SectorOverview> {
+    let dailies: Vec<&SectorDaily> = dailies.iter().filter(|d| d.date <= *date).collect();
+    let latest = dailies.last()?;
+
+    let return_since = |days: i64| {
+        let date_from = *date - Duration::days(days);
+        dailies
+            .iter()
+            .rev()
+            .find(|d| d.date <= date_from)
+            .filter(|d| d.close > 0.0)
+            .map(|d| latest.close / d.close - 1.0)
+    };
+
+    let percentile = |field: fn(&SectorDaily) -> Option<f64>| {
+        let values: Vec<f64> = dailies.iter().filter_map(|d| field(d)).collect();
+        stats::percentile_rank(&values, field(latest)?)
+    };
+
+    Some(SectorOverview {
+        code: code.to_string(),
+        name: name.to_string(),
+        return_1m: return_since(30),
+        return_3m: return_since(91),
+        return_12m: return_since(365),
+        pe: latest.pe,
+        pe_percentile: percentile(|d| d.pe),
+        pb: latest.pb,
+        pb_percentile: percentile(|d| d.pb),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let d = |y, m| NaiveDate::from_ymd_opt(y, m, 1).unwrap();
+        let daily = |date, close, pe| SectorDaily {
+            date,
+            close,
+            pe: Some(pe),
+            pb: None,
+        };
+        let dailies = vec![
+            daily(d(2024, 1), 100.0, 10.0),
+            daily(d(2024, 10), 80.0, 20.0),
+            daily(d(2024, 12), 100.0, 30.0),
+            daily(d(2025, 1), 120.0, 15.0),
+        ];
+
+        let overview = summarize("801010", "农林牧渔", &dailies, &d(2025, 1)).unwrap();
+        assert!((overview.return_1m.unwrap() - 0.2).abs() < 1e-9);
+        assert!((overview.return_3m.unwrap() - 0.5).abs() < 1e-9);
+        assert!((overview.return_12m.unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(overview.pe_percentile, Some(0.5));
+        assert_eq!(overview.pb_percentile, None);
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::data::stock::StockFinancialSummary;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Solvency {
+    /// EBIT divided by interest expense
+    pub interest_coverage: Option<f64>,
+    /// Share of interest-bearing debt due within one year
+    pub short_term_debt_ratio: Option<f64>,
+    /// Cash divided by debt due within one year, below 1 means refinancing is needed
+    pub cash_to_short_term_debt: Option<f64>,
+}
+
+pub fn compute(summary: &StockFinancialSummary) -> Solvency {
+    let interest_coverage = match (summary.ebit, summary.interest_expense) {
+        (Some(ebit), Some(interest_expense)) if interest_expense > 0.0 => {
+            Some(ebit / interest_expense)
+        }
+        _ => None,
+    };
+
+    let short_term_debt_ratio = match (summary.short_term_debt, summary.total_debt) {
+        (Some(short_term_debt), Some(total_debt)) if total_debt > 0.0 => {
+            Some(short_term_debt / total_debt)
+        }
+        _ => None,
+    };
+
+    let cash_to_short_term_debt = match (summary.cash_and_equivalents, summary.short_term_debt) {
+        (Some(cash), Some(short_term_debt)) if short_term_debt > 0.0 => {
+            Some(cash / short_term_debt)
+        }
+        _ => None,
+    };
+
+    Solvency {
+        interest_coverage,
+        short_term_debt_ratio,
+        cash_to_short_term_debt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let solvency = compute(&StockFinancialSummary {
+            ebit: Some(30.0),
+            interest_expense: Some(10.0),
+            short_term_debt: Some(40.0),
+            total_debt: Some(100.0),
+            cash_and_equivalents: Some(20.0),
+            ..Default::default()
+        });
+        assert_eq!(solvency.interest_coverage, Some(3.0));
+        assert_eq!(solvency.short_term_debt_ratio, Some(0.4));
+        assert_eq!(solvency.cash_to_short_term_debt, Some(0.5));
+    }
+}
@@ -1,18 +1,21 @@
 use std::collections::HashMap;
 
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use log::warn;
 use regex::Regex;
-use serde_json::json;
+use serde_json::{Value, json};
+use strum::IntoEnumIterator;
 
 use crate::{
     data::{daily::*, stock::*},
     ds::aktools,
     error::*,
+    financial::{management_discussion, price_action::Bar},
     ticker::Ticker,
-    utils::datetime::*,
+    utils::{datetime::*, net::http_get},
 };
 
-#[derive(strum::Display)]
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
 pub enum StockValuationFieldName {
     Price,
     MarketCap,
@@ -24,7 +27,39 @@ pub enum StockValuationFieldName {
     Ps,
 }
 
-pub async fn fetch_stock_daily_valuations(ticker: &Ticker) -> InvmstResult<DailyDataset> {
+impl DailyField for StockValuationFieldName {}
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
+pub enum StockMarginFieldName {
+    /// Outstanding balance of margin financing, i.e. buying on margin
+    FinancingBalance,
+    /// Amount bought on margin of the day
+    FinancingBuy,
+    /// Outstanding balance of securities lending, i.e. short selling
+    ShortBalance,
+    /// Shares lent out and not yet returned, i.e. short interest
+    ShortVolume,
+}
+
+impl DailyField for StockMarginFieldName {}
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
+pub enum StockNorthboundFieldName {
+    /// Shares held by investors through the Stock Connect
+    HoldingShares,
+    /// Fraction of the A-shares held
+    HoldingRatio,
+    /// Shares bought net of the day
+    NetBuyShares,
+    /// Amount bought net of the day
+    NetBuyAmount,
+}
+
+impl DailyField for StockNorthboundFieldName {}
+
+pub async fn fetch_stock_daily_valuations(
+    ticker: &Ticker,
+) -> InvmstResult<DailyDataset<StockValuationFieldName>> {
     match ticker.exchange.as_str() {
         "SSE" | "SZSE" => {
             let json = aktools::call_public_api(
@@ -35,39 +70,16 @@ pub async fn fetch_stock_daily_valuations(ticker: &Ticker) -> InvmstResult<Daily
             )
             .await?;
 
-            let mut value_field_names: HashMap<String, String> = HashMap::new();
-            value_field_names.insert(
-                StockValuationFieldName::Price.to_string(),
-                "当日收盘价".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::MarketCap.to_string(),
-                "总市值".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Pe.to_string(),
-                "PE(静)".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::PeTtm.to_string(),
-                "PE(TTM)".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Peg.to_string(),
-                "PEG值".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Pb.to_string(),
-                "市净率".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Pcf.to_string(),
-                "市现率".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Ps.to_string(),
-                "市销率".to_string(),
-            );
+            let value_field_names = [
+                (StockValuationFieldName::Price, "当日收盘价"),
+                (StockValuationFieldName::MarketCap, "总市值"),
+                (StockValuationFieldName::Pe, "PE(静)"),
+                (StockValuationFieldName::PeTtm, "PE(TTM)"),
+                (StockValuationFieldName::Peg, "PEG值"),
+                (StockValuationFieldName::Pb, "市净率"),
+                (StockValuationFieldName::Pcf, "市现率"),
+                (StockValuationFieldName::Ps, "市销率"),
+            ];
 
             DailyDataset::from_json(&json, "数据日期", &value_field_names)
         }
@@ -118,10 +130,10 @@ pub async fn fetch_stock_daily_valuations(ticker: &Ticker) -> InvmstResult<Daily
                             (item["date"].as_str(), item.get("value"))
                         {
                             if let Some(date) = date_from_str(date_str) {
-                                daily_values_map
-                                    .entry(date)
-                                    .or_default()
-                                    .insert(indicator.to_string(), value.clone());
+                                daily_values_map.entry(date).or_default().insert(
+                                    indicator.to_string(),
+                                    normalize_hk_valuation(indicator, value),
+                                );
                             }
                         }
                     }
@@ -141,31 +153,14 @@ pub async fn fetch_stock_daily_valuations(ticker: &Ticker) -> InvmstResult<Daily
 
             let json = json!(daily_values);
 
-            let mut value_field_names: HashMap<String, String> = HashMap::new();
-            value_field_names.insert(
-                StockValuationFieldName::Price.to_string(),
-                "当日收盘价".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::MarketCap.to_string(),
-                "总市值".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Pe.to_string(),
-                "市盈率(静)".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::PeTtm.to_string(),
-                "市盈率(TTM)".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Pb.to_string(),
-                "市净率".to_string(),
-            );
-            value_field_names.insert(
-                StockValuationFieldName::Pcf.to_string(),
-                "市现率".to_string(),
-            );
+            let value_field_names = [
+                (StockValuationFieldName::Price, "当日收盘价"),
+                (StockValuationFieldName::MarketCap, "总市值"),
+                (StockValuationFieldName::Pe, "市盈率(静)"),
+                (StockValuationFieldName::PeTtm, "市盈率(TTM)"),
+                (StockValuationFieldName::Pb, "市净率"),
+                (StockValuationFieldName::Pcf, "市现率"),
+            ];
 
             DailyDataset::from_json(&json, "date", &value_field_names)
         }
@@ -276,6 +271,591 @@ pub async fn fetch_stock_dividends(
     }
 }
 
+/// Fetch daily balances of margin trading of Eastmoney, only A-shares of margin trading targets
+/// have them
+pub async fn fetch_stock_margin_balances(
+    ticker: &Ticker,
+) -> InvmstResult<DailyDataset<StockMarginFieldName>> {
+    if !matches!(ticker.exchange.as_str(), "SSE" | "SZSE") {
+        return Err(InvmstError::Invalid(
+            "EXCHANGE_NOT_SUPPORTED",
+            format!("Not yet supported exchange '{}'", ticker.exchange),
+        ));
+    }
+
+    let mut items: Vec<Value> = vec![];
+    for page_number in 1..=MARGIN_PAGES_MAX {
+        let bytes = http_get(
+            MARGIN_API_URL,
+            None,
+            &HashMap::from([
+                ("reportName".to_string(), "RPTA_WEB_RZRQ_GGMX".to_string()),
+                ("columns".to_string(), "ALL".to_string()),
+                (
+                    "filter".to_string(),
+                    format!("(SCODE=\"{}\")", ticker.symbol),
+                ),
+                ("sortColumns".to_string(), "DATE".to_string()),
+                ("sortTypes".to_string(), "-1".to_string()),
+                ("pageSize".to_string(), MARGIN_PAGE_SIZE.to_string()),
+                ("pageNumber".to_string(), page_number.to_string()),
+                ("source".to_string(), "WEB".to_string()),
+                ("client".to_string(), "WEB".to_string()),
+            ]),
+            &HashMap::new(),
+        )
+        .await?;
+        let json: Value = serde_json::from_slice(&bytes)?;
+
+        // 非融资融券标的没有数据，result 为空
+        if let Some(array) = json["result"]["data"].as_array() {
+            items.extend(array.iter().cloned());
+        }
+        if json["result"]["pages"].as_u64().unwrap_or(0) <= page_number as u64 {
+            break;
+        }
+    }
+
+    DailyDataset::from_json(
+        &Value::Array(items),
+        "DATE",
+        &[
+            (StockMarginFieldName::FinancingBalance, "RZYE"),
+            (StockMarginFieldName::FinancingBuy, "RZMRE"),
+            (StockMarginFieldName::ShortBalance, "RQYE"),
+            (StockMarginFieldName::ShortVolume, "RQYL"),
+        ],
+    )
+}
+
+/// Fetch holdings of northbound investors through the Stock Connect, only A-shares in the Stock
+/// Connect have them. Holdings are disclosed quarterly rather than daily since August 2024.
+pub async fn fetch_stock_northbound_holdings(
+    ticker: &Ticker,
+) -> InvmstResult<DailyDataset<StockNorthboundFieldName>> {
+    if !matches!(ticker.exchange.as_str(), "SSE" | "SZSE") {
+        return Err(InvmstError::Invalid(
+            "EXCHANGE_NOT_SUPPORTED",
+            format!("Not yet supported exchange '{}'", ticker.exchange),
+        ));
+    }
+
+    let mut json = aktools::call_public_api(
+        "/stock_hsgt_individual_em",
+        &json!({
+            "symbol": ticker.symbol,
+        }),
+    )
+    .await?;
+
+    // In percentage
+    if let Some(array) = json.as_array_mut() {
+        for item in array {
+            if let Some(ratio) = item["持股数量占A股百分比"].as_f64() {
+                item["持股数量占A股百分比"] = json!(ratio / 100.0);
+            }
+        }
+    }
+
+    DailyDataset::from_json(
+        &json,
+        "持股日期",
+        &[
+            (StockNorthboundFieldName::HoldingShares, "持股数量"),
+            (
+                StockNorthboundFieldName::HoldingRatio,
+                "持股数量占A股百分比",
+            ),
+            (StockNorthboundFieldName::NetBuyShares, "今日增持股数"),
+            (StockNorthboundFieldName::NetBuyAmount, "今日增持资金"),
+        ],
+    )
+}
+
+/// Fetch forward-adjusted daily OHLCV bars between the dates, sorted by date ascending
+pub async fn fetch_stock_daily_bars(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<Bar>> {
+    let path = match ticker.exchange.as_str() {
+        "SSE" | "SZSE" => "/stock_zh_a_hist",
+        "HKEX" => "/stock_hk_hist",
+        _ => {
+            return Err(InvmstError::Invalid(
+                "EXCHANGE_NOT_SUPPORTED",
+                format!("Not yet supported exchange '{}'", ticker.exchange),
+            ));
+        }
+    };
+
+    let json = aktools::call_public_api(
+        path,
+        &json!({
+            "symbol": ticker.symbol,
+            "period": "daily",
+            "start_date": date_start.format("%Y%m%d").to_string(),
+            "end_date": date_end.format("%Y%m%d").to_string(),
+            "adjust": "qfq",
+        }),
+    )
+    .await?;
+
+    let mut bars: Vec<Bar> = vec![];
+    if let Some(array) = json.as_array() {
+        for item in array {
+            if let (Some(date), Some(open), Some(high), Some(low), Some(close), Some(volume)) = (
+                date_from_str(item["日期"].as_str().unwrap_or_default()),
+                item["开盘"].as_f64(),
+                item["最高"].as_f64(),
+                item["最低"].as_f64(),
+                item["收盘"].as_f64(),
+                item["成交量"].as_f64(),
+            ) {
+                bars.push(Bar {
+                    date,
+                    open,
+                    high,
+                    low,
+                    close,
+                    volume,
+                });
+            }
+        }
+    }
+    bars.sort_by_key(|bar| bar.date);
+
+    Ok(bars)
+}
+
+pub async fn fetch_stock_quote(ticker: &Ticker) -> InvmstResult<StockQuote> {
+    match ticker.exchange.as_str() {
+        "SSE" | "SZSE" => {
+            let json = aktools::call_public_api(
+                "/stock_bid_ask_em",
+                &json!({
+                    "symbol": ticker.symbol,
+                }),
+            )
+            .await?;
+
+            let mut values: HashMap<String, f64> = HashMap::new();
+            if let Some(array) = json.as_array() {
+                for item in array {
+                    if let (Some(name), Some(value)) =
+                        (item["item"].as_str(), item["value"].as_f64())
+                    {
+                        values.insert(name.to_string(), value);
+                    }
+                }
+            }
+
+            match values.get("最新") {
+                Some(price) if *price > 0.0 => Ok(StockQuote {
+                    price: *price,
+                    change: values.get("涨跌").copied(),
+                    change_rate: values.get("涨幅").map(|v| v / 100.0),
+                    prev_close: values.get("昨收").copied(),
+                    time: Local::now(),
+                }),
+                _ => Err(InvmstError::NoData(
+                    "NO_QUOTE",
+                    format!("No quote of '{ticker}'"),
+                )),
+            }
+        }
+        "HKEX" => {
+            let json = aktools::call_public_api("/stock_hk_spot_em", &json!({})).await?;
+
+            let item = json.as_array().and_then(|array| {
+                array
+                    .iter()
+                    .find(|item| item["代码"].as_str() == Some(ticker.symbol.as_str()))
+            });
+
+            match item.and_then(|item| item["最新价"].as_f64().map(|price| (item, price))) {
+                Some((item, price)) if price > 0.0 => Ok(StockQuote {
+                    price,
+                    change: item["涨跌额"].as_f64(),
+                    change_rate: item["涨跌幅"].as_f64().map(|v| v / 100.0),
+                    prev_close: item["昨收"].as_f64(),
+                    time: Local::now(),
+                }),
+                _ => Err(InvmstError::NoData(
+                    "NO_QUOTE",
+                    format!("No quote of '{ticker}'"),
+                )),
+            }
+        }
+        _ => Err(InvmstError::Invalid(
+            "EXCHANGE_NOT_SUPPORTED",
+            format!("Not yet supported exchange '{}'", ticker.exchange),
+        )),
+    }
+}
+
+/// Fetch announcements of governance and trading halt topics, only A-shares are supported as HKEX
+/// announcements are not categorized
+pub async fn fetch_stock_governance_announcements(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<StockAnnouncement>> {
+    let mut result = vec![];
+
+    if let "SSE" | "SZSE" = ticker.exchange.as_str() {
+        for topic in AnnouncementTopic::iter() {
+            let keyword = match topic {
+                AnnouncementTopic::RelatedParty => "关联交易",
+                AnnouncementTopic::Guarantee => "担保",
+                AnnouncementTopic::AuditorChange => "会计师事务所",
+                AnnouncementTopic::ShareholderSale => "减持",
+                AnnouncementTopic::TradingHalt => "停牌",
+            };
+
+            let json = aktools::call_public_api(
+                "/stock_zh_a_disclosure_report_cninfo",
+                &json!({
+                    "symbol": ticker.symbol,
+                    "market": "沪深京",
+                    "keyword": keyword,
+                    "start_date": date_start.format("%Y%m%d").to_string(),
+                    "end_date": date_end.format("%Y%m%d").to_string(),
+                }),
+            )
+            .await?;
+
+            if let Some(array) = json.as_array() {
+                for item in array {
+                    if let (Some(date), Some(title)) = (
+                        date_from_str(item["公告时间"].as_str().unwrap_or_default()),
+                        item["公告标题"].as_str(),
+                    ) {
+                        result.push(StockAnnouncement {
+                            date,
+                            title: title.to_string(),
+                            topic,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    result.sort_by_key(|announcement| std::cmp::Reverse(announcement.date));
+
+    Ok(result)
+}
+
+/// Fetch forecasts of the sell-side research reports between the dates, latest first, only
+/// A-shares are supported
+pub async fn fetch_stock_analyst_forecasts(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<StockAnalystForecast>> {
+    let mut result: Vec<StockAnalystForecast> = vec![];
+
+    if let "SSE" | "SZSE" = ticker.exchange.as_str() {
+        for page_number in 1..=RESEARCH_REPORT_PAGES_MAX {
+            let bytes = http_get(
+                RESEARCH_REPORT_API_URL,
+                None,
+                &HashMap::from([
+                    ("code".to_string(), ticker.symbol.clone()),
+                    ("qType".to_string(), "0".to_string()),
+                    (
+                        "beginTime".to_string(),
+                        date_start.format("%Y-%m-%d").to_string(),
+                    ),
+                    (
+                        "endTime".to_string(),
+                        date_end.format("%Y-%m-%d").to_string(),
+                    ),
+                    (
+                        "pageSize".to_string(),
+                        RESEARCH_REPORT_PAGE_SIZE.to_string(),
+                    ),
+                    ("pageNo".to_string(), page_number.to_string()),
+                ]),
+                &HashMap::new(),
+            )
+            .await?;
+            let json: Value = serde_json::from_slice(&bytes)?;
+
+            if let Some(array) = json["data"].as_array() {
+                for item in array {
+                    let Some(date) = item["publishDate"]
+                        .as_str()
+                        .and_then(|s| s.get(..10))
+                        .and_then(date_from_str)
+                    else {
+                        continue;
+                    };
+
+                    // 数值字段为字符串，没有预测时为空字符串
+                    let number = |key: &str| {
+                        item[key]
+                            .as_f64()
+                            .or_else(|| item[key].as_str().and_then(|s| s.trim().parse().ok()))
+                            .filter(|value: &f64| *value > 0.0)
+                    };
+                    let text = |key: &str| {
+                        item[key]
+                            .as_str()
+                            .map(|s| s.trim())
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_string())
+                    };
+
+                    result.push(StockAnalystForecast {
+                        date,
+                        institution: text("orgSName").unwrap_or_default(),
+                        rating: text("emRatingName"),
+                        // “本年”以发布年份计
+                        fiscal_year: date.year(),
+                        eps: number("predictThisYearEps"),
+                        eps_next_year: number("predictNextYearEps"),
+                        // 目标价区间取上限，只有一个值时上限为空
+                        target_price: number("indvAimPriceT").or_else(|| number("indvAimPriceL")),
+                    });
+                }
+            }
+            if json["TotalPage"].as_u64().unwrap_or(0) <= page_number as u64 {
+                break;
+            }
+        }
+    }
+
+    result.sort_by_key(|forecast| std::cmp::Reverse(forecast.date));
+
+    Ok(result)
+}
+
+/// Fetch announcements of penalties, sanctions and investigations by regulators, only A-shares
+/// are supported
+pub async fn fetch_stock_penalties(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<StockPenalty>> {
+    let mut result: Vec<StockPenalty> = vec![];
+
+    if let "SSE" | "SZSE" = ticker.exchange.as_str() {
+        for keyword in ["处罚", "立案", "警示函", "监管函"] {
+            let json = aktools::call_public_api(
+                "/stock_zh_a_disclosure_report_cninfo",
+                &json!({
+                    "symbol": ticker.symbol,
+                    "market": "沪深京",
+                    "keyword": keyword,
+                    "start_date": date_start.format("%Y%m%d").to_string(),
+                    "end_date": date_end.format("%Y%m%d").to_string(),
+                }),
+            )
+            .await?;
+
+            if let Some(array) = json.as_array() {
+                for item in array {
+                    if let (Some(date), Some(title)) = (
+                        date_from_str(item["公告时间"].as_str().unwrap_or_default()),
+                        item["公告标题"].as_str(),
+                    ) {
+                        // 融资文件中常见“未被处罚”的声明，不是处罚
+                        if PENALTY_NEGATION_WORDS
+                            .iter()
+                            .any(|word| title.contains(word))
+                            || result.iter().any(|p| p.date == date && p.title == title)
+                        {
+                            continue;
+                        }
+
+                        result.push(StockPenalty {
+                            date,
+                            title: title.to_string(),
+                            category: PenaltyCategory::classify(title),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    result.sort_by_key(|penalty| std::cmp::Reverse(penalty.date));
+
+    Ok(result)
+}
+
+/// Fetch the latest ESG rating of China Securities Index (Huazheng), None if not rated
+pub async fn fetch_stock_esg_rating(ticker: &Ticker) -> InvmstResult<Option<StockEsgRating>> {
+    if !matches!(ticker.exchange.as_str(), "SSE" | "SZSE") {
+        return Ok(None);
+    }
+
+    let json = aktools::call_public_api("/stock_esg_hz_sina", &json!({})).await?;
+
+    let grade = |item: &Value, key: &str| {
+        item[key]
+            .as_str()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let mut latest: Option<StockEsgRating> = None;
+    if let Some(array) = json.as_array() {
+        for item in array.iter().filter(|item| {
+            item["股票代码"]
+                .as_str()
+                .is_some_and(|code| code.contains(&ticker.symbol))
+        }) {
+            let rating = StockEsgRating {
+                date: date_from_str(item["日期"].as_str().unwrap_or_default()),
+                score: item["ESG评分"].as_f64(),
+                grade: grade(item, "ESG等级"),
+                environmental_grade: grade(item, "环境等级"),
+                social_grade: grade(item, "社会等级"),
+                governance_grade: grade(item, "公司治理等级"),
+            };
+            if latest
+                .as_ref()
+                .is_none_or(|latest| rating.date > latest.date)
+            {
+                latest = Some(rating);
+            }
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Fetch recent disclosures of all topics with their links, the full texts are downloaded if
+/// requested, only A-shares are supported
+pub async fn fetch_announcements(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+    full_text: bool,
+) -> InvmstResult<Vec<StockDisclosure>> {
+    let mut result = fetch_notice_report(ticker, "全部", date_start, date_end).await?;
+    result.truncate(DISCLOSURES_LIMIT);
+
+    if full_text {
+        for disclosure in &mut result {
+            // 全文只用于补充标题，下载失败时保留标题
+            match fetch_announcement_content(&disclosure.url).await {
+                Ok(content) => disclosure.content = content,
+                Err(err) => warn!(
+                    "[Announcements] Unable to download '{}': {err}",
+                    disclosure.url
+                ),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Fetch the plain text of an announcement of Eastmoney, None if the link is not to Eastmoney
+pub async fn fetch_announcement_content(url: &str) -> InvmstResult<Option<String>> {
+    let content = fetch_announcement_text(url, |text| {
+        text.chars().count() >= ANNOUNCEMENT_CONTENT_MAX_CHARS
+    })
+    .await?;
+
+    Ok(content.and_then(|content| clip_announcement_content(&content)))
+}
+
+/// Trimmed and cut to the max chars, None if nothing is left
+fn clip_announcement_content(content: &str) -> Option<String> {
+    let content: String = content
+        .trim()
+        .chars()
+        .take(ANNOUNCEMENT_CONTENT_MAX_CHARS)
+        .collect();
+
+    (!content.is_empty()).then_some(content)
+}
+
+/// Fetch the section of management discussion and analysis of the latest annual or semi-annual
+/// report published in the year before the date, only A-shares are supported
+pub async fn fetch_stock_management_discussion(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<Option<StockDisclosure>> {
+    let reports = fetch_notice_report(
+        ticker,
+        "财务报告",
+        &(*date - Duration::days(MANAGEMENT_DISCUSSION_BACKWARD_DAYS)),
+        date,
+    )
+    .await?;
+
+    for report in reports
+        .into_iter()
+        .filter(|report| management_discussion::is_periodic_report(&report.title))
+    {
+        // 管理层讨论与分析位于年报前部，读到该节结束即可，不必下载全文
+        let text = fetch_announcement_text(&report.url, |text| {
+            text.chars().count() >= MANAGEMENT_DISCUSSION_SCAN_MAX_CHARS
+                || management_discussion::extract_section(text).is_some()
+        })
+        .await?;
+
+        if let Some(section) = text
+            .as_deref()
+            .and_then(management_discussion::extract_section)
+        {
+            return Ok(Some(StockDisclosure {
+                content: Some(section.to_string()),
+                ..report
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fetch changes of total shares, only A-shares are supported
+pub async fn fetch_stock_share_changes(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<StockShareChange>> {
+    let mut result = vec![];
+
+    if let "SSE" | "SZSE" = ticker.exchange.as_str() {
+        let json = aktools::call_public_api(
+            "/stock_share_change_cninfo",
+            &json!({
+                "symbol": ticker.symbol,
+                "start_date": date_start.format("%Y%m%d").to_string(),
+                "end_date": date_end.format("%Y%m%d").to_string(),
+            }),
+        )
+        .await?;
+
+        if let Some(array) = json.as_array() {
+            for item in array {
+                if let (Some(date), Some(total_shares)) = (
+                    date_from_str(item["变动日期"].as_str().unwrap_or_default()),
+                    item["总股本"].as_f64(),
+                ) {
+                    result.push(StockShareChange {
+                        date,
+                        // In units of ten thousand shares
+                        total_shares: total_shares * 1e4,
+                        reason: item["变动原因"].as_str().unwrap_or_default().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    result.sort_by_key(|share_change| share_change.date);
+
+    Ok(result)
+}
+
 pub async fn fetch_stock_financial_summary(
     ticker: &Ticker,
     fiscal_quater: &FiscalQuarter,
@@ -337,6 +917,9 @@ pub async fn fetch_stock_financial_summary(
                             "存货周转天数" => {
                                 result.days_inventory_outstanding = item[&quarter_key].as_f64();
                             }
+                            "应付账款周转天数" => {
+                                result.days_payable_outstanding = item[&quarter_key].as_f64();
+                            }
                             "应收账款周转天数" => {
                                 result.days_sales_outstanding = item[&quarter_key].as_f64();
                             }
@@ -477,6 +1060,159 @@ pub async fn fetch_stock_financial_summary(
     }
 }
 
+/// Fetch the full statements of the fiscal quarter, empty for HKEX whose itemized statements are
+/// not yet supported
+pub async fn fetch_stock_statements(
+    ticker: &Ticker,
+    fiscal_quarter: &FiscalQuarter,
+) -> InvmstResult<StockStatements> {
+    match ticker.exchange.as_str() {
+        "SSE" | "SZSE" => {
+            let mut result = StockStatements::default();
+
+            let symbol = format!(
+                "{}{}",
+                if ticker.exchange == "SSE" { "SH" } else { "SZ" },
+                ticker.symbol
+            );
+            let report_date = fiscal_quarter.end_date().to_string();
+
+            if let Some(item) =
+                fetch_report("/stock_profit_sheet_by_report_em", &symbol, &report_date).await
+            {
+                result.income = IncomeStatement {
+                    operating_revenue: item["TOTAL_OPERATE_INCOME"].as_f64(),
+                    operating_cost: item["OPERATE_COST"].as_f64(),
+                    selling_expense: item["SALE_EXPENSE"].as_f64(),
+                    administrative_expense: item["MANAGE_EXPENSE"].as_f64(),
+                    research_expense: item["RESEARCH_EXPENSE"].as_f64(),
+                    finance_expense: item["FINANCE_EXPENSE"].as_f64(),
+                    interest_expense: item["FE_INTEREST_EXPENSE"].as_f64(),
+                    operating_profit: item["OPERATE_PROFIT"].as_f64(),
+                    total_profit: item["TOTAL_PROFIT"].as_f64(),
+                    income_tax: item["INCOME_TAX"].as_f64(),
+                    net_profit: item["NETPROFIT"].as_f64(),
+                    net_profit_attributable: item["PARENT_NETPROFIT"].as_f64(),
+                };
+            }
+
+            if let Some(item) =
+                fetch_report("/stock_balance_sheet_by_report_em", &symbol, &report_date).await
+            {
+                result.balance_sheet = BalanceSheet {
+                    cash: item["MONETARYFUNDS"].as_f64(),
+                    accounts_receivable: item["ACCOUNTS_RECE"].as_f64(),
+                    inventory: item["INVENTORY"].as_f64(),
+                    total_current_assets: item["TOTAL_CURRENT_ASSETS"].as_f64(),
+                    fixed_assets: item["FIXED_ASSET"].as_f64(),
+                    intangible_assets: item["INTANGIBLE_ASSET"].as_f64(),
+                    goodwill: item["GOODWILL"].as_f64(),
+                    total_assets: item["TOTAL_ASSETS"].as_f64(),
+                    short_term_borrowings: item["SHORT_LOAN"].as_f64(),
+                    accounts_payable: item["ACCOUNTS_PAYABLE"].as_f64(),
+                    noncurrent_liabilities_due_within_one_year: item["NONCURRENT_LIAB_1YEAR"]
+                        .as_f64(),
+                    total_current_liabilities: item["TOTAL_CURRENT_LIAB"].as_f64(),
+                    long_term_borrowings: item["LONG_LOAN"].as_f64(),
+                    bonds_payable: item["BOND_PAYABLE"].as_f64(),
+                    total_liabilities: item["TOTAL_LIABILITIES"].as_f64(),
+                    equity_attributable: item["TOTAL_PARENT_EQUITY"].as_f64(),
+                    total_equity: item["TOTAL_EQUITY"].as_f64(),
+                };
+            }
+
+            if let Some(item) =
+                fetch_report("/stock_cash_flow_sheet_by_report_em", &symbol, &report_date).await
+            {
+                result.cash_flow = CashFlowStatement {
+                    operating_cash_flow: item["NETCASH_OPERATE"].as_f64(),
+                    investing_cash_flow: item["NETCASH_INVEST"].as_f64(),
+                    financing_cash_flow: item["NETCASH_FINANCE"].as_f64(),
+                    capital_expenditure: item["CONSTRUCT_LONG_ASSET"].as_f64(),
+                    depreciation: item["FA_IR_DEPR"].as_f64(),
+                    intangible_amortization: item["IA_AMORTIZE"].as_f64(),
+                    prepaid_expense_amortization: item["LPE_AMORTIZE"].as_f64(),
+                    staff_cash_paid: item["PAY_STAFF_CASH"].as_f64(),
+                    dividends_interest_paid: item["ASSIGN_DIVIDEND_PORFIT"].as_f64(),
+                };
+            }
+
+            Ok(result)
+        }
+        "HKEX" => Ok(StockStatements::default()),
+        _ => Err(InvmstError::Invalid(
+            "EXCHANGE_NOT_SUPPORTED",
+            format!("Not yet supported exchange '{}'", ticker.exchange),
+        )),
+    }
+}
+
+/// Fetch basic EPS of every fiscal year, latest first
+pub async fn fetch_stock_annual_eps(ticker: &Ticker) -> InvmstResult<Vec<(i32, f64)>> {
+    let mut result: Vec<(i32, f64)> = vec![];
+
+    match ticker.exchange.as_str() {
+        "SSE" | "SZSE" => {
+            let json = aktools::call_public_api(
+                "/stock_financial_abstract",
+                &json!({
+                    "symbol": ticker.symbol,
+                }),
+            )
+            .await?;
+
+            if let Some(item) = json.as_array().and_then(|array| {
+                array
+                    .iter()
+                    .find(|item| item["指标"].as_str() == Some("基本每股收益"))
+            }) {
+                if let Some(object) = item.as_object() {
+                    for (key, value) in object {
+                        if let (Some(year), Some(eps)) = (
+                            key.strip_suffix("1231")
+                                .and_then(|year| year.parse::<i32>().ok()),
+                            value.as_f64(),
+                        ) {
+                            result.push((year, eps));
+                        }
+                    }
+                }
+            }
+        }
+        "HKEX" => {
+            let json = aktools::call_public_api(
+                "/stock_financial_hk_analysis_indicator_em",
+                &json!({
+                    "symbol": ticker.symbol,
+                    "indicator": "年度",
+                }),
+            )
+            .await?;
+
+            if let Some(array) = json.as_array() {
+                for item in array {
+                    if let (Some(report_date), Some(eps)) = (
+                        date_from_str(item["REPORT_DATE"].as_str().unwrap_or_default()),
+                        item["BASIC_EPS"].as_f64(),
+                    ) {
+                        result.push((report_date.year(), eps));
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(InvmstError::Invalid(
+                "EXCHANGE_NOT_SUPPORTED",
+                format!("Not yet supported exchange '{}'", ticker.exchange),
+            ));
+        }
+    }
+
+    result.sort_by_key(|(year, _)| std::cmp::Reverse(*year));
+
+    Ok(result)
+}
+
 pub async fn fetch_stock_info(ticker: &Ticker) -> InvmstResult<StockInfo> {
     match ticker.exchange.as_str() {
         "SSE" | "SZSE" => {
@@ -500,12 +1236,23 @@ pub async fn fetch_stock_info(ticker: &Ticker) -> InvmstResult<StockInfo> {
                             "行业" => {
                                 result.industry = item["value"].as_str().map(|v| v.to_string());
                             }
+                            "上市时间" => {
+                                result.listing_date = date_from_str(&match &item["value"] {
+                                    Value::Number(n) => n.to_string(),
+                                    v => v.as_str().unwrap_or_default().to_string(),
+                                });
+                            }
                             _ => {}
                         }
                     }
                 }
             }
 
+            match fetch_stock_largest_holder_ratio(ticker).await {
+                Ok(ratio) => result.largest_holder_ratio = ratio,
+                Err(err) => warn!("[Largest Holder] {err}"),
+            }
+
             Ok(result)
         }
         "HKEX" => {
@@ -528,6 +1275,24 @@ pub async fn fetch_stock_info(ticker: &Ticker) -> InvmstResult<StockInfo> {
                 }
             }
 
+            {
+                let json = aktools::call_public_api(
+                    "/stock_hk_security_profile_em",
+                    &json!({
+                        "symbol": ticker.symbol,
+                    }),
+                )
+                .await?;
+
+                if let Some(item) = json.as_array().and_then(|array| array.first()) {
+                    // 上市日期 may come with a time part
+                    result.listing_date = item["上市日期"]
+                        .as_str()
+                        .and_then(|s| s.split_whitespace().next())
+                        .and_then(date_from_str);
+                }
+            }
+
             Ok(result)
         }
         _ => Err(InvmstError::Invalid(
@@ -536,3 +1301,277 @@ pub async fn fetch_stock_info(ticker: &Ticker) -> InvmstResult<StockInfo> {
         )),
     }
 }
+
+/// Scheduled disclosure date of the next report after the date, A-shares only, None if the report
+/// due next is not scheduled yet
+pub async fn fetch_stock_next_earnings_date(
+    ticker: &Ticker,
+    date: &NaiveDate,
+) -> InvmstResult<Option<NaiveDate>> {
+    match ticker.exchange.as_str() {
+        "SSE" | "SZSE" => {
+            // 最近结束的报告期可能已经披露，此时下一份报告是再下一个报告期
+            let fiscal_quarter = prev_fiscal_quarter(Some(date));
+            for fiscal_quarter in [fiscal_quarter.clone(), fiscal_quarter.next()] {
+                let json = aktools::call_public_api(
+                    "/stock_yysj_em",
+                    &json!({
+                        "symbol": "沪深A股",
+                        "date": fiscal_quarter.end_date().format("%Y%m%d").to_string(),
+                    }),
+                )
+                .await?;
+
+                let Some(item) = json.as_array().and_then(|array| {
+                    array
+                        .iter()
+                        .find(|item| item["股票代码"].as_str() == Some(ticker.symbol.as_str()))
+                }) else {
+                    return Ok(None);
+                };
+
+                if let Some(disclosed) =
+                    date_from_str(item["实际披露时间"].as_str().unwrap_or_default())
+                {
+                    if disclosed <= *date {
+                        continue;
+                    }
+                }
+
+                // 预约时间可能多次变更，取最后一次
+                let scheduled = [
+                    "三次变更日期",
+                    "二次变更日期",
+                    "一次变更日期",
+                    "首次预约时间",
+                ]
+                .iter()
+                .find_map(|key| date_from_str(item[*key].as_str().unwrap_or_default()));
+
+                return Ok(scheduled.filter(|scheduled| scheduled >= date));
+            }
+
+            Ok(None)
+        }
+        _ => Err(InvmstError::Invalid(
+            "EXCHANGE_NOT_SUPPORTED",
+            format!("Not yet supported exchange '{}'", ticker.exchange),
+        )),
+    }
+}
+
+/// Stake of the largest shareholder in the latest disclosure, A-shares only
+async fn fetch_stock_largest_holder_ratio(ticker: &Ticker) -> InvmstResult<Option<f64>> {
+    let json = aktools::call_public_api(
+        "/stock_main_stock_holder",
+        &json!({
+            "stock": ticker.symbol,
+        }),
+    )
+    .await?;
+
+    let mut latest: Option<(NaiveDate, f64)> = None;
+    if let Some(array) = json.as_array() {
+        for item in array {
+            if let (Some(date), Some(ratio)) = (
+                date_from_str(item["截至日期"].as_str().unwrap_or_default()),
+                item["持股比例"].as_f64(),
+            ) {
+                // 同一截至日期取最大股东
+                if latest.is_none_or(|(d, r)| date > d || (date == d && ratio > r)) {
+                    latest = Some((date, ratio));
+                }
+            }
+        }
+    }
+
+    Ok(latest.map(|(_, ratio)| ratio / 100.0))
+}
+
+/// Fetch the announcements of a category of Eastmoney, e.g. '全部' or '财务报告', latest first
+async fn fetch_notice_report(
+    ticker: &Ticker,
+    category: &str,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> InvmstResult<Vec<StockDisclosure>> {
+    let mut result = vec![];
+
+    if let "SSE" | "SZSE" = ticker.exchange.as_str() {
+        let json = aktools::call_public_api(
+            "/stock_individual_notice_report",
+            &json!({
+                "security": ticker.symbol,
+                "symbol": category,
+                "begin_date": date_start.format("%Y%m%d").to_string(),
+                "end_date": date_end.format("%Y%m%d").to_string(),
+            }),
+        )
+        .await?;
+
+        if let Some(array) = json.as_array() {
+            for item in array {
+                if let (Some(date), Some(title), Some(url)) = (
+                    date_from_str(item["公告日期"].as_str().unwrap_or_default()),
+                    item["公告标题"].as_str(),
+                    item["网址"].as_str(),
+                ) {
+                    result.push(StockDisclosure {
+                        date,
+                        title: title.to_string(),
+                        url: url.to_string(),
+                        content: None,
+                    });
+                }
+            }
+        }
+    }
+
+    result.sort_by_key(|disclosure| std::cmp::Reverse(disclosure.date));
+
+    Ok(result)
+}
+
+/// Fetch the pages of an announcement of Eastmoney until the text is enough or all pages are read,
+/// None if the link is not to Eastmoney
+async fn fetch_announcement_text(
+    url: &str,
+    is_enough: impl Fn(&str) -> bool,
+) -> InvmstResult<Option<String>> {
+    // 链接形如 https://data.eastmoney.com/notices/detail/600519/AN202501011234567890.html
+    let Some(art_code) = url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.strip_suffix(".html"))
+        .filter(|code| code.starts_with("AN"))
+    else {
+        return Ok(None);
+    };
+
+    let mut text = String::new();
+    let mut page_index = 1;
+    loop {
+        let bytes = http_get(
+            ANNOUNCEMENT_CONTENT_API_URL,
+            None,
+            &HashMap::from([
+                ("art_code".to_string(), art_code.to_string()),
+                ("client_source".to_string(), "web".to_string()),
+                ("page_index".to_string(), page_index.to_string()),
+            ]),
+            &HashMap::new(),
+        )
+        .await?;
+        let json: Value = serde_json::from_slice(&bytes)?;
+
+        text.push_str(json["data"]["notice_content"].as_str().unwrap_or_default());
+
+        let page_size = json["data"]["page_size"].as_u64().unwrap_or(1);
+        if page_index >= page_size || is_enough(&text) {
+            break;
+        }
+        page_index += 1;
+    }
+
+    Ok(Some(text))
+}
+
+/// Fetch the statement item of the report date from Eastmoney, a statement failing to fetch is
+/// left empty with a warning rather than failing the others
+async fn fetch_report(path: &str, symbol: &str, report_date: &str) -> Option<Value> {
+    match aktools::call_public_api(path, &json!({ "symbol": symbol })).await {
+        Ok(json) => find_report(&json, report_date).cloned(),
+        Err(err) => {
+            warn!("[Statements] Unable to fetch {path} of {symbol}: {err}");
+            None
+        }
+    }
+}
+
+/// Value of a valuation indicator of Baidu for HK stocks in the units of A-shares, i.e. the market
+/// cap given in 100 millions is converted to yuan
+fn normalize_hk_valuation(indicator: &str, value: &Value) -> Value {
+    match (indicator, value.as_f64()) {
+        ("总市值", Some(market_cap)) => json!(market_cap * 100_000_000.0),
+        _ => value.clone(),
+    }
+}
+
+/// Find the statement item of the report date from the reports of Eastmoney
+fn find_report<'a>(json: &'a Value, report_date: &str) -> Option<&'a Value> {
+    json.as_array()?.iter().find(|item| {
+        item["REPORT_DATE"]
+            .as_str()
+            .is_some_and(|s| s.starts_with(report_date))
+    })
+}
+
+static ANNOUNCEMENT_CONTENT_API_URL: &str =
+    "https://np-cnotice-stock.eastmoney.com/api/content/ann";
+/// Long announcements like annual reports are cut, the beginning summarizes them anyway
+static ANNOUNCEMENT_CONTENT_MAX_CHARS: usize = 5000;
+static DISCLOSURES_LIMIT: usize = 50;
+/// The latest annual report is published within four months after the fiscal year ends
+static MANAGEMENT_DISCUSSION_BACKWARD_DAYS: i64 = 400;
+/// Text of a report read at most to find the section, which is near the beginning
+static MANAGEMENT_DISCUSSION_SCAN_MAX_CHARS: usize = 300_000;
+static MARGIN_API_URL: &str = "https://datacenter-web.eastmoney.com/api/data/v1/get";
+/// Pages of the latest balances to fetch, covering about the default backward days
+static MARGIN_PAGES_MAX: usize = 3;
+static MARGIN_PAGE_SIZE: usize = 500;
+static PENALTY_NEGATION_WORDS: &[&str] = &["未被", "不存在", "未受到", "无违法"];
+static RESEARCH_REPORT_API_URL: &str = "https://reportapi.eastmoney.com/report/list";
+static RESEARCH_REPORT_PAGES_MAX: usize = 5;
+static RESEARCH_REPORT_PAGE_SIZE: usize = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::financial::factors::{self, FactorInputs};
+
+    #[tokio::test]
+    async fn test_fetch_announcement_content() {
+        // 非东方财富的链接不下载
+        assert_eq!(
+            fetch_announcement_content("https://static.cninfo.com.cn/finalpage/2025-01-01/1.PDF")
+                .await
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(
+            clip_announcement_content("  董事会决议公告\n").as_deref(),
+            Some("董事会决议公告")
+        );
+        assert_eq!(clip_announcement_content(" \n "), None);
+        assert_eq!(
+            clip_announcement_content(&"年".repeat(ANNOUNCEMENT_CONTENT_MAX_CHARS + 10))
+                .unwrap()
+                .chars()
+                .count(),
+            ANNOUNCEMENT_CONTENT_MAX_CHARS
+        );
+    }
+
+    #[test]
+    fn test_normalize_hk_valuation() {
+        // 百度港股总市值以亿为单位
+        let market_cap = normalize_hk_valuation("总市值", &json!(500.0))
+            .as_f64()
+            .unwrap();
+        assert_eq!(market_cap, 5e10);
+        assert_eq!(
+            normalize_hk_valuation("市盈率(TTM)", &json!(12.5)),
+            json!(12.5)
+        );
+        assert_eq!(normalize_hk_valuation("总市值", &Value::Null), Value::Null);
+
+        let size = factors::compute(&FactorInputs {
+            market_cap: Some(market_cap),
+            ..Default::default()
+        })
+        .size
+        .unwrap();
+        assert!(size > -3.0 && size < 0.0);
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::data::stock::{AnnouncementTopic, StockAnnouncement};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Suspension {
+    /// Last date with a price on or before the evaluation date
+    pub last_trading_date: NaiveDate,
+    /// Calendar days since the last trading date
+    pub stale_days: i64,
+    /// Trading halt announcements within the recent days, latest first
+    pub halt_announcements: Vec<StockAnnouncement>,
+}
+
+/// Detect interrupted trading from prices sorted by date ascending and the announcements, None if
+/// the stock trades normally without recent halts
+pub fn detect(
+    prices: &[(NaiveDate, f64)],
+    announcements: &[StockAnnouncement],
+    date: &NaiveDate,
+) -> Option<Suspension> {
+    let (last_trading_date, _) = prices.iter().rev().find(|(d, _)| d <= date)?;
+
+    let halt_announcements: Vec<StockAnnouncement> = announcements
+        .iter()
+        .filter(|announcement| {
+            matches!(announcement.topic, AnnouncementTopic::TradingHalt)
+                && announcement.date <= *date
+                && announcement.date > *date - Duration::days(HALT_RECENT_DAYS)
+        })
+        .cloned()
+        .collect();
+
+    let suspension = Suspension {
+        last_trading_date: *last_trading_date,
+        stale_days: (*date - *last_trading_date).num_days(),
+        halt_announcements,
+    };
+
+    (suspension.is_suspended() || !suspension.halt_announcements.is_empty()).then_some(suspension)
+}
+
+impl Suspension {
+    /// No trade for longer than the longest market holiday, so the price is frozen
+    pub fn is_suspended(&self) -> bool {
+        self.stale_days > SUSPENSION_DAYS_MIN
+    }
+
+    pub fn assessments(&self) -> Vec<String> {
+        let mut assessments: Vec<String> = vec![];
+
+        if self.is_suspended() {
+            assessments.push(format!(
+                "Trading suspended since {}, the price is {} days stale",
+                self.last_trading_date, self.stale_days
+            ));
+            assessments.push(
+                "Illiquid while suspended, the position cannot be entered or exited until trading resumes"
+                    .to_string(),
+            );
+        }
+
+        if !self.halt_announcements.is_empty() {
+            assessments.push(format!(
+                "{} trading halt announcements in the last {HALT_RECENT_DAYS} days, liquidity may be interrupted",
+                self.halt_announcements.len()
+            ));
+        }
+
+        assessments
+    }
+}
+
+/// Longer than the Spring Festival and National Day holidays
+static SUSPENSION_DAYS_MIN: i64 = 10;
+
+static HALT_RECENT_DAYS: i64 = 90;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let d = |m, day| NaiveDate::from_ymd_opt(2025, m, day).unwrap();
+        let prices = [(d(3, 3), 10.0), (d(3, 4), 10.5)];
+
+        assert!(detect(&prices, &[], &d(3, 10)).is_none());
+
+        let suspension = detect(&prices, &[], &d(4, 4)).unwrap();
+        assert!(suspension.is_suspended());
+        assert_eq!(suspension.stale_days, 31);
+        assert_eq!(suspension.assessments().len(), 2);
+
+        let halt = StockAnnouncement {
+            date: d(3, 5),
+            title: "关于重大资产重组停牌的公告".to_string(),
+            topic: AnnouncementTopic::TradingHalt,
+        };
+        let suspension = detect(&prices, &[halt], &d(3, 10)).unwrap();
+        assert!(!suspension.is_suspended());
+        assert_eq!(suspension.assessments().len(), 1);
+    }
+}
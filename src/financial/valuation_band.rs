@@ -0,0 +1,256 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{data::daily::DailyDataset, financial::stock::StockValuationFieldName};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, strum::Display, strum::EnumIter,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum BandMultiple {
+    Pe,
+    Pb,
+}
+
+impl BandMultiple {
+    fn field(&self) -> StockValuationFieldName {
+        match self {
+            BandMultiple::Pe => StockValuationFieldName::PeTtm,
+            BandMultiple::Pb => StockValuationFieldName::Pb,
+        }
+    }
+}
+
+/// Prices implied by the mean and ±1σ of the multiple over the trailing window of the date
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BandPoint {
+    pub date: NaiveDate,
+    pub price: f64,
+    pub multiple: f64,
+    pub lower: f64,
+    pub middle: f64,
+    pub upper: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValuationBand {
+    pub multiple: BandMultiple,
+    /// Sorted by date ascending
+    pub points: Vec<BandPoint>,
+}
+
+impl ValuationBand {
+    /// Line chart of the price within the band as a standalone SVG document
+    pub fn to_svg(&self, title: &str) -> String {
+        let values = self
+            .points
+            .iter()
+            .flat_map(|point| [point.price, point.lower, point.upper]);
+        let (min, max) = values.fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        let range = if max > min { max - min } else { 1.0 };
+        let steps = self.points.len().saturating_sub(1).max(1) as f64;
+
+        let plot_width = SVG_WIDTH - 2.0 * SVG_PADDING;
+        let plot_height = SVG_HEIGHT - 2.0 * SVG_PADDING;
+        let x = |i: usize| SVG_PADDING + plot_width * i as f64 / steps;
+        let y = |value: f64| SVG_PADDING + plot_height * (max - value) / range;
+        let polyline = |value: &dyn Fn(&BandPoint) -> f64| {
+            self.points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| format!("{:.1},{:.1}", x(i), y(value(point))))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        // 上下轨之间填充为色带
+        let band_area = format!(
+            "{} {}",
+            polyline(&|point| point.upper),
+            self.points
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, point)| format!("{:.1},{:.1}", x(i), y(point.lower)))
+                .collect::<Vec<String>>()
+                .join(" ")
+        );
+
+        let mut labels: Vec<String> = vec![];
+        if let (Some(first), Some(last)) = (self.points.first(), self.points.last()) {
+            labels.push(format!(
+                r#"<text x="{:.1}" y="{:.1}" font-size="12">{}</text>"#,
+                SVG_PADDING,
+                SVG_HEIGHT - SVG_PADDING / 3.0,
+                first.date
+            ));
+            labels.push(format!(
+                r#"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="end">{}</text>"#,
+                SVG_WIDTH - SVG_PADDING,
+                SVG_HEIGHT - SVG_PADDING / 3.0,
+                last.date
+            ));
+        }
+        for value in [min, max] {
+            labels.push(format!(
+                r#"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="end">{value:.2}</text>"#,
+                SVG_PADDING - 4.0,
+                y(value) + 4.0
+            ));
+        }
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{SVG_HEIGHT}" viewBox="0 0 {SVG_WIDTH} {SVG_HEIGHT}">
+<rect width="100%" height="100%" fill="white"/>
+<text x="{:.1}" y="{:.1}" font-size="14" text-anchor="middle">{}</text>
+<polygon points="{band_area}" fill="#4a90d9" fill-opacity="0.15" stroke="none"/>
+<polyline points="{}" fill="none" stroke="#4a90d9" stroke-dasharray="4 3"/>
+<polyline points="{}" fill="none" stroke="#4a90d9"/>
+<polyline points="{}" fill="none" stroke="#4a90d9" stroke-dasharray="4 3"/>
+<polyline points="{}" fill="none" stroke="#d0021b" stroke-width="1.5"/>
+{}
+</svg>
+"##,
+            SVG_WIDTH / 2.0,
+            SVG_PADDING / 2.0,
+            escape_xml(title),
+            polyline(&|point| point.upper),
+            polyline(&|point| point.middle),
+            polyline(&|point| point.lower),
+            polyline(&|point| point.price),
+            labels.join("\n")
+        )
+    }
+}
+
+/// Band of the multiple between the dates, None if the multiple has too few positive values, e.g.
+/// PE of a company in losses. Each point takes the statistics of the trailing window, so the band
+/// follows the re-rating of the stock rather than the whole history.
+pub fn compute(
+    daily_valuations: &DailyDataset<StockValuationFieldName>,
+    multiple: BandMultiple,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+) -> Option<ValuationBand> {
+    let window_start = *date_start - Duration::days(WINDOW_DAYS);
+    let multiples: Vec<(NaiveDate, f64)> = daily_valuations
+        .get_values::<f64>(&window_start, date_end, multiple.field())
+        .into_iter()
+        .filter(|(_, value)| *value > 0.0)
+        .collect();
+    let prices: Vec<(NaiveDate, f64)> =
+        daily_valuations.get_values(date_start, date_end, StockValuationFieldName::Price);
+
+    // 滑动窗口累计和，避免每个日期重复统计
+    let mut points: Vec<BandPoint> = vec![];
+    let (mut head, mut tail) = (0, 0);
+    let (mut sum, mut sum_sq) = (0.0, 0.0);
+    for (date, price) in prices {
+        while tail < multiples.len() && multiples[tail].0 <= date {
+            sum += multiples[tail].1;
+            sum_sq += multiples[tail].1.powi(2);
+            tail += 1;
+        }
+        while head < tail && multiples[head].0 <= date - Duration::days(WINDOW_DAYS) {
+            sum -= multiples[head].1;
+            sum_sq -= multiples[head].1.powi(2);
+            head += 1;
+        }
+
+        let count = (tail - head) as f64;
+        if count < MIN_SAMPLES as f64 || multiples[tail - 1].0 != date {
+            continue;
+        }
+
+        let current = multiples[tail - 1].1;
+        let mean = sum / count;
+        let std = (sum_sq / count - mean.powi(2)).max(0.0).sqrt();
+        let base = price / current;
+        points.push(BandPoint {
+            date,
+            price,
+            multiple: current,
+            lower: base * (mean - std).max(0.0),
+            middle: base * mean,
+            upper: base * (mean + std),
+        });
+    }
+
+    (!points.is_empty()).then_some(ValuationBand { multiple, points })
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+static MIN_SAMPLES: usize = 60;
+static SVG_HEIGHT: f64 = 400.0;
+static SVG_PADDING: f64 = 48.0;
+static SVG_WIDTH: f64 = 800.0;
+
+/// Trailing window of the statistics, about 3 years
+static WINDOW_DAYS: i64 = 3 * 365;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let date_start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let items: Vec<Value> = (0..200)
+            .map(|i| {
+                // 每股收益固定为 1，市盈率在 10 和 20 之间交替
+                let pe = if i % 2 == 0 { 10.0 } else { 20.0 };
+                json!({
+                    "date": (date_start + Duration::days(i)).to_string(),
+                    "price": pe,
+                    "pe": pe,
+                })
+            })
+            .collect();
+        let daily_valuations = DailyDataset::from_json(
+            &Value::Array(items),
+            "date",
+            &[
+                (StockValuationFieldName::Price, "price"),
+                (StockValuationFieldName::PeTtm, "pe"),
+            ],
+        )
+        .unwrap();
+
+        let band = compute(
+            &daily_valuations,
+            BandMultiple::Pe,
+            &(date_start + Duration::days(100)),
+            &(date_start + Duration::days(199)),
+        )
+        .unwrap();
+        assert_eq!(band.points.len(), 100);
+
+        let point = band.points.last().unwrap();
+        assert!((point.middle - 15.0).abs() < 1e-9);
+        assert!((point.lower - 10.0).abs() < 1e-9);
+        assert!((point.upper - 20.0).abs() < 1e-9);
+
+        let svg = band.to_svg("600900 PE band");
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polyline").count(), 4);
+
+        assert!(
+            compute(
+                &daily_valuations,
+                BandMultiple::Pb,
+                &date_start,
+                &(date_start + Duration::days(199)),
+            )
+            .is_none()
+        );
+    }
+}
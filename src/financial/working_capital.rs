@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use crate::{
+    data::stock::{StockFinancialSummary, StockFiscalMetricset},
+    financial::fiscal_series::FiscalSeries,
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct WorkingCapital {
+    /// Cash conversion cycle in days of each fiscal quarter, latest first
+    pub cycles: Vec<Option<f64>>,
+    /// Change of the latest cycle from the same quarter a year ago, in days
+    pub change_yoy: Option<f64>,
+    /// The cycle has lengthened year over year by more than the tolerance for two quarters in a row
+    pub deteriorating: bool,
+}
+
+/// DSO + DIO - DPO, payables are treated as zero if unavailable, which gives the operating cycle
+pub fn cash_conversion_cycle(summary: &StockFinancialSummary) -> Option<f64> {
+    Some(
+        summary.days_sales_outstanding? + summary.days_inventory_outstanding?
+            - summary.days_payable_outstanding.unwrap_or(0.0),
+    )
+}
+
+/// Analyze the cash conversion cycle of fiscal metricsets sorted latest first,
+/// comparing same quarters to avoid seasonality
+pub fn analyze(stock_fiscal_metricsets: &[StockFiscalMetricset]) -> WorkingCapital {
+    let cycles: Vec<Option<f64>> = stock_fiscal_metricsets
+        .iter()
+        .map(|(_, stock_metrics)| cash_conversion_cycle(&stock_metrics.financial_summary))
+        .collect();
+
+    let series = FiscalSeries::new(stock_fiscal_metricsets);
+    let change_yoy = |i: usize| {
+        let fiscal_quarter = series.quarters().nth(i)?;
+
+        Some(
+            series.value(fiscal_quarter, cash_conversion_cycle)?
+                - series.value(&fiscal_quarter.prev_year(), cash_conversion_cycle)?,
+        )
+    };
+
+    let deteriorating = [change_yoy(0), change_yoy(1)]
+        .iter()
+        .all(|change| change.is_some_and(|c| c > DETERIORATION_TOLERANCE_DAYS));
+
+    WorkingCapital {
+        change_yoy: change_yoy(0),
+        deteriorating,
+        cycles,
+    }
+}
+
+static DETERIORATION_TOLERANCE_DAYS: f64 = 10.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::stock::StockMetricset,
+        utils::datetime::{FiscalQuarter, Quarter},
+    };
+
+    #[test]
+    fn test_analyze() {
+        let mut fiscal_quarter = FiscalQuarter::new(2024, Quarter::Q4);
+        let mut stock_fiscal_metricsets: Vec<StockFiscalMetricset> = vec![];
+        for dso in [90.0, 85.0, 60.0, 60.0, 60.0, 60.0] {
+            stock_fiscal_metricsets.push((
+                fiscal_quarter.clone(),
+                StockMetricset {
+                    financial_summary: StockFinancialSummary {
+                        days_sales_outstanding: Some(dso),
+                        days_inventory_outstanding: Some(30.0),
+                        days_payable_outstanding: Some(20.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ));
+            fiscal_quarter = fiscal_quarter.prev();
+        }
+
+        let working_capital = analyze(&stock_fiscal_metricsets);
+        assert_eq!(working_capital.cycles[0], Some(100.0));
+        assert_eq!(working_capital.change_yoy, Some(30.0));
+        assert!(working_capital.deteriorating);
+    }
+}
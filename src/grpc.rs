@@ -0,0 +1,227 @@
+use std::{net::SocketAddr, pin::Pin, str::FromStr};
+
+use chrono::{Local, NaiveDate};
+use futures::{Stream, stream};
+use strum::{EnumMessage, IntoEnumIterator};
+use tonic::{Request, Response, Status, transport::Server};
+
+use crate::{
+    error::{InvmstError, InvmstResult},
+    evaluate::{self, EvaluateOptions, EvaluationEvent},
+    financial::{get_stock_daily_valuations, stock::StockValuationFieldName},
+    llm::ChatCompletionOptions,
+    master::{Master, MasterAnalysis},
+    ticker::Ticker,
+    utils,
+};
+
+mod proto {
+    tonic::include_proto!("invmst");
+}
+
+use proto::invmst_server::{Invmst, InvmstServer};
+
+pub async fn serve(addr: &str) -> InvmstResult<()> {
+    let addr = SocketAddr::from_str(addr).map_err(|_| {
+        InvmstError::Invalid("INVALID_ADDRESS", format!("Invalid address '{addr}'"))
+    })?;
+
+    Server::builder()
+        .add_service(InvmstServer::new(InvmstService))
+        .serve(addr)
+        .await?;
+
+    Ok(())
+}
+
+struct InvmstService;
+
+#[tonic::async_trait]
+impl Invmst for InvmstService {
+    type EvaluateStreamStream =
+        Pin<Box<dyn Stream<Item = Result<proto::EvaluationEvent, Status>> + Send>>;
+
+    async fn evaluate(
+        &self,
+        request: Request<proto::EvaluateRequest>,
+    ) -> Result<Response<proto::Evaluation>, Status> {
+        let request = request.into_inner();
+        let options = evaluate_options(&request).map_err(to_status)?;
+
+        let evaluation = evaluate::run(&request.ticker, &options)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(proto::Evaluation {
+            id: evaluation.id,
+            master_analyses: evaluation
+                .master_analyses
+                .into_iter()
+                .map(|(master, master_analysis)| to_proto_analysis(master, master_analysis))
+                .collect(),
+            bear_case: evaluation
+                .bear_case
+                .map(|bear_case| serde_json::to_string(&bear_case).unwrap_or_default()),
+            failure_modes: evaluation
+                .failure_modes
+                .map(|failure_modes| serde_json::to_string(&failure_modes).unwrap_or_default()),
+            note: evaluation.note,
+            master_errors: evaluation
+                .master_errors
+                .into_iter()
+                .map(|(master, err)| (master.to_string(), err))
+                .collect(),
+        }))
+    }
+
+    async fn evaluate_stream(
+        &self,
+        request: Request<proto::EvaluateRequest>,
+    ) -> Result<Response<Self::EvaluateStreamStream>, Status> {
+        let request = request.into_inner();
+        let options = evaluate_options(&request).map_err(to_status)?;
+
+        let stream = evaluate::run_stream(&request.ticker, &options)
+            .await
+            .map_err(to_status)?;
+
+        let events = stream::unfold(stream, |mut stream| async move {
+            let event = stream.next().await?;
+            let event = match event {
+                EvaluationEvent::Stage(stage) => {
+                    proto::evaluation_event::Event::Stage(stage.to_string())
+                }
+                EvaluationEvent::MasterStarted(master) => {
+                    proto::evaluation_event::Event::MasterStarted(master.to_string())
+                }
+                EvaluationEvent::MasterFinished(master, master_analysis) => {
+                    proto::evaluation_event::Event::MasterFinished(Box::new(to_proto_analysis(
+                        master,
+                        master_analysis,
+                    )))
+                }
+                EvaluationEvent::Finished(evaluation) => {
+                    proto::evaluation_event::Event::Finished(proto::Finished { id: evaluation.id })
+                }
+                EvaluationEvent::Error(err) => {
+                    proto::evaluation_event::Event::Error(err.to_string())
+                }
+            };
+
+            Some((Ok(proto::EvaluationEvent { event: Some(event) }), stream))
+        });
+
+        Ok(Response::new(Box::pin(events)))
+    }
+
+    async fn list_masters(
+        &self,
+        _request: Request<proto::ListMastersRequest>,
+    ) -> Result<Response<proto::ListMastersResponse>, Status> {
+        Ok(Response::new(proto::ListMastersResponse {
+            masters: Master::iter()
+                .map(|master| proto::Master {
+                    key: master.to_string(),
+                    name: master.get_message().unwrap_or_default().to_string(),
+                    aliases: master
+                        .get_serializations()
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_valuation(
+        &self,
+        request: Request<proto::GetValuationRequest>,
+    ) -> Result<Response<proto::Valuation>, Status> {
+        let request = request.into_inner();
+
+        let ticker = Ticker::from_str(&request.ticker).map_err(to_status)?;
+        let date = parse_date(request.date.as_deref())
+            .map_err(to_status)?
+            .unwrap_or(Local::now().date_naive());
+
+        let daily_valuations = get_stock_daily_valuations(&ticker)
+            .await
+            .map_err(to_status)?;
+        let get_value = |field_name: StockValuationFieldName| {
+            daily_valuations.get_latest_value::<f64>(&date, field_name)
+        };
+
+        Ok(Response::new(proto::Valuation {
+            price: get_value(StockValuationFieldName::Price),
+            market_cap: get_value(StockValuationFieldName::MarketCap),
+            pe: get_value(StockValuationFieldName::Pe),
+            pe_ttm: get_value(StockValuationFieldName::PeTtm),
+            peg: get_value(StockValuationFieldName::Peg),
+            pb: get_value(StockValuationFieldName::Pb),
+            pcf: get_value(StockValuationFieldName::Pcf),
+            ps: get_value(StockValuationFieldName::Ps),
+        }))
+    }
+}
+
+fn evaluate_options(request: &proto::EvaluateRequest) -> InvmstResult<EvaluateOptions> {
+    Ok(EvaluateOptions {
+        backward_days: request.backward_days,
+        date: parse_date(request.date.as_deref())?,
+        masters: request.masters.clone(),
+        exclude_masters: request.exclude_masters.clone(),
+        monte_carlo: request.monte_carlo.unwrap_or(false),
+        holding: None,
+        bear_case: request.bear_case.unwrap_or(false),
+        pre_mortem: request.pre_mortem.unwrap_or(false),
+        agentic: request.agentic.unwrap_or(false),
+        focus_areas: request.focus_areas.clone(),
+        note: request.note.clone(),
+        llm_options: ChatCompletionOptions::default(),
+    })
+}
+
+fn parse_date(date_str: Option<&str>) -> InvmstResult<Option<NaiveDate>> {
+    match date_str {
+        Some(date_str) => match utils::datetime::date_from_str(date_str) {
+            Some(date) => Ok(Some(date)),
+            None => Err(InvmstError::Invalid(
+                "INVALID_DATE",
+                format!("Can not parse '{date_str}' as date"),
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn to_proto_analysis(master: Master, master_analysis: MasterAnalysis) -> proto::MasterAnalysis {
+    proto::MasterAnalysis {
+        master: master.to_string(),
+        prospect: master_analysis.prospect.to_string(),
+        rating: master_analysis.rating,
+        explanation: master_analysis.explanation,
+        horizon: master_analysis.horizon.map(|horizon| horizon.to_string()),
+        target_price_low: master_analysis.target_price.as_ref().map(|t| t.low),
+        target_price_high: master_analysis.target_price.as_ref().map(|t| t.high),
+        risks: master_analysis.risks,
+        catalysts: master_analysis.catalysts,
+        action: master_analysis.action.map(|action| action.to_string()),
+        validation: master_analysis
+            .validation
+            .map(|validation| validation.to_string()),
+        details: master_analysis.details.map(|details| details.to_string()),
+        completeness: master_analysis.completeness,
+        reasoning: master_analysis.reasoning,
+        provider: master_analysis.provider,
+    }
+}
+
+fn to_status(err: InvmstError) -> Status {
+    match err {
+        InvmstError::Invalid(_, _) | InvmstError::Required(_, _) => {
+            Status::invalid_argument(err.to_string())
+        }
+        InvmstError::NotExists(_, _) => Status::not_found(err.to_string()),
+        _ => Status::internal(err.to_string()),
+    }
+}
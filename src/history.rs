@@ -0,0 +1,199 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{LazyLock, Mutex},
+};
+
+use chrono::{DateTime, Local};
+use log::warn;
+
+use crate::{
+    APP_DATA_DIR,
+    data::stock::StockSnapshot,
+    error::{InvmstError, InvmstResult},
+    evaluate::Evaluation,
+    ticker::Ticker,
+};
+
+pub mod ask;
+pub mod diff;
+pub mod matrix;
+
+pub async fn list(ticker: Option<&str>) -> InvmstResult<Vec<Evaluation>> {
+    let ticker = match ticker {
+        Some(ticker) => Some(Ticker::from_str(ticker)?.to_string()),
+        None => None,
+    };
+
+    let mut evaluations: Vec<Evaluation> = read_evaluations(&HISTORY_DIR)?
+        .into_iter()
+        .filter(|evaluation| ticker.as_ref().is_none_or(|t| *t == evaluation.ticker))
+        .collect();
+    evaluations.sort_by_key(|evaluation| std::cmp::Reverse(evaluation.created_at));
+
+    Ok(evaluations)
+}
+
+/// ID of a new evaluation, unique even if the ticker is evaluated more than once in a millisecond
+pub fn new_id(created_at: &DateTime<Local>, symbol: &str) -> String {
+    let stamp = created_at.format("%Y%m%d%H%M%S%3f").to_string();
+
+    // 只需记住同一毫秒内已分配的 ID，保存前的评估不会在目录中
+    let mut issued = ISSUED_IDS.lock().unwrap_or_else(|err| err.into_inner());
+    if issued.0 != stamp {
+        *issued = (stamp.clone(), vec![]);
+    }
+
+    let mut seq = 0;
+    loop {
+        let id = if seq == 0 {
+            format!("{stamp}-{symbol}")
+        } else {
+            format!("{stamp}-{symbol}_{seq}")
+        };
+        if !issued.1.contains(&id) && !HISTORY_DIR.join(format!("{id}.json")).exists() {
+            issued.1.push(id.clone());
+            return id;
+        }
+        seq += 1;
+    }
+}
+
+pub async fn load(id: &str) -> InvmstResult<Evaluation> {
+    let path = evaluation_path(id)?;
+    if !path.exists() {
+        return Err(InvmstError::NotExists(
+            "EVALUATION_NOT_EXISTS",
+            format!("Evaluation '{id}' not exists"),
+        ));
+    }
+
+    Ok(serde_json::from_slice(&fs::read(&path)?)?)
+}
+
+pub async fn save(evaluation: &Evaluation) -> InvmstResult<()> {
+    let path = evaluation_path(&evaluation.id)?;
+    fs::create_dir_all(&*HISTORY_DIR)?;
+    fs::write(&path, serde_json::to_vec_pretty(evaluation)?)?;
+
+    Ok(())
+}
+
+/// Data the masters analyzed in the evaluation, after anomalies were dropped
+pub async fn load_snapshot(id: &str) -> InvmstResult<StockSnapshot> {
+    let path = snapshot_path(id)?;
+    if !path.exists() {
+        return Err(InvmstError::NotExists(
+            "SNAPSHOT_NOT_EXISTS",
+            format!("Snapshot of evaluation '{id}' not exists"),
+        ));
+    }
+
+    StockSnapshot::from_json(&fs::read_to_string(&path)?)
+}
+
+pub async fn save_snapshot(id: &str, snapshot: &StockSnapshot) -> InvmstResult<()> {
+    let path = snapshot_path(id)?;
+    fs::create_dir_all(&*SNAPSHOTS_DIR)?;
+    fs::write(&path, snapshot.to_json()?)?;
+
+    Ok(())
+}
+
+/// Evaluations in the directory, files unable to be read are skipped rather than failing all
+fn read_evaluations(dir: &Path) -> InvmstResult<Vec<Evaluation>> {
+    let mut evaluations: Vec<Evaluation> = vec![];
+    if dir.exists() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match fs::read(&path)
+                    .map_err(InvmstError::from)
+                    .and_then(|bytes| Ok(serde_json::from_slice::<Evaluation>(&bytes)?))
+                {
+                    Ok(evaluation) => evaluations.push(evaluation),
+                    Err(err) => warn!("[History] Skipped '{}': {err}", path.display()),
+                }
+            }
+        }
+    }
+
+    Ok(evaluations)
+}
+
+pub static HISTORY_DIR: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("history"));
+static ISSUED_IDS: Mutex<(String, Vec<String>)> = Mutex::new((String::new(), vec![]));
+static SNAPSHOTS_DIR: LazyLock<PathBuf> = LazyLock::new(|| HISTORY_DIR.join("snapshots"));
+
+fn evaluation_path(id: &str) -> InvmstResult<PathBuf> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(InvmstError::Invalid(
+            "INVALID_EVALUATION_ID",
+            format!("Invalid evaluation ID '{id}'"),
+        ));
+    }
+
+    Ok(HISTORY_DIR.join(format!("{id}.json")))
+}
+
+fn snapshot_path(id: &str) -> InvmstResult<PathBuf> {
+    let file_name = evaluation_path(id)?
+        .file_name()
+        .map(|file_name| file_name.to_owned())
+        .unwrap_or_default();
+
+    Ok(SNAPSHOTS_DIR.join(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::master::Master;
+
+    #[test]
+    fn test_new_id() {
+        let created_at = Local::now();
+        let a = new_id(&created_at, "600900");
+        let b = new_id(&created_at, "600900");
+        let c = new_id(&created_at, "000001");
+        assert_ne!(a, b);
+        assert!(a.ends_with("-600900"));
+        assert!(b.starts_with(&a) && b.ends_with("_1"));
+        assert!(c.ends_with("-000001"));
+        assert!(evaluation_path(&b).is_ok());
+    }
+
+    #[test]
+    fn test_read_evaluations() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        let evaluation = Evaluation::with_ratings(
+            "20250102150000000-600900",
+            NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+            &[(Master::WarrenBuffett, 80)],
+        );
+        fs::write(
+            dir.join(format!("{}.json", evaluation.id)),
+            serde_json::to_vec_pretty(&evaluation).unwrap(),
+        )
+        .unwrap();
+        fs::write(dir.join("corrupt.json"), b"{").unwrap();
+
+        let evaluations = read_evaluations(dir).unwrap();
+        assert_eq!(evaluations.len(), 1);
+        assert_eq!(evaluations[0].id, evaluation.id);
+        assert_eq!(evaluations[0].created_at, evaluation.created_at);
+        assert_eq!(
+            evaluations[0].master_analyses[&Master::WarrenBuffett].rating,
+            80
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    error::InvmstResult,
+    evaluate::Evaluation,
+    llm,
+    llm::{ChatCompletionOptions, ChatCompletionStream, ChatMessage, Role},
+};
+
+/// Answer a follow-up question with the saved evaluation in context
+pub async fn ask(
+    evaluation: &Evaluation,
+    question: &str,
+    options: &ChatCompletionOptions,
+) -> InvmstResult<ChatCompletionStream> {
+    let data_json = json!({
+        "ticker": evaluation.ticker,
+        "date": evaluation.date,
+        "price_summary": evaluation.price_summary,
+        "master_analyses": evaluation.master_analyses.iter().map(|(master, analysis)| {
+            json!({
+                "master": master.to_string(),
+                "analysis": analysis,
+            })
+        }).collect::<Vec<_>>(),
+        "bear_case": evaluation.bear_case,
+        "failure_modes": evaluation.failure_modes,
+    });
+    debug!("[History Ask Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", options).await?;
+
+    let prompt = format!(
+        r#"
+下面是对一只股票的一次评估，包括价格摘要、各位投资大师的分析及其依据的评估项和数据：
+```
+{prompt_json}
+```
+
+请基于以上评估回答问题。注意以下几点：
+- 回答必须以评估中的数据和分析为依据，引用具体的数字和大师观点。
+- 如果评估中没有足够的信息回答问题，请直接说明，不要编造数据。
+
+问题：{question}
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位专业的投资分析师，负责解读投资大师们的评估结果。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    llm::chat_completion_stream(&messages, options).await
+}
@@ -0,0 +1,225 @@
+use std::str::FromStr;
+
+use log::debug;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    error::{InvmstError, InvmstResult},
+    evaluate::Evaluation,
+    financial::get_stock_fiscal_metricset,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    master::Master,
+    ticker::Ticker,
+    utils,
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EvaluationDiff {
+    pub ticker: String,
+    pub from: Evaluation,
+    pub to: Evaluation,
+    pub masters: Vec<MasterDiff>,
+    /// What changed in the fundamentals between the two dates, written by the LLM
+    pub narrative: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MasterDiff {
+    pub master: Master,
+    pub rating_from: Option<u64>,
+    pub rating_to: Option<u64>,
+    pub assessments_added: Vec<String>,
+    pub assessments_removed: Vec<String>,
+}
+
+impl MasterDiff {
+    pub fn rating_delta(&self) -> Option<i64> {
+        Some(self.rating_to? as i64 - self.rating_from? as i64)
+    }
+}
+
+/// Compare two evaluations of the same ticker, the earlier one is taken as the base
+pub fn compare(a: Evaluation, b: Evaluation) -> InvmstResult<EvaluationDiff> {
+    if a.ticker != b.ticker {
+        return Err(InvmstError::Invalid(
+            "EVALUATION_TICKER_MISMATCH",
+            format!(
+                "Evaluation '{}' is for '{}' but '{}' is for '{}'",
+                a.id, a.ticker, b.id, b.ticker
+            ),
+        ));
+    }
+
+    let (from, to) = if (a.date, a.created_at) <= (b.date, b.created_at) {
+        (a, b)
+    } else {
+        (b, a)
+    };
+
+    let mut masters: Vec<Master> = from
+        .master_analyses
+        .keys()
+        .chain(to.master_analyses.keys())
+        .copied()
+        .collect();
+    masters.sort_by_key(|master| master.to_string());
+    masters.dedup();
+
+    let master_diffs = masters
+        .into_iter()
+        .map(|master| {
+            let analysis_from = from.master_analyses.get(&master);
+            let analysis_to = to.master_analyses.get(&master);
+            let assessments_from = analysis_from.map(|a| &a.assessments[..]).unwrap_or(&[]);
+            let assessments_to = analysis_to.map(|a| &a.assessments[..]).unwrap_or(&[]);
+
+            MasterDiff {
+                master,
+                rating_from: analysis_from.map(|a| a.rating),
+                rating_to: analysis_to.map(|a| a.rating),
+                assessments_added: assessments_to
+                    .iter()
+                    .filter(|s| !assessments_from.contains(s))
+                    .cloned()
+                    .collect(),
+                assessments_removed: assessments_from
+                    .iter()
+                    .filter(|s| !assessments_to.contains(s))
+                    .cloned()
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(EvaluationDiff {
+        ticker: to.ticker.clone(),
+        from,
+        to,
+        masters: master_diffs,
+        narrative: None,
+    })
+}
+
+/// Let the LLM describe what changed in the fundamentals between the dates of the evaluations
+pub async fn narrate(diff: &EvaluationDiff) -> InvmstResult<String> {
+    let ticker = Ticker::from_str(&diff.ticker)?;
+
+    let (fiscal_quarter_from, metricset_from) = get_stock_fiscal_metricset(
+        &ticker,
+        Some(utils::datetime::prev_fiscal_quarter(Some(&diff.from.date))),
+    )
+    .await?;
+    let (fiscal_quarter_to, metricset_to) = get_stock_fiscal_metricset(
+        &ticker,
+        Some(utils::datetime::prev_fiscal_quarter(Some(&diff.to.date))),
+    )
+    .await?;
+
+    let explanations = |evaluation: &Evaluation| {
+        evaluation
+            .master_analyses
+            .iter()
+            .map(|(master, analysis)| {
+                json!({
+                    "master": master.to_string(),
+                    "prospect": analysis.prospect.to_string(),
+                    "rating": analysis.rating,
+                    "explanation": analysis.explanation,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let data_json = json!({
+        "before": {
+            "date": diff.from.date,
+            "fiscal_quarter": fiscal_quarter_from.to_string(),
+            "financial_summary": metricset_from.financial_summary,
+            "analyses": explanations(&diff.from),
+        },
+        "after": {
+            "date": diff.to.date,
+            "fiscal_quarter": fiscal_quarter_to.to_string(),
+            "financial_summary": metricset_to.financial_summary,
+            "analyses": explanations(&diff.to),
+        },
+        "changes": diff.masters,
+    });
+    debug!("[History Diff Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", &ChatCompletionOptions::default()).await?;
+
+    let prompt = format!(
+        r#"
+下面是同一只股票在两个日期的财务摘要与投资大师分析，以及评分和评估项的变化：
+```
+{prompt_json}
+```
+
+请用简洁的中文说明两个日期之间基本面发生了哪些变化，以及这些变化如何解释评分的变动。注意以下几点：
+- 财报期相同时说明没有新的财报，变化主要来自价格或估值。
+- 不超过 300 字，直接返回文本，不要使用 JSON 或代码块。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位严谨的证券分析师。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, &ChatCompletionOptions::default()).await?;
+    debug!("[History Diff LLM] {bot_message:?}");
+
+    Ok(bot_message.content.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn evaluation(id: &str, date: NaiveDate, rating: u64, assessments: &[&str]) -> Evaluation {
+        let mut evaluation = Evaluation::with_ratings(id, date, &[(Master::WarrenBuffett, rating)]);
+        if let Some(analysis) = evaluation.master_analyses.get_mut(&Master::WarrenBuffett) {
+            analysis.assessments = assessments.iter().map(|s| s.to_string()).collect();
+        }
+        evaluation
+    }
+
+    #[test]
+    fn test_compare() {
+        let later = evaluation(
+            "b",
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            70,
+            &["x", "z"],
+        );
+        let earlier = evaluation(
+            "a",
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            50,
+            &["x", "y"],
+        );
+
+        let diff = compare(later, earlier).unwrap();
+        assert_eq!(diff.from.id, "a");
+        assert_eq!(diff.masters.len(), 1);
+        assert_eq!(diff.masters[0].rating_delta(), Some(20));
+        assert_eq!(diff.masters[0].assessments_added, vec!["z"]);
+        assert_eq!(diff.masters[0].assessments_removed, vec!["y"]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{evaluate::Evaluation, master::Master};
+
+/// Ratings of the masters over the dates of past evaluations of a ticker
+#[derive(Clone, Debug, Serialize)]
+pub struct RatingMatrix {
+    pub ticker: String,
+    /// Sorted ascending, one column per date
+    pub dates: Vec<NaiveDate>,
+    pub rows: Vec<RatingRow>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RatingRow {
+    pub master: Master,
+    /// One cell per date, None if the master was not in the evaluation of the date
+    pub cells: Vec<Option<RatingCell>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RatingCell {
+    pub evaluation_id: String,
+    pub rating: u64,
+    /// Change from the previous rating of the master, None for the first one
+    pub delta: Option<i64>,
+}
+
+impl RatingCell {
+    /// Upgraded or downgraded, small changes are noise of the LLM
+    pub fn is_notable(&self) -> bool {
+        self.delta.is_some_and(|delta| delta.abs() >= NOTABLE_DELTA)
+    }
+}
+
+/// Matrix of the evaluations of the same ticker, the latest evaluation of a date is taken if the
+/// date is evaluated more than once, and only the latest dates are kept if limited
+pub fn build(ticker: &str, evaluations: &[Evaluation], dates_limit: Option<usize>) -> RatingMatrix {
+    let mut latest_of_dates: HashMap<NaiveDate, &Evaluation> = HashMap::new();
+    for evaluation in evaluations {
+        let latest = latest_of_dates.entry(evaluation.date).or_insert(evaluation);
+        if evaluation.created_at > latest.created_at {
+            *latest = evaluation;
+        }
+    }
+
+    let mut columns: Vec<&Evaluation> = latest_of_dates.into_values().collect();
+    columns.sort_by_key(|evaluation| evaluation.date);
+    // 先在全部日期上计算变化，再截取最近的日期，使首列也有变化
+    let skipped = dates_limit.map_or(0, |dates_limit| columns.len().saturating_sub(dates_limit));
+
+    let mut masters: Vec<Master> = columns[skipped..]
+        .iter()
+        .flat_map(|evaluation| evaluation.master_analyses.keys().copied())
+        .collect();
+    masters.sort_by_key(|master| master.to_string());
+    masters.dedup();
+
+    let rows = masters
+        .into_iter()
+        .map(|master| {
+            let mut rating_prev: Option<u64> = None;
+            let mut cells: Vec<Option<RatingCell>> = columns
+                .iter()
+                .map(|evaluation| {
+                    let rating = evaluation.master_analyses.get(&master)?.rating;
+                    let delta = rating_prev.map(|prev| rating as i64 - prev as i64);
+                    rating_prev = Some(rating);
+
+                    Some(RatingCell {
+                        evaluation_id: evaluation.id.clone(),
+                        rating,
+                        delta,
+                    })
+                })
+                .collect();
+            cells.drain(..skipped);
+
+            RatingRow { master, cells }
+        })
+        .collect();
+    columns.drain(..skipped);
+
+    RatingMatrix {
+        ticker: ticker.to_string(),
+        dates: columns.iter().map(|evaluation| evaluation.date).collect(),
+        rows,
+    }
+}
+
+static NOTABLE_DELTA: i64 = 5;
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn evaluation(id: &str, date: &str, ratings: &[(Master, u64)]) -> Evaluation {
+        Evaluation::with_ratings(
+            id,
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            ratings,
+        )
+    }
+
+    #[test]
+    fn test_build() {
+        let mut rerun = evaluation("c2", "2025-08-31", &[(Master::WarrenBuffett, 75)]);
+        rerun.created_at += Duration::hours(1);
+        let evaluations = vec![
+            evaluation(
+                "a",
+                "2025-04-30",
+                &[(Master::WarrenBuffett, 60), (Master::PeterLynch, 50)],
+            ),
+            evaluation("b", "2025-05-31", &[(Master::WarrenBuffett, 62)]),
+            evaluation("c1", "2025-08-31", &[(Master::WarrenBuffett, 80)]),
+            rerun,
+        ];
+
+        let matrix = build("SSE:600900", &evaluations, None);
+        assert_eq!(matrix.dates.len(), 3);
+        assert_eq!(matrix.rows.len(), 2);
+
+        let row = |master: Master| matrix.rows.iter().find(|row| row.master == master).unwrap();
+        let cells: Vec<&RatingCell> = row(Master::WarrenBuffett).cells.iter().flatten().collect();
+        assert_eq!(cells[0].delta, None);
+        assert!(!cells[1].is_notable());
+        assert_eq!(cells[2].evaluation_id, "c2");
+        assert_eq!(cells[2].delta, Some(13));
+        assert!(cells[2].is_notable());
+        assert!(row(Master::PeterLynch).cells[1].is_none());
+
+        let matrix = build("SSE:600900", &evaluations, Some(2));
+        assert_eq!(matrix.dates.len(), 2);
+        assert_eq!(matrix.rows.len(), 1);
+        assert_eq!(matrix.rows[0].cells[0].as_ref().unwrap().delta, Some(2));
+    }
+}
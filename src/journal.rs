@@ -0,0 +1,244 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::LazyLock};
+
+use chrono::{Local, NaiveDate};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    APP_DATA_DIR,
+    data::daily::DailyDataset,
+    error::{InvmstError, InvmstResult},
+    financial::{Prospect, get_stock_daily_valuations, stock::StockValuationFieldName},
+    history,
+    ticker::Ticker,
+};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, strum::Display, strum::EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum TradeAction {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub ticker: String,
+    pub action: TradeAction,
+    pub shares: f64,
+    pub price: f64,
+    pub date: NaiveDate,
+    pub evaluation_id: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct JournalReview {
+    pub entry: JournalEntry,
+    pub price_current: Option<f64>,
+    /// Return since the decision, positive when the price moved in favor of the action
+    pub return_rate: Option<f64>,
+    pub pe_at_entry: Option<f64>,
+    pub pe_current: Option<f64>,
+    pub thesis: Option<(Prospect, u64)>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JournalConfig {
+    entries: Vec<JournalEntry>,
+}
+
+pub async fn add_entry(
+    ticker: &str,
+    action: &str,
+    shares: f64,
+    price: Option<f64>,
+    date: Option<NaiveDate>,
+    evaluation_id: Option<&str>,
+) -> InvmstResult<JournalEntry> {
+    let ticker = Ticker::from_str(ticker)?;
+    let action = TradeAction::from_str(action)?;
+    let date = date.unwrap_or(Local::now().date_naive());
+
+    validate_positive("INVALID_SHARES", "Shares", shares)?;
+    if let Some(price) = price {
+        validate_positive("INVALID_PRICE", "Price", price)?;
+    }
+
+    if let Some(evaluation_id) = evaluation_id {
+        let evaluation = history::load(evaluation_id).await?;
+        if evaluation.ticker != ticker.to_string() {
+            return Err(InvmstError::Invalid(
+                "EVALUATION_TICKER_MISMATCH",
+                format!(
+                    "Evaluation '{evaluation_id}' is for '{}' rather than '{ticker}'",
+                    evaluation.ticker
+                ),
+            ));
+        }
+    }
+
+    let price = match price {
+        Some(price) => price,
+        None => get_stock_daily_valuations(&ticker)
+            .await?
+            .get_latest_value::<f64>(&date, StockValuationFieldName::Price)
+            .filter(|price| *price > 0.0)
+            .ok_or(InvmstError::NoData(
+                "NO_PRICE",
+                format!("No price of '{ticker}' on {date}, specify it explicitly"),
+            ))?,
+    };
+
+    let mut cfg: JournalConfig = confy::load_path(&*JOURNAL_CONFIG_PATH)?;
+
+    let entry = JournalEntry {
+        id: cfg.entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1,
+        ticker: ticker.to_string(),
+        action,
+        shares,
+        price,
+        date,
+        evaluation_id: evaluation_id.map(|s| s.to_string()),
+    };
+    cfg.entries.push(entry.clone());
+
+    confy::store_path(&*JOURNAL_CONFIG_PATH, &cfg)?;
+
+    Ok(entry)
+}
+
+pub async fn list_entries() -> InvmstResult<Vec<JournalEntry>> {
+    let cfg: JournalConfig = confy::load_path(&*JOURNAL_CONFIG_PATH)?;
+
+    Ok(cfg.entries)
+}
+
+pub async fn remove_entry(id: u64) -> InvmstResult<()> {
+    let mut cfg: JournalConfig = confy::load_path(&*JOURNAL_CONFIG_PATH)?;
+
+    let count = cfg.entries.len();
+    cfg.entries.retain(|entry| entry.id != id);
+    if cfg.entries.len() == count {
+        return Err(InvmstError::NotExists(
+            "JOURNAL_ENTRY_NOT_EXISTS",
+            format!("Journal entry '{id}' not exists"),
+        ));
+    }
+
+    confy::store_path(&*JOURNAL_CONFIG_PATH, &cfg)?;
+
+    Ok(())
+}
+
+pub async fn review() -> InvmstResult<Vec<JournalReview>> {
+    let entries = list_entries().await?;
+
+    let today = Local::now().date_naive();
+    let mut daily_valuations_cache: HashMap<String, Option<DailyDataset<StockValuationFieldName>>> =
+        HashMap::new();
+
+    let mut reviews: Vec<JournalReview> = vec![];
+    for entry in entries {
+        if !daily_valuations_cache.contains_key(&entry.ticker) {
+            // 单只股票取数失败时仍复盘其余记录，价格留空
+            let daily_valuations = match Ticker::from_str(&entry.ticker) {
+                Ok(ticker) => get_stock_daily_valuations(&ticker).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = &daily_valuations {
+                warn!(
+                    "[Journal] Unable to fetch valuations of {}: {err}",
+                    entry.ticker
+                );
+            }
+            daily_valuations_cache.insert(entry.ticker.clone(), daily_valuations.ok());
+        }
+        let daily_valuations = daily_valuations_cache[&entry.ticker].as_ref();
+        let valuation = |date: &NaiveDate, field_name: StockValuationFieldName| {
+            daily_valuations?.get_latest_value::<f64>(date, field_name)
+        };
+
+        let price_current = valuation(&today, StockValuationFieldName::Price);
+        let return_rate = price_current.map(|price_current| {
+            let change = (price_current - entry.price) / entry.price;
+            match entry.action {
+                TradeAction::Buy => change,
+                TradeAction::Sell => -change,
+            }
+        });
+
+        let thesis = match &entry.evaluation_id {
+            Some(evaluation_id) => history::load(evaluation_id)
+                .await
+                .ok()
+                .and_then(|evaluation| evaluation.rating_avg())
+                .map(|rating| (Prospect::from_rating(rating), rating)),
+            None => None,
+        };
+
+        reviews.push(JournalReview {
+            price_current,
+            return_rate,
+            pe_at_entry: valuation(&entry.date, StockValuationFieldName::PeTtm),
+            pe_current: valuation(&today, StockValuationFieldName::PeTtm),
+            thesis,
+            entry,
+        });
+    }
+
+    Ok(reviews)
+}
+
+fn validate_positive(code: &'static str, name: &str, value: f64) -> InvmstResult<()> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(InvmstError::Invalid(
+            code,
+            format!("{name} must be positive, got {value}"),
+        ))
+    }
+}
+
+static JOURNAL_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("journal.toml"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_positive() {
+        assert!(validate_positive("INVALID_SHARES", "Shares", 100.0).is_ok());
+        for value in [0.0, -1.0, f64::NAN] {
+            assert!(matches!(
+                validate_positive("INVALID_PRICE", "Price", value),
+                Err(InvmstError::Invalid("INVALID_PRICE", _))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_journal_round_trip() {
+        let cfg = JournalConfig {
+            entries: vec![JournalEntry {
+                id: 1,
+                ticker: "SSE:600900".to_string(),
+                action: TradeAction::Buy,
+                shares: 100.0,
+                price: 28.5,
+                date: NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+                evaluation_id: Some("20250102150000000-600900".to_string()),
+            }],
+        };
+
+        let loaded: JournalConfig = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        let entry = &loaded.entries[0];
+        assert_eq!(entry.ticker, "SSE:600900");
+        assert_eq!(entry.action, TradeAction::Buy);
+        assert_eq!(entry.price, 28.5);
+        assert_eq!(entry.date, cfg.entries[0].date);
+        assert_eq!(entry.evaluation_id, cfg.entries[0].evaluation_id);
+    }
+}
@@ -1,6 +1,10 @@
 //! # invmst lib
 
-use std::{collections::HashMap, path::PathBuf, sync::LazyLock};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{LazyLock, OnceLock},
+};
 
 use directories::ProjectDirs;
 use rayon::iter::*;
@@ -18,24 +22,82 @@ pub async fn init() {
         .init();
 }
 
-static APP_DATA_DIR: LazyLock<PathBuf> =
-    LazyLock::new(|| match ProjectDirs::from("", "", env!("CARGO_PKG_NAME")) {
+/// Override the directory of data and history, takes precedence over INVMST_DATA_DIR and the
+/// config, must be called before any data is accessed
+pub fn set_data_dir(data_dir: &Path) {
+    let _ = DATA_DIR_OVERRIDE.set(data_dir.to_path_buf());
+}
+
+/// Global config is kept in the data directory if it is overridden, so profiles are isolated
+static APP_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
+    if let Some(data_dir) = data_dir_override() {
+        return data_dir.join("config.toml");
+    }
+
+    match ProjectDirs::from("", "", env!("CARGO_PKG_NAME")) {
+        Some(proj_dirs) => proj_dirs.config_dir().join("config.toml"),
+        None => std::env::current_dir()
+            .expect("Unable to get current directory!")
+            .join("config.toml"),
+    }
+});
+
+static APP_DATA_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    if let Some(data_dir) = data_dir_override() {
+        return data_dir;
+    }
+
+    if let Some(data_dir) = config::load().ok().and_then(|cfg| cfg.data_dir) {
+        return data_dir;
+    }
+
+    match ProjectDirs::from("", "", env!("CARGO_PKG_NAME")) {
         Some(proj_dirs) => proj_dirs.data_dir().to_path_buf(),
         None => std::env::current_dir()
             .expect("Unable to get current directory!")
             .join("data"),
-    });
+    }
+});
+
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
 static CHANNEL_BUFFER_DEFAULT: usize = 64;
 static LLM_CHAT_TEMPERATURE_DEFAULT: f64 = 0.6;
 
+mod alert;
+mod config;
 mod data;
 mod ds;
 mod evaluate;
+mod export;
 mod financial;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod history;
+mod journal;
 mod llm;
 mod master;
+mod metrics;
+mod migrate;
+mod paper;
+mod portfolio;
+mod preset;
+mod quote;
+#[cfg(feature = "charts")]
+mod report;
+mod schedule;
+mod screen;
+mod server;
 mod ticker;
+mod watchlist;
+
+fn data_dir_override() -> Option<PathBuf> {
+    DATA_DIR_OVERRIDE.get().cloned().or_else(|| {
+        std::env::var_os("INVMST_DATA_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    })
+}
 
 impl VecOptions<'_> {
     pub fn get(&self, name: &str) -> Option<String> {
@@ -1,7 +1,11 @@
-use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::LazyLock};
+use std::{collections::HashMap, fs, path::PathBuf, str::FromStr, sync::LazyLock};
 
+use log::warn;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tokio::sync::mpsc::Receiver;
+use url::Url;
 
 use crate::{
     APP_DATA_DIR, LLM_CHAT_TEMPERATURE_DEFAULT,
@@ -9,7 +13,9 @@ use crate::{
     llm::provider::{ChatProvider, open_ai::OpenAiProvider},
 };
 
-#[derive(Debug, Default, Serialize, Deserialize, strum::Display, strum::EnumString)]
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, strum::Display, strum::EnumString,
+)]
 #[strum(ascii_case_insensitive)]
 pub enum Protocol {
     #[default]
@@ -18,22 +24,44 @@ pub enum Protocol {
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(flatten)]
+    provider: ProviderConfig,
+    /// Providers tried in order when the one above fails
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fallbacks: Vec<ProviderConfig>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProviderConfig {
     protocol: Protocol,
     base_url: String,
     api_key: String,
     model: String,
+    /// Context window of the model in tokens, a default is assumed if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context_window: Option<usize>,
 }
 
 #[derive(Debug)]
 pub enum ChatCompletionEvent {
     Content(String),
     ReasoningContent(String),
+    /// Sent when the answer is complete, the caller runs the tool and continues the chat with
+    /// the result
+    ToolCall(ToolCall),
     Error(InvmstError),
 }
 
+#[derive(Clone, Debug)]
 pub struct ChatCompletionOptions {
     pub enable_think: bool, // Some multi-mode-models can switch between think/nothink mode, such as qwen3
     pub temperature: f64,
+    /// Overrides the model of the config
+    pub model: Option<String>,
+    /// Overrides the context window of the config
+    pub context_window: Option<usize>,
+    /// Tools offered to the LLM, none by default
+    pub tools: Vec<ToolDefinition>,
 }
 
 pub struct ChatCompletionStream {
@@ -45,6 +73,26 @@ pub struct ChatMessage {
     pub role: Role,
     pub content: String,
     pub reasoning: Option<String>,
+    /// Tools the bot requested to call
+    pub tool_calls: Vec<ToolCall>,
+    /// Call answered by the message of a tool
+    pub tool_call_id: Option<String>,
+}
+
+/// Tool the LLM may call, the parameters are described by JSON schema
+#[derive(Clone, Debug)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// Call of a tool requested by the LLM, the arguments are a JSON object in string
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: String,
 }
 
 #[allow(dead_code)]
@@ -54,91 +102,314 @@ pub enum Role {
     Bot,
     User,
     System,
+    Tool,
 }
 
 pub async fn chat_completion(
     messages: &[ChatMessage],
     options: &ChatCompletionOptions,
 ) -> InvmstResult<ChatMessage> {
-    let cfg: Config = confy::load_path(&*CHAT_CONFIG_PATH)?;
-
-    let provider = match cfg.protocol {
-        Protocol::OpenAI => OpenAiProvider::new(&cfg.base_url, &cfg.api_key, &cfg.model),
-    };
+    Ok(chat_completion_with_provider(messages, options).await?.0)
+}
 
-    provider.chat_completion(messages, options).await
+/// Try the configured providers in order until one answers, returns the answer and the label of
+/// the provider that produced it
+pub async fn chat_completion_with_provider(
+    messages: &[ChatMessage],
+    options: &ChatCompletionOptions,
+) -> InvmstResult<(ChatMessage, String)> {
+    failover(chat_providers()?, options, |provider_cfg| async move {
+        match provider_cfg.protocol {
+            Protocol::OpenAI => {
+                provider_cfg
+                    .open_ai()
+                    .chat_completion(messages, options)
+                    .await
+            }
+        }
+    })
+    .await
 }
 
+/// Failover only happens before the stream starts, errors in the stream are passed through
 pub async fn chat_completion_stream(
     messages: &[ChatMessage],
     options: &ChatCompletionOptions,
 ) -> InvmstResult<ChatCompletionStream> {
-    let cfg: Config = confy::load_path(&*CHAT_CONFIG_PATH)?;
-
-    let provider = match cfg.protocol {
-        Protocol::OpenAI => OpenAiProvider::new(&cfg.base_url, &cfg.api_key, &cfg.model),
-    };
+    let (stream, _) = failover(chat_providers()?, options, |provider_cfg| async move {
+        match provider_cfg.protocol {
+            Protocol::OpenAI => {
+                provider_cfg
+                    .open_ai()
+                    .chat_completion_stream(messages, options)
+                    .await
+            }
+        }
+    })
+    .await?;
 
-    provider.chat_completion_stream(messages, options).await
+    Ok(stream)
 }
 
-pub async fn config_chat(protocol: &str, options: &HashMap<String, String>) -> InvmstResult<()> {
-    let mut cfg: Config = confy::load_path(&*CHAT_CONFIG_PATH).unwrap_or(Config::default());
+/// Set the primary provider, or append a fallback provider tried after the existing ones
+pub async fn config_chat(
+    protocol: &str,
+    options: &HashMap<String, String>,
+    fallback: bool,
+) -> InvmstResult<()> {
+    // 配置文件损坏时报错而不是覆盖，以免丢失其中的设置
+    let mut cfg = if CHAT_CONFIG_PATH.exists() {
+        parse_chat_config(&fs::read_to_string(&*CHAT_CONFIG_PATH)?)
+            .map_err(|msg| invalid_chat_config(&msg))?
+    } else if fallback {
+        return Err(InvmstError::Required(
+            "LLM_NOT_CONFIGURED",
+            "Configure the primary LLM provider before adding fallbacks".to_string(),
+        ));
+    } else {
+        Config::default()
+    };
+
+    let slot = if fallback { cfg.fallbacks.len() + 1 } else { 0 };
+    let mut provider_cfg = if fallback {
+        ProviderConfig::default()
+    } else {
+        cfg.provider.clone()
+    };
 
-    cfg.protocol = Protocol::from_str(protocol)?;
+    provider_cfg.protocol = Protocol::from_str(protocol)?;
 
     if let Some(base_url) = options.get("base_url") {
-        cfg.base_url = base_url.trim().to_string();
+        provider_cfg.base_url = base_url.trim().to_string();
     }
 
     if let Some(api_key) = options.get("api_key") {
-        cfg.api_key = api_key.trim().to_string();
+        provider_cfg.api_key = api_key.trim().to_string();
+
+        // 明文密钥优先存入系统密钥环，失败时仍写入配置文件
+        if !provider_cfg.api_key.is_empty() && !provider_cfg.api_key.contains("${") {
+            match keyring_set(slot, &provider_cfg.api_key).await {
+                Ok(()) => provider_cfg.api_key = KEYRING_MARKER.to_string(),
+                Err(err) => warn!("[Keyring] Keep api_key in config file: {err}"),
+            }
+        }
     }
 
     if let Some(model) = options.get("model") {
-        cfg.model = model.trim().to_string();
+        provider_cfg.model = model.trim().to_string();
+    }
+
+    if let Some(context_window) = options.get("context_window") {
+        let context_window = context_window.trim();
+        provider_cfg.context_window = if context_window.is_empty() {
+            None
+        } else {
+            Some(
+                context_window
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        InvmstError::Invalid(
+                            "INVALID_CONTEXT_WINDOW",
+                            format!("Invalid context_window '{context_window}'"),
+                        )
+                    })?,
+            )
+        };
     }
 
-    if cfg.base_url.is_empty() {
+    if provider_cfg.base_url.is_empty() {
         return Err(InvmstError::Required(
             "OPTION_REQUIRED",
             "Required option 'base_url' is missing".to_string(),
         ));
     }
 
-    if cfg.api_key.is_empty() {
+    if provider_cfg.api_key.is_empty() {
         return Err(InvmstError::Required(
             "OPTION_REQUIRED",
             "Required option 'api_key' is missing".to_string(),
         ));
     }
 
-    if cfg.model.is_empty() {
+    if provider_cfg.model.is_empty() {
         return Err(InvmstError::Required(
             "OPTION_REQUIRED",
             "Required option 'model' is missing".to_string(),
         ));
     }
 
+    if fallback {
+        cfg.fallbacks.push(provider_cfg);
+    } else {
+        cfg.provider = provider_cfg;
+    }
     confy::store_path(&*CHAT_CONFIG_PATH, &cfg)?;
 
     Ok(())
 }
 
+/// Effective chat config as key and value pairs, with the api_key masked and followed by its
+/// source if it is not written in the config file, keys of fallbacks are prefixed by their order
+pub async fn config_chat_show() -> InvmstResult<Vec<(String, String)>> {
+    let cfg = load_chat_config()?;
+
+    let mut items: Vec<(String, String)> = vec![];
+    for (slot, raw) in std::iter::once(cfg.provider)
+        .chain(cfg.fallbacks)
+        .enumerate()
+    {
+        let prefix = if slot == 0 {
+            "".to_string()
+        } else {
+            format!("fallbacks[{slot}].")
+        };
+
+        let raw_api_key = raw.api_key.clone();
+        let provider_cfg = resolve_provider(raw, slot).await?;
+
+        let mut api_key = mask_secret(&provider_cfg.api_key);
+        if raw_api_key != provider_cfg.api_key {
+            api_key.push_str(&format!(" ({raw_api_key})"));
+        }
+
+        items.push((
+            format!("{prefix}protocol"),
+            provider_cfg.protocol.to_string(),
+        ));
+        items.push((format!("{prefix}base_url"), provider_cfg.base_url));
+        items.push((format!("{prefix}api_key"), api_key));
+        items.push((format!("{prefix}model"), provider_cfg.model));
+        if let Some(context_window) = provider_cfg.context_window {
+            items.push((
+                format!("{prefix}context_window"),
+                context_window.to_string(),
+            ));
+        }
+    }
+
+    Ok(items)
+}
+
+/// Fit the data JSON into the context window, leaving room for the system prompt, the
+/// instructions around the data and the answer. The smallest window of the providers is used so
+/// that the prompt also fits the fallbacks, and what has been omitted is noted in the data. Short
+/// system prompts are covered by the reserve for instructions and can be left empty.
+pub async fn fit_json(
+    data_json: &Value,
+    system: &str,
+    options: &ChatCompletionOptions,
+) -> InvmstResult<Value> {
+    // 未配置上下文窗口时不做截断，由服务端决定能否接受
+    let context_window = match options.context_window {
+        Some(context_window) => context_window,
+        None => {
+            let cfg = load_chat_config()?;
+            let context_window = std::iter::once(&cfg.provider)
+                .chain(&cfg.fallbacks)
+                .filter_map(|provider_cfg| provider_cfg.context_window)
+                .min();
+            match context_window {
+                Some(context_window) => context_window,
+                None => return Ok(data_json.clone()),
+            }
+        }
+    };
+    let max_tokens = context_window
+        .saturating_sub(ANSWER_RESERVED_TOKENS + INSTRUCTION_RESERVED_TOKENS)
+        .saturating_sub(token::estimate(system));
+
+    let mut fitted = data_json.clone();
+    let notes = token::truncate_json(&mut fitted, max_tokens);
+    if !notes.is_empty() {
+        warn!("[LLM Context] Data truncated to fit {context_window} tokens: {notes:?}");
+        if let Value::Object(fields) = &mut fitted {
+            fields.insert("omitted_for_length".to_string(), notes.into());
+        }
+    }
+
+    Ok(fitted)
+}
+
 mod provider;
+mod token;
+
+/// Reserved in the context window for the answer and the instructions around the data
+static ANSWER_RESERVED_TOKENS: usize = 4096;
+static INSTRUCTION_RESERVED_TOKENS: usize = 2048;
 
-static CHAT_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("llm-chat.toml"));
+static ENV_REF_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap());
+/// Stored as the api_key in the config file if the key is kept in the OS keyring
+static KEYRING_MARKER: &str = "@keyring";
+
+static CHAT_CONFIG_FALLBACKS_KEY: &str = "fallbacks";
+static CHAT_CONFIG_KEYS: &[&str] = &["protocol", "base_url", "api_key", "model"];
+static CHAT_CONFIG_OPTIONAL_KEYS: &[&str] = &["context_window"];
+static LLM_OPTION_KEYS: &[&str] = &["context_window", "model", "temperature", "think"];
+
+pub static CHAT_CONFIG_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| APP_DATA_DIR.join("llm-chat.toml"));
 
 impl Default for ChatCompletionOptions {
     fn default() -> Self {
         Self {
             enable_think: false,
             temperature: LLM_CHAT_TEMPERATURE_DEFAULT,
+            model: None,
+            context_window: None,
+            tools: vec![],
         }
     }
 }
 
 impl ChatCompletionOptions {
+    /// Apply overrides like 'temperature' -> '0.2', 'model' -> 'gpt-4o', 'think' -> 'true' and
+    /// 'context_window' -> '128000'
+    pub fn with_overrides(mut self, overrides: &HashMap<String, String>) -> InvmstResult<Self> {
+        for (key, value) in overrides {
+            let invalid = || {
+                InvmstError::Invalid(
+                    "INVALID_LLM_OPTION",
+                    format!("Invalid value '{value}' of LLM option '{key}'"),
+                )
+            };
+
+            match key.to_lowercase().as_str() {
+                "temperature" => {
+                    self.temperature = value.parse().map_err(|_| invalid())?;
+                }
+                "model" => {
+                    self.model = (!value.is_empty()).then(|| value.to_string());
+                }
+                "context_window" => {
+                    self.context_window = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|context_window| *context_window > 0)
+                            .ok_or_else(invalid)?,
+                    );
+                }
+                "think" => {
+                    self.enable_think = value.parse().map_err(|_| invalid())?;
+                }
+                _ => {
+                    return Err(InvmstError::Invalid(
+                        "UNKNOWN_LLM_OPTION",
+                        format!(
+                            "Unknown LLM option '{key}', available options: {}",
+                            LLM_OPTION_KEYS.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
     pub fn with_enable_think(mut self, enable_think: bool) -> Self {
         self.enable_think = enable_think;
         self
@@ -148,6 +419,40 @@ impl ChatCompletionOptions {
         self.temperature = temperature;
         self
     }
+
+    pub fn with_tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.tools = tools;
+        self
+    }
+}
+
+impl ChatMessage {
+    /// Result of a tool call, to continue the chat after the bot message requesting it
+    pub fn tool_result(tool_call: &ToolCall, content: &str) -> Self {
+        Self {
+            role: Role::Tool,
+            content: content.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: Some(tool_call.id.clone()),
+        }
+    }
+}
+
+impl ProviderConfig {
+    /// Model and host of the provider, e.g. gpt-4o@api.openai.com
+    fn label(&self) -> String {
+        let host = Url::parse(&self.base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_string()))
+            .unwrap_or(self.base_url.clone());
+
+        format!("{}@{host}", self.model)
+    }
+
+    fn open_ai(&self) -> OpenAiProvider {
+        OpenAiProvider::new(&self.base_url, &self.api_key, &self.model)
+    }
 }
 
 impl ChatCompletionStream {
@@ -163,3 +468,448 @@ impl ChatCompletionStream {
         self.receiver.recv().await
     }
 }
+
+fn invalid_chat_config(msg: &str) -> InvmstError {
+    InvmstError::Invalid(
+        "INVALID_LLM_CONFIG",
+        format!("Invalid LLM config '{}': {msg}", CHAT_CONFIG_PATH.display()),
+    )
+}
+
+/// Replace each ${NAME} in the value of the key with the environment variable
+fn expand_env(key: &str, value: &str) -> InvmstResult<String> {
+    let mut expanded = value.to_string();
+    for captures in ENV_REF_REGEX.captures_iter(value) {
+        let name = &captures[1];
+        let env_value = std::env::var(name).map_err(|_| {
+            InvmstError::Required(
+                "ENV_VAR_NOT_SET",
+                format!("Environment variable '{name}' referenced by '{key}' is not set"),
+            )
+        })?;
+        expanded = expanded.replace(&captures[0], &env_value);
+    }
+
+    Ok(expanded)
+}
+
+/// Providers in the order to try, not resolved yet
+fn chat_providers() -> InvmstResult<Vec<ProviderConfig>> {
+    let cfg = load_chat_config()?;
+
+    Ok(std::iter::once(cfg.provider).chain(cfg.fallbacks).collect())
+}
+
+/// Try the providers in order until one succeeds, returns the result and the label of the
+/// provider. Each provider is resolved only when reached, a provider failing to resolve (e.g. an
+/// unset environment variable) is skipped like a failed request.
+async fn failover<T, F, Fut>(
+    providers: Vec<ProviderConfig>,
+    options: &ChatCompletionOptions,
+    attempt: F,
+) -> InvmstResult<(T, String)>
+where
+    F: Fn(ProviderConfig) -> Fut,
+    Fut: Future<Output = InvmstResult<T>>,
+{
+    let mut errors: Vec<String> = vec![];
+    for (slot, raw) in providers.into_iter().enumerate() {
+        let mut provider_cfg = match resolve_provider(raw.clone(), slot).await {
+            Ok(provider_cfg) => provider_cfg,
+            Err(err) => {
+                warn!("[LLM Failover] Skipped {}: {err}", raw.label());
+                errors.push(format!("{}: {err}", raw.label()));
+                continue;
+            }
+        };
+        // 模型覆盖只作用于主提供方
+        if let (0, Some(model)) = (slot, &options.model) {
+            provider_cfg.model = model.to_string();
+        }
+
+        let label = provider_cfg.label();
+        match attempt(provider_cfg).await {
+            Ok(result) => return Ok((result, label)),
+            Err(err) => {
+                warn!("[LLM Failover] {label}: {err}");
+                errors.push(format!("{label}: {err}"));
+            }
+        }
+    }
+
+    Err(InvmstError::LlmError(errors.join("; ")))
+}
+
+/// Slot 0 is the primary provider and others are the fallbacks in order
+#[cfg(feature = "keyring")]
+fn keyring_entry(slot: usize) -> keyring::Result<keyring::Entry> {
+    // 以配置文件路径区分不同数据目录下的密钥
+    let mut user = CHAT_CONFIG_PATH.display().to_string();
+    if slot > 0 {
+        user.push_str(&format!("#{slot}"));
+    }
+
+    keyring::Entry::new(env!("CARGO_PKG_NAME"), &user)
+}
+
+#[cfg(feature = "keyring")]
+async fn keyring_get(slot: usize) -> InvmstResult<String> {
+    Ok(tokio::task::spawn_blocking(move || keyring_entry(slot)?.get_password()).await??)
+}
+
+#[cfg(not(feature = "keyring"))]
+async fn keyring_get(_slot: usize) -> InvmstResult<String> {
+    Err(InvmstError::Invalid(
+        "KEYRING_UNSUPPORTED",
+        "The api_key is kept in the OS keyring, but keyring support is not built in".to_string(),
+    ))
+}
+
+#[cfg(feature = "keyring")]
+async fn keyring_set(slot: usize, secret: &str) -> InvmstResult<()> {
+    let secret = secret.to_string();
+    Ok(tokio::task::spawn_blocking(move || keyring_entry(slot)?.set_password(&secret)).await??)
+}
+
+#[cfg(not(feature = "keyring"))]
+async fn keyring_set(_slot: usize, _secret: &str) -> InvmstResult<()> {
+    Err(InvmstError::Invalid(
+        "KEYRING_UNSUPPORTED",
+        "Keyring support is not built in".to_string(),
+    ))
+}
+
+/// Load the chat config, reporting what is wrong instead of falling back to defaults. Without
+/// config file, the config is taken from INVMST_LLM_* environment variables, e.g. for CI.
+fn load_chat_config() -> InvmstResult<Config> {
+    if !CHAT_CONFIG_PATH.exists() {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        return match (
+            env("INVMST_LLM_BASE_URL"),
+            env("INVMST_LLM_API_KEY"),
+            env("INVMST_LLM_MODEL"),
+        ) {
+            (Some(base_url), Some(api_key), Some(model)) => Ok(Config {
+                provider: ProviderConfig {
+                    protocol: match env("INVMST_LLM_PROTOCOL") {
+                        Some(protocol) => Protocol::from_str(&protocol)?,
+                        None => Protocol::default(),
+                    },
+                    base_url,
+                    api_key,
+                    model,
+                    context_window: env("INVMST_LLM_CONTEXT_WINDOW")
+                        .and_then(|context_window| context_window.parse().ok()),
+                },
+                fallbacks: vec![],
+            }),
+            _ => Err(InvmstError::Required(
+                "LLM_NOT_CONFIGURED",
+                "LLM for 'chat' is not configured, run `invmst llm config` first or set INVMST_LLM_BASE_URL, INVMST_LLM_API_KEY and INVMST_LLM_MODEL".to_string(),
+            )),
+        };
+    }
+
+    parse_chat_config(&fs::read_to_string(&*CHAT_CONFIG_PATH)?)
+        .map_err(|msg| invalid_chat_config(&msg))
+}
+
+/// Keep only a few leading and trailing characters so the key can be recognized but not used
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 12 {
+        return "*".repeat(chars.len());
+    }
+
+    format!(
+        "{}****{}",
+        chars[..3].iter().collect::<String>(),
+        chars[chars.len() - 4..].iter().collect::<String>()
+    )
+}
+
+/// Expand environment variables and fetch the api_key from the OS keyring if it is kept there
+async fn resolve_provider(
+    mut provider_cfg: ProviderConfig,
+    slot: usize,
+) -> InvmstResult<ProviderConfig> {
+    provider_cfg.base_url = expand_env("base_url", &provider_cfg.base_url)?;
+    provider_cfg.api_key = if provider_cfg.api_key == KEYRING_MARKER {
+        keyring_get(slot).await?
+    } else {
+        expand_env("api_key", &provider_cfg.api_key)?
+    };
+    provider_cfg.model = expand_env("model", &provider_cfg.model)?;
+
+    Ok(provider_cfg)
+}
+
+/// Check unknown keys, types and required keys one by one for precise messages, since the
+/// errors of deserializing as a whole are vague
+fn parse_chat_config(content: &str) -> Result<Config, String> {
+    let table: toml::Table = content
+        .parse()
+        .map_err(|err: toml::de::Error| format!("not valid TOML, {}", err.message()))?;
+
+    let provider_table: toml::Table = table
+        .iter()
+        .filter(|(key, _)| *key != CHAT_CONFIG_FALLBACKS_KEY)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    validate_provider_table(&provider_table, "")?;
+
+    match table.get(CHAT_CONFIG_FALLBACKS_KEY) {
+        None => {}
+        Some(toml::Value::Array(fallbacks)) => {
+            for (index, fallback) in fallbacks.iter().enumerate() {
+                let prefix = format!("{CHAT_CONFIG_FALLBACKS_KEY}[{}].", index + 1);
+                match fallback {
+                    toml::Value::Table(fallback_table) => {
+                        validate_provider_table(fallback_table, &prefix)?;
+                    }
+                    value => {
+                        return Err(format!(
+                            "'{}' should be a table, found {}",
+                            prefix.trim_end_matches('.'),
+                            value.type_str()
+                        ));
+                    }
+                }
+            }
+        }
+        Some(value) => {
+            return Err(format!(
+                "key '{CHAT_CONFIG_FALLBACKS_KEY}' should be an array of tables, found {}",
+                value.type_str()
+            ));
+        }
+    }
+
+    toml::Value::Table(table)
+        .try_into()
+        .map_err(|err: toml::de::Error| err.message().to_string())
+}
+
+/// Keys of the table are reported with the prefix, e.g. 'fallbacks[1].model'
+fn validate_provider_table(table: &toml::Table, prefix: &str) -> Result<(), String> {
+    if let Some(key) = table.keys().find(|key| {
+        !CHAT_CONFIG_KEYS.contains(&key.as_str())
+            && !CHAT_CONFIG_OPTIONAL_KEYS.contains(&key.as_str())
+    }) {
+        return Err(format!(
+            "unknown key '{prefix}{key}', available keys: {}/{}/{CHAT_CONFIG_FALLBACKS_KEY}",
+            CHAT_CONFIG_KEYS.join("/"),
+            CHAT_CONFIG_OPTIONAL_KEYS.join("/")
+        ));
+    }
+
+    for key in CHAT_CONFIG_KEYS {
+        match table.get(*key) {
+            None => return Err(format!("required key '{prefix}{key}' is missing")),
+            Some(toml::Value::String(value)) if value.trim().is_empty() => {
+                return Err(format!("required key '{prefix}{key}' is empty"));
+            }
+            Some(toml::Value::String(_)) => {}
+            Some(value) => {
+                return Err(format!(
+                    "key '{prefix}{key}' should be a string, found {}",
+                    value.type_str()
+                ));
+            }
+        }
+    }
+
+    match table.get("context_window") {
+        None => {}
+        Some(toml::Value::Integer(n)) if *n > 0 => {}
+        Some(value) => {
+            return Err(format!(
+                "key '{prefix}context_window' should be a positive integer, found {value}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chat_config() {
+        let valid = r#"
+protocol = "OpenAI"
+base_url = "https://api.openai.com/v1"
+api_key = "sk-xxx"
+model = "gpt-4o"
+"#;
+        assert_eq!(parse_chat_config(valid).unwrap().provider.model, "gpt-4o");
+
+        let unknown = format!("{valid}modle = \"gpt-4o\"\n");
+        assert!(
+            parse_chat_config(&unknown)
+                .unwrap_err()
+                .contains("unknown key 'modle'")
+        );
+
+        let wrong_type = valid.replace(r#""gpt-4o""#, "4");
+        assert!(
+            parse_chat_config(&wrong_type)
+                .unwrap_err()
+                .contains("key 'model' should be a string, found integer")
+        );
+
+        let missing = valid.replace(r#"api_key = "sk-xxx""#, "");
+        assert!(
+            parse_chat_config(&missing)
+                .unwrap_err()
+                .contains("required key 'api_key' is missing")
+        );
+
+        let context_window = format!("{valid}context_window = 128000\n");
+        assert_eq!(
+            parse_chat_config(&context_window)
+                .unwrap()
+                .provider
+                .context_window,
+            Some(128000)
+        );
+        let zero_context_window = format!("{valid}context_window = 0\n");
+        assert!(
+            parse_chat_config(&zero_context_window)
+                .unwrap_err()
+                .contains("key 'context_window' should be a positive integer")
+        );
+    }
+
+    #[test]
+    fn test_parse_chat_config_fallbacks() {
+        let valid = r#"
+protocol = "OpenAI"
+base_url = "https://api.openai.com/v1"
+api_key = "sk-xxx"
+model = "gpt-4o"
+
+[[fallbacks]]
+protocol = "OpenAI"
+base_url = "https://api.deepseek.com/v1"
+api_key = "${DEEPSEEK_API_KEY}"
+model = "deepseek-chat"
+"#;
+        let cfg = parse_chat_config(valid).unwrap();
+        assert_eq!(cfg.fallbacks.len(), 1);
+        assert_eq!(cfg.fallbacks[0].label(), "deepseek-chat@api.deepseek.com");
+
+        let missing = valid.replace(r#"model = "deepseek-chat""#, "");
+        assert!(
+            parse_chat_config(&missing)
+                .unwrap_err()
+                .contains("required key 'fallbacks[1].model' is missing")
+        );
+
+        let serialized = toml::to_string(&cfg).unwrap();
+        assert_eq!(
+            parse_chat_config(&serialized).unwrap().fallbacks[0].model,
+            "deepseek-chat"
+        );
+    }
+
+    #[test]
+    fn test_expand_env() {
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(
+            expand_env("base_url", "${PATH}/v1").unwrap(),
+            format!("{path}/v1")
+        );
+        assert_eq!(expand_env("model", "gpt-4o").unwrap(), "gpt-4o");
+        assert!(expand_env("api_key", "${INVMST_TEST_NOT_SET}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        let provider = |model: &str, base_url: &str| ProviderConfig {
+            model: model.to_string(),
+            base_url: base_url.to_string(),
+            api_key: "sk-xxx".to_string(),
+            ..Default::default()
+        };
+        let providers = vec![
+            provider("primary", "https://a.example.com/v1"),
+            provider("broken", "${INVMST_TEST_NOT_SET}/v1"),
+            provider("fallback", "https://b.example.com/v1"),
+            provider("unused", "https://c.example.com/v1"),
+        ];
+        let options = ChatCompletionOptions {
+            model: Some("override".to_string()),
+            ..Default::default()
+        };
+
+        let tried: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(vec![]);
+        let (model, label) = failover(providers.clone(), &options, |provider_cfg| {
+            tried.lock().unwrap().push(provider_cfg.model.clone());
+            async move {
+                if provider_cfg.model == "fallback" {
+                    Ok(provider_cfg.model)
+                } else {
+                    Err(InvmstError::LlmError("unavailable".to_string()))
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(model, "fallback");
+        assert_eq!(label, "fallback@b.example.com");
+        assert_eq!(*tried.lock().unwrap(), vec!["override", "fallback"]);
+
+        let err = failover(providers, &options, |_| async {
+            Err::<(), _>(InvmstError::LlmError("unavailable".to_string()))
+        })
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("INVMST_TEST_NOT_SET"));
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let overrides = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+
+        let options = ChatCompletionOptions::default()
+            .with_overrides(&overrides(&[
+                ("temperature", "0.2"),
+                ("model", "gpt-4o"),
+                ("context_window", "128000"),
+                ("think", "true"),
+            ]))
+            .unwrap();
+        assert_eq!(options.temperature, 0.2);
+        assert_eq!(options.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(options.context_window, Some(128000));
+        assert!(options.enable_think);
+
+        for pairs in [
+            [("temprature", "0.2")],
+            [("temperature", "cold")],
+            [("context_window", "0")],
+            [("think", "maybe")],
+        ] {
+            assert!(
+                ChatCompletionOptions::default()
+                    .with_overrides(&overrides(&pairs))
+                    .is_err(),
+                "{pairs:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mask_secret() {
+        assert_eq!(mask_secret("sk-1234567890abcdef"), "sk-****cdef");
+        assert_eq!(mask_secret("short"), "*****");
+    }
+}
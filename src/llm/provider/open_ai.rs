@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use futures::StreamExt;
 use serde::Serialize;
 use serde_json::{Value, json};
@@ -6,7 +8,7 @@ use tokio::sync::mpsc;
 use crate::{
     CHANNEL_BUFFER_DEFAULT,
     error::*,
-    llm::{ChatCompletionEvent, ChatCompletionStream, provider::*},
+    llm::{ChatCompletionEvent, ChatCompletionStream, ToolCall, provider::*},
     utils::net::join_url,
 };
 
@@ -34,6 +36,7 @@ impl ChatProvider for OpenAiProvider {
     ) -> InvmstResult<ChatMessage> {
         let mut content = String::new();
         let mut reasoning_content = String::new();
+        let mut tool_calls: Vec<ToolCall> = vec![];
 
         let mut stream = self.chat_completion_stream(messages, options).await?;
         while let Some(event) = stream.next().await {
@@ -44,6 +47,9 @@ impl ChatProvider for OpenAiProvider {
                 ChatCompletionEvent::ReasoningContent(delta) => {
                     reasoning_content.push_str(&delta);
                 }
+                ChatCompletionEvent::ToolCall(tool_call) => {
+                    tool_calls.push(tool_call);
+                }
                 ChatCompletionEvent::Error(err) => {
                     return Err(err);
                 }
@@ -58,6 +64,8 @@ impl ChatProvider for OpenAiProvider {
             } else {
                 Some(reasoning_content)
             },
+            tool_calls,
+            tool_call_id: None,
         })
     }
 
@@ -85,12 +93,28 @@ impl ChatProvider for OpenAiProvider {
             }
         }
 
-        let request_body = json!({
+        let mut request_body = json!({
             "model": self.model,
             "messages": messages_json_value,
             "temperature": options.temperature,
             "stream": true,
         });
+        if !options.tools.is_empty() {
+            request_body["tools"] = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.parameters,
+                        }
+                    })
+                })
+                .collect();
+        }
 
         let client = reqwest::Client::builder().build()?;
 
@@ -106,6 +130,9 @@ impl ChatProvider for OpenAiProvider {
             let (sender, receiver) = mpsc::channel(CHANNEL_BUFFER_DEFAULT);
 
             tokio::spawn(async move {
+                // 工具调用的参数分散在多个片段中，按序号拼接完整后再发送
+                let mut tool_call_deltas: BTreeMap<u64, ToolCall> = BTreeMap::new();
+
                 let mut stream = response.bytes_stream();
                 while let Some(chunk) = stream.next().await {
                     match chunk {
@@ -120,7 +147,16 @@ impl ChatProvider for OpenAiProvider {
 
                                     match serde_json::from_str::<Value>(data) {
                                         Ok(json) => {
-                                            if let Some(delta_content) =
+                                            if let Some(deltas) =
+                                                json["choices"][0]["delta"]["tool_calls"].as_array()
+                                            {
+                                                for delta in deltas {
+                                                    merge_tool_call_delta(
+                                                        &mut tool_call_deltas,
+                                                        delta,
+                                                    );
+                                                }
+                                            } else if let Some(delta_content) =
                                                 json["choices"][0]["delta"]["content"].as_str()
                                             {
                                                 let _ = sender
@@ -153,6 +189,10 @@ impl ChatProvider for OpenAiProvider {
                         }
                     }
                 }
+
+                for tool_call in tool_call_deltas.into_values() {
+                    let _ = sender.send(ChatCompletionEvent::ToolCall(tool_call)).await;
+                }
             });
 
             Ok(ChatCompletionStream { receiver })
@@ -176,6 +216,9 @@ enum OpenAiRole {
 
     #[strum(serialize = "system")]
     System,
+
+    #[strum(serialize = "tool")]
+    Tool,
 }
 
 impl From<Role> for OpenAiRole {
@@ -184,6 +227,7 @@ impl From<Role> for OpenAiRole {
             Role::User => OpenAiRole::User,
             Role::Bot => OpenAiRole::Assistant,
             Role::System => OpenAiRole::System,
+            Role::Tool => OpenAiRole::Tool,
         }
     }
 }
@@ -198,8 +242,85 @@ impl Serialize for OpenAiRole {
 }
 
 fn chat_message_to_json_value(chat_message: &ChatMessage) -> Value {
-    json!({
+    let mut value = json!({
         "role": Into::<OpenAiRole>::into(chat_message.role).to_string(),
         "content": chat_message.content
-    })
+    });
+
+    if !chat_message.tool_calls.is_empty() {
+        value["tool_calls"] = chat_message
+            .tool_calls
+            .iter()
+            .map(|tool_call| {
+                json!({
+                    "id": tool_call.id,
+                    "type": "function",
+                    "function": {
+                        "name": tool_call.name,
+                        "arguments": tool_call.arguments,
+                    }
+                })
+            })
+            .collect();
+    }
+
+    if let Some(tool_call_id) = &chat_message.tool_call_id {
+        value["tool_call_id"] = tool_call_id.as_str().into();
+    }
+
+    value
+}
+
+/// The id and name come in the first delta of a call, and the arguments are split over the deltas
+fn merge_tool_call_delta(tool_calls: &mut BTreeMap<u64, ToolCall>, delta: &Value) {
+    let index = delta["index"].as_u64().unwrap_or_default();
+    let tool_call = tool_calls.entry(index).or_insert_with(|| ToolCall {
+        id: String::new(),
+        name: String::new(),
+        arguments: String::new(),
+    });
+
+    if let Some(id) = delta["id"].as_str() {
+        tool_call.id = id.to_string();
+    }
+    if let Some(name) = delta["function"]["name"].as_str() {
+        tool_call.name.push_str(name);
+    }
+    if let Some(arguments) = delta["function"]["arguments"].as_str() {
+        tool_call.arguments.push_str(arguments);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_tool_call_delta() {
+        let mut tool_calls: BTreeMap<u64, ToolCall> = BTreeMap::new();
+        for delta in [
+            json!({"index": 0, "id": "call_1", "function": {"name": "get_news", "arguments": ""}}),
+            json!({"index": 0, "function": {"arguments": "{\"ticker\":"}}),
+            json!({"index": 0, "function": {"arguments": "\"600900\"}"}}),
+            json!({"index": 1, "id": "call_2", "function": {"name": "get_peers", "arguments": "{}"}}),
+        ] {
+            merge_tool_call_delta(&mut tool_calls, &delta);
+        }
+
+        assert_eq!(
+            tool_calls.into_values().collect::<Vec<_>>(),
+            vec![
+                ToolCall {
+                    id: "call_1".to_string(),
+                    name: "get_news".to_string(),
+                    arguments: r#"{"ticker":"600900"}"#.to_string(),
+                },
+                ToolCall {
+                    id: "call_2".to_string(),
+                    name: "get_peers".to_string(),
+                    arguments: "{}".to_string(),
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,243 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use serde_json::Value;
+use tiktoken_rs::o200k_base_singleton;
+
+/// Estimate the tokens of the text with the o200k_base tokenizer, with a margin since other models
+/// tokenize differently, especially for Chinese
+pub fn estimate(text: &str) -> usize {
+    let tokens = o200k_base_singleton()
+        .encode_with_special_tokens(text)
+        .len();
+
+    tokens + tokens / ESTIMATE_MARGIN_DIVISOR
+}
+
+/// Shrink the JSON until it fits in the tokens, returns notes of what has been omitted. The largest
+/// arrays are halved first keeping the latest elements, with the omitted elements summarized in the
+/// notes, then the longest strings are cut, so scalars like scores are always kept.
+pub fn truncate_json(value: &mut Value, max_tokens: usize) -> Vec<String> {
+    let mut omitted_items: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    let mut cut_strings: BTreeMap<String, usize> = BTreeMap::new();
+
+    while estimate(&value.to_string()) > max_tokens {
+        if let Some(pointer) = largest_array(value) {
+            if let Some(Value::Array(items)) = value.pointer_mut(&pointer) {
+                let drop_count = items.len() / 2;
+                let dropped: Vec<Value> = if latest_at_end(items) {
+                    items.drain(..drop_count).collect()
+                } else {
+                    items.split_off(items.len() - drop_count)
+                };
+                omitted_items.entry(pointer).or_default().extend(dropped);
+            }
+        } else if let Some(pointer) = longest_string(value) {
+            if let Some(Value::String(s)) = value.pointer_mut(&pointer) {
+                let chars_count = s.chars().count();
+                *s = s.chars().take(chars_count / 2).collect::<String>() + "…";
+                *cut_strings.entry(pointer).or_default() += chars_count / 2;
+            }
+        } else {
+            break;
+        }
+    }
+
+    omitted_items
+        .into_iter()
+        .map(|(pointer, items)| match summarize(&items) {
+            Some(summary) => format!("{pointer}: {} items omitted, {summary}", items.len()),
+            None => format!("{pointer}: {} items omitted", items.len()),
+        })
+        .chain(
+            cut_strings
+                .into_iter()
+                .map(|(pointer, count)| format!("{pointer}: {count} characters omitted")),
+        )
+        .collect()
+}
+
+/// JSON pointer of the array taking the most characters, arrays of a single element are skipped
+fn largest_array(value: &Value) -> Option<String> {
+    let mut largest: Option<(String, usize)> = None;
+    walk(value, "", &mut |pointer, v| {
+        if let Value::Array(items) = v {
+            if items.len() > 1 {
+                let size = v.to_string().len();
+                if largest.as_ref().is_none_or(|(_, s)| size > *s) {
+                    largest = Some((pointer.to_string(), size));
+                }
+            }
+        }
+    });
+
+    largest.map(|(pointer, _)| pointer)
+}
+
+/// Summary of the omitted elements: the range of their dates and the range and mean of their
+/// numeric fields, e.g. "date 2024-01-02 ~ 2024-06-28, close 10.2 ~ 12.5 (mean 11.3)"
+fn summarize(items: &[Value]) -> Option<String> {
+    let mut parts: Vec<String> = vec![];
+
+    if let Some(key) = SORT_KEYS
+        .iter()
+        .find(|key| items.iter().any(|item| item[**key].is_string()))
+    {
+        let labels: Vec<&str> = items
+            .iter()
+            .filter_map(|item| item[*key].as_str())
+            .collect();
+        if let (Some(min), Some(max)) = (labels.iter().min(), labels.iter().max()) {
+            parts.push(format!("{key} {min} ~ {max}"));
+        }
+    }
+
+    let mut series: Vec<(String, Vec<f64>)> = vec![];
+    for item in items {
+        let fields: Vec<(&str, &Value)> = match item {
+            Value::Object(fields) => fields.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+            value => vec![("value", value)],
+        };
+        for (key, field) in fields {
+            let Some(number) = field.as_f64() else {
+                continue;
+            };
+            if SORT_KEYS.contains(&key) {
+                continue;
+            }
+            match series.iter_mut().find(|(k, _)| k == key) {
+                Some((_, numbers)) => numbers.push(number),
+                None => series.push((key.to_string(), vec![number])),
+            }
+        }
+    }
+    for (key, numbers) in series.iter().take(SUMMARY_FIELDS_LIMIT) {
+        let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+        parts.push(format!("{key} {min:.4} ~ {max:.4} (mean {mean:.4})"));
+    }
+
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// Whether the elements are sorted by date ascending, judged by the first and the last element
+fn latest_at_end(items: &[Value]) -> bool {
+    let (Some(first), Some(last)) = (items.first(), items.last()) else {
+        return false;
+    };
+
+    // 按日期或财季等时间字段判断，无法判断时保留开头的元素
+    for key in SORT_KEYS {
+        let ordering = match (&first[key], &last[key]) {
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => a
+                .as_f64()
+                .partial_cmp(&b.as_f64())
+                .unwrap_or(Ordering::Equal),
+            _ => continue,
+        };
+
+        return ordering == Ordering::Less;
+    }
+
+    false
+}
+
+/// JSON pointer of the longest string that can still be cut
+fn longest_string(value: &Value) -> Option<String> {
+    let mut longest: Option<(String, usize)> = None;
+    walk(value, "", &mut |pointer, v| {
+        if let Value::String(s) = v {
+            let chars_count = s.chars().count();
+            if chars_count > STRING_CUT_MIN_CHARS
+                && longest.as_ref().is_none_or(|(_, c)| chars_count > *c)
+            {
+                longest = Some((pointer.to_string(), chars_count));
+            }
+        }
+    });
+
+    longest.map(|(pointer, _)| pointer)
+}
+
+fn walk(value: &Value, pointer: &str, visit: &mut impl FnMut(&str, &Value)) {
+    visit(pointer, value);
+
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                walk(item, &format!("{pointer}/{i}"), visit);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let key = key.replace('~', "~0").replace('/', "~1");
+                walk(field, &format!("{pointer}/{key}"), visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+static ESTIMATE_MARGIN_DIVISOR: usize = 10;
+static SORT_KEYS: &[&str] = &["date", "fiscal_quarter", "quarter", "year"];
+static STRING_CUT_MIN_CHARS: usize = 200;
+/// Numeric fields summarized for omitted elements, the rest are only counted
+static SUMMARY_FIELDS_LIMIT: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_estimate() {
+        assert_eq!(estimate(""), 0);
+        assert!(estimate("贵州茅台的护城河来自品牌和稀缺性") > 5);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let items = vec![
+            json!({"date": "2024-01-03", "close": 12.0, "name": "a"}),
+            json!({"date": "2024-01-02", "close": 10.0, "name": "b"}),
+        ];
+        assert_eq!(
+            summarize(&items).unwrap(),
+            "date 2024-01-02 ~ 2024-01-03, close 10.0000 ~ 12.0000 (mean 11.0000)"
+        );
+        assert_eq!(
+            summarize(&[json!(1), json!(3)]).unwrap(),
+            "value 1.0000 ~ 3.0000 (mean 2.0000)"
+        );
+        assert!(summarize(&[json!("text")]).is_none());
+    }
+
+    #[test]
+    fn test_truncate_json() {
+        let mut value = json!({
+            "score": 0.8,
+            "quarters": (0..200).map(|i| json!({"fiscal_quarter": format!("{}Q4", 2200 - i), "revenue": i})).collect::<Vec<_>>(),
+            "prices": (0..400).map(|i| json!({"date": format!("2024-{:03}", i), "close": i})).collect::<Vec<_>>(),
+        });
+        let notes = truncate_json(&mut value, 2000);
+
+        assert!(estimate(&value.to_string()) <= 2000);
+        assert_eq!(value["score"], 0.8);
+        assert_eq!(value["quarters"][0]["fiscal_quarter"], "2200Q4");
+        assert_eq!(
+            value["prices"].as_array().unwrap().last().unwrap()["date"],
+            "2024-399"
+        );
+        assert_eq!(notes.len(), 2);
+        assert!(notes[0].starts_with("/prices: "));
+        assert!(notes[0].contains("date 2024-000 ~ "));
+        assert!(notes[0].contains("close 0.0000 ~ "));
+
+        let mut value = json!({"news": "利好".repeat(2000)});
+        let notes = truncate_json(&mut value, 500);
+        assert!(estimate(&value.to_string()) <= 500);
+        assert_eq!(notes.len(), 1);
+    }
+}
@@ -1,8 +1,10 @@
 //! # invmst CLI
 
-use std::env;
+use std::{env, path::PathBuf, process, str::FromStr};
 
 use clap::Parser;
+use colored::Colorize;
+use invmst::api;
 
 use crate::cli::Commands;
 
@@ -13,32 +15,157 @@ mod cli;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[arg(
+        long = "data-dir",
+        global = true,
+        help = "Directory of data and history, overrides INVMST_DATA_DIR and the config"
+    )]
+    data_dir: Option<PathBuf>,
+
+    #[arg(
+        long = "no-color",
+        global = true,
+        help = "Disable colors in output, also disabled if NO_COLOR is set"
+    )]
+    no_color: bool,
+
+    #[arg(
+        short = 'q',
+        long = "quiet",
+        global = true,
+        help = "Suppress spinners and colors and print only the final result, for scripts"
+    )]
+    quiet: bool,
 }
 
+/// Options whose value is a master or style group, never taken as the '@' shorthand
+const MASTER_OPTIONS: [&str; 4] = ["-m", "--master", "-x", "--exclude-master"];
+
 #[tokio::main]
 async fn main() {
     invmst::init().await;
 
-    let mut args: Vec<String> = vec![];
-    env::args().for_each(|arg| {
-        if let Some(master_stripped) = arg.strip_prefix('@') {
-            args.push("--master".to_string());
-            args.push(master_stripped.to_string());
-        } else {
-            args.push(arg);
+    let cli = Cli::parse_from(expand_master_shorthand(env::args()));
+    if let Some(data_dir) = &cli.data_dir {
+        invmst::set_data_dir(data_dir);
+    }
+    cli::set_quiet(cli.quiet);
+    // 遵循 https://no-color.org 约定，NO_COLOR 非空即禁用颜色
+    if cli.no_color || cli.quiet || env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+        cli::set_color(false);
+    }
+    // 旧版 Windows 控制台需开启虚拟终端才能解析颜色转义，中文由标准库以 UTF-16 写入控制台
+    #[cfg(windows)]
+    if colored::control::set_virtual_terminal(true).is_err() {
+        cli::set_color(false);
+    }
+
+    // 迁移失败不影响不涉及数据目录的命令，由用户自行处理
+    match api::data_migrate().await {
+        Ok(notes) => {
+            if !cli.quiet {
+                notes.iter().for_each(|note| eprintln!("[I] {note}"));
+            }
+        }
+        Err(err) => {
+            eprintln!("[W] {}", err.to_string().yellow());
         }
-    });
+    }
 
-    let cli = Cli::parse_from(args);
     match &cli.command {
+        Commands::Alerts(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Ask(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Config(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Data(cmd) => {
+            cmd.exec().await;
+        }
         Commands::Evaluate(cmd) => {
             cmd.exec().await;
         }
+        Commands::History(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Journal(cmd) => {
+            cmd.exec().await;
+        }
         Commands::Llm(cmd) => {
             cmd.exec().await;
         }
         Commands::Masters(cmd) => {
             cmd.exec().await;
         }
+        Commands::Matrix(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Metrics(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Paper(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Portfolio(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Presets(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Prompts(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Quote(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Schedule(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Scheduler(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Sectors(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Serve(cmd) => {
+            cmd.exec().await;
+        }
+        Commands::Watchlist(cmd) => {
+            cmd.exec().await;
+        }
+    }
+
+    let exit_code = cli::exit_code();
+    if exit_code != 0 {
+        process::exit(exit_code);
     }
 }
+
+/// Expand '@buffett' into '--master buffett', a style group like '@value' is kept as the value
+fn expand_master_shorthand(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut expanded: Vec<String> = vec![];
+    for arg in args {
+        let is_option_value = expanded
+            .last()
+            .is_some_and(|prev| MASTER_OPTIONS.contains(&prev.as_str()));
+
+        match arg.strip_prefix('@') {
+            Some(master_stripped) if !is_option_value => {
+                let master = if api::MasterStyle::from_str(master_stripped).is_ok() {
+                    arg.clone()
+                } else {
+                    master_stripped.to_string()
+                };
+                expanded.push("--master".to_string());
+                expanded.push(master);
+            }
+            _ => expanded.push(arg),
+        }
+    }
+
+    expanded
+}
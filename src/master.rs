@@ -1,10 +1,25 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
-use chrono::NaiveDate;
-use serde::Serialize;
-use serde_json::Value;
+use chrono::{Local, NaiveDate};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use strum::{EnumMessage, EnumProperty, IntoEnumIterator};
 
-use crate::{data::stock::*, error::*, financial::Prospect};
+use crate::{
+    config,
+    config::ConfigKey,
+    data::stock::*,
+    error::*,
+    evaluate::BACKWARD_DAYS_DEFAULT,
+    financial::{
+        Prospect, compliance, consensus, cyclicality::Cyclicality, dcf, suspension::Suspension,
+    },
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    utils,
+    utils::stats::{self, LinearFit},
+};
 
 #[derive(
     Clone,
@@ -13,34 +28,123 @@ use crate::{data::stock::*, error::*, financial::Prospect};
     Eq,
     Hash,
     PartialEq,
+    Serialize,
+    Deserialize,
     strum::Display,
     strum::EnumIter,
     strum::EnumMessage,
+    strum::EnumProperty,
     strum::EnumString,
 )]
 #[strum(ascii_case_insensitive)]
 pub enum Master {
     #[strum(
         message = "Benjamin Graham",
+        detailed_message = "Buy with a margin of safety below intrinsic value, favoring sound balance sheets, stable earnings and dividends",
         serialize = "graham",
         serialize = "benjamin-graham",
-        serialize = "格雷厄姆"
+        serialize = "格雷厄姆",
+        props(styles = "value")
     )]
     BenjaminGraham,
 
+    #[strum(
+        message = "David Einhorn",
+        detailed_message = "Short-seller skepticism, looking for accounting red flags, aggressive revenue recognition and insider selling",
+        serialize = "einhorn",
+        serialize = "david-einhorn",
+        serialize = "艾因霍恩",
+        props(styles = "value,contrarian")
+    )]
+    DavidEinhorn,
+
+    #[strum(
+        message = "Jesse Livermore",
+        detailed_message = "Trade with the trend, entering on breakouts at pivotal points and confirming with volume",
+        serialize = "livermore",
+        serialize = "jesse-livermore",
+        serialize = "利弗莫尔",
+        props(styles = "technical")
+    )]
+    JesseLivermore,
+
+    #[strum(
+        message = "John Bogle",
+        detailed_message = "Judge a stock by its excess return over the index against its extra risk, favoring the low-cost market",
+        serialize = "bogle",
+        serialize = "john-bogle",
+        serialize = "博格",
+        props(styles = "macro")
+    )]
+    JohnBogle,
+
+    #[strum(
+        message = "Li Lu",
+        detailed_message = "Invest in businesses with durable advantages run by honest owners, bought far below intrinsic value",
+        serialize = "lilu",
+        serialize = "li-lu",
+        serialize = "李录",
+        props(styles = "value,contrarian")
+    )]
+    LiLu,
+
     #[strum(
         message = "Peter Lynch",
+        detailed_message = "Buy growth at a reasonable price, categorizing companies and weighing PEG and earnings growth",
         serialize = "lynch",
         serialize = "peter-lynch",
-        serialize = "彼得林奇"
+        serialize = "彼得林奇",
+        props(styles = "growth")
     )]
     PeterLynch,
 
+    #[strum(
+        message = "Quant Factor",
+        detailed_message = "Score value, quality, momentum, low volatility and size factors systematically without judgment calls",
+        serialize = "quant",
+        serialize = "quant-factor",
+        serialize = "多因子",
+        props(styles = "quant")
+    )]
+    QuantFactor,
+
+    #[strum(
+        message = "Stanley Druckenmiller",
+        detailed_message = "Follow liquidity and sector rotation, concentrating on the strongest trends of the cycle",
+        serialize = "druckenmiller",
+        serialize = "stanley-druckenmiller",
+        serialize = "德鲁肯米勒",
+        props(styles = "macro,technical")
+    )]
+    StanleyDruckenmiller,
+
+    #[strum(
+        message = "Terry Smith",
+        detailed_message = "Buy good companies, do not overpay, do nothing, focusing on high returns on capital",
+        serialize = "smith",
+        serialize = "terry-smith",
+        serialize = "特里史密斯",
+        props(styles = "growth")
+    )]
+    TerrySmith,
+
+    #[strum(
+        message = "Walter Schloss",
+        detailed_message = "Buy statistically cheap stocks near book value with low debt, diversified widely",
+        serialize = "schloss",
+        serialize = "walter-schloss",
+        serialize = "施洛斯",
+        props(styles = "value,contrarian")
+    )]
+    WalterSchloss,
+
     #[strum(
         message = "Warren Buffett",
+        detailed_message = "Own wonderful businesses with durable moats and honest management at fair prices",
         serialize = "buffett",
         serialize = "warren-buffett",
-        serialize = "巴菲特"
+        serialize = "巴菲特",
+        props(styles = "value")
     )]
     WarrenBuffett,
 }
@@ -48,13 +152,18 @@ pub enum Master {
 impl Master {
     pub async fn analyze(
         &self,
-        stock_info: &StockInfo,
-        stock_events: &StockEvents,
-        stock_daily_data: &StockDailyData,
-        stock_fiscal_metricsets: &[StockFiscalMetricset],
+        snapshot: &StockSnapshot,
         options: &MasterAnalyzeOptions,
     ) -> InvmstResult<MasterAnalysis> {
-        match self {
+        let StockSnapshot {
+            info: stock_info,
+            events: stock_events,
+            daily_data: stock_daily_data,
+            fiscal_metricsets: stock_fiscal_metricsets,
+            ..
+        } = snapshot;
+
+        let mut analysis = match self {
             Master::BenjaminGraham => {
                 benjamin_graham::analyze(
                     stock_info,
@@ -65,6 +174,46 @@ impl Master {
                 )
                 .await
             }
+            Master::DavidEinhorn => {
+                david_einhorn::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
+            Master::JesseLivermore => {
+                jesse_livermore::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
+            Master::JohnBogle => {
+                john_bogle::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
+            Master::LiLu => {
+                li_lu::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
             Master::PeterLynch => {
                 peter_lynch::analyze(
                     stock_info,
@@ -75,6 +224,46 @@ impl Master {
                 )
                 .await
             }
+            Master::QuantFactor => {
+                quant_factor::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
+            Master::StanleyDruckenmiller => {
+                stanley_druckenmiller::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
+            Master::TerrySmith => {
+                terry_smith::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
+            Master::WalterSchloss => {
+                walter_schloss::analyze(
+                    stock_info,
+                    stock_events,
+                    stock_daily_data,
+                    stock_fiscal_metricsets,
+                    options,
+                )
+                .await
+            }
             Master::WarrenBuffett => {
                 warren_buffett::analyze(
                     stock_info,
@@ -85,21 +274,514 @@ impl Master {
                 )
                 .await
             }
+        }?;
+
+        // 财务历史短于请求的窗口时明确说明，以免各项分析看起来只是数据缺失
+        if let Some(short_history) = &options.short_history {
+            analysis.assessments.insert(0, short_history.assessment());
+        }
+
+        // 停牌时价格冻结、无法交易，流动性警示放在最前
+        if let Some(suspension) = &options.suspension {
+            analysis.assessments.splice(0..0, suspension.assessments());
+        }
+
+        // 上市时间短的公司缺少穿越周期的公开记录，依赖长期历史的大师限制评分
+        let date = options.date.unwrap_or(Local::now().date_naive());
+        if self.requires_long_history()
+            && let Some(years) = listing_years(stock_info, &date)
+            && years < LISTING_YEARS_MIN
+            && analysis.rating > RECENT_LISTING_RATING_CAP
+        {
+            analysis.rating = RECENT_LISTING_RATING_CAP;
+            analysis.prospect = Prospect::from_rating(analysis.rating);
+            analysis.assessments.push(format!(
+                "Listed only {years:.1} years ago, rating capped at {RECENT_LISTING_RATING_CAP} as the method needs a longer public record"
+            ));
+        }
+
+        Ok(analysis)
+    }
+
+    /// Masters selected by a key of a master, or by a style prefixed with '@' like '@value'
+    pub fn select(s: &str) -> Option<Vec<Master>> {
+        match s.strip_prefix('@') {
+            Some(style) => {
+                let style = MasterStyle::from_str(style).ok()?;
+                Some(
+                    Master::iter()
+                        .filter(|master| master.styles().contains(&style))
+                        .collect(),
+                )
+            }
+            None => Master::from_str(s).ok().map(|master| vec![master]),
+        }
+    }
+
+    /// Investing styles from the enum metadata
+    pub fn styles(&self) -> Vec<MasterStyle> {
+        self.get_str("styles")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|s| MasterStyle::from_str(s.trim()).ok())
+            .collect()
+    }
+
+    /// Philosophy, data inputs and current settings the analysis depends on
+    pub fn detail(&self) -> InvmstResult<MasterDetail> {
+        let mut settings: Vec<(String, String)> = vec![];
+
+        if self.uses_backward_days() {
+            let backward_days = config::load()?
+                .backward_days
+                .unwrap_or(BACKWARD_DAYS_DEFAULT);
+            settings.push((
+                ConfigKey::BackwardDays.to_string(),
+                backward_days.to_string(),
+            ));
+        }
+
+        if self.uses_compliance() {
+            settings.push((
+                ConfigKey::SkipCompliance.to_string(),
+                config::load()?.skip_compliance.to_string(),
+            ));
+        }
+
+        if *self == Master::WarrenBuffett {
+            let assumptions = dcf::load_assumptions();
+            settings.extend([
+                ("dcf.growth".to_string(), assumptions.growth.to_string()),
+                (
+                    "dcf.discount_rate".to_string(),
+                    assumptions.discount_rate.to_string(),
+                ),
+                (
+                    "dcf.terminal_growth".to_string(),
+                    assumptions.terminal_growth.to_string(),
+                ),
+                ("dcf.margin".to_string(), assumptions.margin.to_string()),
+                ("dcf.years".to_string(), assumptions.years.to_string()),
+            ]);
+        }
+
+        Ok(MasterDetail {
+            master: *self,
+            name: self.get_message().unwrap_or_default().to_string(),
+            keys: self
+                .get_serializations()
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            styles: self.styles(),
+            philosophy: self.get_detailed_message().unwrap_or_default().to_string(),
+            inputs: self
+                .inputs()
+                .iter()
+                .map(|input| input.to_string())
+                .collect(),
+            settings,
+        })
+    }
+
+    fn inputs(&self) -> &'static [MasterInput] {
+        match self {
+            Master::BenjaminGraham => &[
+                MasterInput::FiscalMetrics,
+                MasterInput::DailyValuations,
+                MasterInput::Dividends,
+                MasterInput::ShareChanges,
+            ],
+            Master::DavidEinhorn => &[
+                MasterInput::FiscalMetrics,
+                MasterInput::Announcements,
+                MasterInput::Compliance,
+            ],
+            Master::JesseLivermore => &[
+                MasterInput::DailyBars,
+                MasterInput::MarginBalances,
+                MasterInput::NorthboundHoldings,
+            ],
+            Master::JohnBogle => &[
+                MasterInput::DailyValuations,
+                MasterInput::Dividends,
+                MasterInput::IndexPrices,
+            ],
+            Master::LiLu => &[
+                MasterInput::FiscalMetrics,
+                MasterInput::DailyValuations,
+                MasterInput::Announcements,
+                MasterInput::Industry,
+                MasterInput::LargestHolder,
+                MasterInput::Compliance,
+            ],
+            Master::PeterLynch => &[
+                MasterInput::FiscalMetrics,
+                MasterInput::DailyValuations,
+                MasterInput::ShareChanges,
+                MasterInput::ManagementDiscussion,
+                MasterInput::AnalystConsensus,
+            ],
+            Master::QuantFactor => &[MasterInput::FiscalMetrics, MasterInput::DailyValuations],
+            Master::StanleyDruckenmiller => &[
+                MasterInput::DailyValuations,
+                MasterInput::Industry,
+                MasterInput::SectorOverviews,
+                MasterInput::MarginBalances,
+                MasterInput::NorthboundHoldings,
+                MasterInput::AnalystConsensus,
+            ],
+            Master::TerrySmith => &[MasterInput::FiscalMetrics, MasterInput::DailyValuations],
+            Master::WalterSchloss => &[
+                MasterInput::FiscalMetrics,
+                MasterInput::DailyValuations,
+                MasterInput::ListingDate,
+                MasterInput::LargestHolder,
+            ],
+            Master::WarrenBuffett => &[
+                MasterInput::FiscalMetrics,
+                MasterInput::DailyValuations,
+                MasterInput::Dividends,
+                MasterInput::Announcements,
+                MasterInput::ShareChanges,
+                MasterInput::Compliance,
+            ],
         }
     }
+
+    /// Masters given the sell-side expectations, others never see them so that strict value
+    /// masters judge the business on its own rather than anchoring on the market
+    pub fn uses_analyst_consensus(&self) -> bool {
+        self.inputs().contains(&MasterInput::AnalystConsensus)
+    }
+
+    /// Masters given the digest of announcements in their prompts
+    pub fn uses_announcements(&self) -> bool {
+        self.inputs().contains(&MasterInput::Announcements)
+    }
+
+    /// Masters given the compliance risk in their prompts, unless disabled in the config
+    pub fn uses_compliance(&self) -> bool {
+        self.inputs().contains(&MasterInput::Compliance)
+    }
+
+    /// Masters given the reading of the management discussion of the latest periodic report
+    pub fn uses_management_discussion(&self) -> bool {
+        self.inputs().contains(&MasterInput::ManagementDiscussion)
+    }
+
+    fn uses_backward_days(&self) -> bool {
+        !matches!(
+            self,
+            Master::DavidEinhorn | Master::JesseLivermore | Master::TerrySmith
+        )
+    }
+
+    /// Masters whose methods rely on a long public record of the company
+    fn requires_long_history(&self) -> bool {
+        matches!(
+            self,
+            Master::BenjaminGraham
+                | Master::LiLu
+                | Master::TerrySmith
+                | Master::WalterSchloss
+                | Master::WarrenBuffett
+        )
+    }
+}
+
+/// Years since listing as of the date
+fn listing_years(stock_info: &StockInfo, date: &NaiveDate) -> Option<f64> {
+    let listing_date = stock_info.listing_date?;
+
+    Some((*date - listing_date).num_days() as f64 / 365.25)
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum MasterStyle {
+    Value,
+    Growth,
+    Macro,
+    Technical,
+    Quant,
+    Contrarian,
+}
+
+/// Data a master may be given, also deciding what to fetch or prepare for an evaluation
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display)]
+pub enum MasterInput {
+    #[strum(serialize = "Fiscal metrics")]
+    FiscalMetrics,
+
+    #[strum(serialize = "Daily valuations")]
+    DailyValuations,
+
+    #[strum(serialize = "Dividends")]
+    Dividends,
+
+    #[strum(serialize = "Share changes")]
+    ShareChanges,
+
+    #[strum(serialize = "Announcements")]
+    Announcements,
+
+    #[strum(serialize = "Compliance")]
+    Compliance,
+
+    #[strum(serialize = "Daily bars")]
+    DailyBars,
+
+    #[strum(serialize = "Margin balances")]
+    MarginBalances,
+
+    #[strum(serialize = "Northbound holdings")]
+    NorthboundHoldings,
+
+    #[strum(serialize = "Index prices")]
+    IndexPrices,
+
+    #[strum(serialize = "Industry")]
+    Industry,
+
+    #[strum(serialize = "Largest holder")]
+    LargestHolder,
+
+    #[strum(serialize = "Management discussion")]
+    ManagementDiscussion,
+
+    #[strum(serialize = "Analyst consensus")]
+    AnalystConsensus,
+
+    #[strum(serialize = "Sector overviews")]
+    SectorOverviews,
+
+    #[strum(serialize = "Listing date")]
+    ListingDate,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MasterDetail {
+    pub master: Master,
+    pub name: String,
+    pub keys: Vec<String>,
+    pub styles: Vec<MasterStyle>,
+    pub philosophy: String,
+    /// Data the analysis is based on
+    pub inputs: Vec<String>,
+    /// Settings the analysis depends on, with their current values
+    pub settings: Vec<(String, String)>,
 }
 
 #[derive(Debug)]
 pub struct MasterAnalyzeOptions {
     pub backward_days: i64,
     pub date: Option<NaiveDate>,
+    /// Sample valuation assumptions to get a fair value distribution rather than a point
+    pub monte_carlo: bool,
+    /// Cyclicality of earnings, valuations switch to through-cycle earnings if cyclical
+    pub cyclicality: Option<Cyclicality>,
+    /// Existing position, masters return an explicit action if present
+    pub holding: Option<Holding>,
+    /// Fiscal history shorter than the backward days, e.g. for a recent IPO
+    pub short_history: Option<ShortHistory>,
+    /// Interrupted trading, valuations do not rely on a frozen price if suspended
+    pub suspension: Option<Suspension>,
+    /// Summary of the announcements for masters using them, made once per evaluation
+    pub announcement_digest: Option<digest::AnnouncementDigest>,
+    /// Summary of the recent disclosures given to every master, made once per evaluation
+    pub recent_developments: Option<digest::RecentDevelopments>,
+    /// Guidance, tone and strategy changes of the management for masters assessing it
+    pub management_discussion: Option<management::ManagementDiscussion>,
+    /// Consensus estimates and target prices for masters caring about expectations only
+    pub analyst_consensus: Option<consensus::AnalystConsensus>,
+    /// Penalties and ESG grades for masters focused on governance
+    pub compliance_risk: Option<compliance::ComplianceRisk>,
+    /// Tools offered to the masters in the agentic mode
+    pub toolbox: Option<Arc<agent::Toolbox>>,
+    /// Variables of the prompt templates
+    pub prompt_context: prompt::PromptContext,
+    /// LLM options of this evaluation
+    pub llm_options: ChatCompletionOptions,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShortHistory {
+    pub listing_date: Option<NaiveDate>,
+    /// Fiscal quarters available
+    pub quarters: usize,
+    /// Fiscal quarters the backward days cover
+    pub requested_quarters: usize,
+}
+
+impl ShortHistory {
+    pub fn assessment(&self) -> String {
+        let listed = match self.listing_date {
+            Some(listing_date) => format!("Listed on {listing_date}, "),
+            None => String::new(),
+        };
+
+        format!(
+            "{listed}fiscal history is shortened to {} of {} quarters, analyses needing a longer history are skipped",
+            self.quarters, self.requested_quarters
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    pub cost: f64,
+}
+
+impl FromStr for Holding {
+    type Err = InvmstError;
+
+    /// Parse from `key:value` pairs separated by commas, e.g. `cost:12.3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cost: Option<f64> = None;
+        for pair in s
+            .split(',')
+            .map(|pair| pair.trim())
+            .filter(|pair| !pair.is_empty())
+        {
+            match pair.split_once(':') {
+                Some(("cost", value)) => {
+                    cost = value.trim().parse::<f64>().ok().filter(|v| *v > 0.0);
+                    if cost.is_none() {
+                        return Err(InvmstError::Invalid(
+                            "INVALID_HOLDING",
+                            format!("Invalid cost '{value}'"),
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(InvmstError::Invalid(
+                        "INVALID_HOLDING",
+                        format!("Unknown holding field '{pair}'"),
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            cost: cost.ok_or(InvmstError::Required(
+                "HOLDING_COST_REQUIRED",
+                "Missing cost of holding".to_string(),
+            ))?,
+        })
+    }
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Action {
+    Add,
+    Hold,
+    Trim,
+    Exit,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Horizon {
+    /// Within 1 year
+    Short,
+    /// 1 to 3 years
+    Medium,
+    /// More than 3 years
+    Long,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TargetPrice {
+    pub low: f64,
+    pub high: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MasterAnalysis {
     pub prospect: Prospect,
     pub rating: u64,
     pub explanation: String,
+    #[serde(default)]
+    pub horizon: Option<Horizon>,
+    #[serde(default)]
+    pub target_price: Option<TargetPrice>,
+    #[serde(default)]
+    pub risks: Vec<String>,
+    #[serde(default)]
+    pub catalysts: Vec<String>,
+    /// Action on the existing position, only present if evaluated with a holding
+    #[serde(default)]
+    pub action: Option<Action>,
+    #[serde(default)]
+    pub validation: Option<ValidationStatus>,
+    /// Heuristic assessments the analysis was based on
+    #[serde(default)]
+    pub assessments: Vec<String>,
+    /// Master specific raw numbers, e.g. factor z-scores
+    #[serde(default)]
+    pub details: Option<Value>,
+    /// Average completeness of the data of the heuristic analyses
+    #[serde(default)]
+    pub completeness: Option<f64>,
+    /// Chain-of-thought of the LLM, if the model returns it
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    /// LLM provider that produced the answer, which may be a fallback one
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum ValidationStatus {
+    /// Consistent at the first attempt
+    Passed,
+    /// Contradictory at the first attempt but fixed after re-prompting
+    Revised,
+    /// Still contradictory after re-prompting
+    Inconsistent,
 }
 
 impl MasterAnalysis {
@@ -125,29 +807,409 @@ impl MasterAnalysis {
             ))?
             .to_string();
 
+        let horizon = json["horizon"]
+            .as_str()
+            .and_then(|s| Horizon::from_str(s).ok());
+
+        let target_price = match (
+            json["target_price"]["low"].as_f64(),
+            json["target_price"]["high"].as_f64(),
+        ) {
+            (Some(low), Some(high)) if low > 0.0 && low <= high => Some(TargetPrice { low, high }),
+            _ => None,
+        };
+
+        let items = |key: &str| -> Vec<String> {
+            json[key]
+                .as_array()
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .take(KEY_ITEMS_LIMIT)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let action = json["action"]
+            .as_str()
+            .and_then(|s| Action::from_str(s).ok());
+
         Ok(Self {
             prospect,
             rating,
             explanation,
+            horizon,
+            target_price,
+            risks: items("risks"),
+            catalysts: items("catalysts"),
+            action,
+            validation: None,
+            assessments: vec![],
+            details: None,
+            completeness: None,
+            reasoning: None,
+            provider: None,
+        })
+    }
+
+    /// Find contradictions between the prospect, the rating and the heuristic score in 0 to 1, and
+    /// the missing action if the user holds the stock
+    pub fn contradictions(&self, heuristic_score: Option<f64>, holding: bool) -> Vec<String> {
+        let mut contradictions: Vec<String> = vec![];
+
+        if holding && self.action.is_none() {
+            contradictions.push(
+                "已说明持有该股票，但缺少 action 字段或取值不在 Add | Hold | Trim | Exit 之中"
+                    .to_string(),
+            );
+        }
+
+        let rating_prospect = Prospect::from_rating(self.rating);
+        if matches!(
+            (&self.prospect, &rating_prospect),
+            (Prospect::Bullish, Prospect::Bearish) | (Prospect::Bearish, Prospect::Bullish)
+        ) {
+            contradictions.push(format!(
+                "prospect 为 {}，但 rating 为 {}，两者方向相反",
+                self.prospect, self.rating
+            ));
+        }
+
+        if let Some(heuristic_score) = heuristic_score {
+            let heuristic_rating = heuristic_score * 100.0;
+            if (self.rating as f64 - heuristic_rating).abs() > HEURISTIC_RATING_TOLERANCE {
+                contradictions.push(format!(
+                    "rating 为 {}，但各项分析的综合得分折合约为 {heuristic_rating:.0}，偏离过大",
+                    self.rating
+                ));
+            }
+        }
+
+        contradictions
+    }
+}
+
+/// Chat with the LLM for the analysis, re-prompting once if the result contradicts itself or the
+/// heuristic scores in the data, or lacks the action for a holding. With the toolbox, the LLM may
+/// request more data before answering.
+async fn chat_analysis(
+    mut messages: Vec<ChatMessage>,
+    data_json: &Value,
+    name: &str,
+    holding: bool,
+    toolbox: Option<&agent::Toolbox>,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<MasterAnalysis> {
+    let heuristic_score = heuristic_score(data_json);
+    let assessments = heuristic_assessments(data_json);
+    let completeness = heuristic_completeness(data_json);
+
+    let (bot_message, provider) = match toolbox {
+        Some(toolbox) => agent::chat(&mut messages, toolbox, name, llm_options).await?,
+        None => llm::chat_completion_with_provider(&messages, llm_options).await?,
+    };
+    debug!("[{name} LLM] {provider} {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let mut analysis = MasterAnalysis::from_json(&json_str)
+        .map_err(|err| InvmstError::LlmError(format!("Invalid answer of {name}: {err}")))?;
+    analysis.reasoning = bot_message.reasoning.clone();
+    analysis.provider = Some(provider);
+
+    let contradictions = analysis.contradictions(heuristic_score, holding);
+    if contradictions.is_empty() {
+        analysis.validation = Some(ValidationStatus::Passed);
+        analysis.assessments = assessments;
+        analysis.completeness = completeness;
+        return Ok(analysis);
+    }
+    warn!("[{name} Validation] {contradictions:?}");
+
+    messages.push(bot_message);
+    messages.push(ChatMessage {
+        role: Role::User,
+        content: format!(
+            r#"
+上面的结果存在以下矛盾：
+{}
+
+请重新审视数据，修正后按相同的 JSON 格式返回完整结果。如果坚持偏离综合得分，请在 explanation 中说明理由。
+"#,
+            contradictions
+                .iter()
+                .map(|c| format!("- {c}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+        reasoning: None,
+        tool_calls: vec![],
+        tool_call_id: None,
+    });
+
+    // 修正失败时保留首次的结果，标记为未通过校验
+    let (bot_message, provider, mut revised) =
+        match revise_analysis(&messages, name, llm_options).await {
+            Ok(revision) => revision,
+            Err(err) => {
+                warn!("[{name} Validation] Unable to revise: {err}");
+                analysis.validation = Some(ValidationStatus::Inconsistent);
+                analysis.assessments = assessments;
+                analysis.completeness = completeness;
+                return Ok(analysis);
+            }
+        };
+    revised.validation = Some(
+        if revised.contradictions(heuristic_score, holding).is_empty() {
+            ValidationStatus::Revised
+        } else {
+            ValidationStatus::Inconsistent
+        },
+    );
+    revised.assessments = assessments;
+    revised.completeness = completeness;
+    revised.provider = Some(provider);
+    // 保留两轮的思考过程，便于审查修正的理由
+    revised.reasoning = match (analysis.reasoning, bot_message.reasoning) {
+        (Some(first), Some(second)) => Some(format!("{first}\n\n{second}")),
+        (first, second) => second.or(first),
+    };
+
+    Ok(revised)
+}
+
+async fn revise_analysis(
+    messages: &[ChatMessage],
+    name: &str,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<(ChatMessage, String, MasterAnalysis)> {
+    let (bot_message, provider) = llm::chat_completion_with_provider(messages, llm_options).await?;
+    debug!("[{name} LLM] {provider} {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let revised = MasterAnalysis::from_json(&json_str)
+        .map_err(|err| InvmstError::LlmError(format!("Invalid answer of {name}: {err}")))?;
+
+    Ok((bot_message, provider, revised))
+}
+
+/// Assessments of the analysis drafts in the data
+fn heuristic_assessments(data_json: &Value) -> Vec<String> {
+    data_json
+        .as_object()
+        .map(|sections| {
+            sections
+                .values()
+                .filter_map(|v| v["assessments"].as_array())
+                .flatten()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
         })
+        .unwrap_or_default()
+}
+
+/// Average score of the analysis drafts in the data
+fn heuristic_score(data_json: &Value) -> Option<f64> {
+    let scores: Vec<f64> = data_json
+        .as_object()?
+        .values()
+        .filter_map(|v| v["score"].as_f64())
+        .collect();
+
+    if scores.is_empty() {
+        None
+    } else {
+        Some(scores.iter().sum::<f64>() / scores.len() as f64)
+    }
+}
+
+/// Average completeness of the analysis drafts in the data
+fn heuristic_completeness(data_json: &Value) -> Option<f64> {
+    let completenesses: Vec<f64> = data_json
+        .as_object()?
+        .values()
+        .filter_map(|v| v["completeness"].as_f64())
+        .collect();
+
+    stats::mean(&completenesses)
+}
+
+/// Linear trend of yearly ratio values sorted latest first, the slope is per year
+fn annual_trend(values: &[f64]) -> Option<LinearFit> {
+    if values.len() < TREND_YEARS_MIN {
+        return None;
+    }
+
+    let values: Vec<f64> = values.iter().rev().copied().collect();
+    stats::linear_trend(&values)
+}
+
+/// 1 if trending up, -1 if trending down, 0 if flat or the fit is too loose to tell
+fn trend_direction(fit: &LinearFit) -> i8 {
+    if fit.r_squared < TREND_R_SQUARED_MIN || fit.slope.abs() < TREND_SLOPE_FLAT {
+        0
+    } else if fit.slope > 0.0 {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Describe the trend of a ratio metric, e.g. "ROE trending down, slope -1.20pp/yr, R²=0.80"
+fn describe_trend(metric: &str, fit: &LinearFit) -> String {
+    let direction = match trend_direction(fit) {
+        1 => "trending up",
+        -1 => "trending down",
+        _ => "without a clear trend",
+    };
+
+    format!(
+        "{metric} {direction}, slope {:+.2}pp/yr, R²={:.2}",
+        fit.slope * 100.0,
+        fit.r_squared
+    )
+}
+
+/// Data the masters used plus their analyses, for the stages running after the masters finish
+fn evaluation_context_json(
+    stock_info: &StockInfo,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    master_analyses: &HashMap<Master, MasterAnalysis>,
+    compliance_risk: Option<&compliance::ComplianceRisk>,
+    date: &NaiveDate,
+) -> Value {
+    json!({
+        "basic_information": stock_info,
+        "price_position": stock_daily_data.get_price_range(date, 365),
+        "financial_summaries": stock_fiscal_metricsets
+            .iter()
+            .map(|(fiscal_quarter, stock_metricset)| json!({
+                "fiscal_quarter": fiscal_quarter.to_string(),
+                "financial_summary": stock_metricset.financial_summary,
+            }))
+            .collect::<Vec<_>>(),
+        "master_analyses": master_analyses
+            .iter()
+            .map(|(master, analysis)| json!({
+                "master": master.to_string(),
+                "prospect": analysis.prospect.to_string(),
+                "rating": analysis.rating,
+                "explanation": analysis.explanation,
+            }))
+            .collect::<Vec<_>>(),
+        "compliance_risk": compliance_risk,
+    })
+}
+
+/// Extra instructions appended to the JSON prompt if the user already holds the stock or the
+/// fiscal history is shorter than requested
+fn holding_prompt(options: &MasterAnalyzeOptions) -> String {
+    let mut notes: Vec<String> = vec![];
+    if let Some(holding) = &options.holding {
+        notes.push(format!(
+            "- 我已经持有该股票，持仓成本为 {:.2}，请结合当前价格与持仓成本给出操作建议。",
+            holding.cost
+        ));
+        notes.push("- 在返回的 JSON 中增加 \"action\" 字段，取值为 \"Add\" | \"Hold\" | \"Trim\" | \"Exit\"，分别表示加仓、持有、减仓、清仓。".to_string());
+    }
+    if let Some(short_history) = &options.short_history {
+        notes.push(format!(
+            "- 该公司上市时间较短，只有 {} 个季度的财务数据，因历史不足而跳过的分析不代表公司存在问题，请在 explanation 中说明分析窗口较短。",
+            short_history.quarters
+        ));
+    }
+
+    if notes.is_empty() {
+        String::new()
+    } else {
+        format!("\n补充说明：\n{}\n", notes.join("\n"))
     }
 }
 
+pub mod agent;
+pub mod bear_case;
 mod benjamin_graham;
+mod david_einhorn;
+pub mod digest;
+mod governance;
+mod jesse_livermore;
+mod john_bogle;
+mod li_lu;
+pub mod management;
+pub mod market;
 mod peter_lynch;
+pub mod pre_mortem;
+pub mod prompt;
+mod quant_factor;
+pub mod sectors;
+mod stanley_druckenmiller;
+mod terry_smith;
+mod walter_schloss;
 mod warren_buffett;
 
+static HEURISTIC_RATING_TOLERANCE: f64 = 50.0;
+
+static LISTING_YEARS_MIN: f64 = 3.0;
+
+/// Highest neutral rating, so a recently listed company is never bullish for long-history masters
+static RECENT_LISTING_RATING_CAP: u64 = 59;
+
+static KEY_ITEMS_LIMIT: usize = 3;
+
+/// Fits explaining less of the variance are treated as no clear trend
+static TREND_R_SQUARED_MIN: f64 = 0.5;
+
+/// Slopes of ratios per year below are treated as flat
+static TREND_SLOPE_FLAT: f64 = 0.005;
+
+static TREND_YEARS_MIN: usize = 3;
+
 static MASTER_ANALYSIS_JSON_PROMPT: &str = r#"
 返回的 JSON 格式示例如下：
 ```
 {
     "prospect": "Bullish" | "Bearish" | "Neutral",
     "rating": 评分为0到100之间的整数,
+    "horizon": "Short" | "Medium" | "Long",
+    "target_price": {"low": 目标价下限, "high": 目标价上限} | null,
+    "risks": ["最主要的风险", ...],
+    "catalysts": ["最主要的催化因素", ...],
     "explanation": "详细阐述分析过程"
 }
 ```
 
 注意以下几点：
+- horizon 表示观点适用的投资期限，Short 为 1 年以内，Medium 为 1 到 3 年，Long 为 3 年以上。
+- 数据不足以估计目标价时 target_price 返回 null。
+- risks 和 catalysts 按重要性排序，各列出最多 3 条，每条一句话。
+{% if industry %}
+- 投资对象属于{{ industry }}行业，结合行业特点进行分析。
+{% endif %}
+{% if backward_days %}
+- 数据覆盖最近 {{ backward_days }} 天。
+{% endif %}
+{% if focus_areas %}
+- 用户特别关注以下方面，请在 explanation 中逐一回应：
+{% for area in focus_areas %}
+  - {{ area }}
+{% endfor %}
+{% endif %}
+{% if data_anomalies %}
+- 以下数据相互矛盾，可能来自数据源的错误，引用时保持谨慎：
+{% for data_anomaly in data_anomalies %}
+  - {{ data_anomaly }}
+{% endfor %}
+{% endif %}
+{% if note %}
+- 用户补充了数据无法反映的背景：“{{ note }}”，请评估其对结论的影响，并在 explanation 中明确提及。
+{% endif %}
+{% if language %}
+- explanation、risks 和 catalysts 使用{{ language }}撰写，其余字段保持原样。
+{% endif %}
 - 不要包含任何额外的解释或文本，仅返回 JSON 数据。
 - 确保返回的结果是合法的 JSON 格式。
 "#;
@@ -155,33 +1217,186 @@ static MASTER_ANALYSIS_JSON_PROMPT: &str = r#"
 #[derive(Debug, Serialize)]
 struct AnalysisDraft {
     score: Option<f64>,
+    /// Fraction of the expected sections whose data was present, so a middling score with little
+    /// data is not mistaken for a middling company
+    completeness: Option<f64>,
     assessments: Vec<String>,
 }
 
+#[cfg(test)]
+impl MasterAnalysis {
+    /// Analysis with only the rating and its prospect, shared by tests
+    pub fn with_rating(rating: u64) -> Self {
+        MasterAnalysis {
+            prospect: Prospect::from_rating(rating),
+            rating,
+            explanation: String::new(),
+            horizon: None,
+            target_price: None,
+            risks: vec![],
+            catalysts: vec![],
+            action: None,
+            validation: None,
+            assessments: vec![],
+            details: None,
+            completeness: None,
+            reasoning: None,
+            provider: None,
+        }
+    }
+}
+
+/// Completeness of an analysis from the weights of the sections having data and all expected
+fn completeness(sum_weights: f64, expected_weights: f64) -> Option<f64> {
+    if expected_weights > 0.0 {
+        Some((sum_weights / expected_weights).min(1.0))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_completeness() {
+        assert_eq!(completeness(1.0, 2.0), Some(0.5));
+        assert_eq!(completeness(3.0, 2.0), Some(1.0));
+        assert_eq!(completeness(0.0, 0.0), None);
+
+        let data_json = json!({
+            "basic_information": {},
+            "analysis_a": {"score": 0.5, "completeness": 0.5, "assessments": []},
+            "analysis_b": {"score": 1.0, "completeness": 1.0, "assessments": []},
+        });
+        assert_eq!(heuristic_completeness(&data_json), Some(0.75));
+    }
+
+    #[test]
+    fn test_describe_trend() {
+        let fit = annual_trend(&[0.10, 0.12, 0.14]).unwrap();
+        assert_eq!(trend_direction(&fit), -1);
+        assert_eq!(
+            describe_trend("ROE", &fit),
+            "ROE trending down, slope -2.00pp/yr, R²=1.00"
+        );
+        assert!(annual_trend(&[0.1, 0.2]).is_none());
+    }
+
+    #[test]
+    fn test_master_styles() {
+        assert_eq!(Master::WarrenBuffett.styles(), vec![MasterStyle::Value]);
+        assert_eq!(
+            Master::StanleyDruckenmiller.styles(),
+            vec![MasterStyle::Macro, MasterStyle::Technical]
+        );
+        assert!(Master::iter().all(|master| !master.styles().is_empty()));
+    }
+
+    #[test]
+    fn test_master_select() {
+        assert_eq!(Master::select("buffett"), Some(vec![Master::WarrenBuffett]));
+        assert_eq!(Master::select("@quant"), Some(vec![Master::QuantFactor]));
+        assert!(
+            Master::select("@value")
+                .unwrap()
+                .contains(&Master::BenjaminGraham)
+        );
+        assert_eq!(Master::select("@unknown"), None);
+        assert_eq!(Master::select("unknown"), None);
+    }
+
+    #[test]
+    fn test_listing_years() {
+        let stock_info = StockInfo {
+            listing_date: NaiveDate::from_ymd_opt(2023, 1, 1),
+            ..Default::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert!((listing_years(&stock_info, &date).unwrap() - 2.0).abs() < 0.01);
+        assert!(listing_years(&StockInfo::default(), &date).is_none());
+        assert!(Master::WarrenBuffett.requires_long_history());
+        assert!(!Master::JesseLivermore.requires_long_history());
+    }
+
     #[test]
     fn test_master_analysis() {
         let json_str = r#"
 {
     "prospect": "bearish",
     "rating": 20,
+    "horizon": "long",
+    "target_price": {"low": 10.5, "high": 12},
+    "risks": ["a", "b", "c", "d"],
+    "catalysts": [],
+    "action": "trim",
     "explanation": "test"
 }
 "#;
 
-        match MasterAnalysis::from_json(&json_str) {
+        match MasterAnalysis::from_json(json_str) {
             Ok(analysis) => {
                 assert_eq!(analysis.prospect, Prospect::Bearish);
                 assert_eq!(analysis.rating, 20);
                 assert_eq!(analysis.explanation, "test");
+                assert_eq!(analysis.horizon, Some(Horizon::Long));
+                assert_eq!(
+                    analysis.target_price,
+                    Some(TargetPrice {
+                        low: 10.5,
+                        high: 12.0
+                    })
+                );
+                assert_eq!(analysis.risks, vec!["a", "b", "c"]);
+                assert!(analysis.catalysts.is_empty());
+                assert_eq!(analysis.action, Some(Action::Trim));
             }
             Err(err) => {
-                println!("{err:?}");
-                assert!(false);
+                panic!("{err:?}");
             }
         }
     }
+
+    #[test]
+    fn test_holding() {
+        assert_eq!(
+            Holding::from_str("cost:12.3").unwrap(),
+            Holding { cost: 12.3 }
+        );
+        assert!(Holding::from_str("cost:abc").is_err());
+        assert!(Holding::from_str("shares:100").is_err());
+        assert!(Holding::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_contradictions() {
+        let analysis = MasterAnalysis::from_json(
+            r#"{"prospect": "Bullish", "rating": 15, "explanation": "test"}"#,
+        )
+        .unwrap();
+        assert_eq!(analysis.contradictions(None, false).len(), 1);
+        assert_eq!(analysis.contradictions(Some(0.8), false).len(), 2);
+        assert_eq!(analysis.contradictions(None, true).len(), 2);
+
+        let analysis = MasterAnalysis::from_json(
+            r#"{"prospect": "Neutral", "rating": 55, "explanation": "test"}"#,
+        )
+        .unwrap();
+        assert!(analysis.contradictions(Some(0.4), false).is_empty());
+
+        let analysis = MasterAnalysis::from_json(
+            r#"{"prospect": "Neutral", "rating": 55, "explanation": "test", "action": "Hold"}"#,
+        )
+        .unwrap();
+        assert!(analysis.contradictions(Some(0.4), true).is_empty());
+
+        let data_json = serde_json::json!({
+            "basic_information": {},
+            "analysis_a": {"score": 0.2, "assessments": []},
+            "analysis_b": {"score": 0.6, "assessments": []},
+            "analysis_c": {"score": null, "assessments": []},
+        });
+        assert!((heuristic_score(&data_json).unwrap() - 0.4).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use log::{debug, info};
+use serde_json::{Value, json};
+
+use crate::{
+    data::stock::{StockFinancialSummary, StockSnapshot},
+    error::{InvmstError, InvmstResult},
+    financial::get_sector_overviews,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, ToolCall, ToolDefinition},
+    utils::datetime::Quarter,
+};
+
+/// Data the masters may request on demand in the agentic mode, shared by the masters of an
+/// evaluation
+#[derive(Clone, Debug)]
+pub struct Toolbox {
+    pub snapshot: Arc<StockSnapshot>,
+    pub date: NaiveDate,
+    /// Tool calls allowed for each master
+    pub budget: usize,
+}
+
+/// Chat with the tools of the toolbox offered, running the requested tools until the LLM answers
+/// or the budget runs out. The tool calls and results are appended to the messages, so a follow-up
+/// prompt keeps the data fetched. At most budget + 1 requests are sent, an LLM still calling tools
+/// after that is an error.
+pub async fn chat(
+    messages: &mut Vec<ChatMessage>,
+    toolbox: &Toolbox,
+    name: &str,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<(ChatMessage, String)> {
+    let mut calls_count = 0;
+    for _ in 0..=toolbox.budget {
+        // 预算用完后不再提供工具，要求模型基于已有数据作答
+        let options = if calls_count < toolbox.budget {
+            llm_options.clone().with_tools(Toolbox::definitions())
+        } else {
+            llm_options.clone()
+        };
+
+        let (bot_message, provider) =
+            llm::chat_completion_with_provider(messages, &options).await?;
+        if bot_message.tool_calls.is_empty() {
+            return Ok((bot_message, provider));
+        }
+
+        let tool_calls = bot_message.tool_calls.clone();
+        messages.push(bot_message);
+        for tool_call in &tool_calls {
+            calls_count += 1;
+            let result = if calls_count > toolbox.budget {
+                "Budget of tool calls exhausted, answer with the data at hand".to_string()
+            } else {
+                info!("[{name} Tool] {}({})", tool_call.name, tool_call.arguments);
+                toolbox.call(tool_call).await
+            };
+            debug!("[{name} Tool Result] {result}");
+
+            messages.push(ChatMessage::tool_result(tool_call, &result));
+        }
+    }
+
+    Err(InvmstError::LlmError(format!(
+        "{name} kept calling tools after the budget of {} calls",
+        toolbox.budget
+    )))
+}
+
+impl Toolbox {
+    pub fn definitions() -> Vec<ToolDefinition> {
+        let fields: Vec<String> = serde_json::to_value(StockFinancialSummary::default())
+            .ok()
+            .and_then(|value| {
+                value
+                    .as_object()
+                    .map(|fields| fields.keys().cloned().collect())
+            })
+            .unwrap_or_default();
+
+        vec![
+            ToolDefinition {
+                name: "get_metric_series".to_string(),
+                description: "Series of a financial metric by fiscal quarter, latest first"
+                    .to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "field": {"type": "string", "enum": fields},
+                        "quarters": {"type": "integer", "description": format!("Count of quarters, at most {SERIES_QUARTERS_MAX}")},
+                        "annual_only": {"type": "boolean", "description": "Only fiscal year ends (Q4)"},
+                    },
+                    "required": ["field"],
+                }),
+            },
+            ToolDefinition {
+                name: "get_news".to_string(),
+                description: "Announcements of the company about related parties, guarantees, auditor changes, shareholder sales and trading halts, latest first".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "limit": {"type": "integer", "description": format!("Count of announcements, at most {NEWS_LIMIT_MAX}")},
+                    },
+                }),
+            },
+            ToolDefinition {
+                name: "get_peers".to_string(),
+                description: "Returns and valuations of the industry of the company, with its rank among all industries".to_string(),
+                parameters: json!({"type": "object", "properties": {}}),
+            },
+        ]
+    }
+
+    /// Result of the tool call as JSON, errors are returned as text for the LLM to read
+    pub async fn call(&self, tool_call: &ToolCall) -> String {
+        let arguments: Value = serde_json::from_str(&tool_call.arguments).unwrap_or(json!({}));
+
+        let result = match tool_call.name.as_str() {
+            "get_metric_series" => self.get_metric_series(&arguments),
+            "get_news" => self.get_news(&arguments),
+            "get_peers" => self.get_peers().await,
+            name => Err(format!("Unknown tool '{name}'")),
+        };
+
+        match result {
+            Ok(value) => value.to_string(),
+            Err(msg) => format!("Error: {msg}"),
+        }
+    }
+
+    fn get_metric_series(&self, arguments: &Value) -> Result<Value, String> {
+        let field = arguments["field"]
+            .as_str()
+            .ok_or("Missing argument 'field'")?;
+        let quarters = arguments["quarters"]
+            .as_u64()
+            .map_or(SERIES_QUARTERS_MAX, |n| {
+                (n as usize).min(SERIES_QUARTERS_MAX)
+            });
+        let annual_only = arguments["annual_only"].as_bool().unwrap_or(false);
+
+        let mut series: Vec<Value> = vec![];
+        for (fiscal_quarter, stock_metricset) in self
+            .snapshot
+            .fiscal_metricsets
+            .iter()
+            .filter(|(fiscal_quarter, _)| !annual_only || fiscal_quarter.quarter == Quarter::Q4)
+            .take(quarters)
+        {
+            let summary = serde_json::to_value(&stock_metricset.financial_summary)
+                .map_err(|err| err.to_string())?;
+            let value = summary
+                .get(field)
+                .ok_or_else(|| format!("Unknown field '{field}'"))?;
+            series.push(json!({
+                "fiscal_quarter": fiscal_quarter.to_string(),
+                "value": value,
+            }));
+        }
+
+        Ok(json!(series))
+    }
+
+    fn get_news(&self, arguments: &Value) -> Result<Value, String> {
+        let limit = arguments["limit"]
+            .as_u64()
+            .map_or(NEWS_LIMIT_MAX, |n| (n as usize).min(NEWS_LIMIT_MAX));
+
+        let mut announcements: Vec<_> = self.snapshot.events.announcements.iter().collect();
+        announcements.sort_by_key(|announcement| std::cmp::Reverse(announcement.date));
+
+        Ok(json!(
+            announcements
+                .iter()
+                .take(limit)
+                .map(|announcement| json!({
+                    "date": announcement.date,
+                    "topic": announcement.topic.to_string(),
+                    "title": announcement.title,
+                }))
+                .collect::<Vec<_>>()
+        ))
+    }
+
+    async fn get_peers(&self) -> Result<Value, String> {
+        let industry = self
+            .snapshot
+            .info
+            .industry
+            .as_ref()
+            .ok_or("Unknown industry of the company")?;
+
+        let overviews = get_sector_overviews(&self.date, PEERS_BACKWARD_DAYS)
+            .await
+            .map_err(|err| err.to_string())?;
+        let rank = overviews
+            .iter()
+            .position(|o| industry.contains(&o.name) || o.name.contains(industry.as_str()))
+            .ok_or_else(|| format!("Industry '{industry}' not matched to a sector"))?;
+
+        Ok(json!({
+            "industry": overviews[rank],
+            "rank_by_return_3m": rank + 1,
+            "industries_count": overviews.len(),
+        }))
+    }
+}
+
+pub static TOOL_CALLS_BUDGET_DEFAULT: usize = 6;
+
+static NEWS_LIMIT_MAX: usize = 30;
+static PEERS_BACKWARD_DAYS: i64 = 365;
+static SERIES_QUARTERS_MAX: usize = 40;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{
+            daily::DailyDataset,
+            stock::{StockDailyData, StockEvents, StockInfo, StockMetricset},
+        },
+        utils::datetime::FiscalQuarter,
+    };
+
+    #[test]
+    fn test_get_metric_series() {
+        let toolbox = Toolbox {
+            snapshot: Arc::new(StockSnapshot {
+                ticker: "SSE:600519".to_string(),
+                info: StockInfo::default(),
+                events: StockEvents::default(),
+                daily_data: StockDailyData {
+                    daily_valuations: DailyDataset::from_json(&json!([]), "date", &[]).unwrap(),
+                    quote: None,
+                    daily_bars: vec![],
+                    margin_balances: None,
+                    northbound_holdings: None,
+                },
+                fiscal_metricsets: [
+                    (2024, Quarter::Q4),
+                    (2024, Quarter::Q3),
+                    (2023, Quarter::Q4),
+                ]
+                .into_iter()
+                .enumerate()
+                .map(|(i, (year, quarter))| {
+                    (
+                        FiscalQuarter { year, quarter },
+                        StockMetricset {
+                            financial_summary: StockFinancialSummary {
+                                gross_margin: Some(0.5 - i as f64 * 0.1),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+            }),
+            date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            budget: TOOL_CALLS_BUDGET_DEFAULT,
+        };
+
+        let series = toolbox
+            .get_metric_series(&json!({"field": "gross_margin", "annual_only": true}))
+            .unwrap();
+        assert_eq!(
+            series,
+            json!([
+                {"fiscal_quarter": "2024Q4", "value": 0.5},
+                {"fiscal_quarter": "2023Q4", "value": 0.3},
+            ])
+        );
+        assert!(
+            toolbox
+                .get_metric_series(&json!({"field": "gross_margin", "quarters": 1}))
+                .unwrap()
+                .as_array()
+                .is_some_and(|series| series.len() == 1)
+        );
+        assert!(
+            toolbox
+                .get_metric_series(&json!({"field": "nope"}))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::stock::{StockDailyData, StockFiscalMetricset, StockInfo},
+    error::InvmstResult,
+    financial::compliance::ComplianceRisk,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    master::{Master, MasterAnalysis, evaluation_context_json},
+    utils,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BearCase {
+    pub thesis: String,
+    /// Arguments supporting the thesis, the strongest first
+    pub arguments: Vec<String>,
+}
+
+/// Let the LLM construct the strongest bear argument from the data the masters used, countering
+/// the confirmation bias of mostly bullish masters
+pub async fn construct(
+    stock_info: &StockInfo,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    master_analyses: &HashMap<Master, MasterAnalysis>,
+    compliance_risk: Option<&ComplianceRisk>,
+    date: &NaiveDate,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<BearCase> {
+    let data_json = evaluation_context_json(
+        stock_info,
+        stock_daily_data,
+        stock_fiscal_metricsets,
+        master_analyses,
+        compliance_risk,
+        date,
+    );
+    debug!("[Bear Case Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", llm_options).await?;
+
+    let prompt = format!(
+        r#"
+下面是一只股票的基本信息、价格位置、财务摘要、合规风险，以及多位投资大师的分析：
+```
+{prompt_json}
+```
+
+请站在空头的立场，基于以上数据构建最有力的看空论证，结果以标准的 JSON 对象格式返回，示例如下：
+```
+{{
+    "thesis": "一句话概括看空的核心逻辑",
+    "arguments": ["最有力的论据", ...]
+}}
+```
+
+注意以下几点：
+- 论据必须来自上面的数据，引用具体的数字，不要编造数据。
+- 重点反驳投资大师分析中看多的理由，指出被忽视或低估的风险。
+- arguments 按说服力排序，列出最多 5 条，每条一句话。
+- 不要包含任何额外的解释或文本，仅返回 JSON 数据。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位经验丰富的做空研究员，擅长发现被市场忽视的风险。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, llm_options).await?;
+    debug!("[Bear Case LLM] {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let mut bear_case: BearCase = serde_json::from_str(&json_str)?;
+    bear_case.arguments.truncate(ARGUMENTS_LIMIT);
+
+    Ok(bear_case)
+}
+
+static ARGUMENTS_LIMIT: usize = 5;
@@ -1,18 +1,21 @@
+use chrono::Local;
 use log::debug;
 use serde_json::json;
 
 use crate::{
     data::stock::StockInfo,
     error::InvmstError,
-    financial::stock::StockValuationFieldName,
+    financial::{
+        fiscal_series::FiscalSeries, growth, growth::Growth, solvency,
+        stock::StockValuationFieldName,
+    },
     llm,
-    llm::{ChatCompletionOptions, ChatMessage, Role},
+    llm::{ChatMessage, Role},
     master::{
-        AnalysisDraft, InvmstResult, MASTER_ANALYSIS_JSON_PROMPT, MasterAnalysis,
-        MasterAnalyzeOptions, StockDailyData, StockEvents, StockFiscalMetricset,
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        prompt::Prompt,
     },
-    utils,
-    utils::datetime::Quarter,
 };
 
 pub async fn analyze(
@@ -29,46 +32,69 @@ pub async fn analyze(
         ));
     }
 
+    let eps_growth = growth::compute(
+        stock_fiscal_metricsets,
+        |summary| summary.earnings_per_share,
+        Some(&stock_events.share_changes),
+    );
+
     let data_json = json!({
         "basic_information": stock_info,
-        "analysis_valuation": analyze_valuation(stock_daily_data, stock_fiscal_metricsets).await?,
+        "eps_growth": eps_growth,
+        "analysis_valuation": analyze_valuation(stock_daily_data, stock_fiscal_metricsets, options).await?,
+        "price_position": stock_daily_data.get_price_range(
+            &options.date.unwrap_or(Local::now().date_naive()),
+            options.backward_days,
+        ),
         "analysis_financial_health": analyze_financial_health(stock_fiscal_metricsets).await?,
-        "analysis_earnings_stability": analyze_earnings_stability(stock_fiscal_metricsets).await?,
+        "analysis_earnings_stability": analyze_earnings_stability(stock_fiscal_metricsets, &eps_growth).await?,
         "analysis_dividend": analyze_dividend(stock_events, options.backward_days).await?,
+        "recent_developments": options.recent_developments,
     });
     debug!("[Benjamin Graham Data] {data_json}");
+    let llm_system = Prompt::BenjaminGraham.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
 
     let prompt = format!(
         r#"
 基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
 ```
-{data_json}
+{prompt_json}
 ```
 
-{MASTER_ANALYSIS_JSON_PROMPT}
-"#
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
     );
 
     let messages: Vec<ChatMessage> = vec![
         ChatMessage {
             role: Role::System,
-            content: LLM_SYSTEM.to_string(),
+            content: llm_system,
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         },
         ChatMessage {
             role: Role::User,
             content: prompt.to_string(),
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         },
     ];
 
-    let bot_message = llm::chat_completion(&messages, &ChatCompletionOptions::default()).await?;
-    debug!("[Benjamin Graham LLM] {bot_message:?}");
-
-    let json_str = utils::markdown::extract_code_block(&bot_message.content);
-    let analysis = MasterAnalysis::from_json(&json_str)?;
-
-    Ok(analysis)
+    chat_analysis(
+        messages,
+        &data_json,
+        "Benjamin Graham",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
 }
 
 async fn analyze_dividend(
@@ -79,6 +105,9 @@ async fn analyze_dividend(
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
 
+    // 回溯期太短时不评估分红，不算数据缺失
+    let expected_weights = if backward_days > 180 { 1.0 } else { 0.0 };
+
     // 股息分红
     {
         if backward_days > 180 {
@@ -107,15 +136,21 @@ async fn analyze_dividend(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, expected_weights),
+        assessments,
+    })
 }
 
 async fn analyze_earnings_stability(
     stock_fiscal_metricsets: &[StockFiscalMetricset],
+    eps_growth: &Growth,
 ) -> InvmstResult<AnalysisDraft> {
     if stock_fiscal_metricsets.len() < 8 {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec![
                 "Insufficient historical data for earning stability analysis".to_string(),
             ],
@@ -126,37 +161,38 @@ async fn analyze_earnings_stability(
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
 
-    // 每股收益持续增长
-    {
-        let mut growth_rates: Vec<f64> = vec![];
-        for i in 0..stock_fiscal_metricsets.len() - 1 {
-            if let (Some(earnings_per_share_current), Some(earnings_per_share_prev)) = (
-                stock_fiscal_metricsets[i]
-                    .1
-                    .financial_summary
-                    .earnings_per_share,
-                stock_fiscal_metricsets[i + 1]
-                    .1
-                    .financial_summary
-                    .earnings_per_share,
-            ) {
-                growth_rates.push(
-                    (earnings_per_share_current - earnings_per_share_prev)
-                        / earnings_per_share_prev,
-                );
-            }
+    // 每股收益的复合增长
+    if let (Some(cagr), Some(years)) = (eps_growth.cagr, eps_growth.cagr_years) {
+        let weight = 1.0;
+        if cagr > 0.0 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Earning per share compounded positively over {years} years (CAGR {cagr:.2})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Earning per share shrank over {years} years (CAGR {cagr:.2})"
+            ));
         }
+        sum_weights += weight;
+    }
 
+    // 每股收益持续同比增长
+    if let Some(positive_ratio) = eps_growth.positive_ratio() {
         let weight = 1.0;
-        let growth_rate_avg = growth_rates.iter().sum::<f64>() / growth_rates.len() as f64;
-        if growth_rate_avg > 0.0 {
+        if positive_ratio >= 0.75 {
             sum_scores += weight;
             assessments.push(format!(
-                "Average earning per share growth rate is positive value: {growth_rate_avg}"
+                "Earning per share grew year over year in most quarters ({positive_ratio:.2})"
+            ));
+        } else if positive_ratio >= 0.5 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Earning per share grew year over year in some quarters ({positive_ratio:.2})"
             ));
         } else {
             assessments.push(format!(
-                "Average earning per share growth rate is negative value: {growth_rate_avg}"
+                "Earning per share fell year over year in most quarters ({positive_ratio:.2})"
             ));
         }
         sum_weights += weight;
@@ -176,7 +212,11 @@ async fn analyze_earnings_stability(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    })
 }
 
 async fn analyze_financial_health(
@@ -185,6 +225,7 @@ async fn analyze_financial_health(
     if stock_fiscal_metricsets.is_empty() {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec![
                 "Insufficient historical data for financial health analysis".to_string(),
             ],
@@ -228,6 +269,55 @@ async fn analyze_financial_health(
         sum_weights += weight;
     }
 
+    let solvency = solvency::compute(&stock_metrics.financial_summary);
+
+    // 利息保障倍数
+    if let Some(interest_coverage) = solvency.interest_coverage {
+        let weight = 1.0;
+        if interest_coverage >= 5.0 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Strong interest coverage ({interest_coverage:.1}x)"
+            ));
+        } else if interest_coverage >= 3.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Acceptable interest coverage ({interest_coverage:.1}x)"
+            ));
+        } else {
+            assessments.push(format!("Weak interest coverage ({interest_coverage:.1}x)"));
+
+            // 账面负债率不高，但利息负担沉重
+            if stock_metrics
+                .financial_summary
+                .debt_to_assets
+                .is_some_and(|v| v <= 0.5)
+            {
+                assessments.push(
+                    "Weak interest coverage despite acceptable headline debt ratio".to_string(),
+                );
+            }
+        }
+        sum_weights += weight;
+    }
+
+    // 短期债务再融资压力
+    if let (Some(short_term_debt_ratio), Some(cash_to_short_term_debt)) = (
+        solvency.short_term_debt_ratio,
+        solvency.cash_to_short_term_debt,
+    ) {
+        let weight = 1.0;
+        if cash_to_short_term_debt >= 1.0 {
+            sum_scores += weight;
+            assessments.push("Cash covers debt due within one year".to_string());
+        } else {
+            assessments.push(format!(
+                "Needs refinancing, cash covers {cash_to_short_term_debt:.2} of debt due within one year ({short_term_debt_ratio:.2} of total debt)"
+            ));
+        }
+        sum_weights += weight;
+    }
+
     let score = if sum_weights > 0.0 {
         Some(sum_scores / sum_weights)
     } else {
@@ -242,16 +332,22 @@ async fn analyze_financial_health(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 4.0),
+        assessments,
+    })
 }
 
 async fn analyze_valuation(
     stock_daily_data: &StockDailyData,
     stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
 ) -> InvmstResult<AnalysisDraft> {
     if stock_fiscal_metricsets.is_empty() {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec!["Insufficient historical data for valuation analysis".to_string()],
         });
     }
@@ -261,64 +357,75 @@ async fn analyze_valuation(
     let mut assessments: Vec<String> = vec![];
 
     let latest_stock_fiscal_metricsets = stock_fiscal_metricsets.first().unwrap();
-    let (fiscal_quater, stock_metrics) = latest_stock_fiscal_metricsets;
-
-    let fiscal_date_str = format!(
-        "{}{}",
-        fiscal_quater.year,
-        match fiscal_quater.quarter {
-            Quarter::Q1 => "0331",
-            Quarter::Q2 => "0630",
-            Quarter::Q3 => "0930",
-            Quarter::Q4 => "1231",
+    let (fiscal_quarter, stock_metrics) = latest_stock_fiscal_metricsets;
+
+    // 以评估日的价格对最新的基本面估值
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let price = stock_daily_data.get_valuation(&date, &StockValuationFieldName::Price);
+    let market_cap = stock_daily_data.get_valuation(&date, &StockValuationFieldName::MarketCap);
+
+    // 如果净流动资产高于市值，这可能表明公司被低估，存在安全边际；缺少完整报表时以净资产近似
+    let net_current_assets = stock_metrics
+        .statements
+        .net_current_asset_value()
+        .or(stock_metrics.financial_summary.net_assets);
+    if let (Some(net_current_assets), Some(market_cap)) = (net_current_assets, market_cap) {
+        let weight = 1.0;
+        if net_current_assets > market_cap * 1.3 {
+            sum_scores += weight;
+            assessments.push("Undervalued price".to_string());
+        } else if net_current_assets > market_cap {
+            sum_scores += weight / 2.0;
+            assessments.push("Acceptable price".to_string());
+        } else {
+            assessments.push("Overvalued price".to_string());
         }
-    );
-    if let Some(date) = utils::datetime::date_from_str(&fiscal_date_str) {
-        let price = stock_daily_data
-            .daily_valuations
-            .get_latest_value::<f64>(&date, &StockValuationFieldName::Price.to_string());
-        let market_cap = stock_daily_data
-            .daily_valuations
-            .get_latest_value::<f64>(&date, &StockValuationFieldName::MarketCap.to_string());
-
-        // 如果净流动资产高于市值，这可能表明公司被低估，存在安全边际
-        if let (Some(net_assets), Some(market_cap)) =
-            (stock_metrics.financial_summary.net_assets, market_cap)
-        {
-            let weight = 1.0;
-            if net_assets > market_cap * 1.3 {
-                sum_scores += weight;
-                assessments.push("Undervalued price".to_string());
-            } else if net_assets > market_cap {
-                sum_scores += weight / 2.0;
-                assessments.push("Acceptable price".to_string());
-            } else {
-                assessments.push("Overvalued price".to_string());
-            }
-            sum_weights += weight;
+        sum_weights += weight;
+    }
+
+    // 季报的每股收益是年初至今的累计值，用滚动四个季度的值估值，周期性企业按整个周期的平均盈利估值
+    let mut earnings_per_share = FiscalSeries::new(stock_fiscal_metricsets)
+        .ttm(fiscal_quarter, |summary| summary.earnings_per_share);
+    if let Some(cyclicality) = &options.cyclicality {
+        if let Some(assessment) = cyclicality.assessment() {
+            earnings_per_share = cyclicality.normalized_eps;
+            assessments.push(assessment);
         }
+    }
 
-        // 格雷厄姆数字（合理股价）= sqrt( 22.5 × 每股收益 × 每股账面价值 )
-        if let (Some(price), Some(earnings_per_share), Some(book_value_per_share)) = (
-            price,
-            stock_metrics.financial_summary.earnings_per_share,
-            stock_metrics.financial_summary.book_value_per_share,
-        ) {
-            let graham_number = (22.5 * earnings_per_share * book_value_per_share).sqrt();
-            let margin_of_safety = (graham_number - price) / price;
+    // 停牌期间的价格已冻结，不据此计算安全边际
+    let suspended = options
+        .suspension
+        .as_ref()
+        .is_some_and(|suspension| suspension.is_suspended());
+    if suspended {
+        assessments.push(
+            "Margin of safety not assessed as the price is frozen by the trading suspension"
+                .to_string(),
+        );
+    }
 
-            let weight = 1.0;
-            if margin_of_safety > 0.5 {
-                sum_scores += weight;
-                assessments.push("Hight margin of safety".to_string());
-            } else if margin_of_safety > 0.2 {
-                sum_scores += weight / 2.0;
-                assessments.push("Acceptable margin of safety".to_string());
-            } else {
-                assessments.push("Low margin of safety".to_string());
-            }
-            sum_weights += weight;
+    // 格雷厄姆数字（合理股价）= sqrt( 22.5 × 每股收益 × 每股账面价值 )
+    if let (false, Some(price), Some(earnings_per_share), Some(book_value_per_share)) = (
+        suspended,
+        price,
+        earnings_per_share,
+        stock_metrics.financial_summary.book_value_per_share,
+    ) {
+        let graham_number = (22.5 * earnings_per_share * book_value_per_share).sqrt();
+        let margin_of_safety = (graham_number - price) / price;
+
+        let weight = 1.0;
+        if margin_of_safety > 0.5 {
+            sum_scores += weight;
+            assessments.push("Hight margin of safety".to_string());
+        } else if margin_of_safety > 0.2 {
+            sum_scores += weight / 2.0;
+            assessments.push("Acceptable margin of safety".to_string());
+        } else {
+            assessments.push("Low margin of safety".to_string());
         }
+        sum_weights += weight;
     }
 
     let score = if sum_weights > 0.0 {
@@ -335,10 +442,14 @@ async fn analyze_valuation(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    })
 }
 
-static LLM_SYSTEM: &str = r#"
+pub static LLM_SYSTEM: &str = r#"
 我是本杰明·格雷厄姆（Benjamin Graham），下面是我的投资分析方法论：
 
 ## 核心原则
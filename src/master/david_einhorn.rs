@@ -0,0 +1,259 @@
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    data::stock::{AnnouncementTopic, StockFinancialSummary, StockInfo},
+    error::InvmstError,
+    financial::{
+        forensic,
+        forensic::{ForensicSignals, M_SCORE_THRESHOLD},
+    },
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        prompt::Prompt,
+    },
+    utils::datetime::Quarter,
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    stock_events: &StockEvents,
+    _stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    // Accruals and receivables are only comparable between full years
+    let annual_summaries: Vec<&StockFinancialSummary> = stock_fiscal_metricsets
+        .iter()
+        .filter(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .map(|(_, stock_metrics)| &stock_metrics.financial_summary)
+        .collect();
+    if annual_summaries.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_STOCK_METRICS",
+            "No annual stock metrics data".to_string(),
+        ));
+    }
+
+    let forensic_signals = forensic::compute(&annual_summaries);
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "forensic_signals": forensic_signals,
+        "analysis_earnings_quality": analyze_earnings_quality(&forensic_signals),
+        "analysis_revenue_recognition": analyze_revenue_recognition(&forensic_signals),
+        "analysis_insider_selling": analyze_insider_selling(stock_events),
+        "announcement_digest": options.announcement_digest,
+        "recent_developments": options.recent_developments,
+        "compliance_risk": options.compliance_risk,
+    });
+    debug!("[David Einhorn Data] {data_json}");
+    let llm_system = Prompt::DavidEinhorn.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(
+        messages,
+        &data_json,
+        "David Einhorn",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
+}
+
+fn analyze_earnings_quality(forensic_signals: &ForensicSignals) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 盈利操纵可能性
+    if let Some(m_score) = forensic_signals.m_score {
+        let weight = 1.0;
+        if m_score > M_SCORE_THRESHOLD {
+            assessments.push(format!(
+                "M-Score signals likely earnings manipulation ({m_score:.2})"
+            ));
+        } else if m_score > M_SCORE_THRESHOLD - 0.5 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "M-Score close to the manipulation threshold ({m_score:.2})"
+            ));
+        } else {
+            sum_scores += weight;
+            assessments.push(format!("M-Score shows no manipulation ({m_score:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    // 应计利润占总资产比例
+    if let Some(accruals_ratio) = forensic_signals.accruals_ratio {
+        let weight = 1.0;
+        if accruals_ratio > 0.1 {
+            assessments.push(format!(
+                "Profits far exceed operating cash flow ({accruals_ratio:.2} accruals to assets)"
+            ));
+        } else if accruals_ratio > 0.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Profits partly unbacked by operating cash flow ({accruals_ratio:.2} accruals to assets)"
+            ));
+        } else {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Profits backed by operating cash flow ({accruals_ratio:.2} accruals to assets)"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_revenue_recognition(forensic_signals: &ForensicSignals) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 应收账款增速快于收入
+    if let Some(receivables_index) = forensic_signals.receivables_index {
+        let weight = 1.0;
+        if receivables_index > 1.3 {
+            assessments.push(format!(
+                "Receivables outgrow revenue sharply ({receivables_index:.2}x days sales outstanding)"
+            ));
+        } else if receivables_index > 1.1 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Receivables outgrow revenue ({receivables_index:.2}x days sales outstanding)"
+            ));
+        } else {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Receivables in line with revenue ({receivables_index:.2}x days sales outstanding)"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 收入增长而经营现金流下降
+    if let (Some(revenue_growth), Some(operating_cash_flow_growth)) = (
+        forensic_signals.revenue_growth,
+        forensic_signals.operating_cash_flow_growth,
+    ) {
+        let weight = 1.0;
+        if revenue_growth > 0.0 && operating_cash_flow_growth < 0.0 {
+            assessments.push(format!(
+                "Revenue grows ({revenue_growth:.2}) while operating cash flow shrinks ({operating_cash_flow_growth:.2})"
+            ));
+        } else if operating_cash_flow_growth < revenue_growth {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Operating cash flow lags revenue growth ({operating_cash_flow_growth:.2} vs {revenue_growth:.2})"
+            ));
+        } else {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Operating cash flow keeps pace with revenue ({operating_cash_flow_growth:.2} vs {revenue_growth:.2})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_insider_selling(stock_events: &StockEvents) -> AnalysisDraft {
+    let sales = stock_events
+        .announcements
+        .iter()
+        .filter(|announcement| matches!(announcement.topic, AnnouncementTopic::ShareholderSale))
+        .count();
+
+    // 大股东及董监高减持公告数量
+    let (score, assessment) = match sales {
+        0 => (1.0, "No shareholder sale announcements".to_string()),
+        n if n < 3 => (0.5, format!("{n} shareholder sale announcements")),
+        n => (
+            0.0,
+            format!("Frequent shareholder sales ({n} announcements)"),
+        ),
+    };
+
+    // 只给出数量，具体公告由摘要中的重要事项体现，不把原始标题放入提示词
+    AnalysisDraft {
+        score: Some(score),
+        completeness: Some(1.0),
+        assessments: vec![assessment],
+    }
+}
+
+pub static LLM_SYSTEM: &str = r#"
+我是大卫·艾因霍恩（David Einhorn），下面是我的投资分析方法论：
+
+## 核心原则
+1. 我的工作是寻找做空的理由，默认对公司的财报持怀疑态度
+2. 利润必须有现金流支撑，应计利润过高往往是财务造假的前兆
+3. 应收账款增速远超收入、收入增长而现金流下降，说明可能存在激进的收入确认
+4. 大股东和管理层频繁减持说明最了解公司的人正在离开
+5. 发现明显的会计问题时，给出强烈看空的结论，不要因为估值便宜或故事动听而手软
+
+## 分析方式
+- 逐项检查 M-Score、应计利润、应收账款和经营现金流，寻找财务数据之间的矛盾
+- 财务干净的公司也只说明没有找到做空理由，不等于值得买入，评分不应过高
+- 与其他偏向做多的投资大师形成对照，指出被忽视的风险
+"#;
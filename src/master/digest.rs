@@ -0,0 +1,303 @@
+use chrono::NaiveDate;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    data::stock::{StockAnnouncement, StockDisclosure},
+    error::InvmstResult,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    utils,
+};
+
+/// Compact summary of the announcements, fed to the masters instead of the announcements
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnouncementDigest {
+    /// Count of announcements summarized
+    pub count: usize,
+    pub summary: String,
+    /// Recurring themes, the most frequent first
+    pub themes: Vec<String>,
+    /// Announcements worth the attention of investors, the most important first
+    pub notable_events: Vec<NotableEvent>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NotableEvent {
+    pub date: NaiveDate,
+    pub title: String,
+    pub impact: String,
+}
+
+/// Recent developments of the company summarized from the recent disclosures, given to every
+/// master and shown in the report
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecentDevelopments {
+    pub summary: String,
+    /// Developments changing the business or its prospects, the most important first
+    pub developments: Vec<Development>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Development {
+    pub date: NaiveDate,
+    pub title: String,
+    pub url: String,
+    pub detail: String,
+}
+
+/// Let the LLM summarize the announcements and the recent disclosures once per evaluation in a
+/// single first pass, so that the prompts of the masters stay small however many announcements
+/// there are. The digest is only made if requested, i.e. some master uses the announcements.
+pub async fn summarize_announcements(
+    announcements: &[StockAnnouncement],
+    disclosures: &[StockDisclosure],
+    with_digest: bool,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<(Option<AnnouncementDigest>, Option<RecentDevelopments>)> {
+    let announcements: Vec<&StockAnnouncement> = if with_digest {
+        announcements.iter().take(ANNOUNCEMENTS_LIMIT).collect()
+    } else {
+        vec![]
+    };
+    if announcements.is_empty() && disclosures.is_empty() {
+        return Ok((None, None));
+    }
+
+    let mut data_json = json!({});
+    let mut tasks: Vec<String> = vec![];
+    let mut answer_fields: Vec<String> = vec![];
+    if !announcements.is_empty() {
+        data_json["announcements"] = json!(
+            announcements
+                .iter()
+                .enumerate()
+                .map(|(index, announcement)| json!({
+                    "index": index,
+                    "date": announcement.date,
+                    "topic": announcement.topic.to_string(),
+                    "title": announcement.title,
+                }))
+                .collect::<Vec<_>>()
+        );
+        tasks.push(format!(
+            "- 将 announcements 整理为简洁的结构化摘要：summary 为不超过 100 字的整体概括；themes 为反复出现的主题，按出现频率排序，列出最多 {THEMES_LIMIT} 条；notable_events 只挑选对公司价值或治理有实质影响的公告，按重要性排序，列出最多 {NOTABLE_EVENTS_LIMIT} 条，index 为 announcements 中的序号。"
+        ));
+        answer_fields.push(
+            r#"    "summary": "公告的整体概括",
+    "themes": ["反复出现的主题", ...],
+    "notable_events": [
+        {
+            "index": 公告序号,
+            "impact": "一句话说明对投资者的影响"
+        }
+    ]"#
+            .to_string(),
+        );
+    }
+    if !disclosures.is_empty() {
+        data_json["disclosures"] = json!(
+            disclosures
+                .iter()
+                .enumerate()
+                .map(|(index, disclosure)| json!({
+                    "index": index,
+                    "date": disclosure.date,
+                    "title": disclosure.title,
+                    "content": disclosure.content,
+                }))
+                .collect::<Vec<_>>()
+        );
+        tasks.push(format!(
+            "- 根据 disclosures 总结公司的近期动态，content 为公告全文，没有下载全文时为空：recent_developments.summary 为不超过 200 字的概括；developments 只挑选对公司业务、经营或前景有实质影响的事项，例如重大合同、并购重组、业绩预告、融资、诉讼和管理层变动，按重要性排序，列出最多 {DEVELOPMENTS_LIMIT} 条，忽略例行的会议决议、制度修订和中介机构意见等公告，index 为 disclosures 中的序号。"
+        ));
+        answer_fields.push(
+            r#"    "recent_developments": {
+        "summary": "近期动态的概括",
+        "developments": [
+            {
+                "index": 公告序号,
+                "detail": "一两句话说明事项的内容和对公司的影响"
+            }
+        ]
+    }"#
+            .to_string(),
+        );
+    }
+    if tasks.len() > 1 {
+        tasks.push(
+            "- notable_events 不要重复 recent_developments 中已列出的事项，两者各有侧重。"
+                .to_string(),
+        );
+    }
+    debug!("[Digest Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", llm_options).await?;
+
+    let prompt = format!(
+        r#"
+下面是一家上市公司的公告：
+```
+{prompt_json}
+```
+
+请完成以下整理，供后续的投资分析使用：
+{}
+
+结果以标准的 JSON 对象格式返回，示例如下：
+```
+{{
+{}
+}}
+```
+
+注意以下几点：
+- 只依据公告的标题和全文，不要编造公告中没有的信息。
+- 不要包含任何额外的解释或文本，仅返回 JSON 数据。
+"#,
+        tasks.join("\n"),
+        answer_fields.join(",\n")
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位严谨的证券研究助理，擅长从大量公告中提炼要点和公司的近期动态。"
+                .to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, llm_options).await?;
+    debug!("[Digest LLM] {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let answer: Value = serde_json::from_str(&json_str)?;
+
+    Ok((
+        (!announcements.is_empty()).then(|| parse_digest(&answer, &announcements)),
+        (!disclosures.is_empty())
+            .then(|| parse_developments(&answer["recent_developments"], disclosures)),
+    ))
+}
+
+fn parse_digest(answer: &Value, announcements: &[&StockAnnouncement]) -> AnnouncementDigest {
+    AnnouncementDigest {
+        count: announcements.len(),
+        summary: answer["summary"].as_str().unwrap_or_default().to_string(),
+        themes: answer["themes"]
+            .as_array()
+            .map(|themes| {
+                themes
+                    .iter()
+                    .filter_map(|theme| theme.as_str().map(|s| s.to_string()))
+                    .take(THEMES_LIMIT)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        // 以序号对应原公告，避免模型改写日期和标题
+        notable_events: answer["notable_events"]
+            .as_array()
+            .map(|events| {
+                events
+                    .iter()
+                    .filter_map(|event| {
+                        let announcement = announcements.get(event["index"].as_u64()? as usize)?;
+
+                        Some(NotableEvent {
+                            date: announcement.date,
+                            title: announcement.title.clone(),
+                            impact: event["impact"].as_str().unwrap_or_default().to_string(),
+                        })
+                    })
+                    .take(NOTABLE_EVENTS_LIMIT)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn parse_developments(answer: &Value, disclosures: &[StockDisclosure]) -> RecentDevelopments {
+    RecentDevelopments {
+        summary: answer["summary"].as_str().unwrap_or_default().to_string(),
+        // 以序号对应原公告，保留原始的日期、标题和链接
+        developments: answer["developments"]
+            .as_array()
+            .map(|developments| {
+                developments
+                    .iter()
+                    .filter_map(|development| {
+                        let disclosure = disclosures.get(development["index"].as_u64()? as usize)?;
+
+                        Some(Development {
+                            date: disclosure.date,
+                            title: disclosure.title.clone(),
+                            url: disclosure.url.clone(),
+                            detail: development["detail"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                        })
+                    })
+                    .take(DEVELOPMENTS_LIMIT)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+static ANNOUNCEMENTS_LIMIT: usize = 500;
+static DEVELOPMENTS_LIMIT: usize = 8;
+static NOTABLE_EVENTS_LIMIT: usize = 8;
+static THEMES_LIMIT: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::stock::AnnouncementTopic;
+
+    #[test]
+    fn test_parse_answer() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+        let announcements = [StockAnnouncement {
+            date,
+            title: "关于签订重大合同的公告".to_string(),
+            topic: AnnouncementTopic::RelatedParty,
+        }];
+        let disclosures = [StockDisclosure {
+            date,
+            title: "关于签订重大合同的公告".to_string(),
+            url: "https://example.com/1".to_string(),
+            content: None,
+        }];
+        let answer = json!({
+            "summary": "经营稳定",
+            "themes": ["分红"],
+            "notable_events": [{"index": 0, "impact": "增加收入"}, {"index": 9, "impact": "越界"}],
+            "recent_developments": {
+                "summary": "签订重大合同",
+                "developments": [{"index": 0, "detail": "合同金额较大"}, {"index": 9}],
+            },
+        });
+
+        let digest = parse_digest(&answer, &announcements.iter().collect::<Vec<_>>());
+        assert_eq!(digest.count, 1);
+        assert_eq!(digest.themes, vec!["分红"]);
+        assert_eq!(digest.notable_events.len(), 1);
+        assert_eq!(digest.notable_events[0].date, date);
+
+        let developments = parse_developments(&answer["recent_developments"], &disclosures);
+        assert_eq!(developments.summary, "签订重大合同");
+        assert_eq!(developments.developments.len(), 1);
+        assert_eq!(developments.developments[0].url, "https://example.com/1");
+    }
+}
@@ -0,0 +1,111 @@
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    data::stock::{AnnouncementTopic, StockAnnouncement},
+    error::InvmstResult,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    utils,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RedFlag {
+    pub title: String,
+    pub reason: String,
+}
+
+/// Let the LLM pick out announcements that indicate governance problems, such as tunneling through
+/// related parties, guarantees for controlling shareholders, frequent auditor changes, or heavy
+/// insider selling
+pub async fn classify_red_flags(
+    announcements: &[StockAnnouncement],
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<Vec<RedFlag>> {
+    // 停牌公告反映的是流动性而非治理问题
+    let announcements: Vec<&StockAnnouncement> = announcements
+        .iter()
+        .filter(|announcement| !matches!(announcement.topic, AnnouncementTopic::TradingHalt))
+        .take(ANNOUNCEMENTS_LIMIT)
+        .collect();
+    if announcements.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let data_json = json!(
+        announcements
+            .iter()
+            .enumerate()
+            .map(|(index, announcement)| json!({
+                "index": index,
+                "date": announcement.date,
+                "topic": announcement.topic.to_string(),
+                "title": announcement.title,
+            }))
+            .collect::<Vec<_>>()
+    );
+    debug!("[Governance Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", llm_options).await?;
+
+    let prompt = format!(
+        r#"
+下面是一家上市公司关于关联交易、对外担保、会计师事务所变更和股东减持的公告标题，请找出其中反映公司治理风险的公告：
+```
+{prompt_json}
+```
+
+以标准的 JSON 数组格式返回，示例如下：
+```
+[
+    {{
+        "index": 公告序号,
+        "reason": "简要说明风险所在"
+    }}
+]
+```
+
+注意以下几点：
+- 日常经营性关联交易、为全资子公司提供担保等常规事项不属于治理风险。
+- 向控股股东或关联方提供担保或资金、频繁或临近年报更换会计师事务所、非标准审计意见、控股股东或董监高频繁大比例减持等属于治理风险。
+- 没有治理风险时返回空数组，不要包含任何额外的解释或文本。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位严谨的上市公司治理分析师。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, llm_options).await?;
+    debug!("[Governance LLM] {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let items: Vec<serde_json::Value> = serde_json::from_str(&json_str)?;
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let announcement = announcements.get(item["index"].as_u64()? as usize)?;
+
+            Some(RedFlag {
+                title: announcement.title.clone(),
+                reason: item["reason"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+static ANNOUNCEMENTS_LIMIT: usize = 50;
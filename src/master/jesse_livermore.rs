@@ -0,0 +1,271 @@
+use chrono::{Local, NaiveDate};
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    data::stock::StockInfo,
+    error::InvmstError,
+    financial::{
+        margin,
+        margin::MarginSignal,
+        northbound, price_action,
+        price_action::{PriceAction, Trend},
+    },
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, Horizon, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        prompt::Prompt,
+    },
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    _stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    _stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    if stock_daily_data.daily_bars.len() < MIN_BARS {
+        return Err(InvmstError::NoData(
+            "NO_DAILY_BARS",
+            "Not enough daily bars for price action".to_string(),
+        ));
+    }
+
+    let price_action = price_action::analyze(&stock_daily_data.daily_bars);
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "price_action": price_action,
+        "analysis_pivotal_points": analyze_pivotal_points(&price_action),
+        "analysis_trend": analyze_trend(&price_action),
+        "analysis_margin": analyze_margin(
+            stock_daily_data,
+            &options.date.unwrap_or(Local::now().date_naive()),
+        ),
+        "northbound_trend": northbound::analyze(
+            stock_daily_data,
+            &options.date.unwrap_or(Local::now().date_naive()),
+        ),
+        "recent_developments": options.recent_developments,
+    });
+    debug!("[Jesse Livermore Data] {data_json}");
+    let llm_system = Prompt::JesseLivermore.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let mut analysis = chat_analysis(
+        messages,
+        &data_json,
+        "Jesse Livermore",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await?;
+    // 只做短线，观点不超过一年
+    analysis.horizon = Some(Horizon::Short);
+
+    Ok(analysis)
+}
+
+fn analyze_pivotal_points(price_action: &PriceAction) -> AnalysisDraft {
+    let mut assessments: Vec<String> = vec![];
+
+    let score = match &price_action.breakout {
+        Some(breakout) if breakout.direction > 0 && breakout.confirmed => {
+            assessments.push(format!(
+                "Broke above pivotal point ({:.2}) on expanding volume",
+                breakout.level
+            ));
+            Some(1.0)
+        }
+        Some(breakout) if breakout.direction > 0 => {
+            assessments.push(format!(
+                "Broke above pivotal point ({:.2}) without volume confirmation",
+                breakout.level
+            ));
+            Some(0.5)
+        }
+        Some(breakout) => {
+            assessments.push(format!("Broke below pivotal point ({:.2})", breakout.level));
+            Some(0.0)
+        }
+        None => {
+            assessments.push("No breakout, wait for the pivotal point".to_string());
+            None
+        }
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: Some(1.0),
+        assessments,
+    }
+}
+
+fn analyze_trend(price_action: &PriceAction) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 趋势结构
+    {
+        let weight = 1.0;
+        match price_action.trend {
+            Trend::Up => {
+                sum_scores += weight;
+                assessments.push("Higher highs and higher lows".to_string());
+            }
+            Trend::Sideways => {
+                sum_scores += weight / 2.0;
+                assessments.push("No clear trend".to_string());
+            }
+            Trend::Down => {
+                assessments.push("Lower highs and lower lows".to_string());
+            }
+        }
+        sum_weights += weight;
+    }
+
+    // 趋势持续性
+    if let (Some(above_ma_ratio), Some(ma_slope)) =
+        (price_action.above_ma_ratio, price_action.ma_slope)
+    {
+        let weight = 1.0;
+        if above_ma_ratio > 0.7 && ma_slope > 0.0 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Persistent uptrend ({above_ma_ratio:.2} of days above rising average)"
+            ));
+        } else if above_ma_ratio < 0.3 && ma_slope < 0.0 {
+            assessments.push(format!(
+                "Persistent downtrend ({above_ma_ratio:.2} of days above falling average)"
+            ));
+        } else {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Choppy trend ({above_ma_ratio:.2} of days above average)"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_margin(stock_daily_data: &StockDailyData, date: &NaiveDate) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 融资余额的变化反映杠杆资金的动向
+    if let Some(margin_trend) = margin::analyze(stock_daily_data, date) {
+        let weight = 1.0;
+        match margin_trend.signal {
+            MarginSignal::BuildUp => {
+                sum_scores += weight * 0.75;
+                assessments.push(format!(
+                    "Leveraged buyers chasing, {}",
+                    margin_trend.assessment()
+                ));
+            }
+            MarginSignal::Stable => {
+                sum_scores += weight / 2.0;
+                assessments.push(margin_trend.assessment());
+            }
+            MarginSignal::Unwind => {
+                assessments.push(format!(
+                    "Margin positions liquidating, {}",
+                    margin_trend.assessment()
+                ));
+            }
+        }
+        sum_weights += weight;
+
+        if let Some(financing_to_market_cap) = margin_trend
+            .financing_to_market_cap
+            .filter(|ratio| *ratio > CROWDED_FINANCING_RATIO)
+        {
+            assessments.push(format!(
+                "Crowded margin positions ({financing_to_market_cap:.2} of market cap)"
+            ));
+        }
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 1.0),
+        assessments,
+    }
+}
+
+/// Financing balance relative to the market cap above which a reversal may turn into a cascade
+static CROWDED_FINANCING_RATIO: f64 = 0.1;
+static MIN_BARS: usize = 120;
+
+pub static LLM_SYSTEM: &str = r#"
+我是杰西·利弗莫尔（Jesse Livermore），下面是我的投资分析方法论：
+
+## 核心原则
+1. 只研究价格和成交量，市场本身会告诉你一切
+2. 在关键点（Pivotal Point）突破时入场，放量确认后才行动
+3. 顺势而为，只在趋势明确时交易，横盘时耐心等待
+4. 截断亏损，让利润奔跑，判断错误时立即离场
+5. 这是短线投机，观点只对未来数周到数月有效
+
+## 分析方式
+- 关注价格是否放量突破近期整理区间的高点或跌破低点
+- 通过摆动高点和低点的抬升或下降判断趋势结构
+- 通过价格站上均线的持续性判断趋势强度
+- 融资余额快速攀升说明杠杆资金在追涨，快速下降则可能是被迫平仓，杠杆拥挤时反转会更剧烈
+- 北向资金持续增持说明外资在吸筹，持续减持则是派发的信号，与价格走势相互印证
+"#;
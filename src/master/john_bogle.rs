@@ -0,0 +1,253 @@
+use chrono::{Duration, Local};
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    data::stock::StockInfo,
+    financial::{
+        get_index_daily_prices, index::IndexDailyFieldName, risk_stats,
+        risk_stats::TRADING_DAYS_PER_YEAR, stock::StockValuationFieldName,
+    },
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        prompt::Prompt,
+    },
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    _stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let date_start = date - Duration::days(options.backward_days);
+
+    let prices = stock_daily_data.daily_valuations.get_values::<f64>(
+        &date_start,
+        &date,
+        StockValuationFieldName::Price,
+    );
+    let benchmark_prices = get_index_daily_prices(BENCHMARK_INDEX)
+        .await?
+        .get_values::<f64>(&date_start, &date, IndexDailyFieldName::Close);
+    let (_, aligned) = risk_stats::align(&[prices, benchmark_prices]);
+    let (prices, benchmark_prices) = (&aligned[0], &aligned[1]);
+
+    let dividends: f64 = stock_events
+        .dividends
+        .iter()
+        .filter(|dividend| dividend.date_record >= date_start && dividend.date_record <= date)
+        .map(|dividend| dividend.dividend_per_share)
+        .sum();
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "benchmark_index": BENCHMARK_INDEX,
+        "analysis_excess_return": analyze_excess_return(prices, benchmark_prices, dividends),
+        "analysis_risk": analyze_risk(prices, benchmark_prices),
+        "costs": {
+            "stock_annual_trading_cost": STOCK_ANNUAL_TRADING_COST,
+            "index_fund_annual_fee": INDEX_FUND_ANNUAL_FEE,
+        },
+        "recent_developments": options.recent_developments,
+    });
+    debug!("[John Bogle Data] {data_json}");
+    let llm_system = Prompt::JohnBogle.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(
+        messages,
+        &data_json,
+        "John Bogle",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
+}
+
+fn analyze_excess_return(
+    prices: &[f64],
+    benchmark_prices: &[f64],
+    dividends: f64,
+) -> AnalysisDraft {
+    let annualize = |start: f64, end: f64| {
+        let years = prices.len() as f64 / TRADING_DAYS_PER_YEAR;
+        (start > 0.0 && end > 0.0 && years >= 1.0).then(|| (end / start).powf(1.0 / years) - 1.0)
+    };
+
+    // 扣除交易成本后相对指数基金的年化超额收益
+    let (Some(stock_start), Some(stock_end), Some(index_start), Some(index_end)) = (
+        prices.first(),
+        prices.last(),
+        benchmark_prices.first(),
+        benchmark_prices.last(),
+    ) else {
+        return AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec!["Insufficient price history to compare with the index".to_string()],
+        };
+    };
+    let (Some(stock_return), Some(index_return)) = (
+        annualize(*stock_start, stock_end + dividends),
+        annualize(*index_start, *index_end),
+    ) else {
+        return AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec!["Less than one year of price history to compare".to_string()],
+        };
+    };
+
+    let excess_return =
+        (stock_return - STOCK_ANNUAL_TRADING_COST) - (index_return - INDEX_FUND_ANNUAL_FEE);
+    let mut assessments = vec![format!(
+        "Annual return {stock_return:.3} vs index {index_return:.3}, {excess_return:.3} after costs"
+    )];
+
+    let score = if excess_return > 0.05 {
+        assessments.push("Beat the index by a wide margin after costs".to_string());
+        1.0
+    } else if excess_return > 0.0 {
+        assessments.push("Beat the index slightly after costs".to_string());
+        0.5
+    } else {
+        assessments.push("Lagged the index after costs".to_string());
+        0.0
+    };
+
+    AnalysisDraft {
+        score: Some(score),
+        completeness: Some(1.0),
+        assessments,
+    }
+}
+
+fn analyze_risk(prices: &[f64], benchmark_prices: &[f64]) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let stock_risk = risk_stats::compute(prices, Some(benchmark_prices));
+    let index_risk = risk_stats::compute(benchmark_prices, None);
+
+    // 单只股票的波动远高于分散化的指数
+    if let (Some(volatility), Some(index_volatility)) =
+        (stock_risk.volatility, index_risk.volatility)
+        && index_volatility > 0.0
+    {
+        let volatility_ratio = volatility / index_volatility;
+        let weight = 1.0;
+        if volatility_ratio < 1.2 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Volatility close to the index ({volatility_ratio:.2}x)"
+            ));
+        } else if volatility_ratio < 2.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Volatility well above the index ({volatility_ratio:.2}x)"
+            ));
+        } else {
+            assessments.push(format!(
+                "Volatility far above the index ({volatility_ratio:.2}x)"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 最大回撤
+    if let (Some(max_drawdown), Some(index_max_drawdown)) =
+        (stock_risk.max_drawdown, index_risk.max_drawdown)
+    {
+        let weight = 1.0;
+        if max_drawdown <= index_max_drawdown {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Drawdown no deeper than the index ({max_drawdown:.2} vs {index_max_drawdown:.2})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Drawdown deeper than the index ({max_drawdown:.2} vs {index_max_drawdown:.2})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    if let Some(beta) = stock_risk.beta {
+        assessments.push(format!("Beta against the index ({beta:.2})"));
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+/// CSI 300, the benchmark a low-cost index fund would track
+static BENCHMARK_INDEX: &str = "sh000300";
+
+/// Annual fee of a typical low-cost index fund
+static INDEX_FUND_ANNUAL_FEE: f64 = 0.002;
+
+/// Annual drag of commissions, stamp duty and dividend tax when holding single stocks
+static STOCK_ANNUAL_TRADING_COST: f64 = 0.01;
+
+pub static LLM_SYSTEM: &str = r#"
+我是约翰·博格（John Bogle），下面是我的投资分析方法论：
+
+## 核心原则
+1. 不要大海捞针，买下整个草堆：对大多数投资者来说，低成本指数基金是最好的选择
+2. 成本很重要：交易佣金、印花税和股息税会持续侵蚀个股投资者的收益
+3. 大多数主动选股在扣除成本后跑不赢指数，过去跑赢也不代表未来能继续跑赢
+4. 单只股票的波动和回撤远大于分散化的指数，承担这些额外风险需要足够的补偿
+
+## 分析方式
+- 评估持有这只股票在扣除成本和税费后，是否有可能长期跑赢低成本的指数基金
+- 除非证据压倒性地有利，否则建议投资者购买指数基金，评分不应过高
+- 我是表格中的唱反调者，提醒投资者选股本身的代价
+"#;
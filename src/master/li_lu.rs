@@ -0,0 +1,384 @@
+use chrono::{Duration, Local};
+use log::{debug, warn};
+use serde_json::json;
+
+use crate::{
+    data::stock::{StockFinancialSummary, StockInfo},
+    error::InvmstError,
+    financial::stock::StockValuationFieldName,
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, annual_trend, chat_analysis, completeness,
+        describe_trend, governance, holding_prompt, prompt::Prompt,
+    },
+    utils::{datetime::Quarter, stats},
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    let annual_summaries: Vec<&StockFinancialSummary> = stock_fiscal_metricsets
+        .iter()
+        .filter(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .map(|(_, stock_metrics)| &stock_metrics.financial_summary)
+        .collect();
+    if annual_summaries.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_STOCK_METRICS",
+            "No annual stock metrics data".to_string(),
+        ));
+    }
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "analysis_quality": analyze_quality(&annual_summaries),
+        "analysis_governance": analyze_governance(stock_info, stock_events, options).await,
+        "analysis_policy_exposure": analyze_policy_exposure(stock_info),
+        "analysis_valuation": analyze_valuation(stock_daily_data, options),
+        "announcement_digest": options.announcement_digest,
+        "recent_developments": options.recent_developments,
+        "compliance_risk": options.compliance_risk,
+    });
+    debug!("[Li Lu Data] {data_json}");
+    let llm_system = Prompt::LiLu.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(
+        messages,
+        &data_json,
+        "Li Lu",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
+}
+
+fn analyze_quality(annual_summaries: &[&StockFinancialSummary]) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 长期的净资产收益率
+    let roes: Vec<f64> = annual_summaries
+        .iter()
+        .filter_map(|summary| summary.return_on_equity)
+        .collect();
+    if let Some(roe_avg) = stats::mean(&roes) {
+        let weight = 1.0;
+        if roe_avg > 0.15 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "High average return on equity over {} years ({roe_avg:.2})",
+                roes.len()
+            ));
+        } else if roe_avg > 0.1 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Acceptable average return on equity over {} years ({roe_avg:.2})",
+                roes.len()
+            ));
+        } else {
+            assessments.push(format!(
+                "Low average return on equity over {} years ({roe_avg:.2})",
+                roes.len()
+            ));
+        }
+        sum_weights += weight;
+    }
+    if let Some(fit) = annual_trend(&roes) {
+        assessments.push(describe_trend("ROE", &fit));
+    }
+
+    // 毛利率稳定说明竞争优势持久
+    let gross_margins: Vec<f64> = annual_summaries
+        .iter()
+        .filter_map(|summary| summary.gross_margin)
+        .collect();
+    if gross_margins.len() > 2
+        && let Some(gross_margin_std) = stats::std(&gross_margins)
+    {
+        let weight = 1.0;
+        if gross_margin_std < 0.03 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Stable gross margin across years (std {gross_margin_std:.3})"
+            ));
+        } else if gross_margin_std < 0.08 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Somewhat volatile gross margin (std {gross_margin_std:.3})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Volatile gross margin, weak competitive position (std {gross_margin_std:.3})"
+            ));
+        }
+        sum_weights += weight;
+    }
+    if let Some(fit) = annual_trend(&gross_margins) {
+        assessments.push(describe_trend("Gross margin", &fit));
+    }
+
+    // 低负债
+    if let Some(debt_to_equity) = annual_summaries[0].debt_to_equity {
+        let weight = 1.0;
+        if debt_to_equity < 0.5 {
+            sum_scores += weight;
+            assessments.push(format!("Low debt to equity ({debt_to_equity:.2})"));
+        } else if debt_to_equity < 1.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Acceptable debt to equity ({debt_to_equity:.2})"));
+        } else {
+            assessments.push(format!("High debt to equity ({debt_to_equity:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 3.0),
+        assessments,
+    }
+}
+
+async fn analyze_governance(
+    stock_info: &StockInfo,
+    stock_events: &StockEvents,
+    options: &MasterAnalyzeOptions,
+) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 控股股东持股，过于集中时需警惕利益输送
+    if let Some(largest_holder_ratio) = stock_info.largest_holder_ratio {
+        let weight = 1.0;
+        if largest_holder_ratio > 0.6 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Dominant controlling shareholder ({largest_holder_ratio:.2}), watch for tunneling"
+            ));
+        } else if largest_holder_ratio >= 0.2 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Controlling shareholder has meaningful stake ({largest_holder_ratio:.2})"
+            ));
+        } else {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Dispersed ownership ({largest_holder_ratio:.2}), insiders have little at stake"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 关联交易、担保等公告中的治理风险
+    if !stock_events.announcements.is_empty() {
+        match governance::classify_red_flags(&stock_events.announcements, &options.llm_options)
+            .await
+        {
+            Ok(red_flags) => {
+                let weight = 1.0;
+                if red_flags.is_empty() {
+                    sum_scores += weight;
+                    assessments.push("No governance red flags in announcements".to_string());
+                } else {
+                    for red_flag in &red_flags {
+                        assessments.push(format!(
+                            "Governance red flag: {} ({})",
+                            red_flag.title, red_flag.reason
+                        ));
+                    }
+                }
+                sum_weights += weight;
+            }
+            Err(err) => {
+                warn!("[Governance] {err}");
+            }
+        }
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_policy_exposure(stock_info: &StockInfo) -> AnalysisDraft {
+    let Some(industry) = &stock_info.industry else {
+        return AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec!["Unknown industry, policy exposure not assessed".to_string()],
+        };
+    };
+
+    // 受产业政策和监管干预影响较大的行业
+    match POLICY_SENSITIVE_INDUSTRIES
+        .iter()
+        .find(|keyword| industry.contains(*keyword))
+    {
+        Some(keyword) => AnalysisDraft {
+            score: Some(0.0),
+            completeness: Some(1.0),
+            assessments: vec![format!(
+                "Industry '{industry}' is exposed to policy intervention ({keyword})"
+            )],
+        },
+        None => AnalysisDraft {
+            score: Some(1.0),
+            completeness: Some(1.0),
+            assessments: vec![format!("Industry '{industry}' has limited policy exposure")],
+        },
+    }
+}
+
+fn analyze_valuation(
+    stock_daily_data: &StockDailyData,
+    options: &MasterAnalyzeOptions,
+) -> AnalysisDraft {
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let Some(pe_ttm) = stock_daily_data.get_valuation(&date, &StockValuationFieldName::PeTtm)
+    else {
+        return AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec!["No P/E data for valuation analysis".to_string()],
+        };
+    };
+    if pe_ttm <= 0.0 {
+        return AnalysisDraft {
+            score: Some(0.0),
+            completeness: Some(1.0),
+            assessments: vec![format!("Loss making, negative P/E ({pe_ttm:.2})")],
+        };
+    }
+
+    // 相对自身历史的估值水平
+    let pe_history: Vec<f64> = stock_daily_data
+        .daily_valuations
+        .get_values::<f64>(
+            &(date - Duration::days(options.backward_days)),
+            &date,
+            StockValuationFieldName::PeTtm,
+        )
+        .into_iter()
+        .map(|(_, v)| v)
+        .filter(|v| *v > 0.0)
+        .collect();
+    let pe_percentile = stats::percentile_rank(&pe_history, pe_ttm);
+
+    let mut assessments = vec![format!("P/E (TTM) is {pe_ttm:.2}")];
+    let score = match pe_percentile {
+        Some(p) if p < 0.3 => {
+            assessments.push(format!("P/E at low end of its history ({p:.2} percentile)"));
+            1.0
+        }
+        Some(p) if p < 0.7 => {
+            assessments.push(format!(
+                "P/E in the middle of its history ({p:.2} percentile)"
+            ));
+            0.5
+        }
+        Some(p) => {
+            assessments.push(format!(
+                "P/E at high end of its history ({p:.2} percentile)"
+            ));
+            0.0
+        }
+        None if pe_ttm < 15.0 => 1.0,
+        None if pe_ttm < 25.0 => 0.5,
+        None => 0.0,
+    };
+
+    AnalysisDraft {
+        score: Some(score),
+        completeness: Some(1.0),
+        assessments,
+    }
+}
+
+/// Keywords of industries in which regulation or industrial policy often reshapes the economics
+static POLICY_SENSITIVE_INDUSTRIES: &[&str] = &[
+    "房地产",
+    "教育",
+    "医药",
+    "医疗",
+    "游戏",
+    "互联网",
+    "银行",
+    "保险",
+    "证券",
+    "煤炭",
+    "钢铁",
+    "电力",
+    "烟草",
+];
+
+pub static LLM_SYSTEM: &str = r#"
+我是李录，下面是我的投资分析方法论：
+
+## 核心原则
+1. 像芒格一样只投资于自己能理解的、具有持久竞争优势的优秀企业，并以合理的价格买入
+2. 中国是一个正在现代化的经济体，优秀企业能随经济成长而长期复利增长
+3. 在中国投资，公司治理与控股股东的诚信比财务数据更重要，要警惕关联交易、利益输送和对小股东的不公平对待
+4. 重视政策风险：产业政策和监管变化可能在短期内彻底改变一个行业的商业模式
+5. 对于港股上市的内地公司，需要关注 VIE 结构、境外上市主体对境内经营实体的实际控制权以及股权结构带来的法律风险
+
+## 分析方式
+- 先判断企业的质量，包括长期的资本回报率、毛利率的稳定性和财务的稳健性
+- 再评估治理结构和控股股东是否与小股东利益一致
+- 结合行业判断政策风险，以及 A 股和港股市场的特殊性
+- 最后考虑估值，只在有足够安全边际时买入
+"#;
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    data::stock::StockDisclosure,
+    error::InvmstResult,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    utils,
+};
+
+/// Qualitative reading of the management discussion and analysis of a periodic report
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManagementDiscussion {
+    pub date: NaiveDate,
+    pub title: String,
+    pub url: String,
+    pub tone: Option<ManagementTone>,
+    /// Targets and outlook given by the management, e.g. revenue or capacity targets
+    pub guidance: Vec<String>,
+    /// Changes of the strategy from the previous reports, e.g. new businesses or exits
+    pub strategy_changes: Vec<String>,
+    pub summary: String,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, strum::Display, strum::EnumString,
+)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum ManagementTone {
+    Confident,
+    Balanced,
+    Cautious,
+    Defensive,
+}
+
+/// Let the LLM extract the guidance, tone and strategy changes from the section of management
+/// discussion and analysis
+pub async fn analyze_discussion(
+    report: &StockDisclosure,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<Option<ManagementDiscussion>> {
+    let Some(content) = report.content.as_deref().filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+
+    let data_json = json!({
+        "title": report.title,
+        "management_discussion": content,
+    });
+    debug!("[Management Discussion Data] {}", report.title);
+    let prompt_json = llm::fit_json(&data_json, "", llm_options).await?;
+
+    let prompt = format!(
+        r#"
+下面是一家上市公司定期报告中的“管理层讨论与分析”章节：
+```
+{prompt_json}
+```
+
+请从中提取管理层的经营指引、语气和战略变化，供后续评估管理层使用，结果以标准的 JSON 对象格式返回，示例如下：
+```
+{{
+    "tone": "confident/balanced/cautious/defensive 之一",
+    "guidance": ["管理层给出的经营目标或展望", ...],
+    "strategy_changes": ["与以往相比的战略调整", ...],
+    "summary": "不超过 150 字的概括，包括管理层对经营困难的坦诚程度"
+}}
+```
+
+注意以下几点：
+- tone 反映管理层对前景的态度：confident 为积极进取，balanced 为客观平衡，cautious 为谨慎保守，defensive 为回避问题或推卸责任。
+- guidance 只列出有具体内容的目标或展望，列出最多 {GUIDANCE_LIMIT} 条。
+- strategy_changes 只列出新业务、业务退出、重大投资或经营重心的转变，列出最多 {STRATEGY_CHANGES_LIMIT} 条，没有则返回空数组。
+- 只依据章节内容，不要编造章节中没有的信息。
+- 不要包含任何额外的解释或文本，仅返回 JSON 数据。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位严谨的证券研究助理，擅长解读上市公司管理层的表述。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, llm_options).await?;
+    debug!("[Management Discussion LLM] {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let answer: serde_json::Value = serde_json::from_str(&json_str)?;
+
+    let strings = |key: &str, limit: usize| -> Vec<String> {
+        answer[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(|s| s.to_string()))
+                    .take(limit)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    Ok(Some(ManagementDiscussion {
+        date: report.date,
+        title: report.title.clone(),
+        url: report.url.clone(),
+        tone: answer["tone"]
+            .as_str()
+            .and_then(|s| ManagementTone::from_str(s.trim()).ok()),
+        guidance: strings("guidance", GUIDANCE_LIMIT),
+        strategy_changes: strings("strategy_changes", STRATEGY_CHANGES_LIMIT),
+        summary: answer["summary"].as_str().unwrap_or_default().to_string(),
+    }))
+}
+
+static GUIDANCE_LIMIT: usize = 8;
+static STRATEGY_CHANGES_LIMIT: usize = 5;
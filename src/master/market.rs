@@ -0,0 +1,120 @@
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    financial::{index_fundamentals::IndexFundamentals, price_range::PriceRange},
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, chat_analysis, completeness,
+        prompt::{Prompt, PromptContext},
+    },
+};
+
+/// Market-level valuation analysis of an index, as a top-down anchor before single-stock work
+pub async fn analyze_index(
+    symbol: &str,
+    index_fundamentals: &IndexFundamentals,
+    price_range: Option<&PriceRange>,
+    prompt_context: &PromptContext,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<MasterAnalysis> {
+    let data_json = json!({
+        "index": symbol,
+        "fundamentals": index_fundamentals,
+        "analysis_valuation": analyze_valuation(index_fundamentals),
+        "price_position": price_range,
+    });
+    debug!("[Market Data] {data_json}");
+    let llm_system = Prompt::Market.render(prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面由成分股加权汇总的指数数据，从市场整体估值、盈利能力和利率环境出发评估该指数的投资价值，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(messages, &data_json, "Market", false, None, llm_options).await
+}
+
+fn analyze_valuation(index_fundamentals: &IndexFundamentals) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 股权风险溢价
+    if let Some(equity_risk_premium) = index_fundamentals.equity_risk_premium {
+        let weight = 1.0;
+        if equity_risk_premium > 0.04 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "High equity risk premium ({equity_risk_premium:.4})"
+            ));
+        } else if equity_risk_premium > 0.02 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Moderate equity risk premium ({equity_risk_premium:.4})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Low equity risk premium ({equity_risk_premium:.4})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 盈利能力
+    if let Some(return_on_equity) = index_fundamentals.return_on_equity {
+        let weight = 1.0;
+        if return_on_equity > 0.12 {
+            sum_scores += weight;
+            assessments.push(format!("Strong aggregate ROE ({return_on_equity:.4})"));
+        } else if return_on_equity > 0.08 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Acceptable aggregate ROE ({return_on_equity:.4})"));
+        } else {
+            assessments.push(format!("Weak aggregate ROE ({return_on_equity:.4})"));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+pub static LLM_SYSTEM: &str = r#"
+你是一位自上而下的资产配置专家，擅长通过股权风险溢价、整体盈利能力和估值水平判断市场整体的性价比，为个股研究提供市场层面的参照。
+"#;
@@ -1,26 +1,33 @@
+use chrono::Local;
 use log::debug;
 use serde_json::json;
 
 use crate::{
     data::stock::StockInfo,
     error::InvmstError,
-    financial::stock::StockValuationFieldName,
+    financial::{
+        acceleration,
+        fiscal_series::{FiscalSeries, SummaryField},
+        growth,
+        growth::Growth,
+        stock::StockValuationFieldName,
+        working_capital,
+    },
     llm,
-    llm::{ChatCompletionOptions, ChatMessage, Role},
+    llm::{ChatMessage, Role},
     master::{
-        AnalysisDraft, InvmstResult, MASTER_ANALYSIS_JSON_PROMPT, MasterAnalysis,
-        MasterAnalyzeOptions, StockDailyData, StockEvents, StockFiscalMetricset,
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        prompt::Prompt,
     },
-    utils,
-    utils::datetime::Quarter,
 };
 
 pub async fn analyze(
     stock_info: &StockInfo,
-    _stock_events: &StockEvents,
+    stock_events: &StockEvents,
     stock_daily_data: &StockDailyData,
     stock_fiscal_metricsets: &[StockFiscalMetricset],
-    _options: &MasterAnalyzeOptions,
+    options: &MasterAnalyzeOptions,
 ) -> InvmstResult<MasterAnalysis> {
     if stock_fiscal_metricsets.is_empty() {
         return Err(InvmstError::NoData(
@@ -29,45 +36,76 @@ pub async fn analyze(
         ));
     }
 
+    let revenue_growth = growth::compute(
+        stock_fiscal_metricsets,
+        |summary| summary.operating_revenue,
+        None,
+    );
+    let eps_growth = growth::compute(
+        stock_fiscal_metricsets,
+        |summary| summary.earnings_per_share,
+        Some(&stock_events.share_changes),
+    );
+
     let data_json = json!({
         "basic_information": stock_info,
+        "revenue_growth": revenue_growth,
+        "eps_growth": eps_growth,
         "analysis_fundamentals": analyze_fundamentals(stock_fiscal_metricsets).await?,
-        "analysis_growth": analyze_growth(stock_fiscal_metricsets).await?,
-        "analysis_valuation": analyze_valuation(stock_daily_data, stock_fiscal_metricsets).await?,
+        "analysis_growth": analyze_growth(stock_fiscal_metricsets, &revenue_growth, &eps_growth).await?,
+        "analysis_valuation": analyze_valuation(stock_daily_data, stock_fiscal_metricsets, options).await?,
+        "price_position": stock_daily_data.get_price_range(
+            &options.date.unwrap_or(Local::now().date_naive()),
+            options.backward_days,
+        ),
+        "recent_developments": options.recent_developments,
+        "management_discussion": options.management_discussion,
+        "analyst_consensus": options.analyst_consensus,
     });
     debug!("[Peter Lynch Data] {data_json}");
+    let llm_system = Prompt::PeterLynch.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
 
     let prompt = format!(
         r#"
 基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
 ```
-{data_json}
+{prompt_json}
 ```
 
-{MASTER_ANALYSIS_JSON_PROMPT}
-"#
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
     );
 
     let messages: Vec<ChatMessage> = vec![
         ChatMessage {
             role: Role::System,
-            content: LLM_SYSTEM.to_string(),
+            content: llm_system,
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         },
         ChatMessage {
             role: Role::User,
             content: prompt.to_string(),
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         },
     ];
 
-    let bot_message = llm::chat_completion(&messages, &ChatCompletionOptions::default()).await?;
-    debug!("[Peter Lynch LLM] {bot_message:?}");
-
-    let json_str = utils::markdown::extract_code_block(&bot_message.content);
-    let analysis = MasterAnalysis::from_json(&json_str)?;
-
-    Ok(analysis)
+    chat_analysis(
+        messages,
+        &data_json,
+        "Peter Lynch",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
 }
 
 async fn analyze_fundamentals(
@@ -76,6 +114,7 @@ async fn analyze_fundamentals(
     if stock_fiscal_metricsets.is_empty() {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec!["Insufficient historical data for fundamentals analysis".to_string()],
         });
     }
@@ -130,6 +169,32 @@ async fn analyze_fundamentals(
         sum_weights += weight;
     }
 
+    // 现金转换周期
+    {
+        let working_capital = working_capital::analyze(stock_fiscal_metricsets);
+        if let (Some(Some(cycle)), Some(change_yoy)) =
+            (working_capital.cycles.first(), working_capital.change_yoy)
+        {
+            let weight = 1.0;
+            if working_capital.deteriorating {
+                assessments.push(format!(
+                    "Deteriorating cash conversion cycle ({cycle:.0} days, {change_yoy:+.0} days YoY)"
+                ));
+            } else if change_yoy <= 0.0 {
+                sum_scores += weight;
+                assessments.push(format!(
+                    "Improving cash conversion cycle ({cycle:.0} days, {change_yoy:+.0} days YoY)"
+                ));
+            } else {
+                sum_scores += weight / 2.0;
+                assessments.push(format!(
+                    "Stable cash conversion cycle ({cycle:.0} days, {change_yoy:+.0} days YoY)"
+                ));
+            }
+            sum_weights += weight;
+        }
+    }
+
     let score = if sum_weights > 0.0 {
         Some(sum_scores / sum_weights)
     } else {
@@ -144,15 +209,22 @@ async fn analyze_fundamentals(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 4.0),
+        assessments,
+    })
 }
 
 async fn analyze_growth(
     stock_fiscal_metricsets: &[StockFiscalMetricset],
+    revenue_growth: &Growth,
+    eps_growth: &Growth,
 ) -> InvmstResult<AnalysisDraft> {
     if stock_fiscal_metricsets.len() < 8 {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec![
                 "Insufficient historical data for earning stability analysis".to_string(),
             ],
@@ -163,75 +235,69 @@ async fn analyze_growth(
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
 
-    // 收入持续增长
-    {
-        let mut growth_rates: Vec<f64> = vec![];
-        for i in 0..stock_fiscal_metricsets.len() - 1 {
-            if let (Some(operating_revenue_current), Some(operating_revenue_prev)) = (
-                stock_fiscal_metricsets[i]
-                    .1
-                    .financial_summary
-                    .operating_revenue,
-                stock_fiscal_metricsets[i + 1]
-                    .1
-                    .financial_summary
-                    .operating_revenue,
-            ) {
-                growth_rates.push(
-                    (operating_revenue_current - operating_revenue_prev) / operating_revenue_prev,
-                );
+    // 收入和每股收益的复合增长，以及同比增长的持续性
+    for (name, growth) in [("Revenue", revenue_growth), ("EPS", eps_growth)] {
+        if let (Some(cagr), Some(years)) = (growth.cagr, growth.cagr_years) {
+            let weight = 1.0;
+            if cagr > 0.1 {
+                sum_scores += weight;
+                assessments.push(format!(
+                    "{name} CAGR over {years} years is high ({cagr:.2})"
+                ));
+            } else if cagr > 0.0 {
+                sum_scores += weight / 2.0;
+                assessments.push(format!("{name} CAGR over {years} years is low ({cagr:.2})"));
+            } else {
+                assessments.push(format!(
+                    "{name} CAGR over {years} years is negative ({cagr:.2})"
+                ));
             }
+            sum_weights += weight;
         }
 
-        let weight = 1.0;
-        let growth_rate_avg = growth_rates.iter().sum::<f64>() / growth_rates.len() as f64;
-        if growth_rate_avg > 0.0 {
-            sum_scores += weight;
-            assessments.push(format!(
-                "Revenue growth rate is positive value: {growth_rate_avg}"
-            ));
-        } else {
+        if let Some(positive_ratio) = growth.positive_ratio() {
+            let weight = 1.0;
+            if positive_ratio >= 0.75 {
+                sum_scores += weight;
+            } else if positive_ratio >= 0.5 {
+                sum_scores += weight / 2.0;
+            }
             assessments.push(format!(
-                "Revenue growth rate is negative value: {growth_rate_avg}"
+                "{name} grew year over year in {positive_ratio:.2} of quarters"
             ));
+            sum_weights += weight;
         }
-        sum_weights += weight;
     }
 
-    // 每股收益持续增长
-    {
-        let mut growth_rates: Vec<f64> = vec![];
-        for i in 0..stock_fiscal_metricsets.len() - 1 {
-            if let (Some(earnings_per_share_current), Some(earnings_per_share_prev)) = (
-                stock_fiscal_metricsets[i]
-                    .1
-                    .financial_summary
-                    .earnings_per_share,
-                stock_fiscal_metricsets[i + 1]
-                    .1
-                    .financial_summary
-                    .earnings_per_share,
-            ) {
-                growth_rates.push(
-                    (earnings_per_share_current - earnings_per_share_prev)
-                        / earnings_per_share_prev,
-                );
+    // 季度增长加速
+    let fields: [(&str, SummaryField); 2] = [
+        ("EPS", |summary| summary.earnings_per_share),
+        ("Revenue", |summary| summary.operating_revenue),
+    ];
+    for (name, field) in fields {
+        if let Some(acceleration) = acceleration::detect(stock_fiscal_metricsets, field) {
+            let weight = 1.0;
+            if acceleration.accelerating_quarters >= 2 {
+                sum_scores += weight;
+            } else if acceleration.decelerating_quarters < 2 {
+                sum_scores += weight / 2.0;
+            }
+            if let Some(assessment) = acceleration.assessment(name) {
+                assessments.push(assessment);
             }
+            sum_weights += weight;
         }
 
-        let weight = 1.0;
-        let growth_rate_avg = growth_rates.iter().sum::<f64>() / growth_rates.len() as f64;
-        if growth_rate_avg > 0.0 {
-            sum_scores += weight;
+        // 环比受季节性影响，仅作参考不计分
+        let series = FiscalSeries::new(stock_fiscal_metricsets);
+        if let (Some(latest_quarter), Some((fiscal_quarter, growth))) =
+            (series.latest_quarter(), series.qoq(field).first())
+            && fiscal_quarter == latest_quarter
+        {
             assessments.push(format!(
-                "Average earning per share growth rate is positive value: {growth_rate_avg}"
-            ));
-        } else {
-            assessments.push(format!(
-                "Average earning per share growth rate is negative value: {growth_rate_avg}"
+                "{name} of the latest quarter changed {growth:.2} from the previous quarter, which may be seasonal"
             ));
         }
-        sum_weights += weight;
     }
 
     let score = if sum_weights > 0.0 {
@@ -248,16 +314,22 @@ async fn analyze_growth(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 6.0),
+        assessments,
+    })
 }
 
 async fn analyze_valuation(
     stock_daily_data: &StockDailyData,
     stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
 ) -> InvmstResult<AnalysisDraft> {
     if stock_fiscal_metricsets.is_empty() {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec!["Insufficient historical data for valuation analysis".to_string()],
         });
     }
@@ -266,54 +338,49 @@ async fn analyze_valuation(
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
 
-    let latest_stock_fiscal_metricsets = stock_fiscal_metricsets.first().unwrap();
-    let (fiscal_quater, _) = latest_stock_fiscal_metricsets;
-
-    let fiscal_date_str = format!(
-        "{}{}",
-        fiscal_quater.year,
-        match fiscal_quater.quarter {
-            Quarter::Q1 => "0331",
-            Quarter::Q2 => "0630",
-            Quarter::Q3 => "0930",
-            Quarter::Q4 => "1231",
+    // 以评估日的价格对最新的基本面估值
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let mut pe = stock_daily_data.get_valuation(&date, &StockValuationFieldName::Pe);
+
+    // 周期性企业按整个周期的平均盈利估值
+    if let Some(cyclicality) = &options.cyclicality {
+        if let (Some(assessment), Some(normalized_eps), Some(price)) = (
+            cyclicality.assessment(),
+            cyclicality.normalized_eps,
+            stock_daily_data.get_valuation(&date, &StockValuationFieldName::Price),
+        ) {
+            pe = (normalized_eps > 0.0).then(|| price / normalized_eps);
+            assessments.push(assessment);
         }
-    );
-    if let Some(date) = utils::datetime::date_from_str(&fiscal_date_str) {
-        let pe = stock_daily_data
-            .daily_valuations
-            .get_latest_value::<f64>(&date, &StockValuationFieldName::Pe.to_string());
-        let peg = stock_daily_data
-            .daily_valuations
-            .get_latest_value::<f64>(&date, &StockValuationFieldName::Peg.to_string());
-
-        if let Some(pe) = pe {
-            let weight = 1.0;
-            if pe < 15.0 {
-                sum_scores += weight;
-                assessments.push("Good P/E".to_string());
-            } else if pe < 25.0 {
-                sum_scores += weight / 2.0;
-                assessments.push("Acceptable P/E".to_string());
-            } else {
-                assessments.push("Unacceptable P/E".to_string());
-            }
-            sum_weights += weight;
+    }
+    let peg = stock_daily_data.get_valuation(&date, &StockValuationFieldName::Peg);
+
+    if let Some(pe) = pe {
+        let weight = 1.0;
+        if pe < 15.0 {
+            sum_scores += weight;
+            assessments.push("Good P/E".to_string());
+        } else if pe < 25.0 {
+            sum_scores += weight / 2.0;
+            assessments.push("Acceptable P/E".to_string());
+        } else {
+            assessments.push("Unacceptable P/E".to_string());
         }
+        sum_weights += weight;
+    }
 
-        if let Some(peg) = peg {
-            let weight = 1.0;
-            if peg < 1.0 {
-                sum_scores += weight;
-                assessments.push("Good PEG".to_string());
-            } else if peg < 2.0 {
-                sum_scores += weight / 2.0;
-                assessments.push("Acceptable PEG".to_string());
-            } else {
-                assessments.push("Unacceptable PEG".to_string());
-            }
-            sum_weights += weight;
+    if let Some(peg) = peg {
+        let weight = 1.0;
+        if peg < 1.0 {
+            sum_scores += weight;
+            assessments.push("Good PEG".to_string());
+        } else if peg < 2.0 {
+            sum_scores += weight / 2.0;
+            assessments.push("Acceptable PEG".to_string());
+        } else {
+            assessments.push("Unacceptable PEG".to_string());
         }
+        sum_weights += weight;
     }
 
     let score = if sum_weights > 0.0 {
@@ -330,10 +397,14 @@ async fn analyze_valuation(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    })
 }
 
-static LLM_SYSTEM: &str = r#"
+pub static LLM_SYSTEM: &str = r#"
 我是彼得·林奇（Peter Lynch），下面是我的投资分析方法论：
 
 ## 核心原则
@@ -348,6 +419,8 @@ static LLM_SYSTEM: &str = r#"
 2. 检视主要指标，如市盈率与增长比率（PEG）
 3. 在一段较长的时间上检视盈利的稳定性
 4. 是否有可控的负债水平
+5. 结合管理层讨论与分析，判断管理层的指引是否具体可信、战略是否专注于主业
+6. 对照分析师的一致预期，机构覆盖少、预期偏低而盈利持续超预期的公司更可能成为被忽视的十倍股
 
 ## 评分等级（百分制）
 - 80-100：卓越企业，价格诱人
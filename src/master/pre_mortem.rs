@@ -0,0 +1,124 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::NaiveDate;
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::stock::{StockDailyData, StockFiscalMetricset, StockInfo},
+    error::InvmstResult,
+    financial::compliance::ComplianceRisk,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    master::{Master, MasterAnalysis, evaluation_context_json},
+    utils,
+};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, strum::Display, strum::EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Likelihood {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FailureMode {
+    pub cause: String,
+    pub likelihood: Likelihood,
+    /// Signal to watch that would show the failure is unfolding
+    pub warning_sign: String,
+}
+
+/// Let the LLM imagine the investment failed in 3 years and list the most likely causes, sorted
+/// by likelihood descending
+pub async fn analyze(
+    stock_info: &StockInfo,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    master_analyses: &HashMap<Master, MasterAnalysis>,
+    compliance_risk: Option<&ComplianceRisk>,
+    date: &NaiveDate,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<Vec<FailureMode>> {
+    let data_json = evaluation_context_json(
+        stock_info,
+        stock_daily_data,
+        stock_fiscal_metricsets,
+        master_analyses,
+        compliance_risk,
+        date,
+    );
+    debug!("[Pre-mortem Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", llm_options).await?;
+
+    let prompt = format!(
+        r#"
+下面是一只股票的基本信息、价格位置、财务摘要、合规风险，以及多位投资大师的分析：
+```
+{prompt_json}
+```
+
+假设现在买入这只股票，3 年后这笔投资彻底失败了。请基于以上数据，推断导致失败的最可能原因，结果以标准的 JSON 数组格式返回，示例如下：
+```
+[
+    {{
+        "cause": "失败的原因",
+        "likelihood": "Low" | "Medium" | "High",
+        "warning_sign": "提前预示该原因正在发生的信号"
+    }}
+]
+```
+
+注意以下几点：
+- 原因必须与上面的数据相关，尽量具体，不要罗列放之四海而皆准的风险。
+- warning_sign 应当是可以跟踪的指标或事件。
+- 列出最多 5 条，不要包含任何额外的解释或文本，仅返回 JSON 数据。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位严谨的风险分析师，擅长通过事前验尸发现投资决策中的盲点。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, llm_options).await?;
+    debug!("[Pre-mortem LLM] {bot_message:?}");
+
+    let json_str = utils::markdown::extract_code_block(&bot_message.content);
+    let items: Vec<serde_json::Value> = serde_json::from_str(&json_str)?;
+    let mut failure_modes: Vec<FailureMode> = items
+        .iter()
+        .filter_map(|item| {
+            Some(FailureMode {
+                cause: item["cause"].as_str()?.to_string(),
+                likelihood: Likelihood::from_str(item["likelihood"].as_str().unwrap_or_default())
+                    .unwrap_or(Likelihood::Medium),
+                warning_sign: item["warning_sign"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        })
+        .collect();
+    failure_modes.sort_by_key(|failure_mode| std::cmp::Reverse(failure_mode.likelihood as u8));
+    failure_modes.truncate(FAILURE_MODES_LIMIT);
+
+    Ok(failure_modes)
+}
+
+static FAILURE_MODES_LIMIT: usize = 5;
@@ -0,0 +1,285 @@
+use std::{fs, path::PathBuf, str::FromStr, sync::LazyLock};
+
+use log::warn;
+use minijinja::Environment;
+use regex::Regex;
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::{
+    APP_DATA_DIR, config,
+    error::{InvmstError, InvmstResult},
+    master::{
+        MASTER_ANALYSIS_JSON_PROMPT, benjamin_graham, david_einhorn, jesse_livermore, john_bogle,
+        li_lu, market, peter_lynch, quant_factor, sectors, stanley_druckenmiller, terry_smith,
+        walter_schloss, warren_buffett,
+    },
+};
+
+/// Prompts that can be customized by template files under the prompts directory, the compiled
+/// defaults are used if the files do not exist
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(ascii_case_insensitive, serialize_all = "kebab-case")]
+pub enum Prompt {
+    /// Format of the answers of the masters
+    AnalysisJson,
+    BenjaminGraham,
+    DavidEinhorn,
+    JesseLivermore,
+    JohnBogle,
+    LiLu,
+    Market,
+    PeterLynch,
+    QuantFactor,
+    Sectors,
+    StanleyDruckenmiller,
+    TerrySmith,
+    WalterSchloss,
+    WarrenBuffett,
+}
+
+/// Variables the templates can interpolate, e.g. `{{ industry }}` or `{% if focus_areas %}`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PromptContext {
+    pub industry: Option<String>,
+    /// Language of LLM answers
+    pub language: Option<String>,
+    pub backward_days: Option<i64>,
+    /// Aspects the user asks to pay special attention to, e.g. "receivables"
+    pub focus_areas: Vec<String>,
+    /// Comment of the user on context the data cannot capture
+    pub note: Option<String>,
+    /// Values of the data source inconsistent with each other, kept but to be used with care
+    pub data_anomalies: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PromptStatus {
+    pub prompt: Prompt,
+    /// Template file if the prompt is customized
+    pub path: Option<PathBuf>,
+    /// Customized from an older version of the default, which may have changed since
+    pub outdated: bool,
+}
+
+impl Prompt {
+    /// The template file is read on every use, so edits take effect without restarting
+    pub fn load(&self) -> String {
+        let path = self.path();
+        if !path.exists() {
+            return self.default_template().to_string();
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let (version, template) = parse_template(&content);
+                if version.is_none_or(|version| version < PROMPT_VERSION) {
+                    warn!(
+                        "[Prompt] '{self}' is customized from an older default, run `invmst prompts reset {self}` to get the latest"
+                    );
+                }
+
+                template
+            }
+            Err(err) => {
+                warn!("[Prompt] Use the default of '{self}': {err}");
+                self.default_template().to_string()
+            }
+        }
+    }
+
+    /// Render the template with the variables, the default is rendered instead if the template
+    /// is broken
+    pub fn render(&self, context: &PromptContext) -> String {
+        render_template(&self.load(), context).unwrap_or_else(|err| {
+            warn!("[Prompt] Use the default of '{self}': {err}");
+            render_template(self.default_template(), context)
+                .unwrap_or_else(|_| self.default_template().to_string())
+        })
+    }
+
+    pub fn path(&self) -> PathBuf {
+        PROMPTS_DIR.join(format!("{self}.md"))
+    }
+
+    fn default_template(&self) -> &'static str {
+        match self {
+            Prompt::AnalysisJson => MASTER_ANALYSIS_JSON_PROMPT,
+            Prompt::BenjaminGraham => benjamin_graham::LLM_SYSTEM,
+            Prompt::DavidEinhorn => david_einhorn::LLM_SYSTEM,
+            Prompt::JesseLivermore => jesse_livermore::LLM_SYSTEM,
+            Prompt::JohnBogle => john_bogle::LLM_SYSTEM,
+            Prompt::LiLu => li_lu::LLM_SYSTEM,
+            Prompt::Market => market::LLM_SYSTEM,
+            Prompt::PeterLynch => peter_lynch::LLM_SYSTEM,
+            Prompt::QuantFactor => quant_factor::LLM_SYSTEM,
+            Prompt::Sectors => sectors::LLM_SYSTEM,
+            Prompt::StanleyDruckenmiller => stanley_druckenmiller::LLM_SYSTEM,
+            Prompt::TerrySmith => terry_smith::LLM_SYSTEM,
+            Prompt::WalterSchloss => walter_schloss::LLM_SYSTEM,
+            Prompt::WarrenBuffett => warren_buffett::LLM_SYSTEM,
+        }
+    }
+}
+
+impl PromptContext {
+    /// Context with the settings of the global config only
+    pub fn from_config() -> Self {
+        Self {
+            language: config::load().ok().and_then(|cfg| cfg.language),
+            ..Default::default()
+        }
+    }
+}
+
+/// Template file of the prompt to edit, created from the default if it does not exist
+pub async fn edit_path(name: &str) -> InvmstResult<PathBuf> {
+    let prompt = parse_name(name)?;
+
+    let path = prompt.path();
+    if !path.exists() {
+        fs::create_dir_all(&*PROMPTS_DIR)?;
+        fs::write(
+            &path,
+            format!(
+                "<!-- version: {PROMPT_VERSION} -->\n{}\n",
+                prompt.default_template().trim()
+            ),
+        )?;
+    }
+
+    Ok(path)
+}
+
+pub async fn list() -> InvmstResult<Vec<PromptStatus>> {
+    let mut statuses: Vec<PromptStatus> = vec![];
+    for prompt in Prompt::iter() {
+        let path = prompt.path();
+        let status = if path.exists() {
+            let (version, _) = parse_template(&fs::read_to_string(&path)?);
+            PromptStatus {
+                prompt,
+                path: Some(path),
+                outdated: version.is_none_or(|version| version < PROMPT_VERSION),
+            }
+        } else {
+            PromptStatus {
+                prompt,
+                path: None,
+                outdated: false,
+            }
+        };
+        statuses.push(status);
+    }
+
+    Ok(statuses)
+}
+
+/// Remove the template files so the defaults are used, all prompts if no name is given, returns
+/// the prompts reset
+pub async fn reset(name: Option<&str>) -> InvmstResult<Vec<Prompt>> {
+    let prompts: Vec<Prompt> = match name {
+        Some(name) => vec![parse_name(name)?],
+        None => Prompt::iter().collect(),
+    };
+
+    let mut reset_prompts: Vec<Prompt> = vec![];
+    for prompt in prompts {
+        let path = prompt.path();
+        if path.exists() {
+            fs::remove_file(&path)?;
+            reset_prompts.push(prompt);
+        }
+    }
+
+    Ok(reset_prompts)
+}
+
+fn parse_name(name: &str) -> InvmstResult<Prompt> {
+    Prompt::from_str(name).map_err(|_| {
+        InvmstError::Invalid(
+            "INVALID_PROMPT_NAME",
+            format!(
+                "Invalid prompt '{name}', available prompts: {}",
+                Prompt::iter()
+                    .map(|prompt| prompt.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        )
+    })
+}
+
+/// Version in the header line and the template after it
+fn parse_template(content: &str) -> (Option<u32>, String) {
+    match VERSION_HEADER_REGEX.captures(content) {
+        Some(captures) => (
+            captures[1].parse().ok(),
+            content[captures[0].len()..].to_string(),
+        ),
+        None => (None, content.to_string()),
+    }
+}
+
+fn render_template(template: &str, context: &PromptContext) -> Result<String, minijinja::Error> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+
+    env.render_str(template, context)
+}
+
+/// Bumped when the defaults change, so that customized templates can be told outdated
+static PROMPT_VERSION: u32 = 4;
+
+static PROMPTS_DIR: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("prompts"));
+
+static VERSION_HEADER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*<!--\s*version:\s*(\d+)\s*-->[^\n]*\n?").unwrap());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        assert_eq!(
+            parse_template("<!-- version: 2 -->\n我是巴菲特"),
+            (Some(2), "我是巴菲特".to_string())
+        );
+        assert_eq!(
+            parse_template("我是巴菲特"),
+            (None, "我是巴菲特".to_string())
+        );
+        assert_eq!(
+            Prompt::from_str("warren-buffett").ok(),
+            Some(Prompt::WarrenBuffett)
+        );
+        assert_eq!(Prompt::AnalysisJson.to_string(), "analysis-json");
+    }
+
+    #[test]
+    fn test_render_template() {
+        let context = PromptContext {
+            industry: Some("白酒".to_string()),
+            language: None,
+            backward_days: Some(730),
+            focus_areas: vec!["应收账款".to_string(), "存货".to_string()],
+            note: None,
+            data_anomalies: vec![],
+        };
+        assert_eq!(
+            render_template(
+                "{{ industry }}行业，最近 {{ backward_days }} 天\n{% for area in focus_areas %}\n- {{ area }}\n{% endfor %}\n{% if language %}\n使用{{ language }}\n{% endif %}\n",
+                &context
+            )
+            .unwrap(),
+            "白酒行业，最近 730 天\n- 应收账款\n- 存货\n"
+        );
+        assert!(render_template("{% if industry %}", &context).is_err());
+        assert!(
+            Prompt::iter()
+                .all(|prompt| render_template(prompt.default_template(), &context).is_ok())
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use chrono::{Duration, Local};
+use log::{debug, warn};
+use serde_json::json;
+
+use crate::{
+    data::stock::StockInfo,
+    error::InvmstError,
+    financial::{
+        Prospect, factors,
+        factors::{FactorInputs, FactorScores},
+        momentum, risk_stats,
+        stock::StockValuationFieldName,
+    },
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    master::{
+        InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData, StockEvents,
+        StockFiscalMetricset,
+        digest::RecentDevelopments,
+        prompt::{Prompt, PromptContext},
+    },
+    utils::datetime::Quarter,
+};
+
+/// Rate by factor z-scores computed from local data, the LLM only writes the explanation
+pub async fn analyze(
+    stock_info: &StockInfo,
+    _stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    let factor_scores = factors::compute(&factor_inputs(
+        stock_daily_data,
+        stock_fiscal_metricsets,
+        options,
+    ));
+    debug!("[Quant Factor Data] {factor_scores:?}");
+
+    let composite = factor_scores.composite().ok_or(InvmstError::NoData(
+        "NO_FACTOR_SCORES",
+        "No factor could be computed".to_string(),
+    ))?;
+    let rating = factors::rating(composite);
+    let prospect = Prospect::from_rating(rating);
+
+    let explanation = match explain(
+        stock_info,
+        &factor_scores,
+        &prospect,
+        rating,
+        options.recent_developments.as_ref(),
+        &options.prompt_context,
+        &options.llm_options,
+    )
+    .await
+    {
+        Ok(explanation) => explanation,
+        Err(err) => {
+            warn!("[Quant Factor LLM] {err}");
+            format!("Composite factor z-score {composite:.2}")
+        }
+    };
+
+    Ok(MasterAnalysis {
+        prospect,
+        rating,
+        explanation,
+        horizon: None,
+        target_price: None,
+        risks: vec![],
+        catalysts: vec![],
+        action: None,
+        // 评分由因子直接计算，没有可校验的 LLM 结论
+        validation: None,
+        assessments: vec![],
+        details: Some(json!({
+            "factors": factor_scores,
+            "composite": composite,
+        })),
+        completeness: Some(factor_scores.completeness()),
+        reasoning: None,
+        provider: None,
+    })
+}
+
+fn factor_inputs(
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> FactorInputs {
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let daily_valuations = &stock_daily_data.daily_valuations;
+
+    let pe_ttm_history: Vec<f64> = daily_valuations
+        .get_values::<f64>(
+            &(date - Duration::days(options.backward_days)),
+            &date,
+            StockValuationFieldName::PeTtm,
+        )
+        .into_iter()
+        .map(|(_, v)| v)
+        .filter(|v| *v > 0.0)
+        .collect();
+
+    let prices = daily_valuations.get_values::<f64>(
+        &(date - Duration::days(VOLATILITY_BACKWARD_DAYS)),
+        &date,
+        StockValuationFieldName::Price,
+    );
+    let volatility = risk_stats::annualized_volatility(&risk_stats::daily_returns(
+        &prices.iter().map(|(_, p)| *p).collect::<Vec<_>>(),
+    ));
+
+    let all_prices = daily_valuations.get_values::<f64>(
+        &(date - Duration::days(MOMENTUM_BACKWARD_DAYS)),
+        &date,
+        StockValuationFieldName::Price,
+    );
+
+    // Quality is measured on annual reports to avoid seasonality of cumulative quarters
+    let financial_summary = stock_fiscal_metricsets
+        .iter()
+        .find(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .or(stock_fiscal_metricsets.first())
+        .map(|(_, stock_metricset)| &stock_metricset.financial_summary);
+
+    FactorInputs {
+        pe_ttm: stock_daily_data.get_valuation(&date, &StockValuationFieldName::PeTtm),
+        pe_ttm_history,
+        return_on_equity: financial_summary.and_then(|s| s.return_on_equity),
+        gross_margin: financial_summary.and_then(|s| s.gross_margin),
+        debt_to_assets: financial_summary.and_then(|s| s.debt_to_assets),
+        return_12m_ex_1m: momentum::compute(&all_prices, &date).return_12m_ex_1m,
+        volatility,
+        market_cap: stock_daily_data.get_valuation(&date, &StockValuationFieldName::MarketCap),
+    }
+}
+
+async fn explain(
+    stock_info: &StockInfo,
+    factor_scores: &FactorScores,
+    prospect: &Prospect,
+    rating: u64,
+    recent_developments: Option<&RecentDevelopments>,
+    prompt_context: &PromptContext,
+    llm_options: &ChatCompletionOptions,
+) -> InvmstResult<String> {
+    let data_json = json!({
+        "basic_information": stock_info,
+        "factor_z_scores": factor_scores,
+        "prospect": prospect.to_string(),
+        "rating": rating,
+        "recent_developments": recent_developments,
+    });
+
+    let prompt = format!(
+        r#"
+下面是多因子模型对投资对象的打分结果，因子得分为 z 分数，正值表示该因子偏好此股票：
+```
+{data_json}
+```
+
+请解释评分的主要驱动因子和拖累因子，不要修改评分。不超过 200 字，直接返回文本，不要使用 JSON 或代码块。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: Prompt::QuantFactor.render(prompt_context),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, llm_options).await?;
+    debug!("[Quant Factor LLM] {bot_message:?}");
+
+    Ok(bot_message.content.trim().to_string())
+}
+
+static MOMENTUM_BACKWARD_DAYS: i64 = 400;
+
+static VOLATILITY_BACKWARD_DAYS: i64 = 365;
+
+pub static LLM_SYSTEM: &str = r#"
+我是一位量化投资者，使用价值、质量、动量、低波动和规模五个因子为股票打分，只相信数据，不做主观判断。
+"#;
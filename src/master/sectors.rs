@@ -0,0 +1,55 @@
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    error::InvmstResult,
+    financial::sector::SectorOverview,
+    llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    master::prompt::{Prompt, PromptContext},
+};
+
+/// Let a macro-oriented master comment on which sectors the current cycle favors
+pub async fn comment_sectors(sector_overviews: &[SectorOverview]) -> InvmstResult<String> {
+    let data_json = json!(sector_overviews);
+    debug!("[Sectors Data] {data_json}");
+
+    let prompt = format!(
+        r#"
+下面是申万一级行业指数按近 3 个月涨跌幅排序的表现，以及市盈率、市净率在历史区间中的分位数：
+```
+{data_json}
+```
+
+请结合行业轮动规律判断当前经济周期所处的阶段，说明哪些行业更受当前周期青睐、哪些行业应当回避。注意以下几点：
+- 同时考虑动量和估值分位，避免追逐估值已处于高位的行业。
+- 不超过 400 字，直接返回文本，不要使用 JSON 或代码块。
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: Prompt::Sectors.render(&PromptContext::from_config()),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, &ChatCompletionOptions::default()).await?;
+    debug!("[Sectors LLM] {bot_message:?}");
+
+    Ok(bot_message.content.trim().to_string())
+}
+
+pub static LLM_SYSTEM: &str = r#"
+你是一位自上而下的宏观投资者，善于从利率、信用、通胀和盈利周期出发，判断资金在行业之间的轮动方向。
+"#;
@@ -0,0 +1,338 @@
+use chrono::{Duration, Local, NaiveDate};
+use log::{debug, warn};
+use serde_json::json;
+
+use crate::{
+    data::stock::StockInfo,
+    financial::{
+        get_sector_overviews, macro_economy, margin, momentum, northbound,
+        northbound::NorthboundSignal, stock::StockValuationFieldName,
+    },
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        prompt::Prompt,
+    },
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    _stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    _stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    let date = options.date.unwrap_or(Local::now().date_naive());
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "analysis_macro_regime": analyze_macro_regime(&date).await,
+        "analysis_sector_momentum": analyze_sector_momentum(stock_info, &date).await,
+        "analysis_stock_momentum": analyze_stock_momentum(stock_daily_data, &date),
+        "margin_trend": margin::analyze(stock_daily_data, &date),
+        "analyst_consensus": options.analyst_consensus,
+        "recent_developments": options.recent_developments,
+    });
+    debug!("[Stanley Druckenmiller Data] {data_json}");
+    let llm_system = Prompt::StanleyDruckenmiller.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+- explanation 中需要给出仓位建议：集中重仓、标准仓位、轻仓试探或不参与，并说明理由。
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(
+        messages,
+        &data_json,
+        "Stanley Druckenmiller",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
+}
+
+async fn analyze_macro_regime(date: &NaiveDate) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let date_prev = *date - Duration::days(REGIME_BACKWARD_DAYS);
+
+    // 利率方向，利率下行意味着货币环境宽松
+    match macro_economy::fetch_cn_bond_yield_10y(&(date_prev - Duration::days(30))).await {
+        Ok(yields) => {
+            let yield_at = |d: &NaiveDate| {
+                yields
+                    .iter()
+                    .rev()
+                    .find(|(date, _)| date <= d)
+                    .map(|(_, v)| *v)
+            };
+            if let (Some(yield_now), Some(yield_prev)) = (yield_at(date), yield_at(&date_prev)) {
+                let change = yield_now - yield_prev;
+                let weight = 1.0;
+                if change < -0.2 {
+                    sum_scores += weight;
+                    assessments.push(format!(
+                        "10-year bond yield falling ({yield_prev:.2}% to {yield_now:.2}%), easing rates"
+                    ));
+                } else if change > 0.2 {
+                    assessments.push(format!(
+                        "10-year bond yield rising ({yield_prev:.2}% to {yield_now:.2}%), tightening rates"
+                    ));
+                } else {
+                    sum_scores += weight / 2.0;
+                    assessments.push(format!(
+                        "10-year bond yield stable ({yield_prev:.2}% to {yield_now:.2}%)"
+                    ));
+                }
+                sum_weights += weight;
+            }
+        }
+        Err(err) => {
+            warn!("[Bond Yield] {err}");
+        }
+    }
+
+    // 流动性，M1 与 M2 增速剪刀差扩大说明资金活化
+    match macro_economy::fetch_money_supply().await {
+        Ok(growths) => {
+            let gap_at = |d: &NaiveDate| {
+                growths
+                    .iter()
+                    .rev()
+                    .find(|(month, _, _)| month <= d)
+                    .map(|(_, m1, m2)| m1 - m2)
+            };
+            if let (Some(gap_now), Some(gap_prev)) = (gap_at(date), gap_at(&date_prev)) {
+                let weight = 1.0;
+                if gap_now > gap_prev + 1.0 {
+                    sum_scores += weight;
+                    assessments.push(format!(
+                        "Liquidity improving, M1-M2 growth gap widened ({gap_prev:.1} to {gap_now:.1})"
+                    ));
+                } else if gap_now < gap_prev - 1.0 {
+                    assessments.push(format!(
+                        "Liquidity deteriorating, M1-M2 growth gap narrowed ({gap_prev:.1} to {gap_now:.1})"
+                    ));
+                } else {
+                    sum_scores += weight / 2.0;
+                    assessments.push(format!(
+                        "Liquidity steady, M1-M2 growth gap ({gap_prev:.1} to {gap_now:.1})"
+                    ));
+                }
+                sum_weights += weight;
+            }
+        }
+        Err(err) => {
+            warn!("[Money Supply] {err}");
+        }
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+async fn analyze_sector_momentum(stock_info: &StockInfo, date: &NaiveDate) -> AnalysisDraft {
+    let Some(industry) = &stock_info.industry else {
+        return AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec!["Unknown industry, sector momentum not assessed".to_string()],
+        };
+    };
+
+    // 申万一级行业按 3 个月收益排名
+    let overviews = match get_sector_overviews(date, SECTOR_BACKWARD_DAYS).await {
+        Ok(overviews) => overviews,
+        Err(err) => {
+            warn!("[Sector Overviews] {err}");
+            return AnalysisDraft {
+                score: None,
+                completeness: Some(0.0),
+                assessments: vec!["Sector data unavailable".to_string()],
+            };
+        }
+    };
+    let Some(rank) = overviews
+        .iter()
+        .position(|o| industry.contains(&o.name) || o.name.contains(industry.as_str()))
+    else {
+        return AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec![format!("Industry '{industry}' not matched to a sector")],
+        };
+    };
+
+    let overview = &overviews[rank];
+    let mut assessments = vec![format!(
+        "Sector '{}' ranks {} of {} by 3-month return ({:.3})",
+        overview.name,
+        rank + 1,
+        overviews.len(),
+        overview.return_3m.unwrap_or_default()
+    )];
+    let score = if rank < overviews.len() / 3 {
+        assessments.push("Sector is among the market leaders".to_string());
+        1.0
+    } else if rank < overviews.len() * 2 / 3 {
+        0.5
+    } else {
+        assessments.push("Sector is among the market laggards".to_string());
+        0.0
+    };
+
+    AnalysisDraft {
+        score: Some(score),
+        completeness: Some(1.0),
+        assessments,
+    }
+}
+
+fn analyze_stock_momentum(stock_daily_data: &StockDailyData, date: &NaiveDate) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let momentum = momentum::compute_from_dataset(
+        &stock_daily_data.daily_valuations,
+        date,
+        StockValuationFieldName::Price,
+    );
+
+    // 12 个月动量，跳过最近 1 个月
+    if let Some(return_12m_ex_1m) = momentum.return_12m_ex_1m {
+        let weight = 1.0;
+        if return_12m_ex_1m > 0.2 {
+            sum_scores += weight;
+            assessments.push(format!("Strong 12-month momentum ({return_12m_ex_1m:.2})"));
+        } else if return_12m_ex_1m > 0.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Positive 12-month momentum ({return_12m_ex_1m:.2})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Negative 12-month momentum ({return_12m_ex_1m:.2})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 近 3 个月的趋势
+    if let Some(return_3m) = momentum.return_3m {
+        let weight = 1.0;
+        if return_3m > 0.1 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Accelerating in the last 3 months ({return_3m:.2})"
+            ));
+        } else if return_3m > 0.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Rising in the last 3 months ({return_3m:.2})"));
+        } else {
+            assessments.push(format!("Falling in the last 3 months ({return_3m:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    // 北向资金的增减持，只有沪深港通标的才有，不计入完整度
+    if let Some(northbound_trend) = northbound::analyze(stock_daily_data, date) {
+        let weight = 0.5;
+        match northbound_trend.signal {
+            NorthboundSignal::Accumulation => {
+                sum_scores += weight;
+                assessments.push(format!(
+                    "Foreign investors accumulating, {}",
+                    northbound_trend.assessment()
+                ));
+            }
+            NorthboundSignal::Neutral => {
+                sum_scores += weight / 2.0;
+                assessments.push(northbound_trend.assessment());
+            }
+            NorthboundSignal::Distribution => {
+                assessments.push(format!(
+                    "Foreign investors distributing, {}",
+                    northbound_trend.assessment()
+                ));
+            }
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+static REGIME_BACKWARD_DAYS: i64 = 182;
+
+static SECTOR_BACKWARD_DAYS: i64 = 400;
+
+pub static LLM_SYSTEM: &str = r#"
+我是斯坦利·德鲁肯米勒（Stanley Druckenmiller），下面是我的投资分析方法论：
+
+## 核心原则
+1. 流动性是驱动市场的最重要因素，不是盈利，关注央行和利率的方向
+2. 顺应宏观大势，在宽松的货币环境中做多，在紧缩时收缩防守
+3. 买入处于领涨行业中的领涨股票，动量本身就是信息
+4. 看对时要下重注，集中持仓而不是分散，判断错误时果断止损
+5. 保住本金永远第一，没有把握时宁可空仓
+
+## 分析方式
+- 先判断宏观环境：利率是上行还是下行，流动性是改善还是收紧
+- 再看股票所在行业在市场中的相对强弱
+- 最后看股票自身的价格动量是否确认了判断，融资余额的快速积累或平仓反映杠杆资金的情绪，北向资金的持续增持或减持反映外资的态度
+- 关注分析师一致预期的上修或下修，盈利预期的变化往往领先于股价，目标价隐含的空间反映市场预期的高低
+- 三者共振时给出激进的仓位建议，相互矛盾时降低仓位或不参与
+"#;
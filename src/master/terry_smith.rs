@@ -0,0 +1,358 @@
+use chrono::Local;
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    data::stock::{StockFinancialSummary, StockInfo},
+    error::InvmstError,
+    financial::{
+        capital_intensity,
+        capital_intensity::{invested_capital, nopat},
+        stock::StockValuationFieldName,
+    },
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, annual_trend, chat_analysis, completeness,
+        describe_trend, holding_prompt, prompt::Prompt,
+    },
+    utils::datetime::Quarter,
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    _stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    // Cash flow and capital expenditure accumulate within the year, so only annual reports are used
+    let annual_summaries: Vec<&StockFinancialSummary> = stock_fiscal_metricsets
+        .iter()
+        .filter(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .map(|(_, stock_metrics)| &stock_metrics.financial_summary)
+        .collect();
+    if annual_summaries.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_STOCK_METRICS",
+            "No annual stock metrics data".to_string(),
+        ));
+    }
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "analysis_return_on_capital": analyze_return_on_capital(&annual_summaries),
+        "analysis_cash_conversion": analyze_cash_conversion(&annual_summaries),
+        "analysis_capital_intensity": analyze_capital_intensity(&annual_summaries),
+        "analysis_valuation": analyze_valuation(stock_daily_data, &annual_summaries, options),
+        "recent_developments": options.recent_developments,
+    });
+    debug!("[Terry Smith Data] {data_json}");
+    let llm_system = Prompt::TerrySmith.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(
+        messages,
+        &data_json,
+        "Terry Smith",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
+}
+
+fn analyze_return_on_capital(annual_summaries: &[&StockFinancialSummary]) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 已动用资本回报率，以税后经营利润除以投入资本近似
+    let returns: Vec<f64> = annual_summaries
+        .iter()
+        .filter_map(
+            |summary| match (nopat(summary), invested_capital(summary)) {
+                (Some(nopat), Some(capital)) if capital > 0.0 => Some(nopat / capital),
+                _ => None,
+            },
+        )
+        .collect();
+    if let Some(latest) = returns.first() {
+        let weight = 1.0;
+        if *latest > 0.2 {
+            sum_scores += weight;
+            assessments.push(format!("High return on capital employed ({latest:.2})"));
+        } else if *latest > 0.12 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Acceptable return on capital employed ({latest:.2})"
+            ));
+        } else {
+            assessments.push(format!("Low return on capital employed ({latest:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    // 回报率的持续性
+    if returns.len() > 1 {
+        let weight = 1.0;
+        let years_high = returns.iter().filter(|r| **r > 0.15).count();
+        if years_high == returns.len() {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Return on capital employed stays high in all {} years",
+                returns.len()
+            ));
+        } else {
+            sum_scores += weight * years_high as f64 / returns.len() as f64;
+            assessments.push(format!(
+                "Return on capital employed is high in {years_high} of {} years",
+                returns.len()
+            ));
+        }
+        sum_weights += weight;
+    }
+    if let Some(fit) = annual_trend(&returns) {
+        assessments.push(describe_trend("Return on capital employed", &fit));
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_cash_conversion(annual_summaries: &[&StockFinancialSummary]) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 自由现金流占净利润的比例
+    let (sum_free_cash_flow, sum_net_profit) = annual_summaries.iter().fold(
+        (0.0, 0.0),
+        |(sum_free_cash_flow, sum_net_profit), summary| match (
+            summary.operating_cash_flow,
+            summary.net_profit,
+        ) {
+            (Some(operating_cash_flow), Some(net_profit)) => (
+                sum_free_cash_flow + operating_cash_flow
+                    - summary.capital_expenditure.unwrap_or(0.0),
+                sum_net_profit + net_profit,
+            ),
+            _ => (sum_free_cash_flow, sum_net_profit),
+        },
+    );
+    if sum_net_profit > 0.0 {
+        let cash_conversion = sum_free_cash_flow / sum_net_profit;
+        let weight = 1.0;
+        if cash_conversion > 0.9 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Profits convert into free cash flow ({cash_conversion:.2})"
+            ));
+        } else if cash_conversion > 0.6 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Moderate cash conversion ({cash_conversion:.2})"));
+        } else {
+            assessments.push(format!("Poor cash conversion ({cash_conversion:.2})"));
+        }
+        sum_weights += weight;
+    } else if sum_net_profit < 0.0 {
+        sum_weights += 1.0;
+        assessments.push("Loss making, no profit to convert into cash".to_string());
+    }
+
+    // 毛利率体现定价能力
+    if let Some(gross_margin) = annual_summaries[0].gross_margin {
+        let weight = 1.0;
+        if gross_margin > 0.5 {
+            sum_scores += weight;
+            assessments.push(format!("High gross margin ({gross_margin:.2})"));
+        } else if gross_margin > 0.3 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Moderate gross margin ({gross_margin:.2})"));
+        } else {
+            assessments.push(format!("Low gross margin ({gross_margin:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_capital_intensity(annual_summaries: &[&StockFinancialSummary]) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let capital_intensity = capital_intensity::compute(annual_summaries);
+
+    // 资本开支占收入比例
+    if let Some(capex_to_sales) = capital_intensity.capex_to_sales {
+        let weight = 1.0;
+        if capex_to_sales < 0.05 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Light capital needs ({capex_to_sales:.2} of sales)"
+            ));
+        } else if capex_to_sales < 0.1 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Moderate capital needs ({capex_to_sales:.2} of sales)"
+            ));
+        } else {
+            assessments.push(format!(
+                "Heavy capital needs ({capex_to_sales:.2} of sales)"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 增量资本回报率
+    if let Some(incremental_return) = capital_intensity.incremental_return_on_capital {
+        let weight = 1.0;
+        if incremental_return > 0.2 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "High return on incremental capital ({incremental_return:.2})"
+            ));
+        } else if incremental_return > 0.1 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Acceptable return on incremental capital ({incremental_return:.2})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Low return on incremental capital ({incremental_return:.2})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_valuation(
+    stock_daily_data: &StockDailyData,
+    annual_summaries: &[&StockFinancialSummary],
+    options: &MasterAnalyzeOptions,
+) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let price = stock_daily_data.get_valuation(&date, &StockValuationFieldName::Price);
+
+    // 自由现金流收益率，不为好公司支付过高的价格
+    if let (Some(free_cash_flow_per_share), Some(price)) =
+        (annual_summaries[0].free_cash_flow_per_share, price)
+        && price > 0.0
+    {
+        let free_cash_flow_yield = free_cash_flow_per_share / price;
+        let weight = 1.0;
+        if free_cash_flow_yield > 0.05 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Attractive free cash flow yield ({free_cash_flow_yield:.3})"
+            ));
+        } else if free_cash_flow_yield > 0.025 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Reasonable free cash flow yield ({free_cash_flow_yield:.3})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Overpaying with low free cash flow yield ({free_cash_flow_yield:.3})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 1.0),
+        assessments,
+    }
+}
+
+pub static LLM_SYSTEM: &str = r#"
+我是特里·史密斯（Terry Smith），下面是我的投资分析方法论：
+
+## 核心原则
+1. 买好公司：已动用资本回报率长期保持在高水平，且能把利润转化为现金
+2. 不要买贵：以自由现金流收益率衡量价格，好公司也不值得任何价格
+3. 什么都不做：买入后长期持有，尽量少交易，让复利发挥作用
+
+## 分析方式
+- 高资本回报率必须是持续的，而不是某一年的偶然结果
+- 现金转化率低的利润值得怀疑，利润最终要体现为现金
+- 偏好资本开支少的轻资产公司，增长不需要大量再投资
+- 高毛利率说明公司有定价能力，能抵御竞争与通胀
+- 回避周期性行业、重资产行业以及依赖杠杆的公司
+"#;
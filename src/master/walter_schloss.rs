@@ -0,0 +1,253 @@
+use chrono::{Duration, Local};
+use log::debug;
+use serde_json::json;
+
+use crate::{
+    data::stock::StockInfo,
+    error::InvmstError,
+    financial::stock::StockValuationFieldName,
+    llm,
+    llm::{ChatMessage, Role},
+    master::{
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, chat_analysis, completeness, holding_prompt,
+        listing_years, prompt::Prompt,
+    },
+    utils::stats,
+};
+
+pub async fn analyze(
+    stock_info: &StockInfo,
+    _stock_events: &StockEvents,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<MasterAnalysis> {
+    if stock_fiscal_metricsets.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_STOCK_METRICS",
+            "No stock metrics data".to_string(),
+        ));
+    }
+
+    let data_json = json!({
+        "basic_information": stock_info,
+        "analysis_cheapness": analyze_cheapness(stock_daily_data, options),
+        "price_position": stock_daily_data.get_price_range(
+            &options.date.unwrap_or(Local::now().date_naive()),
+            options.backward_days,
+        ),
+        "analysis_safety": analyze_safety(stock_info, stock_fiscal_metricsets, options),
+        "recent_developments": options.recent_developments,
+    });
+    debug!("[Walter Schloss Data] {data_json}");
+    let llm_system = Prompt::WalterSchloss.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
+
+    let prompt = format!(
+        r#"
+基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
+```
+{prompt_json}
+```
+
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: llm_system,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    chat_analysis(
+        messages,
+        &data_json,
+        "Walter Schloss",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
+}
+
+fn analyze_cheapness(
+    stock_daily_data: &StockDailyData,
+    options: &MasterAnalyzeOptions,
+) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let pb = stock_daily_data.get_valuation(&date, &StockValuationFieldName::Pb);
+
+    // 市净率相对历史均值
+    let pb_history: Vec<f64> = stock_daily_data
+        .daily_valuations
+        .get_values::<f64>(
+            &(date - Duration::days(options.backward_days)),
+            &date,
+            StockValuationFieldName::Pb,
+        )
+        .into_iter()
+        .map(|(_, v)| v)
+        .filter(|v| *v > 0.0)
+        .collect();
+    if let (Some(pb), Some(pb_avg)) = (pb, stats::mean(&pb_history)) {
+        let weight = 1.0;
+        if pb < pb_avg * 0.8 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "P/B well below historical average ({pb:.2} vs {pb_avg:.2})"
+            ));
+        } else if pb < pb_avg {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "P/B below historical average ({pb:.2} vs {pb_avg:.2})"
+            ));
+        } else {
+            assessments.push(format!(
+                "P/B above historical average ({pb:.2} vs {pb_avg:.2})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    // 绝对市净率
+    if let Some(pb) = pb {
+        let weight = 1.0;
+        if pb < 1.0 {
+            sum_scores += weight;
+            assessments.push(format!("Trading below book value ({pb:.2})"));
+        } else if pb < 1.5 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Trading near book value ({pb:.2})"));
+        } else {
+            assessments.push(format!("Trading well above book value ({pb:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    }
+}
+
+fn analyze_safety(
+    stock_info: &StockInfo,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> AnalysisDraft {
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    let (_, stock_metrics) = stock_fiscal_metricsets.first().unwrap();
+
+    // 负债水平
+    if let Some(debt_to_equity) = stock_metrics.financial_summary.debt_to_equity {
+        let weight = 1.0;
+        if debt_to_equity < 0.5 {
+            sum_scores += weight;
+            assessments.push(format!("Low debt to equity ({debt_to_equity:.2})"));
+        } else if debt_to_equity < 1.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!("Acceptable debt to equity ({debt_to_equity:.2})"));
+        } else {
+            assessments.push(format!("High debt to equity ({debt_to_equity:.2})"));
+        }
+        sum_weights += weight;
+    }
+
+    // 经营历史
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    if let Some(years) = listing_years(stock_info, &date) {
+        let weight = 1.0;
+        if years >= 10.0 {
+            sum_scores += weight;
+            assessments.push(format!("Long operating history ({years:.0} years listed)"));
+        } else if years >= 5.0 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Moderate operating history ({years:.0} years listed)"
+            ));
+        } else {
+            assessments.push(format!("Short operating history ({years:.0} years listed)"));
+        }
+        sum_weights += weight;
+    }
+
+    // 内部人持股
+    if let Some(largest_holder_ratio) = stock_info.largest_holder_ratio {
+        let weight = 1.0;
+        if largest_holder_ratio >= 0.3 {
+            sum_scores += weight;
+            assessments.push(format!(
+                "Owners have significant stake ({largest_holder_ratio:.2})"
+            ));
+        } else if largest_holder_ratio >= 0.1 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Owners have moderate stake ({largest_holder_ratio:.2})"
+            ));
+        } else {
+            assessments.push(format!(
+                "Owners have little stake ({largest_holder_ratio:.2})"
+            ));
+        }
+        sum_weights += weight;
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
+    AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 3.0),
+        assessments,
+    }
+}
+
+pub static LLM_SYSTEM: &str = r#"
+我是沃尔特·施洛斯（Walter Schloss），下面是我的投资分析方法论：
+
+## 核心原则
+1. 只买统计上便宜的股票，不追求伟大的企业，价格低于账面价值最好
+2. 以市净率低于其历史平均水平作为主要的买入信号
+3. 负债要少，低负债让便宜的公司有时间等待价值回归
+4. 偏好经营历史长的公司，长期历史证明了它的生存能力
+5. 偏好管理层和大股东持有大量股份的公司，他们与小股东利益一致
+6. 高度分散持仓，单只股票不求完美，依靠组合的统计优势获利
+
+## 分析方式
+- 不在意短期的盈利波动和行业前景，只关心价格相对资产是否便宜
+- 股价接近历史低位时更有吸引力，不担心买得太早
+- 对看起来乏味甚至暂时陷入困境的公司保持兴趣
+"#;
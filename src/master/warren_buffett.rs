@@ -1,22 +1,32 @@
-use log::debug;
+use chrono::{Duration, Local};
+use log::{debug, warn};
 use serde_json::json;
 
 use crate::{
     data::stock::StockInfo,
     error::InvmstError,
+    financial::{
+        capital_intensity,
+        compensation::{self, Alignment},
+        dcf, dilution,
+        fiscal_series::FiscalSeries,
+        research,
+        stock::StockValuationFieldName,
+    },
     llm,
-    llm::{ChatCompletionOptions, ChatMessage, Role},
+    llm::{ChatMessage, Role},
     master::{
-        AnalysisDraft, InvmstResult, MASTER_ANALYSIS_JSON_PROMPT, MasterAnalysis,
-        MasterAnalyzeOptions, StockDailyData, StockEvents, StockFiscalMetricset,
+        AnalysisDraft, InvmstResult, MasterAnalysis, MasterAnalyzeOptions, StockDailyData,
+        StockEvents, StockFiscalMetricset, annual_trend, chat_analysis, completeness,
+        describe_trend, governance, holding_prompt, prompt::Prompt, trend_direction,
     },
-    utils,
+    utils::{datetime::Quarter, stats},
 };
 
 pub async fn analyze(
     stock_info: &StockInfo,
     stock_events: &StockEvents,
-    _stock_daily_data: &StockDailyData,
+    stock_daily_data: &StockDailyData,
     stock_fiscal_metricsets: &[StockFiscalMetricset],
     options: &MasterAnalyzeOptions,
 ) -> InvmstResult<MasterAnalysis> {
@@ -30,52 +40,76 @@ pub async fn analyze(
     let data_json = json!({
         "basic_information": stock_info,
         "analysis_fundamentals": analyze_fundamentals(stock_fiscal_metricsets).await?,
-        "analysis_consistency": analyze_consistency(stock_fiscal_metricsets).await?,
+        "analysis_consistency": analyze_consistency(stock_events, stock_fiscal_metricsets).await?,
         "analysis_moat": analyze_moat(stock_fiscal_metricsets).await?,
-        "analysis_management": analyze_management(stock_events, options.backward_days).await?,
+        "analysis_capital_intensity": analyze_capital_intensity(stock_fiscal_metricsets).await?,
+        "analysis_intrinsic_value": analyze_intrinsic_value(stock_daily_data, stock_fiscal_metricsets, options).await?,
+        "analysis_management": analyze_management(stock_events, stock_daily_data, stock_fiscal_metricsets, options).await?,
+        "announcement_digest": options.announcement_digest,
+        "recent_developments": options.recent_developments,
+        "compliance_risk": options.compliance_risk,
     });
     debug!("[Warren Buffett Data] {data_json}");
+    let llm_system = Prompt::WarrenBuffett.render(&options.prompt_context);
+    let analysis_json_prompt = Prompt::AnalysisJson.render(&options.prompt_context);
+    let prompt_json = llm::fit_json(&data_json, &llm_system, &options.llm_options).await?;
 
     let prompt = format!(
         r#"
 基于下面的数据，使用我的投资分析方法评估投资对象，结果以标准的 JSON 对象格式返回：
 ```
-{data_json}
+{prompt_json}
 ```
 
-{MASTER_ANALYSIS_JSON_PROMPT}
-"#
+{analysis_json_prompt}
+{}
+"#,
+        holding_prompt(options)
     );
 
     let messages: Vec<ChatMessage> = vec![
         ChatMessage {
             role: Role::System,
-            content: LLM_SYSTEM.to_string(),
+            content: llm_system,
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         },
         ChatMessage {
             role: Role::User,
             content: prompt.to_string(),
             reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
         },
     ];
 
-    let bot_message = llm::chat_completion(&messages, &ChatCompletionOptions::default()).await?;
-    debug!("[Warren Buffett LLM] {bot_message:?}");
-
-    let json_str = utils::markdown::extract_code_block(&bot_message.content);
-    let analysis = MasterAnalysis::from_json(&json_str)?;
-
-    Ok(analysis)
+    chat_analysis(
+        messages,
+        &data_json,
+        "Warren Buffett",
+        options.holding.is_some(),
+        options.toolbox.as_deref(),
+        &options.llm_options,
+    )
+    .await
 }
 
-async fn analyze_consistency(
+async fn analyze_capital_intensity(
     stock_fiscal_metricsets: &[StockFiscalMetricset],
 ) -> InvmstResult<AnalysisDraft> {
-    if stock_fiscal_metricsets.len() < 4 {
+    let annual_summaries: Vec<_> = stock_fiscal_metricsets
+        .iter()
+        .filter(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .map(|(_, stock_metrics)| &stock_metrics.financial_summary)
+        .collect();
+    if annual_summaries.is_empty() {
         return Ok(AnalysisDraft {
             score: None,
-            assessments: vec!["Insufficient historical data for consistency analysis".to_string()],
+            completeness: Some(0.0),
+            assessments: vec![
+                "Insufficient historical data for capital intensity analysis".to_string(),
+            ],
         });
     }
 
@@ -83,67 +117,58 @@ async fn analyze_consistency(
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
 
-    // 净利润持续增长
-    {
-        let mut growth_rates: Vec<f64> = vec![];
-        for i in 0..stock_fiscal_metricsets.len() - 1 {
-            if let (Some(net_profit_current), Some(net_profit_prev)) = (
-                stock_fiscal_metricsets[i].1.financial_summary.net_profit,
-                stock_fiscal_metricsets[i + 1]
-                    .1
-                    .financial_summary
-                    .net_profit,
-            ) {
-                growth_rates.push((net_profit_current - net_profit_prev) / net_profit_prev);
-            }
-        }
+    let capital_intensity = capital_intensity::compute(&annual_summaries);
 
+    // 资本开支占收入比例
+    if let Some(capex_to_sales) = capital_intensity.capex_to_sales {
         let weight = 1.0;
-        let growth_rate_avg = growth_rates.iter().sum::<f64>() / growth_rates.len() as f64;
-        if growth_rate_avg > 0.0 {
+        if capex_to_sales < 0.05 {
             sum_scores += weight;
             assessments.push(format!(
-                "Average net profit growth rate is positive value: {growth_rate_avg}"
+                "Light capital needs ({capex_to_sales:.2} of sales)"
+            ));
+        } else if capex_to_sales < 0.15 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Moderate capital needs ({capex_to_sales:.2} of sales)"
             ));
         } else {
             assessments.push(format!(
-                "Average net profit growth rate is negative value: {growth_rate_avg}"
+                "Heavy capital needs ({capex_to_sales:.2} of sales)"
             ));
         }
         sum_weights += weight;
     }
 
-    // 每股净资产持续增长
-    {
-        let mut growth_rates: Vec<f64> = vec![];
-        for i in 0..stock_fiscal_metricsets.len() - 1 {
-            if let (Some(book_value_per_share_current), Some(book_value_per_share_prev)) = (
-                stock_fiscal_metricsets[i]
-                    .1
-                    .financial_summary
-                    .book_value_per_share,
-                stock_fiscal_metricsets[i + 1]
-                    .1
-                    .financial_summary
-                    .book_value_per_share,
-            ) {
-                growth_rates.push(
-                    (book_value_per_share_current - book_value_per_share_prev)
-                        / book_value_per_share_prev,
-                );
-            }
+    // 资本开支与折旧摊销之比，高于 1 说明在扩张而非仅维持
+    if let Some(capex_to_depreciation) = capital_intensity.capex_to_depreciation {
+        if capex_to_depreciation > 1.5 {
+            assessments.push(format!(
+                "Reinvesting well beyond depreciation ({capex_to_depreciation:.2}x)"
+            ));
+        } else {
+            assessments.push(format!(
+                "Capital expenditure is close to maintenance level ({capex_to_depreciation:.2}x depreciation)"
+            ));
         }
+    }
 
+    // 增量资本回报率
+    if let Some(incremental_return) = capital_intensity.incremental_return_on_capital {
         let weight = 1.0;
-        let growth_rate_avg = growth_rates.iter().sum::<f64>() / growth_rates.len() as f64;
-        if growth_rate_avg > 0.0 {
+        if incremental_return > 0.15 {
             sum_scores += weight;
             assessments.push(format!(
-                "Average book value per share growth rate is positive value: {growth_rate_avg}"
+                "High return on incremental capital ({incremental_return:.2})"
+            ));
+        } else if incremental_return > 0.08 {
+            sum_scores += weight / 2.0;
+            assessments.push(format!(
+                "Acceptable return on incremental capital ({incremental_return:.2})"
             ));
         } else {
             assessments.push(format!(
-                "Average book value per share growth rate is negative value: {growth_rate_avg}"
+                "Low return on incremental capital ({incremental_return:.2})"
             ));
         }
         sum_weights += weight;
@@ -155,6 +180,94 @@ async fn analyze_consistency(
         None
     };
 
+    if let Some(score) = score {
+        if score >= 0.75 {
+            assessments.push("Capital light with profitable reinvestment".to_string());
+        } else {
+            assessments.push("Capital intensive or unprofitable reinvestment".to_string());
+        }
+    }
+
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    })
+}
+
+async fn analyze_consistency(
+    stock_events: &StockEvents,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+) -> InvmstResult<AnalysisDraft> {
+    if stock_fiscal_metricsets.len() < 4 {
+        return Ok(AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec!["Insufficient historical data for consistency analysis".to_string()],
+        });
+    }
+
+    let mut sum_scores: f64 = 0.0;
+    let mut sum_weights: f64 = 0.0;
+    let mut assessments: Vec<String> = vec![];
+
+    // 净利润持续增长
+    {
+        let growth_rates: Vec<f64> = FiscalSeries::new(stock_fiscal_metricsets)
+            .yoy(|summary| summary.net_profit)
+            .into_iter()
+            .map(|(_, growth)| growth)
+            .collect();
+
+        if let Some(growth_rate_avg) = stats::winsorized_mean(&growth_rates, stats::WINSORIZE_LIMIT)
+        {
+            let weight = 1.0;
+            if growth_rate_avg > 0.0 {
+                sum_scores += weight;
+                assessments.push(format!(
+                    "Average net profit growth rate is positive value: {growth_rate_avg}"
+                ));
+            } else {
+                assessments.push(format!(
+                    "Average net profit growth rate is negative value: {growth_rate_avg}"
+                ));
+            }
+            sum_weights += weight;
+        }
+    }
+
+    // 每股净资产持续增长
+    {
+        let growth_rates: Vec<f64> = FiscalSeries::new(stock_fiscal_metricsets)
+            .with_share_changes(&stock_events.share_changes)
+            .yoy(|summary| summary.book_value_per_share)
+            .into_iter()
+            .map(|(_, growth)| growth)
+            .collect();
+
+        if let Some(growth_rate_avg) = stats::winsorized_mean(&growth_rates, stats::WINSORIZE_LIMIT)
+        {
+            let weight = 1.0;
+            if growth_rate_avg > 0.0 {
+                sum_scores += weight;
+                assessments.push(format!(
+                    "Average book value per share growth rate is positive value: {growth_rate_avg}"
+                ));
+            } else {
+                assessments.push(format!(
+                    "Average book value per share growth rate is negative value: {growth_rate_avg}"
+                ));
+            }
+            sum_weights += weight;
+        }
+    }
+
+    let score = if sum_weights > 0.0 {
+        Some(sum_scores / sum_weights)
+    } else {
+        None
+    };
+
     if let Some(score) = score {
         if score >= 0.75 {
             assessments.push("Consistent net profit growth".to_string());
@@ -163,7 +276,11 @@ async fn analyze_consistency(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 2.0),
+        assessments,
+    })
 }
 
 async fn analyze_fundamentals(
@@ -172,6 +289,7 @@ async fn analyze_fundamentals(
     if stock_fiscal_metricsets.is_empty() {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec!["Insufficient historical data for fundamentals analysis".to_string()],
         });
     }
@@ -263,13 +381,133 @@ async fn analyze_fundamentals(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 4.0),
+        assessments,
+    })
+}
+
+async fn analyze_intrinsic_value(
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
+) -> InvmstResult<AnalysisDraft> {
+    // Fiscal metrics accumulate within the year, so only Q4 has the full-year cash flow
+    let cash_flow = stock_fiscal_metricsets
+        .iter()
+        .find(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .and_then(|(_, stock_metrics)| stock_metrics.financial_summary.free_cash_flow_per_share);
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let price = stock_daily_data.get_valuation(&date, &StockValuationFieldName::Price);
+
+    let (Some(cash_flow), Some(price)) = (cash_flow, price) else {
+        return Ok(AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments: vec![
+                "Insufficient historical data for intrinsic value analysis".to_string(),
+            ],
+        });
+    };
+    if cash_flow <= 0.0 {
+        return Ok(AnalysisDraft {
+            score: Some(0.0),
+            completeness: Some(1.0),
+            assessments: vec!["No positive annual cash flow to discount".to_string()],
+        });
+    }
+
+    let assumptions = dcf::load_assumptions();
+    let Some(fair_value) = dcf::estimate(cash_flow, &assumptions, options.monte_carlo) else {
+        return Ok(AnalysisDraft {
+            score: None,
+            completeness: Some(1.0),
+            assessments: vec!["Discount rate must exceed terminal growth".to_string()],
+        });
+    };
+
+    let mut assessments: Vec<String> = vec![];
+
+    // 安全边际，蒙特卡洛模拟时使用中位数
+    let value = fair_value.p50.unwrap_or(fair_value.point);
+    assessments.push(format!(
+        "Discounted cash flow value per share: {:.2}",
+        fair_value.point
+    ));
+    if fair_value.rejected_simulations > 0 {
+        assessments.push(format!(
+            "{} of {} simulations rejected as the discount rate did not exceed terminal growth",
+            fair_value.rejected_simulations, assumptions.simulations
+        ));
+    }
+    if let (Some(p10), Some(p50), Some(p90)) = (fair_value.p10, fair_value.p50, fair_value.p90) {
+        assessments.push(format!(
+            "Simulated fair value P10/P50/P90: {p10:.2} / {p50:.2} / {p90:.2}"
+        ));
+        if price < p10 {
+            assessments.push("Price is below the pessimistic fair value".to_string());
+        } else if price > p90 {
+            assessments.push("Price is above the optimistic fair value".to_string());
+        }
+    }
+
+    // 停牌期间的价格已冻结，只给出估值而不评价安全边际
+    if options
+        .suspension
+        .as_ref()
+        .is_some_and(|suspension| suspension.is_suspended())
+    {
+        assessments.push(
+            "Margin of safety not assessed as the price is frozen by the trading suspension"
+                .to_string(),
+        );
+
+        return Ok(AnalysisDraft {
+            score: None,
+            completeness: Some(0.0),
+            assessments,
+        });
+    }
+
+    // 内在价值不为正时安全边际无意义
+    if value <= 0.0 {
+        assessments.push(format!("No intrinsic value ({value:.2})"));
+
+        return Ok(AnalysisDraft {
+            score: Some(0.0),
+            completeness: Some(1.0),
+            assessments,
+        });
+    }
+
+    let margin_of_safety = (value - price) / value;
+    let score = if margin_of_safety > 0.3 {
+        assessments.push(format!("High margin of safety ({margin_of_safety:.2})"));
+        1.0
+    } else if margin_of_safety > 0.0 {
+        assessments.push(format!("Thin margin of safety ({margin_of_safety:.2})"));
+        0.5
+    } else {
+        assessments.push(format!("No margin of safety ({margin_of_safety:.2})"));
+        0.0
+    };
+
+    Ok(AnalysisDraft {
+        score: Some(score),
+        completeness: Some(1.0),
+        assessments,
+    })
 }
 
 async fn analyze_management(
     stock_events: &StockEvents,
-    backward_days: i64,
+    stock_daily_data: &StockDailyData,
+    stock_fiscal_metricsets: &[StockFiscalMetricset],
+    options: &MasterAnalyzeOptions,
 ) -> InvmstResult<AnalysisDraft> {
+    let backward_days = options.backward_days;
+
     let mut sum_scores: f64 = 0.0;
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
@@ -288,6 +526,124 @@ async fn analyze_management(
         }
     }
 
+    // 股本稀释
+    {
+        let date = options.date.unwrap_or(Local::now().date_naive());
+        let dilution = dilution::compute(
+            &stock_events.share_changes,
+            &(date - Duration::days(backward_days)),
+            &date,
+        );
+
+        if let Some(annual_dilution_rate) = dilution.annual_dilution_rate {
+            let weight = 1.0;
+            if annual_dilution_rate < 0.0 {
+                sum_scores += weight;
+                assessments.push(format!(
+                    "Share count shrinking by {:.1}% a year",
+                    -annual_dilution_rate * 100.0
+                ));
+            } else if annual_dilution_rate > 0.02 || dilution.offerings >= 2 {
+                assessments.push(format!(
+                    "Persistent dilution, share count growing {:.1}% a year with {} offerings",
+                    annual_dilution_rate * 100.0,
+                    dilution.offerings
+                ));
+            } else {
+                sum_scores += weight / 2.0;
+                assessments.push(format!(
+                    "Limited dilution, share count growing {:.1}% a year",
+                    annual_dilution_rate * 100.0
+                ));
+            }
+            sum_weights += weight;
+        }
+    }
+
+    // 薪酬与业绩、股东回报是否匹配
+    {
+        let date = options.date.unwrap_or(Local::now().date_naive());
+        let date_start = date - Duration::days(backward_days);
+        // 区间起点可能早于上市或落在停牌期，取最近的价格
+        let shareholder_return = match (
+            stock_daily_data
+                .daily_valuations
+                .get_nearest_value::<f64>(&date_start, StockValuationFieldName::Price),
+            stock_daily_data.get_valuation(&date, &StockValuationFieldName::Price),
+        ) {
+            (Some(price_start), Some(price_end)) if price_start > 0.0 => {
+                let dividends: f64 = stock_events
+                    .dividends
+                    .iter()
+                    .map(|dividend| dividend.dividend_per_share)
+                    .sum();
+                Some((price_end + dividends) / price_start - 1.0)
+            }
+            _ => None,
+        };
+
+        let annual_summaries: Vec<_> = stock_fiscal_metricsets
+            .iter()
+            .filter(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+            .map(|(_, metrics)| &metrics.financial_summary)
+            .collect();
+        let compensation = compensation::compute(&annual_summaries, shareholder_return);
+
+        if let (Some(alignment), Some(compensation_growth), Some(net_profit_growth)) = (
+            compensation.alignment(),
+            compensation.compensation_growth,
+            compensation.net_profit_growth,
+        ) {
+            let weight = 1.0;
+            match alignment {
+                Alignment::Aligned => {
+                    sum_scores += weight;
+                    assessments.push(format!(
+                        "Compensation growth ({compensation_growth:.2}) does not exceed net profit growth ({net_profit_growth:.2})"
+                    ));
+                }
+                Alignment::Neutral => {
+                    sum_scores += weight / 2.0;
+                    assessments.push(format!(
+                        "Compensation growth ({compensation_growth:.2}) slightly exceeds net profit growth ({net_profit_growth:.2})"
+                    ));
+                }
+                Alignment::Misaligned => {
+                    assessments.push(format!(
+                        "Compensation growth ({compensation_growth:.2}) far exceeds net profit growth ({net_profit_growth:.2}) and shareholder return"
+                    ));
+                }
+            }
+            sum_weights += weight;
+        }
+    }
+
+    // 公司治理风险
+    if !stock_events.announcements.is_empty() {
+        match governance::classify_red_flags(&stock_events.announcements, &options.llm_options)
+            .await
+        {
+            Ok(red_flags) => {
+                let weight = 1.0;
+                if red_flags.is_empty() {
+                    sum_scores += weight;
+                    assessments.push("No governance red flags in announcements".to_string());
+                } else {
+                    for red_flag in &red_flags {
+                        assessments.push(format!(
+                            "Governance red flag: {} ({})",
+                            red_flag.title, red_flag.reason
+                        ));
+                    }
+                }
+                sum_weights += weight;
+            }
+            Err(err) => {
+                warn!("[Governance] {err}");
+            }
+        }
+    }
+
     let score = if sum_weights > 0.0 {
         Some(sum_scores / sum_weights)
     } else {
@@ -302,7 +658,11 @@ async fn analyze_management(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, 4.0),
+        assessments,
+    })
 }
 
 async fn analyze_moat(
@@ -311,6 +671,7 @@ async fn analyze_moat(
     if stock_fiscal_metricsets.len() < 4 {
         return Ok(AnalysisDraft {
             score: None,
+            completeness: Some(0.0),
             assessments: vec!["Insufficient historical data for moat analysis".to_string()],
         });
     }
@@ -318,6 +679,8 @@ async fn analyze_moat(
     let mut sum_scores: f64 = 0.0;
     let mut sum_weights: f64 = 0.0;
     let mut assessments: Vec<String> = vec![];
+    // 研发资本化的分析仅适用于研发密集型企业
+    let mut expected_weights: f64 = 4.0;
 
     let roes: Vec<f64> = stock_fiscal_metricsets
         .iter()
@@ -327,6 +690,11 @@ async fn analyze_moat(
         .iter()
         .filter_map(|(_, metrics)| metrics.financial_summary.operating_margin)
         .collect();
+    let annual_summaries: Vec<_> = stock_fiscal_metricsets
+        .iter()
+        .filter(|(fiscal_quarter, _)| fiscal_quarter.quarter == Quarter::Q4)
+        .map(|(fiscal_quarter, metrics)| (fiscal_quarter.year, &metrics.financial_summary))
+        .collect();
 
     // 持续的高资本回报率
     {
@@ -343,29 +711,72 @@ async fn analyze_moat(
             }
             sum_weights += weight;
         }
+
+        let annual_roes: Vec<f64> = annual_summaries
+            .iter()
+            .filter_map(|(_, summary)| summary.return_on_equity)
+            .collect();
+        if let Some(fit) = annual_trend(&annual_roes) {
+            assessments.push(describe_trend("ROE", &fit));
+        }
     }
 
     // 定价权（稳定的高利润率）
     {
         if operating_margins.len() >= 4 {
-            let avg = operating_margins.iter().sum::<f64>() / operating_margins.len() as f64;
-
-            let recent_operating_margins = operating_margins[..3].to_vec();
-            let recent_avg = recent_operating_margins.iter().sum::<f64>()
-                / recent_operating_margins.len() as f64;
+            // 用中位数避免单个季度的异常利润率改变判断
+            let avg = stats::median(&operating_margins).unwrap_or_default();
 
-            let early_operating_margins = operating_margins[operating_margins.len() - 3..].to_vec();
-            let early_avg =
-                early_operating_margins.iter().sum::<f64>() / early_operating_margins.len() as f64;
+            // 年度利润率的回归趋势，明确下滑说明定价权在削弱
+            let annual_operating_margins: Vec<f64> = annual_summaries
+                .iter()
+                .filter_map(|(_, summary)| summary.operating_margin)
+                .collect();
+            let operating_margin_trend = annual_trend(&annual_operating_margins);
+            let declining = operating_margin_trend.is_some_and(|fit| trend_direction(&fit) < 0);
 
             let weight = 1.0;
-            if avg >= 0.15 && recent_avg >= avg * 0.8 && early_avg >= 0.8 {
+            if avg >= 0.15 && !declining {
                 sum_scores += weight;
                 assessments.push("Strong pricing power".to_string());
             } else {
                 assessments.push("Weak pricing power".to_string());
             }
             sum_weights += weight;
+
+            if let Some(fit) = operating_margin_trend {
+                assessments.push(describe_trend("Operating margin", &fit));
+            }
+        }
+    }
+
+    // 研发密集型企业，研发费用资本化后再看资本回报率
+    {
+        let research_intensity = research::compute(&annual_summaries);
+
+        if let (Some((_, research_to_sales)), Some(adjusted_return_on_capital)) = (
+            research_intensity.research_to_sales.first(),
+            research_intensity.adjusted_return_on_capital,
+        ) {
+            if *research_to_sales >= 0.03 {
+                let weight = 1.0;
+                expected_weights += weight;
+                if adjusted_return_on_capital >= 0.15 {
+                    sum_scores += weight;
+                    assessments.push(format!(
+                        "High R&D-adjusted return on capital ({adjusted_return_on_capital:.2}) with R&D at {research_to_sales:.2} of sales"
+                    ));
+                } else {
+                    assessments.push(format!(
+                        "Low R&D-adjusted return on capital ({adjusted_return_on_capital:.2}) with R&D at {research_to_sales:.2} of sales"
+                    ));
+                }
+                sum_weights += weight;
+
+                if let Some(fit) = research_intensity.trend {
+                    assessments.push(describe_trend("R&D intensity", &fit));
+                }
+            }
         }
     }
 
@@ -391,23 +802,14 @@ async fn analyze_moat(
     // 竞争优势（稳定的利润率和资本回报率）
     {
         if roes.len() >= 4 && operating_margins.len() >= 4 {
-            let roe_avg = roes.iter().sum::<f64>() / roes.len() as f64;
-            let roe_std = utils::stats::std(&roes);
-            let roe_stability = if let Some(roe_std) = roe_std {
-                1.0 - (roe_std) / roe_avg
-            } else {
-                0.0
-            };
-
-            let operating_margin_avg =
-                operating_margins.iter().sum::<f64>() / operating_margins.len() as f64;
-            let operating_margin_std = utils::stats::std(&operating_margins);
-            let operating_margin_stability =
-                if let Some(operating_margin_std) = operating_margin_std {
-                    1.0 - (operating_margin_std) / operating_margin_avg
-                } else {
-                    0.0
+            // 用中位数和 MAD 衡量波动，避免单个异常季度掩盖整体的稳定性
+            let stability =
+                |values: &[f64]| match (stats::median(values), stats::robust_std(values)) {
+                    (Some(median), Some(std)) => 1.0 - std / median,
+                    _ => 0.0,
                 };
+            let roe_stability = stability(&roes);
+            let operating_margin_stability = stability(&operating_margins);
 
             let overall_stability = (roe_stability + operating_margin_stability) / 2.0;
 
@@ -437,10 +839,14 @@ async fn analyze_moat(
         }
     }
 
-    Ok(AnalysisDraft { score, assessments })
+    Ok(AnalysisDraft {
+        score,
+        completeness: completeness(sum_weights, expected_weights),
+        assessments,
+    })
 }
 
-static LLM_SYSTEM: &str = r#"
+pub static LLM_SYSTEM: &str = r#"
 我是沃伦·巴菲特（Warren Buffett），下面是我的投资分析方法论：
 
 **核心原则**
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{Duration, Local, NaiveDate};
+use log::warn;
+use serde::Serialize;
+
+use crate::{
+    data::stock::{StockDailyData, StockFinancialSummary},
+    error::InvmstResult,
+    evaluate::summarize_price,
+    financial::{
+        enterprise_value::EnterpriseValue, get_stock_daily_valuations, get_stock_enterprise_value,
+        get_stock_fiscal_metricset, get_stock_momentum, get_stock_quote, get_stock_risk_stats,
+        momentum::Momentum, risk_stats::RiskStats, stock::StockValuationFieldName,
+    },
+    ticker::Ticker,
+    utils::datetime::{FiscalQuarter, Quarter, prev_fiscal_quarter},
+};
+
+#[derive(Clone, Default)]
+pub struct MetricsOptions {
+    pub date: Option<NaiveDate>,
+    /// Index to compute beta against, e.g. 'sh000300'
+    pub benchmark: Option<String>,
+    /// Index whose constituents the relative strength is ranked against, e.g. 'sh000300'
+    pub rs_universe: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Metrics {
+    pub ticker: String,
+    pub date: NaiveDate,
+    pub momentum: Momentum,
+    pub risk_stats: RiskStats,
+    pub enterprise_value: EnterpriseValue,
+    pub trends: MetricsTrends,
+}
+
+/// Recent history of the metrics for sparklines, sorted ascending
+#[derive(Debug, Default, Serialize)]
+pub struct MetricsTrends {
+    /// Daily prices of the backward days
+    pub prices: Vec<f64>,
+    /// Fiscal year and the return on equity of the year
+    pub return_on_equity: Vec<(i32, f64)>,
+    /// Fiscal year and the revenue growth of the year
+    pub revenue_growth: Vec<(i32, f64)>,
+}
+
+pub async fn compute(ticker: &str, options: &MetricsOptions) -> InvmstResult<Metrics> {
+    let ticker = Ticker::from_str(ticker)?;
+    let date = options.date.unwrap_or(Local::now().date_naive());
+
+    let momentum = get_stock_momentum(&ticker, &date, options.rs_universe.as_deref()).await?;
+    let risk_stats = get_stock_risk_stats(
+        &ticker,
+        &date,
+        METRICS_BACKWARD_DAYS,
+        options.benchmark.as_deref(),
+    )
+    .await?;
+    // 企业价值依赖额外的报表接口，失败时留空而不影响其他指标
+    let enterprise_value = match get_stock_enterprise_value(&ticker, &date).await {
+        Ok(enterprise_value) => enterprise_value,
+        Err(err) => {
+            warn!("[Metrics] Unable to compute the enterprise value of {ticker}: {err}");
+            EnterpriseValue::default()
+        }
+    };
+    let trends = compute_trends(&ticker, &date).await?;
+
+    Ok(Metrics {
+        ticker: ticker.to_string(),
+        date,
+        momentum,
+        risk_stats,
+        enterprise_value,
+        trends,
+    })
+}
+
+async fn compute_trends(ticker: &Ticker, date: &NaiveDate) -> InvmstResult<MetricsTrends> {
+    let prices: Vec<f64> = get_stock_daily_valuations(ticker)
+        .await?
+        .get_values::<f64>(
+            &(*date - Duration::days(METRICS_BACKWARD_DAYS)),
+            date,
+            StockValuationFieldName::Price,
+        )
+        .into_iter()
+        .map(|(_, price)| price)
+        .collect();
+
+    // 季度数据为年内累计，只取年报以便逐年比较
+    let latest_year = latest_annual_year(date);
+
+    let mut return_on_equity: Vec<(i32, f64)> = vec![];
+    let mut revenue_growth: Vec<(i32, f64)> = vec![];
+    for year in (latest_year - TRENDS_YEARS + 1)..=latest_year {
+        match get_stock_fiscal_metricset(ticker, Some(FiscalQuarter::new(year, Quarter::Q4))).await
+        {
+            Ok((_, metricset)) => {
+                let financial_summary = &metricset.financial_summary;
+                if let Some(value) = financial_summary.return_on_equity {
+                    return_on_equity.push((year, value));
+                }
+                if let Some(value) = financial_summary.revenue_growth {
+                    revenue_growth.push((year, value));
+                }
+            }
+            Err(err) => warn!("[Metrics] Unable to fetch fiscal metrics of {year}: {err}"),
+        }
+    }
+
+    Ok(MetricsTrends {
+        prices,
+        return_on_equity,
+        revenue_growth,
+    })
+}
+
+/// Latest values of the metrics by name, the variables of alert conditions. Only the daily
+/// valuations are required, metrics unavailable are left out.
+pub async fn snapshot(ticker: &Ticker) -> InvmstResult<HashMap<String, f64>> {
+    let date = Local::now().date_naive();
+
+    let daily_valuations = get_stock_daily_valuations(ticker).await?;
+    let quote = match get_stock_quote(ticker).await {
+        Ok(quote) => Some(quote),
+        Err(err) => {
+            warn!("[Quote] {err}");
+            None
+        }
+    };
+    let stock_daily_data = StockDailyData {
+        daily_valuations,
+        quote,
+        daily_bars: vec![],
+        margin_balances: None,
+        northbound_holdings: None,
+    };
+    let price_summary = summarize_price(&stock_daily_data, &date);
+    let valuation = |field_name| stock_daily_data.get_valuation(&date, &field_name);
+
+    let momentum = get_stock_momentum(ticker, &date, None).await?;
+    let risk_stats = get_stock_risk_stats(ticker, &date, METRICS_BACKWARD_DAYS, None).await?;
+
+    let year = latest_annual_year(&date);
+    let financial_summary =
+        match get_stock_fiscal_metricset(ticker, Some(FiscalQuarter::new(year, Quarter::Q4))).await
+        {
+            Ok((_, metricset)) => Some(metricset.financial_summary),
+            Err(err) => {
+                warn!("[Metrics] Unable to fetch fiscal metrics of {year}: {err}");
+                None
+            }
+        };
+    let annual = |value: fn(&StockFinancialSummary) -> Option<f64>| {
+        financial_summary.as_ref().and_then(value)
+    };
+
+    let values: [(&str, Option<f64>); SNAPSHOT_VARIABLES.len()] = [
+        ("price", price_summary.as_ref().map(|p| p.price)),
+        (
+            "change_rate",
+            price_summary.as_ref().and_then(|p| p.change_rate),
+        ),
+        ("pe", valuation(StockValuationFieldName::PeTtm)),
+        ("pb", valuation(StockValuationFieldName::Pb)),
+        ("ps", valuation(StockValuationFieldName::Ps)),
+        ("market_cap", valuation(StockValuationFieldName::MarketCap)),
+        ("return_1m", momentum.return_1m),
+        ("return_3m", momentum.return_3m),
+        ("return_6m", momentum.return_6m),
+        ("return_12m", momentum.return_12m),
+        ("volatility", risk_stats.volatility),
+        ("max_drawdown", risk_stats.max_drawdown),
+        ("roe", annual(|s| s.return_on_equity)),
+        ("revenue_growth", annual(|s| s.revenue_growth)),
+        ("gross_margin", annual(|s| s.gross_margin)),
+        ("net_margin", annual(|s| s.net_margin)),
+        ("debt_to_assets", annual(|s| s.debt_to_assets)),
+    ];
+
+    Ok(values
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value?)))
+        .collect())
+}
+
+/// Fiscal year of the latest annual report expected to be published as of the date
+fn latest_annual_year(date: &NaiveDate) -> i32 {
+    let fiscal_quarter = prev_fiscal_quarter(Some(date));
+    if fiscal_quarter.quarter == Quarter::Q4 {
+        fiscal_quarter.year
+    } else {
+        fiscal_quarter.year - 1
+    }
+}
+
+static METRICS_BACKWARD_DAYS: i64 = 365;
+
+/// Names of the metrics in the snapshot, ratios such as ROE and returns are fractions
+pub static SNAPSHOT_VARIABLES: [&str; 17] = [
+    "price",
+    "change_rate",
+    "pe",
+    "pb",
+    "ps",
+    "market_cap",
+    "return_1m",
+    "return_3m",
+    "return_6m",
+    "return_12m",
+    "volatility",
+    "max_drawdown",
+    "roe",
+    "revenue_growth",
+    "gross_margin",
+    "net_margin",
+    "debt_to_assets",
+];
+
+static TRENDS_YEARS: i32 = 5;
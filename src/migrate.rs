@@ -0,0 +1,240 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    APP_DATA_DIR,
+    error::{InvmstError, InvmstResult},
+    evaluate::Evaluation,
+    history::HISTORY_DIR,
+    llm::CHAT_CONFIG_PATH,
+};
+
+/// Version of the layout and formats of the data directory, stamped after migrations
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DataDirVersion {
+    schema_version: u32,
+}
+
+/// Bring the data directory up to the current schema version, returns what has been migrated.
+/// A directory without stamp is stamped directly if empty, or treated as version 0 otherwise.
+pub async fn run() -> InvmstResult<Vec<String>> {
+    let version = if VERSION_PATH.exists() {
+        confy::load_path::<DataDirVersion>(&*VERSION_PATH)?.schema_version
+    } else if is_empty_dir(&APP_DATA_DIR) {
+        stamp()?;
+        return Ok(vec![]);
+    } else {
+        0
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(InvmstError::Invalid(
+            "DATA_DIR_TOO_NEW",
+            format!(
+                "Data directory '{}' is of schema version {version}, newer than {SCHEMA_VERSION} supported, please upgrade",
+                APP_DATA_DIR.display()
+            ),
+        ));
+    }
+
+    let mut notes: Vec<String> = vec![];
+    if version < 1 {
+        notes.push(migrate_history_v1(&HISTORY_DIR)?);
+        notes.extend(migrate_chat_config_v1(&CHAT_CONFIG_PATH)?);
+    }
+
+    if version < SCHEMA_VERSION {
+        notes.extend(clear_cache(&CACHE_DIR)?);
+        stamp()?;
+    }
+
+    Ok(notes)
+}
+
+fn is_empty_dir(dir: &Path) -> bool {
+    fs::read_dir(dir).map_or(true, |mut entries| entries.next().is_none())
+}
+
+fn stamp() -> InvmstResult<()> {
+    fs::create_dir_all(&*APP_DATA_DIR)?;
+    confy::store_path(
+        &*VERSION_PATH,
+        DataDirVersion {
+            schema_version: SCHEMA_VERSION,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Caches are rebuilt rather than migrated, so they are dropped whenever the schema changes
+fn clear_cache(cache_dir: &Path) -> InvmstResult<Option<String>> {
+    if !cache_dir.exists() {
+        return Ok(None);
+    }
+
+    fs::remove_dir_all(cache_dir)?;
+
+    Ok(Some(format!("Cleared cache '{}'", cache_dir.display())))
+}
+
+/// Early versions stored a placeholder chat config with empty values when the LLM was not yet
+/// configured, which is now rejected as invalid, so the placeholder is removed
+fn migrate_chat_config_v1(path: &Path) -> InvmstResult<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let Ok(table) = fs::read_to_string(path)?.parse::<toml::Table>() else {
+        return Ok(None);
+    };
+    let is_placeholder = ["base_url", "api_key", "model"].iter().all(|key| {
+        table
+            .get(*key)
+            .and_then(|value| value.as_str())
+            .is_none_or(|value| value.trim().is_empty())
+    });
+    if !is_placeholder {
+        return Ok(None);
+    }
+
+    fs::remove_file(path)?;
+
+    Ok(Some(format!(
+        "Removed the unconfigured LLM placeholder '{}'",
+        path.display()
+    )))
+}
+
+/// Fields added to evaluations over time are filled with defaults, so saved evaluations have the
+/// same shape as new ones for tools reading the files directly
+fn migrate_history_v1(history_dir: &Path) -> InvmstResult<String> {
+    let mut migrated_count = 0;
+    if history_dir.exists() {
+        for entry in fs::read_dir(history_dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            // 无法解析的文件保持原样，留给用户处理
+            match serde_json::from_slice::<Value>(&fs::read(&path)?)
+                .map_err(InvmstError::from)
+                .and_then(upgrade_evaluation)
+            {
+                Ok(evaluation) => {
+                    fs::write(&path, serde_json::to_vec_pretty(&evaluation)?)?;
+                    migrated_count += 1;
+                }
+                Err(err) => {
+                    warn!("[Migrate] Skip '{}': {err}", path.display());
+                }
+            }
+        }
+    }
+
+    Ok(format!(
+        "Migrated {migrated_count} saved evaluations to schema version 1"
+    ))
+}
+
+/// Fill the missing fields of a saved evaluation, fields unknown to this version are kept as they
+/// are rather than dropped by the round trip
+fn upgrade_evaluation(raw: Value) -> InvmstResult<Value> {
+    let evaluation: Evaluation = serde_json::from_value(raw.clone())?;
+
+    let mut upgraded = serde_json::to_value(&evaluation)?;
+    if let (Value::Object(raw), Value::Object(fields)) = (raw, &mut upgraded) {
+        for (key, value) in raw {
+            fields.entry(key).or_insert(value);
+        }
+    }
+
+    Ok(upgraded)
+}
+
+static CACHE_DIR: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("cache"));
+
+static SCHEMA_VERSION: u32 = 1;
+
+static VERSION_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("version.toml"));
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::master::Master;
+
+    #[test]
+    fn test_migrate_chat_config_v1() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        let path = dir.join("llm-chat.toml");
+        assert!(migrate_chat_config_v1(&path).unwrap().is_none());
+
+        fs::write(
+            &path,
+            "protocol = \"OpenAI\"\nbase_url = \"https://api.openai.com/v1\"\napi_key = \"sk-xxx\"\nmodel = \"gpt-4o\"\n",
+        )
+        .unwrap();
+        assert!(migrate_chat_config_v1(&path).unwrap().is_none());
+        assert!(path.exists());
+
+        fs::write(
+            &path,
+            "protocol = \"OpenAI\"\nbase_url = \"\"\napi_key = \"\"\nmodel = \"\"\n",
+        )
+        .unwrap();
+        assert!(migrate_chat_config_v1(&path).unwrap().is_some());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_migrate_history_v1() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        let evaluation = Evaluation::with_ratings(
+            "20250102150000000-600900",
+            NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+            &[(Master::WarrenBuffett, 80)],
+        );
+        let mut raw = serde_json::to_value(&evaluation).unwrap();
+        raw["future_field"] = Value::from("kept");
+        let path = dir.join(format!("{}.json", evaluation.id));
+        fs::write(&path, serde_json::to_vec(&raw).unwrap()).unwrap();
+        fs::write(dir.join("corrupt.json"), b"{").unwrap();
+
+        assert_eq!(
+            migrate_history_v1(dir).unwrap(),
+            "Migrated 1 saved evaluations to schema version 1"
+        );
+
+        let migrated: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(migrated["future_field"], "kept");
+        assert_eq!(migrated["id"], evaluation.id.as_str());
+        assert_eq!(fs::read(dir.join("corrupt.json")).unwrap(), b"{");
+
+        assert!(migrate_history_v1(&dir.join("missing")).is_ok());
+    }
+
+    #[test]
+    fn test_clear_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join("cache");
+        fs::create_dir_all(dir.join("daily_valuations")).unwrap();
+
+        assert!(clear_cache(&dir).unwrap().is_some());
+        assert!(!dir.exists());
+        assert!(clear_cache(&dir).unwrap().is_none());
+    }
+}
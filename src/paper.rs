@@ -0,0 +1,415 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::LazyLock};
+
+use chrono::{Local, NaiveDate};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    APP_DATA_DIR,
+    error::{InvmstError, InvmstResult},
+    evaluate::{self, EvaluateOptions, Evaluation},
+    financial::{get_stock_daily_valuations, stock::StockValuationFieldName},
+    journal::TradeAction,
+    ticker::Ticker,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaperRules {
+    /// Buy when the consensus rating is greater than or equal to this value
+    pub buy_rating: u64,
+    /// Sell all shares when the consensus rating is less than or equal to this value
+    pub sell_rating: u64,
+    /// Fraction of NAV to allocate on each buy
+    pub position_ratio: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaperAccount {
+    pub rules: PaperRules,
+    pub initial_cash: f64,
+    pub cash: f64,
+    pub positions: Vec<PaperPosition>,
+    pub trades: Vec<PaperTrade>,
+    pub navs: Vec<PaperNav>,
+    /// Realized profit credited to each master that supported the buy
+    pub realized_attribution: HashMap<String, f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaperPosition {
+    pub ticker: String,
+    pub shares: f64,
+    pub cost: f64,
+    pub masters: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaperTrade {
+    pub date: NaiveDate,
+    pub ticker: String,
+    pub action: TradeAction,
+    pub shares: f64,
+    pub price: f64,
+    pub rating: u64,
+    pub evaluation_id: String,
+}
+
+/// Trades of a step and the tickers skipped because their evaluation failed
+#[derive(Debug)]
+pub struct PaperStep {
+    pub trades: Vec<PaperTrade>,
+    pub errors: Vec<(String, InvmstError)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaperNav {
+    pub date: NaiveDate,
+    pub nav: f64,
+}
+
+#[derive(Debug)]
+pub struct PaperReport {
+    pub account: PaperAccount,
+    pub nav: f64,
+    pub return_rate: f64,
+    /// Total traded value divided by the average NAV
+    pub turnover: f64,
+    /// Realized and unrealized profit credited to each master, sorted descending
+    pub attribution: Vec<(String, f64)>,
+}
+
+pub async fn reset(cash: f64, rules: PaperRules) -> InvmstResult<PaperAccount> {
+    if cash <= 0.0 {
+        return Err(InvmstError::Invalid(
+            "INVALID_CASH",
+            format!("Initial cash must be positive rather than {cash}"),
+        ));
+    }
+
+    if rules.sell_rating >= rules.buy_rating {
+        return Err(InvmstError::Invalid(
+            "INVALID_PAPER_RULES",
+            format!(
+                "Sell rating {} must be less than buy rating {}",
+                rules.sell_rating, rules.buy_rating
+            ),
+        ));
+    }
+
+    if rules.position_ratio <= 0.0 || rules.position_ratio > 1.0 {
+        return Err(InvmstError::Invalid(
+            "INVALID_PAPER_RULES",
+            format!(
+                "Position ratio must be in (0, 1] rather than {}",
+                rules.position_ratio
+            ),
+        ));
+    }
+
+    let account = PaperAccount::new(cash, rules);
+    confy::store_path(&*PAPER_CONFIG_PATH, &account)?;
+
+    Ok(account)
+}
+
+pub async fn report() -> InvmstResult<PaperReport> {
+    let account: PaperAccount = confy::load_path(&*PAPER_CONFIG_PATH)?;
+
+    let today = Local::now().date_naive();
+    let mut prices: HashMap<String, f64> = HashMap::new();
+    for position in &account.positions {
+        if let Some(price) = fetch_price(&position.ticker, &today).await? {
+            prices.insert(position.ticker.clone(), price);
+        }
+    }
+
+    Ok(account.report(&prices))
+}
+
+/// Evaluate tickers and trade on the consensus ratings according to the rules, tickers failed to
+/// evaluate are skipped unless all fail
+pub async fn step(tickers: &[String], options: &EvaluateOptions) -> InvmstResult<PaperStep> {
+    if tickers.is_empty() {
+        return Err(InvmstError::Required(
+            "TICKER_REQUIRED",
+            "At least one ticker is required".to_string(),
+        ));
+    }
+
+    let mut account: PaperAccount = confy::load_path(&*PAPER_CONFIG_PATH)?;
+
+    let mut evaluations: Vec<Evaluation> = vec![];
+    let mut errors: Vec<(String, InvmstError)> = vec![];
+    for ticker in tickers {
+        match evaluate::run(ticker, options).await {
+            Ok(evaluation) => evaluations.push(evaluation),
+            Err(err) => {
+                warn!("[Paper] Unable to evaluate {ticker}: {err}");
+                errors.push((ticker.to_string(), err));
+            }
+        }
+    }
+    if evaluations.is_empty() {
+        if let Some((_, err)) = errors.pop() {
+            return Err(err);
+        }
+    }
+
+    let date = options.date.unwrap_or(Local::now().date_naive());
+    let mut prices: HashMap<String, f64> = HashMap::new();
+    for ticker in evaluations
+        .iter()
+        .map(|evaluation| &evaluation.ticker)
+        .chain(account.positions.iter().map(|position| &position.ticker))
+    {
+        // 取价失败的股票不交易，持仓按成本计入净值
+        if !prices.contains_key(ticker) {
+            match fetch_price(ticker, &date).await {
+                Ok(Some(price)) => {
+                    prices.insert(ticker.clone(), price);
+                }
+                Ok(None) => {}
+                Err(err) => warn!("[Paper] Unable to fetch price of {ticker}: {err}"),
+            }
+        }
+    }
+
+    let mut trades: Vec<PaperTrade> = vec![];
+    for evaluation in &evaluations {
+        if let Some(price) = prices.get(&evaluation.ticker) {
+            let nav = account.nav(&prices);
+            if let Some(trade) = account.apply(evaluation, *price, nav) {
+                trades.push(trade);
+            }
+        }
+    }
+
+    let nav = account.nav(&prices);
+    account.navs.retain(|paper_nav| paper_nav.date != date);
+    account.navs.push(PaperNav { date, nav });
+    account.navs.sort_by_key(|paper_nav| paper_nav.date);
+
+    confy::store_path(&*PAPER_CONFIG_PATH, &account)?;
+
+    Ok(PaperStep { trades, errors })
+}
+
+impl Default for PaperRules {
+    fn default() -> Self {
+        Self {
+            buy_rating: 75,
+            sell_rating: 40,
+            position_ratio: 0.1,
+        }
+    }
+}
+
+impl Default for PaperAccount {
+    fn default() -> Self {
+        Self::new(PAPER_CASH_DEFAULT, PaperRules::default())
+    }
+}
+
+impl PaperAccount {
+    pub fn new(cash: f64, rules: PaperRules) -> Self {
+        Self {
+            rules,
+            initial_cash: cash,
+            cash,
+            positions: vec![],
+            trades: vec![],
+            navs: vec![],
+            realized_attribution: HashMap::new(),
+        }
+    }
+
+    pub fn nav(&self, prices: &HashMap<String, f64>) -> f64 {
+        self.cash
+            + self
+                .positions
+                .iter()
+                .map(|position| match prices.get(&position.ticker) {
+                    Some(price) => position.shares * price,
+                    None => position.cost,
+                })
+                .sum::<f64>()
+    }
+
+    fn apply(&mut self, evaluation: &Evaluation, price: f64, nav: f64) -> Option<PaperTrade> {
+        let rating = evaluation.rating_avg()?;
+        let position_index = self
+            .positions
+            .iter()
+            .position(|position| position.ticker == evaluation.ticker);
+
+        let (action, shares) = match position_index {
+            None if rating >= self.rules.buy_rating && price > 0.0 => {
+                let amount = (nav * self.rules.position_ratio).min(self.cash);
+                let shares = (amount / price).floor();
+                if shares <= 0.0 {
+                    return None;
+                }
+
+                // 买入由平均评级触发，未投卖出票的大师都计入归因
+                let mut masters: Vec<String> = evaluation
+                    .master_analyses
+                    .iter()
+                    .filter(|(_, master_analysis)| master_analysis.rating > self.rules.sell_rating)
+                    .map(|(master, _)| master.to_string())
+                    .collect();
+                masters.sort();
+
+                self.cash -= shares * price;
+                self.positions.push(PaperPosition {
+                    ticker: evaluation.ticker.clone(),
+                    shares,
+                    cost: shares * price,
+                    masters,
+                });
+
+                (TradeAction::Buy, shares)
+            }
+            Some(index) if rating <= self.rules.sell_rating => {
+                let position = self.positions.remove(index);
+                let profit = position.shares * price - position.cost;
+                for master in &position.masters {
+                    *self
+                        .realized_attribution
+                        .entry(master.to_string())
+                        .or_insert(0.0) += profit / position.masters.len() as f64;
+                }
+
+                self.cash += position.shares * price;
+
+                (TradeAction::Sell, position.shares)
+            }
+            _ => return None,
+        };
+
+        let trade = PaperTrade {
+            date: evaluation.date,
+            ticker: evaluation.ticker.clone(),
+            action,
+            shares,
+            price,
+            rating,
+            evaluation_id: evaluation.id.clone(),
+        };
+        self.trades.push(trade.clone());
+
+        Some(trade)
+    }
+
+    fn report(self, prices: &HashMap<String, f64>) -> PaperReport {
+        let nav = self.nav(prices);
+
+        let traded_value = self
+            .trades
+            .iter()
+            .fold(0.0, |acc, trade| acc + trade.shares * trade.price);
+        let nav_avg = if self.navs.is_empty() {
+            self.initial_cash
+        } else {
+            self.navs.iter().map(|paper_nav| paper_nav.nav).sum::<f64>() / self.navs.len() as f64
+        };
+
+        let mut attribution = self.realized_attribution.clone();
+        for position in &self.positions {
+            if let Some(price) = prices.get(&position.ticker) {
+                let profit = position.shares * price - position.cost;
+                for master in &position.masters {
+                    *attribution.entry(master.to_string()).or_insert(0.0) +=
+                        profit / position.masters.len() as f64;
+                }
+            }
+        }
+        let mut attribution: Vec<(String, f64)> = attribution.into_iter().collect();
+        attribution.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        PaperReport {
+            nav,
+            return_rate: nav / self.initial_cash - 1.0,
+            turnover: if nav_avg > 0.0 {
+                traded_value / nav_avg
+            } else {
+                0.0
+            },
+            attribution,
+            account: self,
+        }
+    }
+}
+
+static PAPER_CASH_DEFAULT: f64 = 1_000_000.0;
+
+static PAPER_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("paper.toml"));
+
+async fn fetch_price(ticker: &str, date: &NaiveDate) -> InvmstResult<Option<f64>> {
+    let ticker = Ticker::from_str(ticker)?;
+
+    Ok(get_stock_daily_valuations(&ticker)
+        .await?
+        .get_latest_value::<f64>(date, StockValuationFieldName::Price))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::master::Master;
+
+    fn evaluation(ratings: &[(Master, u64)]) -> Evaluation {
+        Evaluation::with_ratings(
+            "test",
+            NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+            ratings,
+        )
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut account = PaperAccount::new(10000.0, PaperRules::default());
+
+        let trade = account
+            .apply(
+                &evaluation(&[
+                    (Master::WarrenBuffett, 100),
+                    (Master::BenjaminGraham, 95),
+                    (Master::PeterLynch, 70),
+                    (Master::JohnBogle, 35),
+                ]),
+                10.0,
+                10000.0,
+            )
+            .unwrap();
+        assert!(matches!(trade.action, TradeAction::Buy));
+        assert_eq!(trade.shares, 100.0);
+        assert_eq!(account.cash, 9000.0);
+        let mut masters = vec![
+            Master::WarrenBuffett.to_string(),
+            Master::BenjaminGraham.to_string(),
+            Master::PeterLynch.to_string(),
+        ];
+        masters.sort();
+        assert_eq!(account.positions[0].masters, masters);
+
+        let trade = account
+            .apply(
+                &evaluation(&[(Master::WarrenBuffett, 30), (Master::BenjaminGraham, 30)]),
+                12.0,
+                10200.0,
+            )
+            .unwrap();
+        assert!(matches!(trade.action, TradeAction::Sell));
+        assert_eq!(account.cash, 10200.0);
+        assert!(account.positions.is_empty());
+        assert_eq!(
+            account.realized_attribution[&Master::BenjaminGraham.to_string()],
+            200.0 / 3.0
+        );
+        assert!(
+            !account
+                .realized_attribution
+                .contains_key(&Master::JohnBogle.to_string())
+        );
+    }
+}
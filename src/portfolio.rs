@@ -0,0 +1,661 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::LazyLock};
+
+use chrono::{Duration, Local, NaiveDate};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    APP_DATA_DIR,
+    data::daily::DailyDataset,
+    error::{InvmstError, InvmstResult},
+    financial::{
+        fx::Currency, get_cny_central_parity, get_index_constituent_weights,
+        get_index_daily_prices, get_stock_daily_valuations, get_stock_events, get_stock_info,
+        index::IndexDailyFieldName, price_range, price_range::PriceRange, risk_stats,
+        stock::StockValuationFieldName,
+    },
+    history, llm,
+    llm::{ChatCompletionOptions, ChatMessage, Role},
+    portfolio::{
+        income::DividendProjection,
+        performance::SectorAttribution,
+        sizing::{RiskEstimate, SizingOptions},
+    },
+    ticker::Ticker,
+};
+
+pub mod income;
+pub mod performance;
+pub mod risk;
+pub mod sizing;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub name: String,
+    pub holdings: Vec<Holding>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Holding {
+    pub ticker: String,
+    pub shares: f64,
+}
+
+#[derive(Debug)]
+pub struct HoldingReport {
+    pub holding: Holding,
+    pub price: Option<f64>,
+    pub value: Option<f64>,
+    pub weight: Option<f64>,
+    /// Consensus rating of the latest saved evaluation
+    pub rating: Option<u64>,
+    pub risk: Option<RiskEstimate>,
+    pub price_range: Option<PriceRange>,
+    pub suggested_weight: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct HoldingIncome {
+    pub holding: Holding,
+    pub projection: DividendProjection,
+    /// Projected dividend income of the next twelve months
+    pub income: f64,
+}
+
+#[derive(Debug)]
+pub struct HoldingPerformance {
+    pub ticker: String,
+    pub industry: Option<String>,
+    /// Weight by value at the start of the period
+    pub weight: f64,
+    /// Return of the period in CNY
+    pub return_rate: f64,
+}
+
+#[derive(Debug)]
+pub struct PortfolioPerformance {
+    pub benchmark: String,
+    pub date_start: NaiveDate,
+    pub date_end: NaiveDate,
+    pub holdings: Vec<HoldingPerformance>,
+    pub portfolio_return: f64,
+    /// Return of the benchmark index itself
+    pub benchmark_return: Option<f64>,
+    /// Return of the sampled benchmark constituents that attribution is computed against
+    pub benchmark_sample_return: f64,
+    /// Index weight covered by the sampled constituents
+    pub benchmark_coverage: f64,
+    pub attributions: Vec<SectorAttribution>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HoldingRisk {
+    pub ticker: String,
+    pub name: Option<String>,
+    pub industry: Option<String>,
+    pub weight: Option<f64>,
+    pub volatility: Option<f64>,
+    pub downside_deviation: Option<f64>,
+    pub beta: Option<f64>,
+    pub max_drawdown: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct PortfolioRisk {
+    pub benchmark: String,
+    pub holdings: Vec<HoldingRisk>,
+    /// Correlations of daily returns between each pair of holdings
+    pub correlations: Vec<(String, String, f64)>,
+    /// Weights aggregated by industry, sorted descending
+    pub industry_weights: Vec<(String, f64)>,
+    /// Herfindahl-Hirschman index of holding weights
+    pub concentration: f64,
+    pub beta: Option<f64>,
+    pub volatility: Option<f64>,
+    pub downside_deviation: Option<f64>,
+    pub max_drawdown: Option<f64>,
+    /// LLM commentary, None if not requested
+    pub commentary: Option<InvmstResult<String>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PortfolioConfig {
+    portfolios: Vec<Portfolio>,
+}
+
+pub async fn add_holding(name: &str, ticker: &str, shares: f64) -> InvmstResult<Portfolio> {
+    let ticker = Ticker::from_str(ticker)?;
+
+    if shares <= 0.0 {
+        return Err(InvmstError::Invalid(
+            "INVALID_SHARES",
+            format!("Shares must be positive rather than {shares}"),
+        ));
+    }
+
+    let mut cfg: PortfolioConfig = confy::load_path(&*PORTFOLIO_CONFIG_PATH)?;
+
+    let index = match cfg.portfolios.iter().position(|p| p.name == name) {
+        Some(index) => index,
+        None => {
+            cfg.portfolios.push(Portfolio {
+                name: name.to_string(),
+                holdings: vec![],
+            });
+            cfg.portfolios.len() - 1
+        }
+    };
+
+    let portfolio = &mut cfg.portfolios[index];
+    match portfolio
+        .holdings
+        .iter_mut()
+        .find(|holding| holding.ticker == ticker.to_string())
+    {
+        Some(holding) => holding.shares += shares,
+        None => portfolio.holdings.push(Holding {
+            ticker: ticker.to_string(),
+            shares,
+        }),
+    }
+    let portfolio = portfolio.clone();
+
+    confy::store_path(&*PORTFOLIO_CONFIG_PATH, &cfg)?;
+
+    Ok(portfolio)
+}
+
+pub async fn get(name: &str) -> InvmstResult<Portfolio> {
+    let cfg: PortfolioConfig = confy::load_path(&*PORTFOLIO_CONFIG_PATH)?;
+
+    cfg.portfolios
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or(InvmstError::NotExists(
+            "PORTFOLIO_NOT_EXISTS",
+            format!("Portfolio '{name}' not exists"),
+        ))
+}
+
+pub async fn list() -> InvmstResult<Vec<Portfolio>> {
+    let cfg: PortfolioConfig = confy::load_path(&*PORTFOLIO_CONFIG_PATH)?;
+
+    Ok(cfg.portfolios)
+}
+
+/// Remove a holding from the portfolio, or the whole portfolio if ticker is None
+pub async fn remove(name: &str, ticker: Option<&str>) -> InvmstResult<()> {
+    let mut cfg: PortfolioConfig = confy::load_path(&*PORTFOLIO_CONFIG_PATH)?;
+
+    let index =
+        cfg.portfolios
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or(InvmstError::NotExists(
+                "PORTFOLIO_NOT_EXISTS",
+                format!("Portfolio '{name}' not exists"),
+            ))?;
+
+    match ticker {
+        Some(ticker) => {
+            let ticker = Ticker::from_str(ticker)?.to_string();
+            let holdings = &mut cfg.portfolios[index].holdings;
+
+            let count = holdings.len();
+            holdings.retain(|holding| holding.ticker != ticker);
+            if holdings.len() == count {
+                return Err(InvmstError::NotExists(
+                    "HOLDING_NOT_EXISTS",
+                    format!("Holding '{ticker}' not exists in portfolio '{name}'"),
+                ));
+            }
+        }
+        None => {
+            cfg.portfolios.remove(index);
+        }
+    }
+
+    confy::store_path(&*PORTFOLIO_CONFIG_PATH, &cfg)?;
+
+    Ok(())
+}
+
+/// Report holdings with current weights and suggested weights sized from the latest ratings
+pub async fn report(name: &str, options: &SizingOptions) -> InvmstResult<Vec<HoldingReport>> {
+    let portfolio = get(name).await?;
+
+    let mut reports: Vec<HoldingReport> = vec![];
+    for holding in portfolio.holdings {
+        let ticker = Ticker::from_str(&holding.ticker)?;
+        let (price, risk, price_range) = fetch_price_and_risk(&ticker).await?;
+
+        let rating = history::list(Some(&holding.ticker))
+            .await?
+            .first()
+            .and_then(|evaluation| evaluation.rating_avg());
+
+        let suggested_weight = match (rating, &risk) {
+            (Some(rating), Some(risk)) => Some(sizing::suggest_position(rating, risk, options)),
+            _ => None,
+        };
+
+        reports.push(HoldingReport {
+            value: price.map(|price| price * holding.shares),
+            price,
+            weight: None,
+            rating,
+            risk,
+            price_range,
+            suggested_weight,
+            holding,
+        });
+    }
+
+    let total_value: f64 = reports.iter().filter_map(|report| report.value).sum();
+    if total_value > 0.0 {
+        for report in &mut reports {
+            report.weight = report.value.map(|value| value / total_value);
+        }
+    }
+
+    Ok(reports)
+}
+
+/// Analyze correlation, concentration, beta against the benchmark index and drawdown of a portfolio
+pub async fn analyze_risk(
+    name: &str,
+    benchmark: &str,
+    enable_commentary: bool,
+) -> InvmstResult<PortfolioRisk> {
+    let portfolio = get(name).await?;
+    if portfolio.holdings.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_HOLDINGS",
+            format!("Portfolio '{name}' has no holdings"),
+        ));
+    }
+
+    let today = Local::now().date_naive();
+    let date_start = today - Duration::days(RISK_BACKWARD_DAYS);
+
+    let benchmark_prices = get_index_daily_prices(benchmark).await?.get_values::<f64>(
+        &date_start,
+        &today,
+        IndexDailyFieldName::Close,
+    );
+    if benchmark_prices.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_BENCHMARK_DATA",
+            format!("No price data of benchmark '{benchmark}'"),
+        ));
+    }
+
+    let mut holdings: Vec<HoldingRisk> = vec![];
+    let mut holdings_prices: Vec<Vec<(NaiveDate, f64)>> = vec![];
+    for holding in &portfolio.holdings {
+        let ticker = Ticker::from_str(&holding.ticker)?;
+        let stock_info = get_stock_info(&ticker).await?;
+        let prices = get_stock_daily_valuations(&ticker)
+            .await?
+            .get_values::<f64>(&date_start, &today, StockValuationFieldName::Price);
+
+        let values: Vec<f64> = prices.iter().map(|(_, price)| *price).collect();
+        let stats = risk_stats::compute(&values, None);
+        holdings.push(HoldingRisk {
+            ticker: holding.ticker.clone(),
+            name: stock_info.name,
+            industry: stock_info.industry,
+            weight: values.last().map(|price| price * holding.shares),
+            volatility: stats.volatility,
+            downside_deviation: stats.downside_deviation,
+            beta: None,
+            max_drawdown: risk_stats::max_drawdown(&values),
+        });
+        holdings_prices.push(prices);
+    }
+
+    let total_value: f64 = holdings.iter().filter_map(|h| h.weight).sum();
+    for holding in &mut holdings {
+        holding.weight = holding
+            .weight
+            .filter(|_| total_value > 0.0)
+            .map(|value| value / total_value);
+    }
+
+    let mut industry_weights: HashMap<String, f64> = HashMap::new();
+    for holding in &holdings {
+        *industry_weights
+            .entry(holding.industry.clone().unwrap_or("Unknown".to_string()))
+            .or_insert(0.0) += holding.weight.unwrap_or(0.0);
+    }
+    let mut industry_weights: Vec<(String, f64)> = industry_weights.into_iter().collect();
+    industry_weights.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let concentration = risk::concentration(
+        &holdings
+            .iter()
+            .map(|h| h.weight.unwrap_or(0.0))
+            .collect::<Vec<_>>(),
+    );
+
+    // Align all holdings with the benchmark so that returns are comparable day by day
+    let (_, aligned) = risk_stats::align(&[holdings_prices, vec![benchmark_prices]].concat());
+    let (benchmark_aligned, holdings_aligned) = aligned.split_last().ok_or(InvmstError::NoData(
+        "NO_PRICE_DATA",
+        "No price data to analyze".to_string(),
+    ))?;
+    let benchmark_returns = risk_stats::daily_returns(benchmark_aligned);
+    let holdings_returns: Vec<Vec<f64>> = holdings_aligned
+        .iter()
+        .map(|prices| risk_stats::daily_returns(prices))
+        .collect();
+
+    for (holding, returns) in holdings.iter_mut().zip(&holdings_returns) {
+        holding.beta = risk_stats::beta(returns, &benchmark_returns);
+    }
+
+    let mut correlations: Vec<(String, String, f64)> = vec![];
+    for i in 0..holdings.len() {
+        for j in i + 1..holdings.len() {
+            if let Some(correlation) =
+                crate::utils::stats::correlation(&holdings_returns[i], &holdings_returns[j])
+            {
+                correlations.push((
+                    holdings[i].ticker.clone(),
+                    holdings[j].ticker.clone(),
+                    correlation,
+                ));
+            }
+        }
+    }
+
+    let navs: Vec<f64> = (0..benchmark_aligned.len())
+        .map(|k| {
+            portfolio
+                .holdings
+                .iter()
+                .zip(holdings_aligned)
+                .map(|(holding, prices)| holding.shares * prices[k])
+                .sum()
+        })
+        .collect();
+    let nav_returns = risk_stats::daily_returns(&navs);
+
+    let mut portfolio_risk = PortfolioRisk {
+        benchmark: benchmark.to_string(),
+        holdings,
+        correlations,
+        industry_weights,
+        concentration,
+        beta: risk_stats::beta(&nav_returns, &benchmark_returns),
+        volatility: risk_stats::annualized_volatility(&nav_returns),
+        downside_deviation: risk_stats::downside_deviation(&nav_returns),
+        max_drawdown: risk_stats::max_drawdown(&navs),
+        commentary: None,
+    };
+
+    if enable_commentary {
+        let commentary = comment_risk(&portfolio_risk).await;
+        if let Err(err) = &commentary {
+            warn!("[Portfolio Risk Commentary] {err}");
+        }
+        portfolio_risk.commentary = Some(commentary);
+    }
+
+    Ok(portfolio_risk)
+}
+
+/// Project next twelve months dividend income of each holding from its dividend history
+pub async fn project_income(name: &str) -> InvmstResult<Vec<HoldingIncome>> {
+    let portfolio = get(name).await?;
+
+    let today = Local::now().date_naive();
+    let mut incomes: Vec<HoldingIncome> = vec![];
+    for holding in portfolio.holdings {
+        let ticker = Ticker::from_str(&holding.ticker)?;
+        let stock_events = get_stock_events(&ticker, Some(&today), INCOME_BACKWARD_DAYS).await?;
+
+        let projection = income::project(&stock_events.dividends, &today);
+        incomes.push(HoldingIncome {
+            income: projection.dividend_per_share_ntm * holding.shares,
+            projection,
+            holding,
+        });
+    }
+
+    Ok(incomes)
+}
+
+/// Compare buy-and-hold returns of current holdings with the benchmark, attributing excess return
+/// to sector allocation and stock selection
+pub async fn analyze_performance(
+    name: &str,
+    benchmark: &str,
+    backward_days: i64,
+) -> InvmstResult<PortfolioPerformance> {
+    let portfolio = get(name).await?;
+
+    let date_end = Local::now().date_naive();
+    let date_start = date_end - Duration::days(backward_days);
+
+    let fx = if portfolio
+        .holdings
+        .iter()
+        .any(|h| Ticker::from_str(&h.ticker).is_ok_and(|t| Currency::of(&t) != Currency::Cny))
+    {
+        Some(get_cny_central_parity().await?)
+    } else {
+        None
+    };
+
+    let mut holdings: Vec<HoldingPerformance> = vec![];
+    for holding in &portfolio.holdings {
+        let ticker = Ticker::from_str(&holding.ticker)?;
+        if let Some((value_start, return_rate)) =
+            period_return(&ticker, &date_start, &date_end, fx.as_ref()).await?
+        {
+            holdings.push(HoldingPerformance {
+                ticker: holding.ticker.clone(),
+                industry: get_stock_info(&ticker).await?.industry,
+                weight: value_start * holding.shares,
+                return_rate,
+            });
+        }
+    }
+    if holdings.is_empty() {
+        return Err(InvmstError::NoData(
+            "NO_PRICE_DATA",
+            format!("No price data of holdings in portfolio '{name}' since {date_start}"),
+        ));
+    }
+
+    let value_total: f64 = holdings.iter().map(|h| h.weight).sum();
+    for holding in &mut holdings {
+        holding.weight /= value_total;
+    }
+
+    let benchmark_prices = get_index_daily_prices(benchmark).await?.get_values::<f64>(
+        &date_start,
+        &date_end,
+        IndexDailyFieldName::Close,
+    );
+    let benchmark_return = match (benchmark_prices.first(), benchmark_prices.last()) {
+        (Some((_, first)), Some((_, last))) if *first > 0.0 => Some(last / first - 1.0),
+        _ => None,
+    };
+
+    // Sample the heaviest constituents to bound the number of requests
+    let mut constituents = get_index_constituent_weights(benchmark).await?;
+    constituents.sort_by(|a, b| b.1.total_cmp(&a.1));
+    constituents.truncate(PERFORMANCE_BENCHMARK_SAMPLE_SIZE);
+
+    let mut benchmark_items: Vec<(String, f64, f64)> = vec![];
+    let mut benchmark_coverage = 0.0;
+    for (ticker, weight) in &constituents {
+        if let Some((_, return_rate)) = period_return(ticker, &date_start, &date_end, None).await? {
+            let industry = get_stock_info(ticker).await?.industry;
+            benchmark_items.push((
+                industry.unwrap_or("Unknown".to_string()),
+                *weight,
+                return_rate,
+            ));
+            benchmark_coverage += weight;
+        }
+    }
+
+    let portfolio_sectors = performance::aggregate(
+        &holdings
+            .iter()
+            .map(|h| {
+                (
+                    h.industry.clone().unwrap_or("Unknown".to_string()),
+                    h.weight,
+                    h.return_rate,
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    let benchmark_sectors = performance::aggregate(&benchmark_items);
+
+    Ok(PortfolioPerformance {
+        benchmark: benchmark.to_string(),
+        date_start,
+        date_end,
+        portfolio_return: performance::total_return(&portfolio_sectors),
+        benchmark_return,
+        benchmark_sample_return: performance::total_return(&benchmark_sectors),
+        benchmark_coverage,
+        attributions: performance::attribute(&portfolio_sectors, &benchmark_sectors),
+        holdings,
+    })
+}
+
+/// Suggested fraction of a portfolio to hold the ticker with the rating
+pub async fn suggest_position(
+    ticker: &str,
+    rating: u64,
+    options: &SizingOptions,
+) -> InvmstResult<Option<f64>> {
+    let ticker = Ticker::from_str(ticker)?;
+    let (_, risk, _) = fetch_price_and_risk(&ticker).await?;
+
+    Ok(risk.map(|risk| sizing::suggest_position(rating, &risk, options)))
+}
+
+static PORTFOLIO_CONFIG_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| APP_DATA_DIR.join("portfolio.toml"));
+
+static INCOME_BACKWARD_DAYS: i64 = 365 * 6;
+static PERFORMANCE_BENCHMARK_SAMPLE_SIZE: usize = 30;
+static RISK_BACKWARD_DAYS: i64 = 365;
+static SIZING_BACKWARD_DAYS: i64 = 365;
+
+async fn comment_risk(portfolio_risk: &PortfolioRisk) -> InvmstResult<String> {
+    let data_json = json!({
+        "benchmark": portfolio_risk.benchmark,
+        "holdings": portfolio_risk.holdings,
+        "correlations": portfolio_risk.correlations,
+        "industry_weights": portfolio_risk.industry_weights,
+        "concentration_hhi": portfolio_risk.concentration,
+        "portfolio_beta": portfolio_risk.beta,
+        "portfolio_volatility": portfolio_risk.volatility,
+        "portfolio_downside_deviation": portfolio_risk.downside_deviation,
+        "portfolio_max_drawdown": portfolio_risk.max_drawdown,
+    });
+    debug!("[Portfolio Risk Data] {data_json}");
+    let prompt_json = llm::fit_json(&data_json, "", &ChatCompletionOptions::default()).await?;
+
+    let prompt = format!(
+        r#"
+下面是一个投资组合近一年的风险数据，请用简洁的中文给出风险总结，指出主要的集中度、相关性和回撤风险，并给出可行的改进建议，不超过 300 字：
+```
+{prompt_json}
+```
+"#
+    );
+
+    let messages: Vec<ChatMessage> = vec![
+        ChatMessage {
+            role: Role::System,
+            content: "你是一位严谨的投资组合风险分析师。".to_string(),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+        ChatMessage {
+            role: Role::User,
+            content: prompt,
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        },
+    ];
+
+    let bot_message = llm::chat_completion(&messages, &ChatCompletionOptions::default()).await?;
+
+    Ok(bot_message.content.trim().to_string())
+}
+
+async fn fetch_price_and_risk(
+    ticker: &Ticker,
+) -> InvmstResult<(Option<f64>, Option<RiskEstimate>, Option<PriceRange>)> {
+    let daily_valuations = get_stock_daily_valuations(ticker).await?;
+
+    let today = Local::now().date_naive();
+    let price = daily_valuations.get_latest_value::<f64>(&today, StockValuationFieldName::Price);
+    let prices = daily_valuations.get_values::<f64>(
+        &(today - Duration::days(SIZING_BACKWARD_DAYS)),
+        &today,
+        StockValuationFieldName::Price,
+    );
+    let price_range = price.and_then(|price| price_range::compute(&prices, &today, price));
+    let prices: Vec<f64> = prices.into_iter().map(|(_, price)| price).collect();
+
+    Ok((price, sizing::estimate_risk(&prices), price_range))
+}
+
+/// Price per share in CNY at the start and return over the period
+async fn period_return(
+    ticker: &Ticker,
+    date_start: &NaiveDate,
+    date_end: &NaiveDate,
+    fx: Option<&DailyDataset<Currency>>,
+) -> InvmstResult<Option<(f64, f64)>> {
+    let prices = get_stock_daily_valuations(ticker).await?.get_values::<f64>(
+        date_start,
+        date_end,
+        StockValuationFieldName::Price,
+    );
+
+    let (Some((day_first, price_first)), Some((day_last, price_last))) =
+        (prices.first(), prices.last())
+    else {
+        return Ok(None);
+    };
+
+    let currency = Currency::of(ticker);
+    let (rate_first, rate_last) = match (currency, fx) {
+        (Currency::Cny, _) => (1.0, 1.0),
+        (_, Some(fx)) => {
+            match (
+                fx.get_latest_value::<f64>(day_first, currency),
+                fx.get_latest_value::<f64>(day_last, currency),
+            ) {
+                (Some(rate_first), Some(rate_last)) => (rate_first / 100.0, rate_last / 100.0),
+                _ => return Ok(None),
+            }
+        }
+        (_, None) => return Ok(None),
+    };
+
+    let value_first = price_first * rate_first;
+    if value_first <= 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some((
+        value_first,
+        price_last * rate_last / value_first - 1.0,
+    )))
+}
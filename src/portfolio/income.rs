@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::data::stock::StockDividend;
+
+/// Bound of the annual growth applied to trailing dividends, to avoid extrapolating one-offs
+static GROWTH_BOUND: f64 = 0.3;
+
+#[derive(Clone, Debug)]
+pub struct DividendProjection {
+    /// Dividends per share with record dates in the trailing twelve months
+    pub dividend_per_share_ttm: f64,
+    /// Annualized growth of yearly dividends per share, bounded
+    pub growth: Option<f64>,
+    /// Projected dividends per share of the next twelve months
+    pub dividend_per_share_ntm: f64,
+    pub assumption: String,
+}
+
+/// Project the next twelve months dividends per share from dividend history
+pub fn project(dividends: &[StockDividend], today: &NaiveDate) -> DividendProjection {
+    let ttm_start = *today - Duration::days(365);
+    let dividend_per_share_ttm: f64 = dividends
+        .iter()
+        .filter(|d| d.date_record > ttm_start && d.date_record <= *today)
+        .map(|d| d.dividend_per_share)
+        .sum();
+
+    // Only complete years take part in the trend
+    let mut yearly: BTreeMap<i32, f64> = BTreeMap::new();
+    for dividend in dividends {
+        if dividend.date_record.year() < today.year() {
+            *yearly.entry(dividend.date_record.year()).or_insert(0.0) +=
+                dividend.dividend_per_share;
+        }
+    }
+
+    let growth = match (yearly.first_key_value(), yearly.last_key_value()) {
+        (Some((year_first, first)), Some((year_last, last)))
+            if year_last > year_first && *first > 0.0 && *last > 0.0 =>
+        {
+            let years = (year_last - year_first) as f64;
+            Some(((last / first).powf(1.0 / years) - 1.0).clamp(-GROWTH_BOUND, GROWTH_BOUND))
+        }
+        _ => None,
+    };
+
+    if dividend_per_share_ttm > 0.0 {
+        let dividend_per_share_ntm = dividend_per_share_ttm * (1.0 + growth.unwrap_or(0.0));
+        let assumption = match growth {
+            Some(growth) => format!(
+                "TTM {dividend_per_share_ttm:.4}/share grown by {:.1}% yearly trend of {} years",
+                growth * 100.0,
+                yearly.len()
+            ),
+            None => format!("TTM {dividend_per_share_ttm:.4}/share held flat, no yearly trend"),
+        };
+
+        DividendProjection {
+            dividend_per_share_ttm,
+            growth,
+            dividend_per_share_ntm,
+            assumption,
+        }
+    } else if let Some((year, last)) = yearly.last_key_value() {
+        DividendProjection {
+            dividend_per_share_ttm,
+            growth,
+            dividend_per_share_ntm: *last,
+            assumption: format!(
+                "No dividend in TTM, assumed to repeat {year} total {last:.4}/share"
+            ),
+        }
+    } else {
+        DividendProjection {
+            dividend_per_share_ttm,
+            growth,
+            dividend_per_share_ntm: 0.0,
+            assumption: "No dividend history".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dividend(year: i32, month: u32, dividend_per_share: f64) -> StockDividend {
+        let date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        StockDividend {
+            date_announce: date,
+            date_record: date,
+            dividend_per_share,
+        }
+    }
+
+    #[test]
+    fn test_project() {
+        let today = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap();
+        let dividends = [
+            dividend(2022, 7, 1.0),
+            dividend(2023, 7, 1.1),
+            dividend(2024, 7, 1.21),
+            dividend(2025, 7, 1.3),
+        ];
+
+        let projection = project(&dividends, &today);
+        assert_eq!(projection.dividend_per_share_ttm, 1.3);
+        assert!((projection.growth.unwrap() - 0.1).abs() < 1e-9);
+        assert!((projection.dividend_per_share_ntm - 1.43).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_project_no_history() {
+        let today = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap();
+        assert_eq!(project(&[], &today).dividend_per_share_ntm, 0.0);
+    }
+}
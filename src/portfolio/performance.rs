@@ -0,0 +1,113 @@
+use std::collections::{BTreeSet, HashMap};
+
+/// Weight and return of one sector, either in the portfolio or in the benchmark
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SectorReturn {
+    pub weight: f64,
+    pub return_rate: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct SectorAttribution {
+    pub sector: String,
+    pub portfolio: Option<SectorReturn>,
+    pub benchmark: Option<SectorReturn>,
+    /// Contribution from over- or under-weighting the sector
+    pub allocation: f64,
+    /// Contribution from picking stocks within the sector, including interaction
+    pub selection: f64,
+}
+
+/// Aggregate (sector, weight, return) items into weights and weighted returns per sector
+pub fn aggregate(items: &[(String, f64, f64)]) -> HashMap<String, SectorReturn> {
+    let mut sums: HashMap<String, (f64, f64)> = HashMap::new();
+    for (sector, weight, return_rate) in items {
+        let sum = sums.entry(sector.to_string()).or_insert((0.0, 0.0));
+        sum.0 += weight;
+        sum.1 += weight * return_rate;
+    }
+
+    let weight_total: f64 = sums.values().map(|(weight, _)| weight).sum();
+
+    sums.into_iter()
+        .map(|(sector, (weight, weighted_return))| {
+            (
+                sector,
+                SectorReturn {
+                    weight: if weight_total > 0.0 {
+                        weight / weight_total
+                    } else {
+                        0.0
+                    },
+                    return_rate: if weight > 0.0 {
+                        weighted_return / weight
+                    } else {
+                        0.0
+                    },
+                },
+            )
+        })
+        .collect()
+}
+
+pub fn total_return(sectors: &HashMap<String, SectorReturn>) -> f64 {
+    sectors.values().map(|s| s.weight * s.return_rate).sum()
+}
+
+/// Brinson-Fachler attribution, allocation and selection of all sectors sum to the excess return
+pub fn attribute(
+    portfolio: &HashMap<String, SectorReturn>,
+    benchmark: &HashMap<String, SectorReturn>,
+) -> Vec<SectorAttribution> {
+    let benchmark_total = total_return(benchmark);
+
+    let sectors: BTreeSet<&String> = portfolio.keys().chain(benchmark.keys()).collect();
+    sectors
+        .into_iter()
+        .map(|sector| {
+            let p = portfolio.get(sector).copied();
+            let b = benchmark.get(sector).copied();
+
+            let weight_p = p.map(|p| p.weight).unwrap_or(0.0);
+            let weight_b = b.map(|b| b.weight).unwrap_or(0.0);
+            // Sectors absent from the benchmark are compared with the benchmark as a whole
+            let return_b = b.map(|b| b.return_rate).unwrap_or(benchmark_total);
+
+            SectorAttribution {
+                sector: sector.to_string(),
+                portfolio: p,
+                benchmark: b,
+                allocation: (weight_p - weight_b) * (return_b - benchmark_total),
+                selection: p
+                    .map(|p| p.weight * (p.return_rate - return_b))
+                    .unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute() {
+        let portfolio = aggregate(&[
+            ("Bank".to_string(), 60.0, 0.10),
+            ("Tech".to_string(), 40.0, 0.30),
+        ]);
+        let benchmark = aggregate(&[
+            ("Bank".to_string(), 0.5, 0.05),
+            ("Tech".to_string(), 0.3, 0.20),
+            ("Energy".to_string(), 0.2, -0.10),
+        ]);
+
+        let attributions = attribute(&portfolio, &benchmark);
+        let effects: f64 = attributions
+            .iter()
+            .map(|a| a.allocation + a.selection)
+            .sum();
+        let excess = total_return(&portfolio) - total_return(&benchmark);
+        assert!((effects - excess).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,4 @@
+/// Herfindahl-Hirschman index of weights, 1 means fully concentrated
+pub fn concentration(weights: &[f64]) -> f64 {
+    weights.iter().map(|w| w * w).sum()
+}
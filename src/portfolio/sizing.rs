@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::financial::risk_stats;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum SizingMethod {
+    /// Fractional Kelly, treating the rating as the win probability and the drawdown as the bet
+    #[default]
+    #[strum(serialize = "kelly")]
+    Kelly,
+
+    /// Scale positions so that each contributes the target volatility, weighted by conviction
+    #[strum(serialize = "vol", serialize = "volatility")]
+    Volatility,
+}
+
+#[derive(Clone, Debug)]
+pub struct SizingOptions {
+    pub method: SizingMethod,
+    /// Fraction of the full Kelly bet, e.g. 0.5 for half Kelly
+    pub kelly_fraction: f64,
+    /// Annualized volatility each position targets
+    pub target_volatility: f64,
+    /// Upper bound of a single position
+    pub max_position: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RiskEstimate {
+    /// Annualized volatility of daily returns
+    pub volatility: f64,
+    /// Maximum peak-to-trough decline, as a positive fraction
+    pub max_drawdown: f64,
+}
+
+impl Default for SizingOptions {
+    fn default() -> Self {
+        Self {
+            method: SizingMethod::default(),
+            kelly_fraction: 0.5,
+            target_volatility: 0.1,
+            max_position: 0.2,
+        }
+    }
+}
+
+/// Estimate volatility and drawdown from prices sorted by date ascending
+pub fn estimate_risk(prices: &[f64]) -> Option<RiskEstimate> {
+    if prices.len() < 2 || prices.iter().any(|price| *price <= 0.0) {
+        return None;
+    }
+
+    Some(RiskEstimate {
+        volatility: risk_stats::annualized_volatility(&risk_stats::daily_returns(prices))?,
+        max_drawdown: risk_stats::max_drawdown(prices)?,
+    })
+}
+
+/// Suggested fraction of the portfolio to hold, from 0 to `max_position`
+pub fn suggest_position(rating: u64, risk: &RiskEstimate, options: &SizingOptions) -> f64 {
+    let p = rating.min(100) as f64 / 100.0;
+
+    let position = match options.method {
+        SizingMethod::Kelly => {
+            // Win or lose the drawdown with equal magnitude, f* = (2p - 1) / loss
+            if risk.max_drawdown > 0.0 {
+                options.kelly_fraction * (2.0 * p - 1.0) / risk.max_drawdown
+            } else {
+                0.0
+            }
+        }
+        SizingMethod::Volatility => {
+            let conviction = (2.0 * p - 1.0).max(0.0);
+            if risk.volatility > 0.0 {
+                conviction * options.target_volatility / risk.volatility
+            } else {
+                0.0
+            }
+        }
+    };
+
+    position.clamp(0.0, options.max_position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_risk() {
+        let risk = estimate_risk(&[10.0, 12.0, 9.0, 11.0]).unwrap();
+        assert!((risk.max_drawdown - 0.25).abs() < 1e-9);
+        assert!(risk.volatility > 0.0);
+    }
+
+    #[test]
+    fn test_suggest_position() {
+        let risk = RiskEstimate {
+            volatility: 0.25,
+            max_drawdown: 0.5,
+        };
+        let options = SizingOptions::default();
+
+        assert_eq!(suggest_position(40, &risk, &options), 0.0);
+        assert!((suggest_position(55, &risk, &options) - 0.1).abs() < 1e-9);
+        assert_eq!(suggest_position(100, &risk, &options), 0.2);
+    }
+}
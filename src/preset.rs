@@ -0,0 +1,92 @@
+use std::{path::PathBuf, sync::LazyLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    APP_DATA_DIR,
+    error::{InvmstError, InvmstResult},
+};
+
+/// Named bundle of evaluation settings, so analyses can be run the same way every time
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    pub masters: Vec<String>,
+    #[serde(default)]
+    pub backward_days: Option<i64>,
+    #[serde(default)]
+    pub monte_carlo: bool,
+    #[serde(default)]
+    pub bear_case: bool,
+    #[serde(default)]
+    pub pre_mortem: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PresetConfig {
+    presets: Vec<Preset>,
+}
+
+/// Load presets from the config, which is created with the built-in presets on first use
+pub async fn list() -> InvmstResult<Vec<Preset>> {
+    let cfg: PresetConfig = confy::load_path(&*PRESET_CONFIG_PATH)?;
+
+    Ok(cfg.presets)
+}
+
+pub async fn get(name: &str) -> InvmstResult<Preset> {
+    list()
+        .await?
+        .into_iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| {
+            InvmstError::NotExists("PRESET_NOT_EXISTS", format!("Preset '{name}' not exists"))
+        })
+}
+
+impl Default for PresetConfig {
+    fn default() -> Self {
+        let preset = |name: &str, description: &str, masters: &[&str]| Preset {
+            name: name.to_string(),
+            description: description.to_string(),
+            masters: masters.iter().map(|s| s.to_string()).collect(),
+            backward_days: None,
+            monte_carlo: false,
+            bear_case: false,
+            pre_mortem: false,
+        };
+
+        Self {
+            presets: vec![
+                Preset {
+                    monte_carlo: true,
+                    bear_case: true,
+                    ..preset(
+                        "deep-value",
+                        "Cheap stocks with a margin of safety, challenged by a bear case",
+                        &["graham", "schloss", "einhorn"],
+                    )
+                },
+                Preset {
+                    backward_days: Some(730),
+                    ..preset(
+                        "growth",
+                        "Growing businesses with momentum behind them",
+                        &["lynch", "smith", "druckenmiller"],
+                    )
+                },
+                Preset {
+                    pre_mortem: true,
+                    ..preset(
+                        "quality",
+                        "Durable compounders held for the long term, with a pre-mortem",
+                        &["buffett", "smith", "lilu"],
+                    )
+                },
+            ],
+        }
+    }
+}
+
+static PRESET_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| APP_DATA_DIR.join("presets.toml"));
@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDate};
+use log::warn;
+use serde::Serialize;
+
+use crate::{
+    data::stock::StockDailyData,
+    error::InvmstResult,
+    evaluate::{PriceSummary, summarize_price},
+    financial::{
+        get_stock_daily_valuations, get_stock_info, get_stock_next_earnings_date, get_stock_quote,
+        stock::StockValuationFieldName,
+    },
+    ticker::Ticker,
+};
+
+#[derive(Debug, Serialize)]
+pub struct Quote {
+    pub ticker: String,
+    pub name: Option<String>,
+    pub date: NaiveDate,
+    pub price_summary: Option<PriceSummary>,
+    pub pe_ttm: Option<f64>,
+    pub pb: Option<f64>,
+    pub ps: Option<f64>,
+    pub market_cap: Option<f64>,
+    pub next_earnings_date: Option<NaiveDate>,
+}
+
+/// Snapshot of the price and valuation of today without any LLM, only the daily valuations are
+/// required while the others are left empty on failure
+pub async fn fetch(ticker: &str) -> InvmstResult<Quote> {
+    let ticker = Ticker::from_str(ticker)?;
+    let date = Local::now().date_naive();
+
+    let daily_valuations = get_stock_daily_valuations(&ticker).await?;
+    let quote = match get_stock_quote(&ticker).await {
+        Ok(quote) => Some(quote),
+        Err(err) => {
+            warn!("[Quote] {err}");
+            None
+        }
+    };
+    let stock_daily_data = StockDailyData {
+        daily_valuations,
+        quote,
+        daily_bars: vec![],
+        margin_balances: None,
+        northbound_holdings: None,
+    };
+
+    let name = match get_stock_info(&ticker).await {
+        Ok(stock_info) => stock_info.name,
+        Err(err) => {
+            warn!("[Stock Info] {err}");
+            None
+        }
+    };
+    let next_earnings_date = match get_stock_next_earnings_date(&ticker, &date).await {
+        Ok(next_earnings_date) => next_earnings_date,
+        Err(err) => {
+            warn!("[Earnings Date] {err}");
+            None
+        }
+    };
+
+    Ok(Quote {
+        ticker: ticker.to_string(),
+        name,
+        date,
+        price_summary: summarize_price(&stock_daily_data, &date),
+        pe_ttm: stock_daily_data.get_valuation(&date, &StockValuationFieldName::PeTtm),
+        pb: stock_daily_data.get_valuation(&date, &StockValuationFieldName::Pb),
+        ps: stock_daily_data.get_valuation(&date, &StockValuationFieldName::Ps),
+        market_cap: stock_daily_data.get_valuation(&date, &StockValuationFieldName::MarketCap),
+        next_earnings_date,
+    })
+}
@@ -0,0 +1,249 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use plotters::{
+    coord::Shift,
+    prelude::*,
+    style::{Color, RGBColor},
+};
+
+use crate::{
+    error::{InvmstError, InvmstResult},
+    financial::risk_stats,
+};
+
+/// Line chart of one or more series over dates
+#[derive(Clone, Debug)]
+pub struct Chart {
+    pub title: String,
+    pub series: Vec<ChartSeries>,
+    /// Values are fractions shown as percentages
+    pub percent: bool,
+    /// Fill the area between the first series and zero, e.g. drawdowns
+    pub area: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChartSeries {
+    pub name: String,
+    /// Sorted by date ascending
+    pub points: Vec<(NaiveDate, f64)>,
+}
+
+impl Chart {
+    /// Save as PNG or SVG by the extension of the path. No font is bundled, so only SVG images
+    /// carry the title, the axis labels and the legend.
+    pub fn save(&self, path: &Path) -> InvmstResult<()> {
+        let size = (CHART_WIDTH, CHART_HEIGHT);
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("png") => {
+                let root = BitMapBackend::new(path, size).into_drawing_area();
+                self.draw(&root, false)
+                    .and_then(|_| root.present())
+                    .map_err(|err| InvmstError::ChartError(err.to_string()))
+            }
+            Some("svg") => {
+                let root = SVGBackend::new(path, size).into_drawing_area();
+                self.draw(&root, true)
+                    .and_then(|_| root.present())
+                    .map_err(|err| InvmstError::ChartError(err.to_string()))
+            }
+            _ => Err(InvmstError::Invalid(
+                "UNSUPPORTED_CHART_FORMAT",
+                format!("Unsupported chart format of '{}'", path.display()),
+            )),
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        with_text: bool,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        root.fill(&WHITE)?;
+
+        let points = self.series.iter().flat_map(|series| series.points.iter());
+        let Some(date_start) = points.clone().map(|(date, _)| *date).min() else {
+            return Ok(());
+        };
+        let date_end = points
+            .clone()
+            .map(|(date, _)| *date)
+            .max()
+            .unwrap_or(date_start);
+        let (mut min, mut max) = points.fold((f64::MAX, f64::MIN), |(min, max), (_, value)| {
+            (min.min(*value), max.max(*value))
+        });
+        if self.area {
+            min = min.min(0.0);
+            max = max.max(0.0);
+        }
+        if max <= min {
+            (min, max) = (min - 1.0, max + 1.0);
+        }
+
+        // 横轴用距首日的天数，标签再换算回日期
+        let x = |date: &NaiveDate| (*date - date_start).num_days() as f64;
+        let x_end = x(&date_end).max(1.0);
+
+        let mut builder = ChartBuilder::on(root);
+        builder.margin(16);
+        if with_text {
+            builder
+                .caption(&self.title, ("sans-serif", 20))
+                .x_label_area_size(32)
+                .y_label_area_size(64);
+        }
+        let mut chart = builder.build_cartesian_2d(0.0..x_end, min..max)?;
+
+        if with_text {
+            let percent = self.percent;
+            chart
+                .configure_mesh()
+                .x_labels(6)
+                .y_labels(8)
+                .x_label_formatter(&|days| {
+                    (date_start + chrono::Duration::days(*days as i64)).to_string()
+                })
+                .y_label_formatter(&|value| {
+                    if percent {
+                        format!("{:.0}%", value * 100.0)
+                    } else {
+                        format!("{value:.2}")
+                    }
+                })
+                .light_line_style(WHITE.mix(0.0))
+                .draw()?;
+        }
+
+        for (i, series) in self.series.iter().enumerate() {
+            let color = PALETTE[i % PALETTE.len()];
+            let data = series.points.iter().map(|(date, value)| (x(date), *value));
+
+            let drawn = if self.area && i == 0 {
+                chart.draw_series(AreaSeries::new(data, 0.0, color.mix(0.3)).border_style(color))?
+            } else {
+                chart.draw_series(LineSeries::new(data, color.stroke_width(2)))?
+            };
+            drawn
+                .label(series.name.as_str())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 16, y)], color));
+        }
+
+        if with_text && self.series.len() > 1 {
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn price_history(ticker: &str, prices: &[(NaiveDate, f64)]) -> Chart {
+    Chart {
+        title: format!("{ticker} Price"),
+        series: vec![ChartSeries {
+            name: "Price".to_string(),
+            points: prices.to_vec(),
+        }],
+        percent: false,
+        area: false,
+    }
+}
+
+/// Decline from the running peak, drawn below zero
+pub fn drawdown(ticker: &str, prices: &[(NaiveDate, f64)]) -> Chart {
+    let values: Vec<f64> = prices.iter().map(|(_, price)| *price).collect();
+    let points = risk_stats::drawdowns(&values)
+        .unwrap_or_default()
+        .into_iter()
+        .zip(prices)
+        .map(|(drawdown, (date, _))| (*date, -drawdown))
+        .collect();
+
+    Chart {
+        title: format!("{ticker} Drawdown"),
+        series: vec![ChartSeries {
+            name: "Drawdown".to_string(),
+            points,
+        }],
+        percent: true,
+        area: true,
+    }
+}
+
+/// Trend of one or more metrics, e.g. valuation multiples or ROE
+pub fn metric_trend(ticker: &str, title: &str, series: Vec<ChartSeries>, percent: bool) -> Chart {
+    Chart {
+        title: format!("{ticker} {title}"),
+        series,
+        percent,
+        area: false,
+    }
+}
+
+static CHART_HEIGHT: u32 = 480;
+static CHART_WIDTH: u32 = 960;
+
+static PALETTE: [RGBColor; 4] = [
+    RGBColor(0x4a, 0x90, 0xd9),
+    RGBColor(0xd0, 0x02, 0x1b),
+    RGBColor(0x41, 0x75, 0x05),
+    RGBColor(0xf5, 0xa6, 0x23),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_save() {
+        let date_start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let prices: Vec<(NaiveDate, f64)> = (0..60)
+            .map(|i| {
+                (
+                    date_start + Duration::days(i),
+                    10.0 + (i as f64 / 5.0).sin(),
+                )
+            })
+            .collect();
+        let dir = std::env::temp_dir().join("invmst_test_charts");
+        fs::create_dir_all(&dir).unwrap();
+
+        let chart = drawdown("600900", &prices);
+        assert!(
+            chart.series[0]
+                .points
+                .iter()
+                .all(|(_, value)| *value <= 0.0)
+        );
+
+        for name in ["drawdown.png", "drawdown.svg"] {
+            let path = dir.join(name);
+            chart.save(&path).unwrap();
+            assert!(fs::metadata(&path).unwrap().len() > 0);
+        }
+        assert!(
+            fs::read_to_string(dir.join("drawdown.svg"))
+                .unwrap()
+                .contains("600900 Drawdown")
+        );
+        assert!(
+            price_history("600900", &prices)
+                .save(&dir.join("price.txt"))
+                .is_err()
+        );
+    }
+}
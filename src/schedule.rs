@@ -0,0 +1,183 @@
+use std::{path::PathBuf, str::FromStr, sync::LazyLock};
+
+use chrono::{DateTime, Local};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    APP_DATA_DIR, alert,
+    error::{InvmstError, InvmstResult},
+    portfolio, watchlist,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScheduleJob {
+    pub id: u64,
+    pub cron: String,
+    pub tickers: Vec<String>,
+    /// Portfolio whose holdings are also evaluated, resolved when the job runs so that changes of
+    /// the holdings take effect
+    #[serde(default)]
+    pub portfolio: Option<String>,
+    /// Watchlist whose tickers are also evaluated, resolved when the job runs like the portfolio
+    #[serde(default)]
+    pub watchlist: Option<String>,
+    /// Condition over the metrics the tickers are screened by instead of being evaluated, e.g.
+    /// "pe < 12 && roe > 15%"
+    #[serde(default)]
+    pub screen: Option<String>,
+    pub masters: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScheduleConfig {
+    jobs: Vec<ScheduleJob>,
+}
+
+pub async fn add_job(
+    cron: &str,
+    tickers: &[String],
+    portfolio: Option<&str>,
+    watchlist: Option<&str>,
+    screen: Option<&str>,
+    masters: &[String],
+) -> InvmstResult<ScheduleJob> {
+    parse_cron(cron)?;
+    if let Some(condition) = screen {
+        alert::parse_condition(condition)?;
+    }
+
+    if let Some(name) = portfolio {
+        portfolio::get(name).await?;
+    }
+    if let Some(name) = watchlist {
+        watchlist::get(name).await?;
+    }
+    if tickers.is_empty() && portfolio.is_none() && watchlist.is_none() {
+        return Err(InvmstError::Required(
+            "TICKER_REQUIRED",
+            "At least one ticker, a portfolio or a watchlist is required".to_string(),
+        ));
+    }
+
+    let mut cfg: ScheduleConfig = confy::load_path(&*SCHEDULE_CONFIG_PATH)?;
+
+    let job = ScheduleJob {
+        id: cfg.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1,
+        cron: cron.trim().to_string(),
+        tickers: tickers.to_vec(),
+        portfolio: portfolio.map(|name| name.to_string()),
+        watchlist: watchlist.map(|name| name.to_string()),
+        screen: screen.map(|condition| condition.trim().to_string()),
+        masters: masters.to_vec(),
+    };
+    cfg.jobs.push(job.clone());
+
+    confy::store_path(&*SCHEDULE_CONFIG_PATH, &cfg)?;
+
+    Ok(job)
+}
+
+pub async fn list_jobs() -> InvmstResult<Vec<ScheduleJob>> {
+    let cfg: ScheduleConfig = confy::load_path(&*SCHEDULE_CONFIG_PATH)?;
+
+    Ok(cfg.jobs)
+}
+
+pub async fn remove_job(id: u64) -> InvmstResult<()> {
+    let mut cfg: ScheduleConfig = confy::load_path(&*SCHEDULE_CONFIG_PATH)?;
+
+    let count = cfg.jobs.len();
+    cfg.jobs.retain(|job| job.id != id);
+    if cfg.jobs.len() == count {
+        return Err(InvmstError::NotExists(
+            "JOB_NOT_EXISTS",
+            format!("Scheduled job '{id}' not exists"),
+        ));
+    }
+
+    confy::store_path(&*SCHEDULE_CONFIG_PATH, &cfg)?;
+
+    Ok(())
+}
+
+impl ScheduleJob {
+    /// Tickers followed by the current holdings of the portfolio and the current tickers of the
+    /// watchlist not already among them
+    pub async fn resolve_tickers(&self) -> InvmstResult<Vec<String>> {
+        let mut tickers = self.tickers.clone();
+
+        if let Some(name) = &self.portfolio {
+            tickers.extend(
+                portfolio::get(name)
+                    .await?
+                    .holdings
+                    .into_iter()
+                    .map(|holding| holding.ticker),
+            );
+        }
+        if let Some(name) = &self.watchlist {
+            tickers.extend(watchlist::get(name).await?.tickers);
+        }
+
+        let mut resolved: Vec<String> = vec![];
+        for ticker in tickers {
+            if !resolved.contains(&ticker) {
+                resolved.push(ticker);
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    pub fn next_run(&self, after: &DateTime<Local>) -> Option<DateTime<Local>> {
+        parse_cron(&self.cron)
+            .ok()?
+            .find_next_occurrence(after, false)
+            .ok()
+    }
+}
+
+static SCHEDULE_CONFIG_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| APP_DATA_DIR.join("schedule.toml"));
+
+fn parse_cron(cron: &str) -> InvmstResult<Cron> {
+    Cron::from_str(cron.trim()).map_err(|err| {
+        InvmstError::Invalid(
+            "INVALID_CRON",
+            format!("Invalid cron expression '{cron}': {err}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, TimeZone, Timelike, Weekday};
+
+    use super::*;
+
+    #[test]
+    fn test_next_run() {
+        let job = ScheduleJob {
+            id: 1,
+            cron: "0 18 * * 1-5".to_string(),
+            tickers: vec!["600900".to_string()],
+            portfolio: None,
+            watchlist: None,
+            screen: None,
+            masters: vec![],
+        };
+
+        // 2025-06-07 is a Saturday
+        let after = Local.with_ymd_and_hms(2025, 6, 7, 12, 0, 0).unwrap();
+        let next = job.next_run(&after).unwrap();
+        assert_eq!(next.weekday(), Weekday::Mon);
+        assert_eq!(next.hour(), 18);
+        assert_eq!(next.minute(), 0);
+    }
+
+    #[test]
+    fn test_parse_cron_invalid() {
+        assert!(parse_cron("not a cron").is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use std::str::FromStr;
+
+use log::warn;
+use serde::Serialize;
+
+use crate::{alert, error::InvmstResult, metrics, ticker::Ticker};
+
+/// Ticker whose metrics meet the condition of a screen, with the values of the metrics it
+/// references
+#[derive(Clone, Debug, Serialize)]
+pub struct ScreenMatch {
+    pub ticker: String,
+    pub values: Vec<(String, f64)>,
+}
+
+/// Screen the tickers by a condition over the latest metrics, e.g. "pe < 12 && roe > 15%".
+/// Tickers whose metrics are unavailable are skipped.
+pub async fn run(tickers: &[String], condition: &str) -> InvmstResult<Vec<ScreenMatch>> {
+    let condition = alert::parse_condition(condition)?;
+
+    let mut matches: Vec<ScreenMatch> = vec![];
+    for ticker in tickers {
+        let snapshot = match Ticker::from_str(ticker) {
+            Ok(ticker) => metrics::snapshot(&ticker).await,
+            Err(err) => Err(err),
+        };
+        let snapshot = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                warn!("[Screen] Unable to fetch metrics of {ticker}: {err}");
+                continue;
+            }
+        };
+
+        if condition.evaluate(&snapshot) == Some(true) {
+            matches.push(ScreenMatch {
+                ticker: ticker.to_string(),
+                values: alert::referenced_values(&condition, &snapshot),
+            });
+        }
+    }
+
+    Ok(matches)
+}
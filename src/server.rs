@@ -0,0 +1,338 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{get, post},
+};
+use chrono::NaiveDate;
+use dashmap::DashMap;
+use futures::{Stream, StreamExt, stream};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{net::TcpListener, sync::broadcast};
+
+use crate::{
+    CHANNEL_BUFFER_DEFAULT,
+    data::stock::StockSnapshot,
+    error::{InvmstError, InvmstResult},
+    evaluate::{self, EvaluateOptions, EvaluationEvent},
+    history,
+    llm::ChatCompletionOptions,
+    master::Holding,
+};
+
+pub async fn serve(addr: &str) -> InvmstResult<()> {
+    let listener = TcpListener::bind(addr).await?;
+    axum::serve(listener, router(Arc::new(ServerState::default()))).await?;
+
+    Ok(())
+}
+
+fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/evaluations", post(create_evaluation))
+        .route("/evaluations/{id}/events", get(evaluation_events))
+        .route("/evaluations/{id}/snapshot", get(evaluation_snapshot))
+        .with_state(state)
+}
+
+#[derive(Default)]
+struct ServerState {
+    evaluations: DashMap<String, Arc<Mutex<EvaluationLog>>>,
+}
+
+/// Events of an evaluation, kept so that late subscribers can replay them
+struct EvaluationLog {
+    events: Vec<(String, Value)>,
+    sender: Option<broadcast::Sender<(String, Value)>>,
+}
+
+#[derive(Deserialize)]
+struct CreateEvaluationRequest {
+    /// Not required if the snapshot is given, whose ticker is used
+    #[serde(default)]
+    ticker: String,
+    backward_days: Option<i64>,
+    date: Option<NaiveDate>,
+    #[serde(default)]
+    masters: Vec<String>,
+    #[serde(default)]
+    exclude_masters: Vec<String>,
+    #[serde(default)]
+    monte_carlo: bool,
+    holding: Option<Holding>,
+    #[serde(default)]
+    bear_case: bool,
+    #[serde(default)]
+    pre_mortem: bool,
+    #[serde(default)]
+    agentic: bool,
+    #[serde(default)]
+    focus_areas: Vec<String>,
+    note: Option<String>,
+    /// Data to evaluate instead of fetching
+    snapshot: Option<StockSnapshot>,
+}
+
+async fn create_evaluation(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<CreateEvaluationRequest>,
+) -> Response {
+    let options = EvaluateOptions {
+        backward_days: request.backward_days,
+        date: request.date,
+        masters: request.masters,
+        exclude_masters: request.exclude_masters,
+        monte_carlo: request.monte_carlo,
+        holding: request.holding,
+        bear_case: request.bear_case,
+        pre_mortem: request.pre_mortem,
+        agentic: request.agentic,
+        focus_areas: request.focus_areas,
+        note: request.note,
+        llm_options: ChatCompletionOptions::default(),
+    };
+
+    let stream = match request.snapshot {
+        Some(snapshot) => evaluate::run_snapshot_stream(snapshot, &options).await,
+        None => evaluate::run_stream(&request.ticker, &options).await,
+    };
+    let mut stream = match stream {
+        Ok(stream) => stream,
+        Err(err) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": err.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let id = stream.id().to_string();
+    let response = Json(json!({ "id": id })).into_response();
+
+    let (sender, _) = broadcast::channel(CHANNEL_BUFFER_DEFAULT);
+    let log = Arc::new(Mutex::new(EvaluationLog {
+        events: vec![],
+        sender: Some(sender),
+    }));
+    state.evaluations.insert(id.clone(), log.clone());
+
+    tokio::spawn(async move {
+        while let Some(event) = stream.next().await {
+            let is_end = matches!(
+                event,
+                EvaluationEvent::Finished(_) | EvaluationEvent::Error(_)
+            );
+            if !record_event(&log, event_to_json(event), is_end) || is_end {
+                break;
+            }
+        }
+        if let Ok(mut log) = log.lock() {
+            log.sender = None;
+        }
+
+        // 结束后保留一段时间供迟到的订阅者回放，之后移除以免内存持续增长
+        tokio::time::sleep(FINISHED_EVALUATION_TTL).await;
+        state.evaluations.remove(&id);
+    });
+
+    response
+}
+
+async fn evaluation_events(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(log) = state.evaluations.get(&id).map(|log| log.clone()) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("Evaluation '{id}' not exists") })),
+        )
+            .into_response();
+    };
+
+    // Subscribe while holding the lock so that no event is missed between replay and live
+    let (history, receiver) = match log.lock() {
+        Ok(log) => (
+            log.events.clone(),
+            log.sender.as_ref().map(|sender| sender.subscribe()),
+        ),
+        Err(_) => (
+            vec![(
+                "error".to_string(),
+                json!({ "error": format!("Events of evaluation '{id}' are unavailable") }),
+            )],
+            None,
+        ),
+    };
+
+    Sse::new(replay_then_follow(history, receiver))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn evaluation_snapshot(Path(id): Path<String>) -> Response {
+    match history::load_snapshot(&id).await {
+        Ok(snapshot) => Json(snapshot).into_response(),
+        Err(err) => {
+            let status = match err {
+                InvmstError::Invalid(..) => StatusCode::BAD_REQUEST,
+                InvmstError::NotExists(..) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+
+            (status, Json(json!({ "error": err.to_string() }))).into_response()
+        }
+    }
+}
+
+/// Keep the event and send it to the subscribers, returns false if the log is unavailable
+fn record_event(log: &Mutex<EvaluationLog>, item: (String, Value), is_end: bool) -> bool {
+    let Ok(mut log) = log.lock() else {
+        return false;
+    };
+
+    if let Some(sender) = &log.sender {
+        let _ = sender.send(item.clone());
+    }
+    log.events.push(item);
+    if is_end {
+        log.sender = None;
+    }
+
+    true
+}
+
+fn replay_then_follow(
+    history: Vec<(String, Value)>,
+    receiver: Option<broadcast::Receiver<(String, Value)>>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let live = stream::unfold(receiver, |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(item) => return Some((item, Some(receiver))),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    stream::iter(history)
+        .chain(live)
+        .map(|(name, data)| Ok(Event::default().event(name).data(data.to_string())))
+}
+
+fn event_to_json(event: EvaluationEvent) -> (String, Value) {
+    match event {
+        EvaluationEvent::Stage(stage) => {
+            ("stage".to_string(), json!({ "stage": stage.to_string() }))
+        }
+        EvaluationEvent::MasterStarted(master) => {
+            ("master_started".to_string(), json!({ "master": master }))
+        }
+        EvaluationEvent::MasterFinished(master, master_analysis) => (
+            "master_finished".to_string(),
+            json!({ "master": master, "analysis": master_analysis }),
+        ),
+        EvaluationEvent::Finished(evaluation) => {
+            ("finished".to_string(), json!({ "id": evaluation.id }))
+        }
+        EvaluationEvent::Error(err) => ("error".to_string(), json!({ "error": err.to_string() })),
+    }
+}
+
+/// How long the events of a finished evaluation are kept for replay
+static FINISHED_EVALUATION_TTL: Duration = Duration::from_secs(30 * 60);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn spawn_server(state: Arc<ServerState>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+
+        format!("http://{addr}")
+    }
+
+    fn insert_log(state: &ServerState, id: &str, live: bool) -> Arc<Mutex<EvaluationLog>> {
+        let log = Arc::new(Mutex::new(EvaluationLog {
+            events: vec![],
+            sender: live.then(|| broadcast::channel(CHANNEL_BUFFER_DEFAULT).0),
+        }));
+        state.evaluations.insert(id.to_string(), log.clone());
+
+        log
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_events() {
+        let state = Arc::new(ServerState::default());
+        let base_url = spawn_server(state.clone()).await;
+
+        // 已结束的评估只回放
+        let log = insert_log(&state, "finished", true);
+        record_event(&log, ("stage".to_string(), json!({ "stage": "a" })), false);
+        record_event(
+            &log,
+            ("finished".to_string(), json!({ "id": "finished" })),
+            true,
+        );
+        let body = reqwest::get(format!("{base_url}/evaluations/finished/events"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(body.find("event: stage").unwrap() < body.find("event: finished").unwrap());
+
+        // 订阅后产生的事件接在回放之后
+        let log = insert_log(&state, "running", true);
+        record_event(&log, ("stage".to_string(), json!({ "stage": "a" })), false);
+        let response = reqwest::get(format!("{base_url}/evaluations/running/events"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        record_event(&log, ("master_started".to_string(), json!({})), false);
+        record_event(&log, ("error".to_string(), json!({ "error": "x" })), true);
+        let body = response.text().await.unwrap();
+        let events: Vec<&str> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("event: "))
+            .collect();
+        assert_eq!(events, vec!["stage", "master_started", "error"]);
+
+        // 日志损坏时返回错误事件
+        let log = insert_log(&state, "poisoned", false);
+        let _ = std::thread::spawn(move || {
+            let _guard = log.lock().unwrap();
+            panic!("poison the log");
+        })
+        .join();
+        let body = reqwest::get(format!("{base_url}/evaluations/poisoned/events"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(body.contains("event: error"));
+
+        let response = reqwest::get(format!("{base_url}/evaluations/missing/events"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use crate::error::InvmstError;
 
@@ -52,3 +52,9 @@ impl FromStr for Ticker {
         }
     }
 }
+
+impl Display for Ticker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.exchange, self.symbol)
+    }
+}
@@ -1,3 +1,4 @@
+pub mod chart;
 pub mod datetime;
 pub mod markdown;
 pub mod net;
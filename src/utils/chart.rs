@@ -0,0 +1,51 @@
+/// Unicode sparkline of the values scaled between their min and max, down-sampled evenly to at
+/// most the width, non-finite values are left blank
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    let values: Vec<f64> = if values.len() > width && width > 1 {
+        (0..width)
+            .map(|i| values[i * (values.len() - 1) / (width - 1)])
+            .collect()
+    } else {
+        values.to_vec()
+    };
+
+    let (min, max) = values
+        .iter()
+        .filter(|value| value.is_finite())
+        .fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+
+    values
+        .iter()
+        .map(|value| {
+            if !value.is_finite() {
+                ' '
+            } else if max > min {
+                let level = ((value - min) / (max - min) * (SPARK_CHARS.len() - 1) as f64).round();
+                SPARK_CHARS[level as usize]
+            } else {
+                SPARK_CHARS[SPARK_CHARS.len() / 2]
+            }
+        })
+        .collect()
+}
+
+static SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(
+            sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], 8),
+            "▁▂▃▄▅▆▇█"
+        );
+        assert_eq!(sparkline(&[1.0, f64::NAN, 3.0], 8), "▁ █");
+        assert_eq!(sparkline(&[2.0, 2.0], 8), "▅▅");
+        assert_eq!(sparkline(&[0.0, 1.0, 2.0, 3.0, 4.0], 3), "▁▅█");
+        assert_eq!(sparkline(&[], 8), "");
+    }
+}
@@ -1,8 +1,9 @@
 use std::fmt::Display;
 
 use chrono::{DateTime, Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, strum::Display)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, strum::Display)]
 pub enum Quarter {
     Q1,
     Q2,
@@ -10,7 +11,7 @@ pub enum Quarter {
     Q4,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FiscalQuarter {
     pub year: i32,
     pub quarter: Quarter,
@@ -66,6 +67,23 @@ impl FiscalQuarter {
         Self { year, quarter }
     }
 
+    /// The last day of the fiscal quarter
+    pub fn end_date(&self) -> NaiveDate {
+        let (month, day) = match self.quarter {
+            Quarter::Q1 => (3, 31),
+            Quarter::Q2 => (6, 30),
+            Quarter::Q3 => (9, 30),
+            Quarter::Q4 => (12, 31),
+        };
+
+        NaiveDate::from_ymd_opt(self.year, month, day).unwrap()
+    }
+
+    /// The same quarter of the previous year
+    pub fn prev_year(&self) -> Self {
+        Self::new(self.year - 1, self.quarter.clone())
+    }
+
     pub fn prev(&self) -> Self {
         Self {
             year: if self.quarter == Quarter::Q1 {
@@ -81,6 +99,22 @@ impl FiscalQuarter {
             },
         }
     }
+
+    pub fn next(&self) -> Self {
+        Self {
+            year: if self.quarter == Quarter::Q4 {
+                self.year + 1
+            } else {
+                self.year
+            },
+            quarter: match self.quarter {
+                Quarter::Q1 => Quarter::Q2,
+                Quarter::Q2 => Quarter::Q3,
+                Quarter::Q3 => Quarter::Q4,
+                Quarter::Q4 => Quarter::Q1,
+            },
+        }
+    }
 }
 
 impl Display for FiscalQuarter {
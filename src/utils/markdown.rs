@@ -1,6 +1,7 @@
 use std::sync::LazyLock;
 
-use regex::Regex;
+use colored::Colorize;
+use regex::{Captures, Regex};
 
 pub fn extract_code_block(s: &str) -> String {
     let s = REGEX_XML_TAG.replace_all(s, "");
@@ -10,11 +11,84 @@ pub fn extract_code_block(s: &str) -> String {
     s.trim().to_string()
 }
 
+/// Render markdown for the terminal, headings and bold text are bolded, list markers become bullets
+/// and tables are drawn, other syntax is kept as is
+pub fn render_terminal(s: &str) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut table_rows: Vec<Vec<String>> = vec![];
+
+    for line in s.lines() {
+        let trimmed = line.trim();
+        if trimmed.len() > 1 && trimmed.starts_with('|') && trimmed.ends_with('|') {
+            // 分隔行只用于对齐，不是表格内容
+            if !REGEX_TABLE_SEPARATOR.is_match(trimmed) {
+                table_rows.push(
+                    trimmed[1..trimmed.len() - 1]
+                        .split('|')
+                        .map(|cell| render_inline(cell.trim()))
+                        .collect(),
+                );
+            }
+            continue;
+        }
+
+        if !table_rows.is_empty() {
+            lines.push(render_table(&std::mem::take(&mut table_rows)));
+        }
+        lines.push(render_line(line));
+    }
+    if !table_rows.is_empty() {
+        lines.push(render_table(&table_rows));
+    }
+
+    lines.join("\n")
+}
+
+fn render_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = " ".repeat(line.len() - trimmed.len());
+
+    if let Some(caps) = REGEX_HEADING.captures(trimmed) {
+        return render_inline(&caps[1]).bold().to_string();
+    }
+
+    if let Some(item) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+    {
+        return format!("{indent}• {}", render_inline(item));
+    }
+
+    format!("{indent}{}", render_inline(trimmed))
+}
+
+fn render_inline(s: &str) -> String {
+    let s = REGEX_BOLD.replace_all(s, |caps: &Captures| caps[1].bold().to_string());
+    let s = REGEX_CODE.replace_all(&s, |caps: &Captures| caps[1].cyan().to_string());
+
+    s.to_string()
+}
+
+fn render_table(rows: &[Vec<String>]) -> String {
+    tabled::builder::Builder::from_iter(rows)
+        .build()
+        .to_string()
+}
+
+static REGEX_BOLD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*\*(.+?)\*\*").expect("BOLD regex is invalid"));
+static REGEX_CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`([^`]+)`").expect("CODE regex is invalid"));
 static REGEX_CODE_BLOCK_START: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?:([\s\S]*?))(\s*```.*\n)([\s\S]*?)").expect("CODE_BLOCK_START regex is invalid")
 });
 static REGEX_CODE_BLOCK_END: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\s*```[\s\S]*").expect("CODE_BLOCK_END regex is invalid"));
+static REGEX_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^#{1,6}\s+(.*)$").expect("HEADING regex is invalid"));
+static REGEX_TABLE_SEPARATOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\|(\s*:?-+:?\s*\|)+$").expect("TABLE_SEPARATOR regex is invalid")
+});
 static REGEX_XML_TAG: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"<[^>]+>[\s\S]*?<\/[^>]+>").expect("XML_TAG regex is invalid"));
 
@@ -52,7 +126,7 @@ I am thinking...
         )) {
             assert_eq!(json.get("foo"), Some(&"bar"));
         } else {
-            assert!(false);
+            panic!();
         }
 
         if let Ok(json) = serde_json::from_str::<HashMap<&str, &str>>(&extract_code_block(
@@ -72,7 +146,22 @@ Some more text
         )) {
             assert_eq!(json.get("foo"), Some(&"bar"));
         } else {
-            assert!(false);
+            panic!();
         }
     }
+
+    #[test]
+    fn test_render_terminal() {
+        colored::control::set_override(false);
+
+        assert_eq!(
+            render_terminal("## Summary\n- **Cheap** at `8x` earnings\n  * low debt"),
+            "Summary\n• Cheap at 8x earnings\n  • low debt"
+        );
+
+        let rendered = render_terminal("| Metric | Value |\n|---|:---:|\n| PE | 8 |\nDone");
+        assert!(rendered.contains("| Metric | Value |"));
+        assert!(!rendered.contains("---|"));
+        assert!(rendered.ends_with("\nDone"));
+    }
 }
@@ -1,3 +1,13 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    /// Fraction of the variance explained by the fit, in [0, 1]
+    pub r_squared: f64,
+}
+
 pub fn mean(values: &[f64]) -> Option<f64> {
     let sum = values.iter().sum::<f64>();
     let count = values.len();
@@ -30,17 +40,285 @@ pub fn std(values: &[f64]) -> Option<f64> {
     None
 }
 
+pub fn covariance(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() {
+        return None;
+    }
+
+    let mean_a = mean(a)?;
+    let mean_b = mean(b)?;
+
+    Some(
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - mean_a) * (y - mean_b))
+            .sum::<f64>()
+            / a.len() as f64,
+    )
+}
+
+pub fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let std_a = std(a)?;
+    let std_b = std(b)?;
+
+    if std_a > 0.0 && std_b > 0.0 {
+        Some(covariance(a, b)? / (std_a * std_b))
+    } else {
+        None
+    }
+}
+
+/// Fraction of values not greater than the value, in [0, 1]
+pub fn percentile_rank(values: &[f64], value: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().filter(|v| **v <= value).count() as f64 / values.len() as f64)
+}
+
+/// Quantile by linear interpolation between the closest ranks, q in [0, 1]
+pub fn quantile(values: &[f64], q: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let rank = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+/// Percentile by linear interpolation between the closest ranks, p in [0, 100]
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    quantile(values, p / 100.0)
+}
+
+pub fn median(values: &[f64]) -> Option<f64> {
+    quantile(values, 0.5)
+}
+
+/// Median absolute deviation from the median
+pub fn mad(values: &[f64]) -> Option<f64> {
+    let median = median(values)?;
+    let deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+
+    self::median(&deviations)
+}
+
+/// Mean after clamping the lowest and highest fraction of values, limit in [0, 0.5), to the
+/// nearest values kept, so one outlier can not dominate the mean
+pub fn winsorized_mean(values: &[f64], limit: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let k = ((sorted.len() as f64 * limit.clamp(0.0, 0.5)).floor() as usize)
+        .min((sorted.len() - 1) / 2);
+    let (lower, upper) = (sorted[k], sorted[sorted.len() - 1 - k]);
+    let clamped: Vec<f64> = values.iter().map(|v| v.clamp(lower, upper)).collect();
+
+    mean(&clamped)
+}
+
+/// Std estimated from the MAD, robust to outliers
+pub fn robust_std(values: &[f64]) -> Option<f64> {
+    Some(mad(values)? * MAD_TO_STD)
+}
+
+/// Least squares fit of y on x, None if there are less than 2 points or x does not vary
+pub fn linear_regression(points: &[(f64, f64)]) -> Option<LinearFit> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let xs: Vec<f64> = points.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<f64> = points.iter().map(|(_, y)| *y).collect();
+    let (mean_x, mean_y) = (mean(&xs)?, mean(&ys)?);
+
+    let ss_xx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if ss_xx == 0.0 {
+        return None;
+    }
+    let ss_xy: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let ss_yy: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+
+    let slope = ss_xy / ss_xx;
+    let r_squared = if ss_yy > 0.0 {
+        (ss_xy * ss_xy / (ss_xx * ss_yy)).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+
+    Some(LinearFit {
+        slope,
+        intercept: mean_y - slope * mean_x,
+        r_squared,
+    })
+}
+
+/// Linear fit of evenly spaced values sorted by time ascending, the slope is per step
+pub fn linear_trend(values: &[f64]) -> Option<LinearFit> {
+    let points: Vec<(f64, f64)> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (i as f64, *v))
+        .collect();
+
+    linear_regression(&points)
+}
+
+/// Growth rate from the previous value, divided by the absolute previous value so that a narrowing
+/// loss counts as growth, None if the previous value is zero or any value is not finite
+pub fn growth_rate(current: f64, prev: f64) -> Option<f64> {
+    if prev == 0.0 || !prev.is_finite() || !current.is_finite() {
+        return None;
+    }
+
+    Some((current - prev) / prev.abs())
+}
+
+/// Growth rates of (current, previous) pairs, pairs without a valid growth rate are skipped
+pub fn growth_rates(pairs: impl IntoIterator<Item = (f64, f64)>) -> Vec<f64> {
+    pairs
+        .into_iter()
+        .filter_map(|(current, prev)| growth_rate(current, prev))
+        .collect()
+}
+
+/// Compound annual growth rate from the start value to the end value over the years, None if the
+/// start value is not positive or the end value is negative, as the rate is undefined across a loss
+pub fn cagr(start: f64, end: f64, years: f64) -> Option<f64> {
+    let valid = start > 0.0 && start.is_finite() && end >= 0.0 && end.is_finite() && years > 0.0;
+    if !valid {
+        return None;
+    }
+
+    Some((end / start).powf(1.0 / years) - 1.0)
+}
+
+/// Growth rates of each value over the value n steps later, for values sorted latest first, values
+/// without a valid growth rate (e.g. NaN for missing ones) are skipped
+pub fn rolling_growth(values: &[f64], n: usize) -> Vec<f64> {
+    growth_rates(values.iter().copied().zip(values.iter().skip(n).copied()))
+}
+
+/// Default limit of winsorized means, clamping the lowest and highest 10% of values
+pub static WINSORIZE_LIMIT: f64 = 0.1;
+
+/// Scale of the MAD to estimate the std of normally distributed values
+static MAD_TO_STD: f64 = 1.4826;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_cagr() {
+        assert!((cagr(100.0, 121.0, 2.0).unwrap() - 0.1).abs() < 1e-9);
+        assert!(cagr(-1.0, 1.0, 1.0).is_none());
+        assert!(cagr(1.0, -1.0, 1.0).is_none());
+        assert!(cagr(1.0, 2.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_correlation() {
+        assert!((correlation(&[1.0, 2.0, 3.0], &[2.0, 4.0, 6.0]).unwrap() - 1.0).abs() < 1e-9);
+        assert!((correlation(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]).unwrap() + 1.0).abs() < 1e-9);
+        assert!(correlation(&[1.0, 1.0], &[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_growth_rates() {
+        assert_eq!(growth_rate(12.0, 10.0).unwrap(), 0.2);
+        assert_eq!(growth_rate(-5.0, -10.0).unwrap(), 0.5);
+        assert_eq!(growth_rate(-10.0, 10.0).unwrap(), -2.0);
+        assert!(growth_rate(1.0, 0.0).is_none());
+        assert!(growth_rate(f64::NAN, 1.0).is_none());
+
+        assert_eq!(
+            growth_rates([(12.0, 10.0), (1.0, 0.0), (f64::INFINITY, 1.0)]),
+            vec![0.2]
+        );
+    }
+
+    #[test]
+    fn test_linear_regression() {
+        let fit = linear_regression(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]).unwrap();
+        assert!((fit.slope - 2.0).abs() < 1e-9);
+        assert!((fit.intercept - 1.0).abs() < 1e-9);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+
+        let fit = linear_trend(&[1.0, 3.0, 1.0, 3.0]).unwrap();
+        assert!(fit.r_squared < 0.5);
+        assert!(linear_regression(&[(1.0, 1.0), (1.0, 2.0)]).is_none());
+        assert!(linear_trend(&[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_mad() {
+        assert_eq!(mad(&[1.0, 2.0, 3.0, 4.0, 100.0]).unwrap(), 1.0);
+        assert!((robust_std(&[1.0, 2.0, 3.0, 4.0, 100.0]).unwrap() - 1.4826).abs() < 1e-9);
+        assert!(mad(&[]).is_none());
+    }
+
     #[test]
     fn test_mean() {
-        assert_eq!(mean(&vec![0.0, 1.0]).unwrap(), 0.5);
+        assert_eq!(mean(&[0.0, 1.0]).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[5.0, 1.0, 100.0]).unwrap(), 5.0);
+        assert_eq!(median(&[1.0, 2.0, 3.0, 4.0]).unwrap(), 2.5);
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0, 5.0], 25.0).unwrap(), 2.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0, 5.0], 100.0).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_percentile_rank() {
+        assert_eq!(percentile_rank(&[1.0, 2.0, 3.0, 4.0], 2.0).unwrap(), 0.5);
+        assert!(percentile_rank(&[], 1.0).is_none());
+    }
+
+    #[test]
+    fn test_quantile() {
+        assert_eq!(quantile(&[3.0, 1.0, 2.0, 4.0], 0.5).unwrap(), 2.5);
+        assert_eq!(quantile(&[3.0, 1.0, 2.0], 1.0).unwrap(), 3.0);
+        assert!(quantile(&[], 0.5).is_none());
+    }
+
+    #[test]
+    fn test_rolling_growth() {
+        assert_eq!(
+            rolling_growth(&[12.0, 11.0, 10.0, f64::NAN, 10.0], 2),
+            vec![0.2, 0.0]
+        );
     }
 
     #[test]
     fn test_std() {
-        assert_eq!(std(&vec![1.0, 1.0]).unwrap(), 0.0);
+        assert_eq!(std(&[1.0, 1.0]).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_winsorized_mean() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 1000.0];
+        assert_eq!(winsorized_mean(&values, WINSORIZE_LIMIT).unwrap(), 5.5);
+        assert_eq!(winsorized_mean(&[1.0, 3.0], 0.0).unwrap(), 2.0);
     }
 }
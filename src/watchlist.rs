@@ -0,0 +1,119 @@
+use std::{path::PathBuf, str::FromStr, sync::LazyLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    APP_DATA_DIR,
+    error::{InvmstError, InvmstResult},
+    ticker::Ticker,
+};
+
+/// Named list of tickers to follow, e.g. evaluated or screened by scheduled jobs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub name: String,
+    pub tickers: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WatchlistConfig {
+    watchlists: Vec<Watchlist>,
+}
+
+/// Add tickers to the watchlist, creating it if needed, tickers already in it are skipped
+pub async fn add_tickers(name: &str, tickers: &[String]) -> InvmstResult<Watchlist> {
+    if tickers.is_empty() {
+        return Err(InvmstError::Required(
+            "TICKER_REQUIRED",
+            "At least one ticker is required".to_string(),
+        ));
+    }
+
+    let tickers: Vec<String> = tickers
+        .iter()
+        .map(|ticker| Ticker::from_str(ticker).map(|ticker| ticker.to_string()))
+        .collect::<InvmstResult<_>>()?;
+
+    let mut cfg: WatchlistConfig = confy::load_path(&*WATCHLIST_CONFIG_PATH)?;
+
+    let index = match cfg.watchlists.iter().position(|w| w.name == name) {
+        Some(index) => index,
+        None => {
+            cfg.watchlists.push(Watchlist {
+                name: name.to_string(),
+                tickers: vec![],
+            });
+            cfg.watchlists.len() - 1
+        }
+    };
+
+    let watchlist = &mut cfg.watchlists[index];
+    for ticker in tickers {
+        if !watchlist.tickers.contains(&ticker) {
+            watchlist.tickers.push(ticker);
+        }
+    }
+    let watchlist = watchlist.clone();
+
+    confy::store_path(&*WATCHLIST_CONFIG_PATH, &cfg)?;
+
+    Ok(watchlist)
+}
+
+pub async fn get(name: &str) -> InvmstResult<Watchlist> {
+    let cfg: WatchlistConfig = confy::load_path(&*WATCHLIST_CONFIG_PATH)?;
+
+    cfg.watchlists
+        .into_iter()
+        .find(|w| w.name == name)
+        .ok_or(InvmstError::NotExists(
+            "WATCHLIST_NOT_EXISTS",
+            format!("Watchlist '{name}' not exists"),
+        ))
+}
+
+pub async fn list() -> InvmstResult<Vec<Watchlist>> {
+    let cfg: WatchlistConfig = confy::load_path(&*WATCHLIST_CONFIG_PATH)?;
+
+    Ok(cfg.watchlists)
+}
+
+/// Remove a ticker from the watchlist, or the whole watchlist if ticker is None
+pub async fn remove(name: &str, ticker: Option<&str>) -> InvmstResult<()> {
+    let mut cfg: WatchlistConfig = confy::load_path(&*WATCHLIST_CONFIG_PATH)?;
+
+    let index =
+        cfg.watchlists
+            .iter()
+            .position(|w| w.name == name)
+            .ok_or(InvmstError::NotExists(
+                "WATCHLIST_NOT_EXISTS",
+                format!("Watchlist '{name}' not exists"),
+            ))?;
+
+    match ticker {
+        Some(ticker) => {
+            let ticker = Ticker::from_str(ticker)?.to_string();
+            let tickers = &mut cfg.watchlists[index].tickers;
+
+            let count = tickers.len();
+            tickers.retain(|t| *t != ticker);
+            if tickers.len() == count {
+                return Err(InvmstError::NotExists(
+                    "TICKER_NOT_EXISTS",
+                    format!("Ticker '{ticker}' not exists in watchlist '{name}'"),
+                ));
+            }
+        }
+        None => {
+            cfg.watchlists.remove(index);
+        }
+    }
+
+    confy::store_path(&*WATCHLIST_CONFIG_PATH, &cfg)?;
+
+    Ok(())
+}
+
+static WATCHLIST_CONFIG_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| APP_DATA_DIR.join("watchlist.toml"));